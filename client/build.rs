@@ -0,0 +1,21 @@
+//! Writes the metadata of our runtime to `metadata.scale`, which is what `subxt` generates the
+//! types we use to talk to the node from.
+//!
+//! Taking it straight from the runtime in this workspace means those types can't drift away from
+//! what the node actually runs, and we don't need a node running in order to build.
+
+use codec::Encode;
+use std::path::PathBuf;
+
+fn main() {
+    let path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("metadata.scale");
+    let metadata = contracts_node_runtime::Runtime::metadata().encode();
+
+    // Only touch the file if something changed, otherwise we'd keep invalidating our own build.
+    if std::fs::read(&path).ok().as_ref() != Some(&metadata) {
+        std::fs::write(&path, metadata).expect("failed to write metadata.scale");
+    }
+
+    // Changes to the runtime rebuild this script anyway, so there's nothing else to watch.
+    println!("cargo:rerun-if-changed=build.rs");
+}
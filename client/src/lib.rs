@@ -0,0 +1,201 @@
+//! A client for our demo contract, built on [`subxt`].
+//!
+//! Unlike the `e2e-tests` helpers, which build extrinsics out of the runtime crate directly, this
+//! only knows about the node through its metadata. That's what a frontend or a script talking to
+//! a live chain would do, so it's what we use to drive the contract from outside of the node.
+//!
+//! The types in [`node_runtime`] are generated from `metadata.scale`, which `build.rs` writes
+//! from the runtime in this workspace.
+
+use codec::Encode;
+use futures::{
+    Stream,
+    StreamExt,
+};
+use subxt::{
+    sp_core::{
+        crypto::AccountId32,
+        hashing::blake2_256,
+        sr25519,
+        H256,
+    },
+    ClientBuilder,
+    DefaultConfig,
+    PairSigner,
+    PolkadotExtrinsicParams,
+};
+
+#[subxt::subxt(runtime_metadata_path = "metadata.scale")]
+pub mod node_runtime {}
+
+use node_runtime::contracts::events::{
+    ContractEmitted,
+    Instantiated,
+};
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub type RuntimeApi =
+    node_runtime::RuntimeApi<DefaultConfig, PolkadotExtrinsicParams<DefaultConfig>>;
+
+/// Where a node started with `--dev` listens for websocket connections.
+pub const DEFAULT_URL: &str = "ws://127.0.0.1:9944";
+
+/// More than enough gas for any of our messages.
+pub const GAS_LIMIT: u64 = 100_000_000_000;
+
+/// The selector ink! gives a constructor or message called `label`, unless it's told to use a
+/// different one.
+pub fn selector(label: &str) -> [u8; 4] {
+    let hash = blake2_256(label.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// An event emitted by our contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractEvent {
+    /// The block the event was emitted in.
+    pub block: H256,
+    /// The event, still SCALE encoded. ink! encodes the index of the event within the contract
+    /// first, followed by its fields.
+    pub data: Vec<u8>,
+}
+
+/// A connection to a node which signs everything it submits with the same key.
+pub struct DemoClient {
+    api: RuntimeApi,
+    signer: PairSigner<DefaultConfig, sr25519::Pair>,
+}
+
+impl DemoClient {
+    /// Connects to the node at `url`, submitting extrinsics on behalf of `signer`.
+    pub async fn connect(url: &str, signer: sr25519::Pair) -> Result<Self> {
+        let api = ClientBuilder::new()
+            .set_url(url)
+            .build()
+            .await?
+            .to_runtime_api::<RuntimeApi>();
+
+        Ok(Self {
+            api,
+            signer: PairSigner::new(signer),
+        })
+    }
+
+    /// The `subxt` API we're using, for anything this doesn't wrap.
+    pub fn api(&self) -> &RuntimeApi {
+        &self.api
+    }
+
+    /// The number of the best block.
+    pub async fn block_number(&self) -> Result<u32> {
+        let header = self
+            .api
+            .client
+            .rpc()
+            .header(None)
+            .await?
+            .ok_or("the node has no best block")?;
+        Ok(header.number)
+    }
+
+    /// Uploads `code` and instantiates it using its `new` constructor, returning the address of
+    /// the new contract.
+    pub async fn deploy(&self, code: Vec<u8>) -> Result<AccountId32> {
+        let events = self
+            .api
+            .tx()
+            .contracts()
+            .instantiate_with_code(0, GAS_LIMIT, None, code, selector("new").to_vec(), Vec::new())?
+            .sign_and_submit_then_watch_default(&self.signer)
+            .await?
+            .wait_for_in_block()
+            .await?
+            .wait_for_success()
+            .await?;
+
+        let instantiated = events
+            .find_first::<Instantiated>()?
+            .ok_or("contract wasn't instantiated")?;
+        Ok(instantiated.contract)
+    }
+
+    /// Calls `message` on `contract` with the given arguments, returning the hash of the block
+    /// the call was included in.
+    ///
+    /// Our node doesn't finalize blocks unless it's asked to, so we don't wait for that.
+    pub async fn call(
+        &self,
+        contract: &AccountId32,
+        message: &str,
+        args: impl Encode,
+    ) -> Result<H256> {
+        let mut data = selector(message).to_vec();
+        args.encode_to(&mut data);
+
+        let events = self
+            .api
+            .tx()
+            .contracts()
+            .call(contract.clone().into(), 0, GAS_LIMIT, None, data)?
+            .sign_and_submit_then_watch_default(&self.signer)
+            .await?
+            .wait_for_in_block()
+            .await?
+            .wait_for_success()
+            .await?;
+        Ok(events.block_hash())
+    }
+
+    /// Has `contract` write `value` to `pallet_template` through the chain extension.
+    pub async fn write_to_storage(&self, contract: &AccountId32, value: u32) -> Result<H256> {
+        self.call(contract, "write_to_storage", value).await
+    }
+
+    /// Has `contract` schedule a call to its `scheduler_handler` at block `at`, which may use up
+    /// to `gas_limit`.
+    pub async fn schedule_call(
+        &self,
+        contract: &AccountId32,
+        at: u32,
+        gas_limit: u64,
+    ) -> Result<H256> {
+        self.call(contract, "schedule_call", (at, gas_limit, None::<u128>))
+            .await
+    }
+
+    /// What `pallet_template` stored as of block `at`, or the best block if that's `None`.
+    pub async fn stored_value(&self, at: Option<H256>) -> Result<Option<u32>> {
+        Ok(self.api.storage().template().something(at).await?)
+    }
+
+    /// Subscribes to the events `contract` emits in the blocks which get imported from now on.
+    pub async fn contract_events(
+        &self,
+        contract: AccountId32,
+    ) -> Result<impl Stream<Item = Result<ContractEvent>> + '_> {
+        let events = self
+            .api
+            .events()
+            .subscribe()
+            .await?
+            .filter_events::<(ContractEmitted,)>();
+
+        Ok(events.filter_map(move |event| {
+            let contract = contract.clone();
+            async move {
+                match event {
+                    Ok(details) if details.event.contract == contract => {
+                        Some(Ok(ContractEvent {
+                            block: details.block_hash,
+                            data: details.event.data,
+                        }))
+                    }
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e.into())),
+                }
+            }
+        }))
+    }
+}
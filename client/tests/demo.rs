@@ -0,0 +1,86 @@
+//! Drives the demo contract from outside of the node, the same way a frontend would.
+//!
+//! Like the `e2e-tests` these need a node binary and a built contract, so they're ignored by
+//! default. Run them with `cargo test -p demo-client -- --ignored`.
+
+use std::time::Duration;
+
+use codec::Encode;
+use demo_client::{
+    ContractEvent,
+    DemoClient,
+    Result,
+    GAS_LIMIT,
+};
+use e2e_tests::{
+    ContractBundle,
+    Node,
+};
+use futures::{
+    Stream,
+    StreamExt,
+};
+use subxt::sp_core::{
+    sr25519,
+    Pair,
+};
+
+/// The argument our runtime passes along to `scheduler_handler`.
+const MAGIC_NUMBER: u32 = 15663040;
+
+fn alice() -> sr25519::Pair {
+    sr25519::Pair::from_string("//Alice", None).expect("//Alice is a valid seed")
+}
+
+/// Spawns a node and connects to it as Alice.
+async fn connect(ws_port: u16) -> Result<(Node, DemoClient)> {
+    let node = Node::spawn(ws_port).await?;
+    let client = DemoClient::connect(&format!("ws://127.0.0.1:{}", ws_port), alice()).await?;
+    Ok((node, client))
+}
+
+async fn next_event(
+    events: &mut (impl Stream<Item = Result<ContractEvent>> + Unpin),
+) -> Result<ContractEvent> {
+    tokio::time::timeout(Duration::from_secs(30), events.next())
+        .await
+        .map_err(|_| "timed out waiting for a contract event")?
+        .ok_or("the event subscription ended")?
+}
+
+#[tokio::test]
+#[ignore = "requires a contracts node and a built contract"]
+async fn write_to_storage_round_trips() -> Result<()> {
+    let (_node, client) = connect(9960).await?;
+    let contract = client.deploy(ContractBundle::chain_extension()?.wasm).await?;
+    let events = client.contract_events(contract.clone()).await?;
+    futures::pin_mut!(events);
+
+    let block = client.write_to_storage(&contract, 42).await?;
+    assert_eq!(client.stored_value(Some(block)).await?, Some(42));
+
+    // `ValueWritten` is the first event of our contract.
+    let event = next_event(&mut events).await?;
+    assert_eq!(event.block, block);
+    assert_eq!(event.data, (0u8, alice().public(), 42u32).encode());
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires a contracts node and a built contract"]
+async fn scheduled_calls_come_back_to_the_contract() -> Result<()> {
+    let (node, client) = connect(9961).await?;
+    let contract = client.deploy(ContractBundle::chain_extension()?.wasm).await?;
+    let events = client.contract_events(contract.clone()).await?;
+    futures::pin_mut!(events);
+
+    let at = client.block_number().await? + 3;
+    client.schedule_call(&contract, at, GAS_LIMIT / 10).await?;
+    node.advance_to(at).await?;
+
+    // `SchedulerTriggered` is the third event of our contract, and it runs on behalf of the
+    // contract itself rather than whoever scheduled the call.
+    let event = next_event(&mut events).await?;
+    assert_eq!(event.data, (2u8, at, contract, MAGIC_NUMBER).encode());
+    Ok(())
+}
@@ -1,26 +1,115 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+//! Every message which is backed by our chain extension carries an annotation line in its docs for
+//! each extension function it calls, of the form:
+//!
+//! ```text
+//! @chain-extension func_id=<u32> pallets=<Pallet,...> status_codes=<u32,...>
+//! ```
+//!
+//! Since ink! copies message docs verbatim into the contract metadata, tooling can pick these lines
+//! out of `metadata.json` and verify that a target chain supports everything the contract needs
+//! before it gets deployed.
+//!
+//! The status codes are the ones `chain_extension_primitives::manifest` lists for the function,
+//! and `manifest::annotation_mismatches()` makes sure they stay that way.
+
 use ink_lang as ink;
 
-/// When we have a custom type we need to make sure that it can be encoded and decoded.
-#[derive(scale::Encode, scale::Decode)]
-pub struct Custom {
-    /// We want to demonstrate how to read dynamically sized types from a chain extension, so we'll
-    /// use a type, `Vec`, whose size we cannot necessarily calculate at compile time.
-    inner: ink_prelude::vec::Vec<u8>,
-}
+/// The types and constants which need to match what the runtime expects live in a crate which is
+/// shared between the two, that way they can't get out of sync.
+pub use chain_extension_primitives::{
+    capabilities,
+    domain,
+    more_capabilities,
+    pack_func_id,
+    status,
+    type_descriptor,
+    unpack_func_id,
+    AssetId,
+    BoundedIdentityInfo,
+    CallCounts,
+    CallbackReceipt,
+    ChainConstants,
+    CollectionId,
+    ContractInfo,
+    Conviction,
+    Custom,
+    Envelope,
+    EraIndex,
+    ErrorCode,
+    FeeEstimate,
+    IdentityJudgement,
+    ItemId,
+    Measure,
+    ProposalIndex,
+    ProxyType,
+    ReservationUsage,
+    RichCustom,
+    ScheduledCallback,
+    SchemaHash,
+    SessionInfo,
+    Timepoint,
+    WeightHistogram,
+    DEFAULT_CALLBACK_SELECTOR,
+    MAIN_EXTENSION_ID,
+    MAIN_EXTENSION_VERSION,
+    MAX_BATCH_WRITE_LEN,
+    MAX_BOUNDED_CUSTOM_LEN,
+    MAX_STORE_BATCH_LEN,
+    MAX_XCM_CALL_LEN,
+    SCHEDULER_EXTENSION_ID,
+    SCHEDULER_EXTENSION_VERSION,
+    STATE_CHANGING_FUNCS,
+    UNSIMULATABLE_FUNCS,
+    VERSION_FUNCTION,
+};
+
+/// What each of the runtime's functions takes and can fail with, which the annotations on our
+/// messages are checked against.
+#[cfg(feature = "std")]
+pub use chain_extension_primitives::manifest;
+
+/// The runtime's balance type, which the `DefaultEnvironment` already matches.
+type Balance = <DefaultEnvironment as Environment>::Balance;
 
+/// The runtime's block number type, which the `DefaultEnvironment` already matches.
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Note that the `extension` IDs have to be integer literals, so we can't use the `FUNC_*` constants
+/// from `chain_extension_primitives` here. Keep them in sync by hand.
+///
+/// Each of them is [`pack_func_id`] of [`MAIN_EXTENSION_ID`] and the function's number. The
+/// extension's id is zero, so they're just small numbers.
+///
+/// The functions which change state (see [`STATE_CHANGING_FUNCS`]) all take a leading `simulate`
+/// flag. When it's `true` the runtime goes through with the call as usual, but then throws away
+/// whatever it changed, so we can find out how a call would go without committing to it. Those in
+/// [`UNSIMULATABLE_FUNCS`] take the flag as well, but fail with
+/// `ExtensionError::SimulateNotSupported` when it's `true`.
 #[ink::chain_extension]
 pub trait MyChainExtension {
     type ErrorCode = ExtensionError;
 
+    /// Asks the runtime which version of this extension it speaks.
+    ///
+    /// If it's not [`MAIN_EXTENSION_VERSION`] the runtime may well misunderstand what we send it,
+    /// so we'd better not use it at all. Runtimes from before we had versions give us back
+    /// `ExtensionError::UnknownFunction`.
+    #[ink(extension = 0)]
+    fn version() -> Result<u32, ExtensionError>;
+
     /// Our first use case is simple, we just want to write a number to the state of our underlying
     /// Substrate chain.
     ///
     /// By default we have to handle a `Result` from the chain extension, but we can explicitly opt
     /// out of returning and handling a `Result` using these two attributes.
     #[ink(extension = 1, returns_result = false, handle_status = false)]
-    fn write_to_storage(value: u32);
+    fn write_to_storage(simulate: bool, value: u32);
+
+    /// Reads back the value last written with `write_to_storage()`, by any contract.
+    #[ink(extension = 39)]
+    fn read_from_storage() -> Result<Option<u32>, ExtensionError>;
 
     /// Here we want to demo what a chain extention with a custom type looks like.
     ///
@@ -30,15 +119,622 @@ pub trait MyChainExtension {
     /// Rust trait definition (e.g `Result<(), Self::ErrorCode>`), but we instead have to use the
     /// concrete type.
     #[ink(extension = 2)]
-    fn custom_type_with_result(custom: Custom) -> Result<(), ExtensionError>;
+    fn custom_type_with_result(simulate: bool, custom: Custom) -> Result<(), ExtensionError>;
+
+    /// Asks the runtime which of the functions above it actually supports.
+    ///
+    /// The answer is a bitmap built out of the flags in [`capabilities`]. Older runtimes which
+    /// don't know about this function will give us back `ExtensionError::UnknownFunction`.
+    #[ink(extension = 4)]
+    fn capabilities() -> Result<u32, ExtensionError>;
+
+    /// Like `capabilities()`, except that the answer is built out of the flags in
+    /// [`more_capabilities`].
+    #[ink(extension = 43)]
+    fn more_capabilities() -> Result<u32, ExtensionError>;
+
+    /// Writes a raw key/value pair into the runtime's `RawStorage`.
+    ///
+    /// Only the contract which governance designated as the "system contract" may do this,
+    /// everyone else gets back `ExtensionError::NotPermitted`.
+    #[ink(extension = 6)]
+    fn write_raw(
+        simulate: bool,
+        key: ink_prelude::vec::Vec<u8>,
+        value: ink_prelude::vec::Vec<u8>,
+    ) -> Result<(), ExtensionError>;
+
+    /// The same as `custom_type_with_result()`, except that we also send along the hash of the
+    /// `Custom` schema we were compiled with.
+    ///
+    /// If the runtime expects a different schema we get back `ExtensionError::SchemaMismatch`
+    /// instead of having it decode our payload into who knows what.
+    #[ink(extension = 7)]
+    fn checked_custom_type(
+        simulate: bool,
+        schema: SchemaHash,
+        custom: Custom,
+    ) -> Result<(), ExtensionError>;
+
+    /// Hands a batch of `(key, value)` pairs over to the runtime, which keeps them on our behalf.
+    ///
+    /// The runtime rejects batches longer than [`MAX_STORE_BATCH_LEN`].
+    #[ink(extension = 8)]
+    fn store_batch(
+        simulate: bool,
+        values: ink_prelude::vec::Vec<(u32, u32)>,
+    ) -> Result<(), ExtensionError>;
+
+    /// Reads back one of the values we previously handed over with `store_batch()`.
+    #[ink(extension = 9)]
+    fn load_value(key: u32) -> Result<Option<u32>, ExtensionError>;
+
+    /// Reads a value out of the runtime's `RawStorage`, which is where the system contract keeps
+    /// its data.
+    ///
+    /// This is the runtime handing a dynamically sized value back to us. We don't need to do
+    /// anything special for that on our end, ink! decodes whatever ends up in the output buffer.
+    #[ink(extension = 10)]
+    fn read_raw(
+        key: ink_prelude::vec::Vec<u8>,
+    ) -> Result<Option<ink_prelude::vec::Vec<u8>>, ExtensionError>;
+
+    /// The blob governance last set in the runtime, however long it is.
+    ///
+    /// The runtime charges us for every byte it hands back. Keep in mind that ink! only has a
+    /// 16 KiB buffer to receive it in, which is why the runtime doesn't let blobs get anywhere
+    /// near that.
+    #[ink(extension = 37)]
+    fn blob() -> Result<ink_prelude::vec::Vec<u8>, ExtensionError>;
+
+    /// How often `func_id` has been called so far, across all contracts. Only functions the
+    /// runtime knows about get counted.
+    #[ink(extension = 38)]
+    fn call_counts(func_id: u32) -> Result<CallCounts, ExtensionError>;
+
+    /// How the chain we're deployed on is set up: its existential deposit, address format, token
+    /// decimals and how much weight its Scheduler has to go around.
+    #[ink(extension = 40)]
+    fn chain_constants() -> Result<ChainConstants<Balance>, ExtensionError>;
+
+    /// How much of its balance `account` is free to spend. Unlike `self.env().balance()` this
+    /// works for any account, not just our own.
+    #[ink(extension = 41)]
+    fn free_balance(account: ink_env::AccountId) -> Result<Balance, ExtensionError>;
+
+    /// How many transactions `account` has signed so far.
+    #[ink(extension = 42)]
+    fn account_nonce(account: ink_env::AccountId) -> Result<u32, ExtensionError>;
+
+    /// The code hash and storage deposit of the contract at `account`, or `None` if there's no
+    /// contract there.
+    #[ink(extension = 44)]
+    fn contract_info(
+        account: ink_env::AccountId,
+    ) -> Result<Option<ContractInfo<ink_env::Hash, Balance>>, ExtensionError>;
+
+    /// Calls the contract at `dest` with `input`, sending along `value` out of our own balance,
+    /// and hands back the callee's return flags and output.
+    ///
+    /// The callee gets at most `gas_limit` of the gas we have left, or all of it if that's zero.
+    /// Copying its output back to us is paid for from whatever it leaves over. A callee which
+    /// reverts doesn't count as a failure, check its flags for that.
+    ///
+    /// The runtime can't simulate this, see [`UNSIMULATABLE_FUNCS`].
+    #[ink(extension = 45)]
+    fn call_contract(
+        simulate: bool,
+        dest: ink_env::AccountId,
+        value: Balance,
+        gas_limit: u64,
+        input: ink_prelude::vec::Vec<u8>,
+    ) -> Result<(u32, ink_prelude::vec::Vec<u8>), ExtensionError>;
+
+    /// Instantiates a new contract from the code with `code_hash`, handing `input` to its
+    /// constructor and sending along `value` out of our own balance. The new contract's address
+    /// is derived from ours, `code_hash` and `salt`, and is what we get back.
+    ///
+    /// The constructor's gas works just like the callee's does for `call_contract()`. Neither can
+    /// be simulated.
+    #[ink(extension = 46)]
+    fn instantiate_contract(
+        simulate: bool,
+        code_hash: ink_env::Hash,
+        value: Balance,
+        gas_limit: u64,
+        input: ink_prelude::vec::Vec<u8>,
+        salt: ink_prelude::vec::Vec<u8>,
+    ) -> Result<ink_env::AccountId, ExtensionError>;
+
+    /// Dispatches `call`, a SCALE encoded call of the runtime, on behalf of whoever called our
+    /// contract. The runtime decides which calls it lets through.
+    ///
+    /// A call which doesn't decode, most likely because it was encoded for another version of
+    /// the runtime, gets us `ExtensionError::UndecodableCall`.
+    #[ink(extension = 47)]
+    fn dispatch(simulate: bool, call: ink_prelude::vec::Vec<u8>) -> Result<(), ExtensionError>;
+
+    /// Like calling `write_for_caller()` once for every value in `values`, except that either all
+    /// of the writes go through or none of them do.
+    ///
+    /// The runtime rejects batches longer than [`MAX_BATCH_WRITE_LEN`].
+    #[ink(extension = 48)]
+    fn batch_write(
+        simulate: bool,
+        values: ink_prelude::vec::Vec<u32>,
+    ) -> Result<(), ExtensionError>;
+
+    /// Approves `call`, a SCALE encoded call of the runtime, as one of the signatories of a
+    /// multisig. Whoever gives the last approval this way has the multisig account dispatch it.
+    ///
+    /// `other_signatories` have to be sorted, and `timepoint` is that of the first approval,
+    /// unless this is it.
+    #[ink(extension = 49)]
+    fn as_multi(
+        simulate: bool,
+        threshold: u16,
+        other_signatories: ink_prelude::vec::Vec<ink_env::AccountId>,
+        timepoint: Option<Timepoint<BlockNumber>>,
+        call: ink_prelude::vec::Vec<u8>,
+        max_weight: u64,
+    ) -> Result<(), ExtensionError>;
+
+    /// Like `as_multi()`, except that it only takes the hash of the call and never dispatches it.
+    #[ink(extension = 50)]
+    fn approve_as_multi(
+        simulate: bool,
+        threshold: u16,
+        other_signatories: ink_prelude::vec::Vec<ink_env::AccountId>,
+        timepoint: Option<Timepoint<BlockNumber>>,
+        call_hash: [u8; 32],
+        max_weight: u64,
+    ) -> Result<(), ExtensionError>;
+
+    /// Makes `delegate` a proxy of ours, which may make the calls `proxy_type` allows on our
+    /// behalf. The deposit for it comes out of our balance.
+    #[ink(extension = 51)]
+    fn add_proxy(
+        simulate: bool,
+        delegate: ink_env::AccountId,
+        proxy_type: ProxyType,
+        delay: BlockNumber,
+    ) -> Result<(), ExtensionError>;
+
+    /// Undoes `add_proxy()`, returning the deposit.
+    #[ink(extension = 52)]
+    fn remove_proxy(
+        simulate: bool,
+        delegate: ink_env::AccountId,
+        proxy_type: ProxyType,
+        delay: BlockNumber,
+    ) -> Result<(), ExtensionError>;
+
+    /// Dispatches `call`, a SCALE encoded call of the runtime, on behalf of `real`, who made us
+    /// their proxy. Only calls we could also `dispatch()` go.
+    ///
+    /// The runtime doesn't tell us how the call itself went, only whether we may act for `real`.
+    #[ink(extension = 53)]
+    fn proxy(
+        simulate: bool,
+        real: ink_env::AccountId,
+        call: ink_prelude::vec::Vec<u8>,
+    ) -> Result<(), ExtensionError>;
+
+    /// The display name `account` set with `pallet_identity`, and what the registrars make of
+    /// it. `None` if `account` hasn't set an identity.
+    #[ink(extension = 54)]
+    fn identity_of(
+        account: ink_env::AccountId,
+    ) -> Result<Option<BoundedIdentityInfo>, ExtensionError>;
+
+    /// Votes for (`aye`) or against referendum `ref_index` of `pallet_democracy` with `balance` of
+    /// our own funds, which stay locked up for as long as `conviction` says.
+    #[ink(extension = 55)]
+    fn vote(
+        simulate: bool,
+        ref_index: u32,
+        aye: bool,
+        conviction: Conviction,
+        balance: Balance,
+    ) -> Result<(), ExtensionError>;
+
+    /// The era `pallet_staking` last planned, or `None` before its first election.
+    #[ink(extension = 56)]
+    fn current_era() -> Result<Option<EraIndex>, ExtensionError>;
+
+    /// Everything backing `validator` in the active era, its own bond included. Zero if it isn't
+    /// one of the active validators.
+    #[ink(extension = 57)]
+    fn validator_stake(validator: ink_env::AccountId) -> Result<Balance, ExtensionError>;
+
+    /// Whether `stash` bonded funds with `pallet_staking`. Controllers don't count.
+    #[ink(extension = 58)]
+    fn is_bonded(stash: ink_env::AccountId) -> Result<bool, ExtensionError>;
+
+    /// Asks `pallet_treasury` to pay `beneficiary` `value`, putting up the bond for it out of our
+    /// own funds. Hands back the index of the proposal.
+    #[ink(extension = 59)]
+    fn propose_spend(
+        simulate: bool,
+        value: Balance,
+        beneficiary: ink_env::AccountId,
+    ) -> Result<ProposalIndex, ExtensionError>;
+
+    /// Proposes a motion to the Council of `pallet_collective` which approves treasury proposal
+    /// `proposal_id` once `threshold` members voted for it. Hands back the hash the motion goes
+    /// by.
+    #[ink(extension = 60)]
+    fn propose_approval(
+        simulate: bool,
+        threshold: u32,
+        proposal_id: ProposalIndex,
+    ) -> Result<ink_env::Hash, ExtensionError>;
+
+    /// Whether `account` is one of the members `pallet_membership` keeps for the Council.
+    #[ink(extension = 61)]
+    fn is_member(account: ink_env::AccountId) -> Result<bool, ExtensionError>;
+
+    /// Sends a message to the chain our runtime has us talk to, which dispatches `call` there as
+    /// us with up to `require_weight_at_most`. `call` has to be encoded for that chain, and the
+    /// runtime rejects any longer than [`MAX_XCM_CALL_LEN`].
+    #[ink(extension = 62)]
+    fn xcm_transact(
+        simulate: bool,
+        call: ink_prelude::vec::Vec<u8>,
+        require_weight_at_most: u64,
+    ) -> Result<(), ExtensionError>;
+
+    /// What `pallet_transaction_payment` would charge for `call`, a call of our runtime encoded
+    /// by whoever gave it to us. This leaves out the extrinsic the call would be wrapped in.
+    #[ink(extension = 63)]
+    fn estimate_fee(
+        call: ink_prelude::vec::Vec<u8>,
+    ) -> Result<FeeEstimate<Balance>, ExtensionError>;
+
+    /// What `ref_time` worth of weight costs in fees, leaving out the fee multiplier. Our runtime
+    /// doesn't charge for `proof_size` yet.
+    #[ink(extension = 64)]
+    fn weight_to_fee(ref_time: u64, proof_size: u64) -> Result<Balance, ExtensionError>;
+
+    /// The hash of block `number`, as long as it's recent enough for the runtime to still know
+    /// it. The current block doesn't have a hash yet.
+    #[ink(extension = 65)]
+    fn block_hash(number: BlockNumber) -> Result<Option<ink_env::Hash>, ExtensionError>;
+
+    /// Who authored the current block, if the runtime can tell.
+    #[ink(extension = 66)]
+    fn block_author() -> Result<Option<ink_env::AccountId>, ExtensionError>;
+
+    /// Which session the chain is in, how long sessions last and how they add up to eras.
+    #[ink(extension = 67)]
+    fn session_info() -> Result<SessionInfo<BlockNumber>, ExtensionError>;
+
+    /// Like `write_to_storage()`, except that every account calling our contract gets a slot of
+    /// its own.
+    #[ink(extension = 11)]
+    fn write_for_caller(simulate: bool, value: u32) -> Result<(), ExtensionError>;
+
+    /// Reads back the value `account` stored with `write_for_caller()`.
+    #[ink(extension = 12)]
+    fn read_for(account: ink_env::AccountId) -> Result<Option<u32>, ExtensionError>;
+
+    /// Transfers `amount` of the chain's native token from our contract to `to`.
+    ///
+    /// ink! can already do this with `self.env().transfer()`, but going through the extension
+    /// shows how value can be moved by runtime code on behalf of a contract.
+    #[ink(extension = 13)]
+    fn transfer_native(
+        simulate: bool,
+        to: ink_env::AccountId,
+        amount: Balance,
+    ) -> Result<(), ExtensionError>;
+
+    /// The same as `transfer_native()`, except that `amount` is sent in its compact encoding.
+    ///
+    /// That's how pallets take balances in their dispatchables too, since most amounts are small
+    /// and fit into a few bytes instead of the full 16.
+    #[ink(extension = 36)]
+    fn transfer_native_compact(
+        simulate: bool,
+        to: ink_env::AccountId,
+        amount: scale::Compact<Balance>,
+    ) -> Result<(), ExtensionError>;
+
+    /// The most gas a call to the extension function `func_id` with `input_len` bytes of input
+    /// can cost.
+    ///
+    /// Unknown functions get us `ExtensionError::UnknownFunction`.
+    #[ink(extension = 14)]
+    fn quote(func_id: u32, input_len: u32) -> Result<u64, ExtensionError>;
+
+    /// Hands an encrypted blob over to the runtime, which holds on to it until `expires_at`.
+    ///
+    /// Returns the id the envelope can be retrieved with.
+    #[ink(extension = 15)]
+    fn store_envelope(
+        simulate: bool,
+        public_key: [u8; 32],
+        expires_at: BlockNumber,
+        ciphertext: ink_prelude::vec::Vec<u8>,
+    ) -> Result<u64, ExtensionError>;
+
+    /// Retrieves an envelope stored by any contract, as long as it hasn't expired yet.
+    #[ink(extension = 16)]
+    fn envelope(
+        id: u64,
+    ) -> Result<Option<Envelope<ink_env::AccountId, BlockNumber>>, ExtensionError>;
+
+    /// How much of `asset` the account `who` holds.
+    #[ink(extension = 17)]
+    fn asset_balance(
+        asset: AssetId,
+        who: ink_env::AccountId,
+    ) -> Result<Balance, ExtensionError>;
+
+    /// Transfers `amount` of `asset` from our contract to `to`.
+    #[ink(extension = 18)]
+    fn asset_transfer(
+        simulate: bool,
+        asset: AssetId,
+        to: ink_env::AccountId,
+        amount: Balance,
+    ) -> Result<(), ExtensionError>;
+
+    /// Mints `amount` of `asset` into the account `to`. Only works if our contract is the issuer
+    /// of `asset`.
+    #[ink(extension = 19)]
+    fn asset_mint(
+        simulate: bool,
+        asset: AssetId,
+        to: ink_env::AccountId,
+        amount: Balance,
+    ) -> Result<(), ExtensionError>;
+
+    /// Commits to `hash`, the BLAKE2 256 hash of some preimage we'll reveal later.
+    ///
+    /// The preimage can be revealed `window` blocks long, starting `opens_in` blocks from now.
+    /// Returns the id of the commitment.
+    #[ink(extension = 20)]
+    fn commit(
+        simulate: bool,
+        hash: ink_env::Hash,
+        opens_in: u32,
+        window: u32,
+    ) -> Result<u64, ExtensionError>;
+
+    /// Reveals the preimage of one of our commitments.
+    #[ink(extension = 21)]
+    fn reveal(
+        simulate: bool,
+        id: u64,
+        preimage: ink_prelude::vec::Vec<u8>,
+    ) -> Result<(), ExtensionError>;
+
+    /// How much of `asset` there is in total.
+    #[ink(extension = 22)]
+    fn asset_total_supply(asset: AssetId) -> Result<Balance, ExtensionError>;
+
+    /// Transfers `amount` of `asset` from `owner` to `to`.
+    ///
+    /// `owner` needs to have approved our contract to move at least `amount` of `asset` through
+    /// `pallet_assets` beforehand, otherwise we get `ExtensionError::AssetUnapproved`.
+    #[ink(extension = 23)]
+    fn asset_transfer_approved(
+        simulate: bool,
+        asset: AssetId,
+        owner: ink_env::AccountId,
+        to: ink_env::AccountId,
+        amount: Balance,
+    ) -> Result<(), ExtensionError>;
+
+    /// Mints `item` of `collection` into the account `owner`, with `metadata` attached to it.
+    ///
+    /// Our contract needs to be both the issuer and the owner of `collection` for this to work.
+    #[ink(extension = 24)]
+    fn nft_mint(
+        simulate: bool,
+        collection: CollectionId,
+        item: ItemId,
+        owner: ink_env::AccountId,
+        metadata: ink_prelude::vec::Vec<u8>,
+    ) -> Result<(), ExtensionError>;
+
+    /// Transfers `item` of `collection` to `to`.
+    ///
+    /// Our contract needs to own the NFT, be the admin of its collection or have been approved
+    /// to transfer it.
+    #[ink(extension = 25)]
+    fn nft_transfer(
+        simulate: bool,
+        collection: CollectionId,
+        item: ItemId,
+        to: ink_env::AccountId,
+    ) -> Result<(), ExtensionError>;
+
+    /// Who holds `item` of `collection`, if it exists.
+    #[ink(extension = 26)]
+    fn nft_owner_of(
+        collection: CollectionId,
+        item: ItemId,
+    ) -> Result<Option<ink_env::AccountId>, ExtensionError>;
+
+    /// Some randomness from the runtime, specific to `subject`.
+    ///
+    /// The same subject gets the same value for the rest of the block.
+    #[ink(extension = 27)]
+    fn random(subject: ink_prelude::vec::Vec<u8>) -> Result<ink_env::Hash, ExtensionError>;
+
+    /// Creates a counter called `name` which is shared with other contracts, with our contract as
+    /// its admin.
+    #[ink(extension = 28)]
+    fn counter_create(
+        simulate: bool,
+        name: ink_prelude::vec::Vec<u8>,
+    ) -> Result<(), ExtensionError>;
+
+    /// Increments the counter called `name`, returning its new value.
+    ///
+    /// Our contract needs to be the counter's admin or one of its members.
+    #[ink(extension = 29)]
+    fn counter_increment(
+        simulate: bool,
+        name: ink_prelude::vec::Vec<u8>,
+    ) -> Result<u64, ExtensionError>;
+
+    /// The current value of the counter called `name`, if there is one.
+    #[ink(extension = 30)]
+    fn counter_value(name: ink_prelude::vec::Vec<u8>) -> Result<Option<u64>, ExtensionError>;
+
+    /// Lets `who` increment the counter called `name` if `allowed` is set, and stops letting it
+    /// otherwise.
+    ///
+    /// Our contract needs to be the counter's admin.
+    #[ink(extension = 31)]
+    fn counter_set_access(
+        simulate: bool,
+        name: ink_prelude::vec::Vec<u8>,
+        who: ink_env::AccountId,
+        allowed: bool,
+    ) -> Result<(), ExtensionError>;
+
+    /// Checks that `signature` is an sr25519 signature of `message` by `public`.
+    #[ink(extension = 32)]
+    fn verify_sr25519(
+        signature: [u8; 64],
+        message: ink_prelude::vec::Vec<u8>,
+        public: [u8; 32],
+    ) -> Result<(), ExtensionError>;
+
+    /// Checks that `signature` is an ecdsa signature of the BLAKE2-256 hash of `message` by
+    /// `public`, which is how Substrate's own ecdsa keys sign things.
+    #[ink(extension = 33)]
+    fn verify_ecdsa(
+        signature: [u8; 65],
+        message: ink_prelude::vec::Vec<u8>,
+        public: [u8; 33],
+    ) -> Result<(), ExtensionError>;
+
+    /// The same as `custom_type_with_result()`, except that `custom` may be at most
+    /// [`MAX_BOUNDED_CUSTOM_LEN`] bytes long.
+    ///
+    /// That bound lets the runtime charge for the call before looking at it, but there's no status
+    /// code for going over it: longer values don't decode, which traps the contract.
+    #[ink(extension = 34)]
+    fn bounded_custom_type(simulate: bool, custom: Custom) -> Result<(), ExtensionError>;
 
-    /// For our final trick we will demonstrate bi-directional communication using chain
-    /// extensions.
+    /// Stores whatever `custom.measure` makes of `custom.payload`.
+    ///
+    /// If `custom.only_for` is set and isn't the account calling us, we get back
+    /// `ExtensionError::NotPermitted` instead. Measures which don't fit the payload give back
+    /// `ExtensionError::CustomCallFailed`.
+    #[ink(extension = 35)]
+    fn rich_custom_type(
+        simulate: bool,
+        custom: RichCustom<ink_env::AccountId>,
+    ) -> Result<(), ExtensionError>;
+}
+
+/// Everything to do with scheduling callbacks, which lives in an extension of its own.
+///
+/// It doesn't have much to do with the rest of [`MyChainExtension`], so keeping it separate lets
+/// contracts (and runtimes) which don't care about one of them ignore it. The upper 16 bits of each
+/// `extension` ID are what tell the runtime which extension a call is meant for, here that's
+/// [`SCHEDULER_EXTENSION_ID`].
+#[ink::chain_extension]
+pub trait SchedulerExtension {
+    type ErrorCode = ExtensionError;
+
+    /// Asks the runtime which version of this extension it speaks, see
+    /// [`MyChainExtension::version`].
+    #[ink(extension = 0x0001_0000)]
+    fn version() -> Result<u32, ExtensionError>;
+
+    /// Here we will demonstrate bi-directional communication using chain extensions.
     ///
     /// This means that we will use a chain extension to call the Scheduler pallet in order to
     /// schedule a call which triggers an `#[ink(message)]` at some future point in time.
-    #[ink(extension = 3)]
-    fn schedule_call(at: u32) -> Result<(), ExtensionError>;
+    ///
+    /// The runtime calls the message with the given `selector`, which needs to be either
+    /// [`DEFAULT_CALLBACK_SELECTOR`] or one governance has allowed.
+    ///
+    /// The callback gets to use up to `gas_limit`, which the runtime caps at its
+    /// `MaxCallbackGasLimit`. Any storage deposit it needs is charged to whoever called us, up to
+    /// `storage_deposit_limit` if one is given.
+    ///
+    /// Whatever value we were sent along with the current message is held on to by the runtime and
+    /// sent back to us with the callback, so the message it invokes needs to be payable.
+    ///
+    /// If block `at` is already full the call may end up being scheduled a few blocks later. The
+    /// runtime hands back where in the Scheduler's agenda the call ended up, which `reschedule()`
+    /// takes, along with a handle which `callback_receipt()` takes once the callback has run.
+    ///
+    /// Calls which are too long to go into the Scheduler's agenda are noted with the Preimage
+    /// pallet instead, in which case we also get back the hash they're noted under. Whoever called
+    /// us puts down a deposit for the preimage until the callback runs.
+    #[ink(extension = 0x0001_0001)]
+    fn schedule_call(
+        simulate: bool,
+        at: u32,
+        selector: [u8; 4],
+        gas_limit: u64,
+        storage_deposit_limit: Option<Balance>,
+    ) -> Result<ScheduledCallback<BlockNumber, ink_env::Hash>, ExtensionError>;
+
+    /// Fetches the weight our past scheduled callbacks actually ended up using.
+    #[ink(extension = 0x0001_0002)]
+    fn callback_weights() -> Result<WeightHistogram, ExtensionError>;
+
+    /// Fetches how much of the current block's weight is reserved for scheduled callbacks, and
+    /// how much of that they've already used.
+    #[ink(extension = 0x0001_0003)]
+    fn callback_reservation() -> Result<ReservationUsage, ExtensionError>;
+
+    /// Fetches the receipt of the scheduled callback with the given handle, or `None` if it
+    /// hasn't run yet or ran so long ago that the runtime has forgotten about it.
+    #[ink(extension = 0x0001_0004)]
+    fn callback_receipt(
+        handle: u64,
+    ) -> Result<Option<CallbackReceipt<BlockNumber, ink_env::Hash>>, ExtensionError>;
+
+    /// Fetches the deposit whoever calls `schedule_call()` has to put down. They get it back once
+    /// the callback has run.
+    #[ink(extension = 0x0001_0005)]
+    fn callback_deposit() -> Result<Balance, ExtensionError>;
+
+    /// Moves the callback at `index` of the Scheduler's agenda for block `when` to block
+    /// `new_when`, and hands back where it ended up.
+    ///
+    /// Just like with `schedule_call()` the callback may end up a few blocks later if `new_when`
+    /// is full. It keeps its handle, along with whatever value and deposit it was scheduled with.
+    /// We only get to move our own callbacks.
+    #[ink(extension = 0x0001_0006)]
+    fn reschedule(
+        simulate: bool,
+        when: u32,
+        index: u32,
+        new_when: u32,
+    ) -> Result<(u32, u32), ExtensionError>;
+
+    /// Like `reschedule()`, except that it finds the callback by the handle `schedule_call()`
+    /// gave us for it.
+    #[ink(extension = 0x0001_0007)]
+    fn reschedule_callback(
+        simulate: bool,
+        handle: u64,
+        new_when: u32,
+    ) -> Result<(u32, u32), ExtensionError>;
+
+    /// Like `schedule_call()`, except that the call is scheduled `blocks` blocks from now rather
+    /// than for a given block. The runtime turns away delays which don't put the call into a
+    /// future block.
+    #[ink(extension = 0x0001_0008)]
+    fn schedule_in(
+        simulate: bool,
+        blocks: u32,
+        selector: [u8; 4],
+        gas_limit: u64,
+        storage_deposit_limit: Option<Balance>,
+    ) -> Result<ScheduledCallback<BlockNumber, ink_env::Hash>, ExtensionError>;
 }
 
 #[derive(Debug, scale::Encode, scale::Decode)]
@@ -46,24 +742,414 @@ pub trait MyChainExtension {
 pub enum ExtensionError {
     CustomCallFailed,
     EncodingFailed,
+    /// The runtime didn't recognize the `func_id` we called it with. This usually means that the
+    /// contract was deployed to a chain which doesn't have (this version of) our extension.
+    UnknownFunction,
+    /// A previous call to `check_runtime()` found that the chain doesn't support the function
+    /// we're trying to use, so we didn't even try calling it.
+    CapabilityMissing,
+    /// The runtime speaks a different version of one of our extensions than we were built
+    /// against.
+    VersionMismatch,
+    /// This contract isn't allowed to use the function it called.
+    NotPermitted,
+    /// The runtime expects a different shape of payload than the one we were compiled with.
+    SchemaMismatch,
+    /// Every block our callback could have been scheduled for is already full.
+    AgendaFull,
+    /// The runtime won't have scheduled callbacks invoke the message with this selector.
+    InvalidCallbackSelector,
+    /// The gas limit for our callback is more than the runtime lets callbacks use.
+    CallbackGasLimitTooHigh,
+    /// The runtime wouldn't act on behalf of whoever called us.
+    BadOrigin,
+    /// The block we wanted something scheduled for has already passed.
+    ScheduledInPast,
+    /// The Scheduler couldn't schedule our call for some other reason.
+    SchedulingFailed,
+    /// We've already scheduled as many calls as the runtime lets us for the time being.
+    SchedulingQuotaExceeded,
+    /// Whoever called us can't afford the deposit for scheduling a callback.
+    InsufficientDeposit,
+    /// We have no pending callback at that address, or with that handle.
+    UnknownCallback,
+    /// The number of blocks we wanted a call scheduled in doesn't get it into a future block.
+    InvalidScheduleDelay,
+    /// Governance hasn't allowed this contract to schedule calls.
+    NotAllowedToSchedule,
+    /// Governance only lets contracts deployed from code it approved call this function, and
+    /// ours isn't.
+    CodeNotApproved,
+    /// The recipient of a transfer doesn't exist yet, and we didn't send enough to cover the
+    /// existential deposit.
+    BelowExistentialDeposit,
+    /// A transfer would have left this contract with less than the existential deposit.
+    WouldReapSender,
+    /// A transfer failed for some other reason.
+    TransferFailed,
+    /// The envelope has expired, or would have expired right away.
+    EnvelopeExpired,
+    /// The ciphertext is too long for an envelope.
+    CiphertextTooLong,
+    /// The account doesn't hold enough of the asset.
+    AssetBalanceLow,
+    /// There's no asset with the given id.
+    UnknownAsset,
+    /// The asset, or the account's holding of it, is frozen.
+    AssetFrozen,
+    /// An asset operation failed for some other reason.
+    AssetOperationFailed,
+    /// There's no such commitment, or it has already been revealed.
+    UnknownCommitment,
+    /// The commitment's reveal window hasn't opened yet.
+    RevealTooEarly,
+    /// The commitment's reveal window has already closed.
+    RevealTooLate,
+    /// The preimage doesn't match what we committed to.
+    InvalidReveal,
+    /// The reveal window of a commitment needs to be at least one block long.
+    EmptyRevealWindow,
+    /// The owner of the asset hasn't approved us to move (this much of) it.
+    AssetUnapproved,
+    /// There's no such NFT, or no such collection.
+    UnknownNft,
+    /// There already is an NFT with this id.
+    NftAlreadyExists,
+    /// The NFT, or its whole collection, is frozen.
+    NftFrozen,
+    /// An NFT operation failed for some other reason, e.g. because the metadata was too long.
+    NftOperationFailed,
+    /// There's no shared counter with this name.
+    UnknownCounter,
+    /// Someone already created a shared counter with this name.
+    CounterExists,
+    /// The name is too long for a shared counter.
+    CounterNameTooLong,
+    /// The shared counter can't have any more members.
+    TooManyCounterMembers,
+    /// The signature doesn't match the message and public key.
+    InvalidSignature,
+    /// The key is too long for raw storage.
+    RawKeyTooLong,
+    /// The value is too long for raw storage.
+    RawValueTooLong,
+    /// The runtime won't store this many values in one go.
+    BatchTooLarge,
+    /// There's no contract at the account we wanted to call.
+    NotAContract,
+    /// The contract we called, or the constructor of the one we instantiated, trapped.
+    CalleeTrapped,
+    /// There's no code with the hash we wanted to instantiate a contract from.
+    UnknownCodeHash,
+    /// We already instantiated this code with this salt.
+    ContractExists,
+    /// The constructor of the contract we wanted to instantiate reverted.
+    ConstructorReverted,
+    /// The runtime call we dispatched failed.
+    DispatchFailed,
+    /// The multisig's threshold or signatories don't add up.
+    InvalidSignatories,
+    /// We already approved this multisig operation.
+    AlreadyApproved,
+    /// The multisig operation doesn't need any more approvals, it only needs to be executed.
+    NoApprovalsNeeded,
+    /// There's no multisig operation waiting on approvals for this call.
+    UnknownMultisig,
+    /// The timepoint doesn't match the one of the multisig operation's first approval.
+    WrongTimepoint,
+    /// The multisig operation's call needs more weight than we set aside for it.
+    MaxWeightTooLow,
+    /// We aren't a proxy of the account we wanted to act for.
+    NotAProxy,
+    /// We already have as many proxies as we may have.
+    TooManyProxies,
+    /// We already have this proxy.
+    DuplicateProxy,
+    /// We don't have the proxy we wanted to remove.
+    UnknownProxy,
+    /// Whoever called us needs an identity a registrar vouches for, and doesn't have one.
+    IdentityNotVerified,
+    /// The referendum we voted on is over, or never existed.
+    ReferendumNotOngoing,
+    /// We voted with more than we have.
+    InsufficientVotingBalance,
+    /// We're already voting on as many referenda as we may.
+    TooManyVotes,
+    /// We can't afford the bond for a spending proposal.
+    InsufficientProposalBond,
+    /// We aren't a member of the Council.
+    NotACouncilMember,
+    /// The Council is already voting on this very motion.
+    DuplicateMotion,
+    /// The Council is already voting on as many motions as it may.
+    TooManyMotions,
+    /// Whoever called us needs to be a member of the Council, and isn't.
+    NotAMember,
+    /// The call we wanted to send to another chain is longer than `MAX_XCM_CALL_LEN`.
+    XcmCallTooLong,
+    /// There's no way for our message to reach the chain it's meant for.
+    XcmUnroutable,
+    /// Our message could be routed, but sending it failed anyway.
+    XcmSendFailed,
+    /// We asked the runtime to simulate a call it can't simulate, see [`UNSIMULATABLE_FUNCS`].
+    SimulateNotSupported,
+    /// The runtime couldn't decode the call we handed it to dispatch.
+    UndecodableCall,
+    /// The runtime handed back a status code we don't know about, most likely because it was
+    /// upgraded after we got deployed. We keep the code around so callers can still make sense of
+    /// it.
+    Unknown(u32),
 }
 
 impl ink_env::chain_extension::FromStatusCode for ExtensionError {
     fn from_status_code(status_code: u32) -> Result<(), Self> {
         match status_code {
-            0 => Ok(()),
-            1 => Err(Self::CustomCallFailed),
-            _ => panic!("encountered unknown status code"),
+            status::SUCCESS => Ok(()),
+            status::CUSTOM_CALL_FAILED => Err(Self::CustomCallFailed),
+            status::UNKNOWN_FUNCTION => Err(Self::UnknownFunction),
+            status::NOT_PERMITTED => Err(Self::NotPermitted),
+            status::SCHEMA_MISMATCH => Err(Self::SchemaMismatch),
+            status::AGENDA_FULL => Err(Self::AgendaFull),
+            status::INVALID_CALLBACK_SELECTOR => Err(Self::InvalidCallbackSelector),
+            status::CALLBACK_GAS_LIMIT_TOO_HIGH => Err(Self::CallbackGasLimitTooHigh),
+            status::BAD_ORIGIN => Err(Self::BadOrigin),
+            status::SCHEDULED_IN_PAST => Err(Self::ScheduledInPast),
+            status::SCHEDULING_FAILED => Err(Self::SchedulingFailed),
+            status::SCHEDULING_QUOTA_EXCEEDED => Err(Self::SchedulingQuotaExceeded),
+            status::INSUFFICIENT_DEPOSIT => Err(Self::InsufficientDeposit),
+            status::UNKNOWN_CALLBACK => Err(Self::UnknownCallback),
+            status::INVALID_SCHEDULE_DELAY => Err(Self::InvalidScheduleDelay),
+            status::NOT_ALLOWED_TO_SCHEDULE => Err(Self::NotAllowedToSchedule),
+            status::CODE_NOT_APPROVED => Err(Self::CodeNotApproved),
+            status::BELOW_EXISTENTIAL_DEPOSIT => Err(Self::BelowExistentialDeposit),
+            status::WOULD_REAP_SENDER => Err(Self::WouldReapSender),
+            status::TRANSFER_FAILED => Err(Self::TransferFailed),
+            status::ENVELOPE_EXPIRED => Err(Self::EnvelopeExpired),
+            status::CIPHERTEXT_TOO_LONG => Err(Self::CiphertextTooLong),
+            status::ASSET_BALANCE_LOW => Err(Self::AssetBalanceLow),
+            status::UNKNOWN_ASSET => Err(Self::UnknownAsset),
+            status::ASSET_FROZEN => Err(Self::AssetFrozen),
+            status::ASSET_OPERATION_FAILED => Err(Self::AssetOperationFailed),
+            status::UNKNOWN_COMMITMENT => Err(Self::UnknownCommitment),
+            status::REVEAL_TOO_EARLY => Err(Self::RevealTooEarly),
+            status::REVEAL_TOO_LATE => Err(Self::RevealTooLate),
+            status::INVALID_REVEAL => Err(Self::InvalidReveal),
+            status::EMPTY_REVEAL_WINDOW => Err(Self::EmptyRevealWindow),
+            status::ASSET_UNAPPROVED => Err(Self::AssetUnapproved),
+            status::UNKNOWN_NFT => Err(Self::UnknownNft),
+            status::NFT_ALREADY_EXISTS => Err(Self::NftAlreadyExists),
+            status::NFT_FROZEN => Err(Self::NftFrozen),
+            status::NFT_OPERATION_FAILED => Err(Self::NftOperationFailed),
+            status::UNKNOWN_COUNTER => Err(Self::UnknownCounter),
+            status::COUNTER_EXISTS => Err(Self::CounterExists),
+            status::COUNTER_NAME_TOO_LONG => Err(Self::CounterNameTooLong),
+            status::TOO_MANY_COUNTER_MEMBERS => Err(Self::TooManyCounterMembers),
+            status::INVALID_SIGNATURE => Err(Self::InvalidSignature),
+            status::RAW_KEY_TOO_LONG => Err(Self::RawKeyTooLong),
+            status::RAW_VALUE_TOO_LONG => Err(Self::RawValueTooLong),
+            status::BATCH_TOO_LARGE => Err(Self::BatchTooLarge),
+            status::NOT_A_CONTRACT => Err(Self::NotAContract),
+            status::CALLEE_TRAPPED => Err(Self::CalleeTrapped),
+            status::UNKNOWN_CODE_HASH => Err(Self::UnknownCodeHash),
+            status::CONTRACT_EXISTS => Err(Self::ContractExists),
+            status::CONSTRUCTOR_REVERTED => Err(Self::ConstructorReverted),
+            status::DISPATCH_FAILED => Err(Self::DispatchFailed),
+            status::INVALID_SIGNATORIES => Err(Self::InvalidSignatories),
+            status::ALREADY_APPROVED => Err(Self::AlreadyApproved),
+            status::NO_APPROVALS_NEEDED => Err(Self::NoApprovalsNeeded),
+            status::UNKNOWN_MULTISIG => Err(Self::UnknownMultisig),
+            status::WRONG_TIMEPOINT => Err(Self::WrongTimepoint),
+            status::MAX_WEIGHT_TOO_LOW => Err(Self::MaxWeightTooLow),
+            status::NOT_A_PROXY => Err(Self::NotAProxy),
+            status::TOO_MANY_PROXIES => Err(Self::TooManyProxies),
+            status::DUPLICATE_PROXY => Err(Self::DuplicateProxy),
+            status::UNKNOWN_PROXY => Err(Self::UnknownProxy),
+            status::REFERENDUM_NOT_ONGOING => Err(Self::ReferendumNotOngoing),
+            status::INSUFFICIENT_VOTING_BALANCE => Err(Self::InsufficientVotingBalance),
+            status::TOO_MANY_VOTES => Err(Self::TooManyVotes),
+            status::INSUFFICIENT_PROPOSAL_BOND => Err(Self::InsufficientProposalBond),
+            status::NOT_A_COUNCIL_MEMBER => Err(Self::NotACouncilMember),
+            status::DUPLICATE_MOTION => Err(Self::DuplicateMotion),
+            status::TOO_MANY_MOTIONS => Err(Self::TooManyMotions),
+            status::XCM_CALL_TOO_LONG => Err(Self::XcmCallTooLong),
+            status::XCM_UNROUTABLE => Err(Self::XcmUnroutable),
+            status::XCM_SEND_FAILED => Err(Self::XcmSendFailed),
+            status::SIMULATE_NOT_SUPPORTED => Err(Self::SimulateNotSupported),
+            status::UNDECODABLE_CALL => Err(Self::UndecodableCall),
+            code => Err(Self::Unknown(code)),
         }
     }
 }
 
+impl ExtensionError {
+    /// This error as a `(domain, code)` pair.
+    ///
+    /// Unlike the variants of this enum, which only make sense to Rust code, the pair's
+    /// [`ErrorCode::id`] is a plain number which never changes meaning. That's what we hand to
+    /// front-ends, which can then show their users a proper message for it.
+    pub fn error_code(&self) -> ErrorCode {
+        let status_code = match self {
+            Self::CustomCallFailed => status::CUSTOM_CALL_FAILED,
+            Self::UnknownFunction => status::UNKNOWN_FUNCTION,
+            Self::NotPermitted => status::NOT_PERMITTED,
+            Self::SchemaMismatch => status::SCHEMA_MISMATCH,
+            Self::AgendaFull => status::AGENDA_FULL,
+            Self::InvalidCallbackSelector => status::INVALID_CALLBACK_SELECTOR,
+            Self::CallbackGasLimitTooHigh => status::CALLBACK_GAS_LIMIT_TOO_HIGH,
+            Self::BadOrigin => status::BAD_ORIGIN,
+            Self::ScheduledInPast => status::SCHEDULED_IN_PAST,
+            Self::SchedulingFailed => status::SCHEDULING_FAILED,
+            Self::SchedulingQuotaExceeded => status::SCHEDULING_QUOTA_EXCEEDED,
+            Self::InsufficientDeposit => status::INSUFFICIENT_DEPOSIT,
+            Self::UnknownCallback => status::UNKNOWN_CALLBACK,
+            Self::InvalidScheduleDelay => status::INVALID_SCHEDULE_DELAY,
+            Self::NotAllowedToSchedule => status::NOT_ALLOWED_TO_SCHEDULE,
+            Self::CodeNotApproved => status::CODE_NOT_APPROVED,
+            Self::BelowExistentialDeposit => status::BELOW_EXISTENTIAL_DEPOSIT,
+            Self::WouldReapSender => status::WOULD_REAP_SENDER,
+            Self::TransferFailed => status::TRANSFER_FAILED,
+            Self::EnvelopeExpired => status::ENVELOPE_EXPIRED,
+            Self::CiphertextTooLong => status::CIPHERTEXT_TOO_LONG,
+            Self::AssetBalanceLow => status::ASSET_BALANCE_LOW,
+            Self::UnknownAsset => status::UNKNOWN_ASSET,
+            Self::AssetFrozen => status::ASSET_FROZEN,
+            Self::AssetOperationFailed => status::ASSET_OPERATION_FAILED,
+            Self::UnknownCommitment => status::UNKNOWN_COMMITMENT,
+            Self::RevealTooEarly => status::REVEAL_TOO_EARLY,
+            Self::RevealTooLate => status::REVEAL_TOO_LATE,
+            Self::InvalidReveal => status::INVALID_REVEAL,
+            Self::EmptyRevealWindow => status::EMPTY_REVEAL_WINDOW,
+            Self::AssetUnapproved => status::ASSET_UNAPPROVED,
+            Self::UnknownNft => status::UNKNOWN_NFT,
+            Self::NftAlreadyExists => status::NFT_ALREADY_EXISTS,
+            Self::NftFrozen => status::NFT_FROZEN,
+            Self::NftOperationFailed => status::NFT_OPERATION_FAILED,
+            Self::UnknownCounter => status::UNKNOWN_COUNTER,
+            Self::CounterExists => status::COUNTER_EXISTS,
+            Self::CounterNameTooLong => status::COUNTER_NAME_TOO_LONG,
+            Self::TooManyCounterMembers => status::TOO_MANY_COUNTER_MEMBERS,
+            Self::InvalidSignature => status::INVALID_SIGNATURE,
+            Self::RawKeyTooLong => status::RAW_KEY_TOO_LONG,
+            Self::RawValueTooLong => status::RAW_VALUE_TOO_LONG,
+            Self::BatchTooLarge => status::BATCH_TOO_LARGE,
+            Self::NotAContract => status::NOT_A_CONTRACT,
+            Self::CalleeTrapped => status::CALLEE_TRAPPED,
+            Self::UnknownCodeHash => status::UNKNOWN_CODE_HASH,
+            Self::ContractExists => status::CONTRACT_EXISTS,
+            Self::ConstructorReverted => status::CONSTRUCTOR_REVERTED,
+            Self::DispatchFailed => status::DISPATCH_FAILED,
+            Self::InvalidSignatories => status::INVALID_SIGNATORIES,
+            Self::AlreadyApproved => status::ALREADY_APPROVED,
+            Self::NoApprovalsNeeded => status::NO_APPROVALS_NEEDED,
+            Self::UnknownMultisig => status::UNKNOWN_MULTISIG,
+            Self::WrongTimepoint => status::WRONG_TIMEPOINT,
+            Self::MaxWeightTooLow => status::MAX_WEIGHT_TOO_LOW,
+            Self::NotAProxy => status::NOT_A_PROXY,
+            Self::TooManyProxies => status::TOO_MANY_PROXIES,
+            Self::DuplicateProxy => status::DUPLICATE_PROXY,
+            Self::UnknownProxy => status::UNKNOWN_PROXY,
+            Self::ReferendumNotOngoing => status::REFERENDUM_NOT_ONGOING,
+            Self::InsufficientVotingBalance => status::INSUFFICIENT_VOTING_BALANCE,
+            Self::TooManyVotes => status::TOO_MANY_VOTES,
+            Self::InsufficientProposalBond => status::INSUFFICIENT_PROPOSAL_BOND,
+            Self::NotACouncilMember => status::NOT_A_COUNCIL_MEMBER,
+            Self::DuplicateMotion => status::DUPLICATE_MOTION,
+            Self::TooManyMotions => status::TOO_MANY_MOTIONS,
+            Self::XcmCallTooLong => status::XCM_CALL_TOO_LONG,
+            Self::XcmUnroutable => status::XCM_UNROUTABLE,
+            Self::XcmSendFailed => status::XCM_SEND_FAILED,
+            Self::SimulateNotSupported => status::SIMULATE_NOT_SUPPORTED,
+            Self::UndecodableCall => status::UNDECODABLE_CALL,
+            Self::Unknown(status_code) => *status_code,
+            // These never made it to the runtime.
+            Self::EncodingFailed => return ErrorCode::new(domain::CONTRACT, 1),
+            Self::CapabilityMissing => return ErrorCode::new(domain::CONTRACT, 2),
+            Self::VersionMismatch => return ErrorCode::new(domain::CONTRACT, 3),
+            Self::IdentityNotVerified => return ErrorCode::new(domain::CONTRACT, 4),
+            Self::NotAMember => return ErrorCode::new(domain::CONTRACT, 5),
+        };
+
+        ErrorCode::from_status(status_code).unwrap_or(ErrorCode::new(domain::UNKNOWN, 0))
+    }
+}
+
 impl From<scale::Error> for ExtensionError {
     fn from(_: scale::Error) -> Self {
         Self::EncodingFailed
     }
 }
 
+/// What our messages which emit `ExtensionFailed` return in place of a `Result`.
+///
+/// ink! reverts any message which returns an `Err`, and the events the message emitted get
+/// reverted along with everything else, so `ExtensionFailed` would never make it out of a message
+/// which returns a `Result`. This encodes just like a `Result`, so callers can decode it as one,
+/// but ink! can't tell that it's an error and lets the message go through. Whatever the message
+/// did before it failed goes through as well, which is why ours fail before they change anything.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Reported<T, E> {
+    /// The message went through.
+    Ok(T),
+    /// The message failed, and if the chain extension is to blame it emitted `ExtensionFailed`.
+    Err(E),
+}
+
+impl<T, E> Reported<T, E> {
+    /// The `Result` this stands in for.
+    pub fn into_result(self) -> Result<T, E> {
+        self.into()
+    }
+
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok(_))
+    }
+
+    pub fn is_err(&self) -> bool {
+        !self.is_ok()
+    }
+
+    pub fn ok(self) -> Option<T> {
+        self.into_result().ok()
+    }
+}
+
+impl<T, E> From<Result<T, E>> for Reported<T, E> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Self::Ok(value),
+            Err(e) => Self::Err(e),
+        }
+    }
+}
+
+impl<T, E> From<Reported<T, E>> for Result<T, E> {
+    fn from(reported: Reported<T, E>) -> Self {
+        match reported {
+            Reported::Ok(value) => Ok(value),
+            Reported::Err(e) => Err(e),
+        }
+    }
+}
+
+/// Runs the body of a message which returns a [`Reported`], so that it gets to use `?`.
+fn reported<T, E>(message: impl FnOnce() -> Result<T, E>) -> Reported<T, E> {
+    message().into()
+}
+
+/// Arguments which have already been SCALE encoded and are passed on to the runtime as they are.
+struct RawArgs(ink_prelude::vec::Vec<u8>);
+
+impl scale::Encode for RawArgs {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(&self.0)
+    }
+}
+
 /// The `Environment` describes the _context_ in which our smart contract is executing in. More
 /// concretely it contains the properties of the blockchain in which our smart contracts are being
 /// executed. These properties include thing such as the types of accounts being used (`AccountId`)
@@ -76,8 +1162,8 @@ impl From<scale::Error> for ExtensionError {
 /// The `DefaultEnvironment` matches the default properties set out in the Subtrate node template.
 use ink_env::{DefaultEnvironment, Environment};
 
-/// The default environment assumes that no chain extensions are present. However, we know there is
-/// at least one (we're implementing it!) so we need to update our `Environment` to match that.
+/// The default environment assumes that no chain extensions are present. However, we know there are
+/// two of them (we're implementing them!) so we need to update our `Environment` to match that.
 ///
 /// We can re-use the rest of the properties from the `Default` environment since we haven't changed
 /// those.
@@ -92,7 +1178,32 @@ impl Environment for CustomEnvironment {
     type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
     type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
 
-    type ChainExtension = MyChainExtension;
+    type ChainExtension = Extensions;
+}
+
+/// All of the chain extensions our contracts get to use.
+///
+/// An `Environment` only has room for a single `ChainExtension`, so we bundle ours up. This is what
+/// lets us write `self.env().extension().scheduler.schedule_call(..)`.
+pub enum Extensions {}
+
+/// What `self.env().extension()` hands us, with one field per chain extension.
+pub struct ExtensionsInstance {
+    /// Everything but scheduling callbacks, see [`MyChainExtension`].
+    pub main: <MyChainExtension as ink_lang::ChainExtensionInstance>::Instance,
+    /// Scheduling callbacks, see [`SchedulerExtension`].
+    pub scheduler: <SchedulerExtension as ink_lang::ChainExtensionInstance>::Instance,
+}
+
+impl ink_lang::ChainExtensionInstance for Extensions {
+    type Instance = ExtensionsInstance;
+
+    fn instantiate() -> Self::Instance {
+        ExtensionsInstance {
+            main: <MyChainExtension as ink_lang::ChainExtensionInstance>::instantiate(),
+            scheduler: <SchedulerExtension as ink_lang::ChainExtensionInstance>::instantiate(),
+        }
+    }
 }
 
 /// Now we need to tell our contract to use our custom environment.
@@ -100,59 +1211,3777 @@ impl Environment for CustomEnvironment {
 /// This will give us access to the chain extension that we've defined.
 #[ink::contract(env = crate::CustomEnvironment)]
 mod chain_extension {
+    use ink_prelude::vec::Vec;
+    use ink_storage::{
+        traits::{
+            PackedLayout,
+            SpreadAllocate,
+            SpreadLayout,
+        },
+        Mapping,
+    };
+
+    /// What the runtime hands to the messages our scheduled callbacks invoke. We don't get a say
+    /// in it (yet).
+    const CALLBACK_ARG: u32 = 15663040;
+
+    /// How many calls we keep track of at once, see `pending_schedules()`.
+    const MAX_PENDING_SCHEDULES: usize = 32;
+
+    /// A call we asked the runtime to schedule, which hasn't run yet as far as we know.
+    #[derive(
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct ScheduleInfo {
+        /// The block we asked for, or the one the call was moved to by `reschedule_call()`. The
+        /// runtime may push the call back a little if the block we asked for is full. For calls
+        /// from `schedule_call_in()` it's the block the runtime scheduled them for.
+        at: BlockNumber,
+        arg: u32,
+        selector: [u8; 4],
+        /// What `callback_receipt()` takes to find out what came of the call.
+        handle: u64,
+        /// Where the call is in the Scheduler's agenda, as the block it's scheduled for and its
+        /// index among that block's calls.
+        address: (BlockNumber, u32),
+        /// The hash of the call, if the runtime only put that into the Scheduler's agenda.
+        preimage: Option<Hash>,
+    }
+
+    /// How we keep rescheduling `scheduler_handler`, see `start_heartbeat()`.
+    #[derive(
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+        Clone,
+        Debug,
+        PartialEq,
+        Eq,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Heartbeat {
+        /// How many blocks apart the beats are.
+        interval: BlockNumber,
+        gas_limit: u64,
+        storage_deposit_limit: Option<Balance>,
+        /// The handle of the next beat. Only that callback gets to schedule the one after it, so
+        /// beats from a heartbeat which has since been stopped or restarted just die out.
+        handle: u64,
+    }
 
     #[ink(storage)]
-    pub struct ChainExtension {}
+    #[derive(SpreadAllocate)]
+    pub struct ChainExtension {
+        /// The capabilities reported by the runtime the last time `check_runtime()` was called.
+        ///
+        /// If we haven't checked yet we optimistically assume everything is available.
+        capabilities: Option<u32>,
+        /// The same as `capabilities`, for the flags in `more_capabilities`.
+        more_capabilities: Option<u32>,
+        /// Values which still live in contract storage, keyed by the order they were stored in.
+        values: Mapping<u32, u32>,
+        /// How many values have been stored in total, this is also the next key we hand out.
+        value_count: u32,
+        /// Every key below this one has already been moved into runtime storage by `migrate()`.
+        migrated: u32,
+        /// The only account which gets to schedule calls, see `schedule_call()`.
+        owner: AccountId,
+        /// The calls we've scheduled which haven't run yet, keyed by the handle the runtime gave
+        /// them.
+        schedules: Mapping<u64, ScheduleInfo>,
+        /// The keys of everything in `schedules`, since we can't iterate over a `Mapping`. There
+        /// are never more than `MAX_PENDING_SCHEDULES` of them.
+        pending_schedules: Vec<u64>,
+        /// The heartbeat we're keeping up, if any.
+        heartbeat: Option<Heartbeat>,
+    }
+
+    /// Errors which come from the contract itself, rather than from the chain extension.
+    #[derive(Debug, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Only the owner of the contract is allowed to do this.
+        NotOwner,
+        /// The chain extension turned us down.
+        Extension(crate::ExtensionError),
+        /// Heartbeats need to be at least a block apart.
+        InvalidInterval,
+        /// There's no pending call with this handle.
+        UnknownSchedule,
+        /// We're already keeping track of `MAX_PENDING_SCHEDULES` calls. Some of them need to
+        /// run, or be cleared with `clear_schedule()`, first.
+        TooManySchedules,
+        /// The call hasn't run yet, as far as the runtime knows.
+        ScheduleStillPending,
+        /// Calls can only be pushed back, not brought forward.
+        RescheduleTooEarly,
+    }
+
+    impl From<crate::ExtensionError> for Error {
+        fn from(e: crate::ExtensionError) -> Self {
+            Self::Extension(e)
+        }
+    }
+
+    #[ink(event)]
+    pub struct ValueWritten {
+        #[ink(topic)]
+        caller: AccountId,
+        value: u32,
+    }
+
+    /// One of our messages failed because of something the chain extension told us.
+    ///
+    /// `error` is the [`crate::ErrorCode::id`] of what went wrong.
+    #[ink(event)]
+    pub struct ExtensionFailed {
+        #[ink(topic)]
+        error: u16,
+    }
 
+    /// One of our scheduled callbacks ran.
+    ///
+    /// `arg` is whatever the runtime handed `scheduler_handler`. There's no way to pass our own
+    /// argument through `schedule_call()` yet, so for now that's always the same number.
     #[ink(event)]
     pub struct SchedulerTriggered {
+        #[ink(topic)]
         at: BlockNumber,
+        /// Who called us, which is always this contract itself since that's who the runtime runs
+        /// our callbacks on behalf of.
+        #[ink(topic)]
+        caller: AccountId,
         arg: u32,
     }
 
+    /// Someone other than the Scheduler called `scheduler_handler`, so we ignored them.
+    #[ink(event)]
+    pub struct CallbackRejected {
+        #[ink(topic)]
+        caller: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
     impl ChainExtension {
-        /// Constructor that initializes the `bool` value to the given `init_value`.
+        /// Constructor that makes whoever deploys the contract its owner.
         #[ink(constructor)]
         pub fn new() -> Self {
-            Self {}
+            ink_lang::utils::initialize_contract(|contract: &mut Self| {
+                contract.owner = Self::env().caller();
+            })
+        }
+
+        /// The account which gets to schedule calls.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Hands ownership of the contract over to `new_owner`. Only the current owner may do
+        /// this.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            self.env().emit_event(OwnershipTransferred {
+                from: self.owner,
+                to: new_owner,
+            });
+            self.owner = new_owner;
+            Ok(())
+        }
+
+        /// Fails with `Error::NotOwner` unless we were called by the owner of the contract.
+        fn only_owner(&self) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            Ok(())
+        }
+
+        /// Probes the chain extension for the functions it supports and remembers the answer so
+        /// that later messages can bail out early with `ExtensionError::CapabilityMissing`.
+        ///
+        /// If the runtime speaks a different version of either of our extensions we fail with
+        /// `ExtensionError::VersionMismatch`, and treat it as not supporting anything at all.
+        ///
+        /// @chain-extension func_id=0 pallets= status_codes=0,2
+        /// @chain-extension func_id=65536 pallets= status_codes=0,2
+        /// @chain-extension func_id=4 pallets= status_codes=0,2
+        /// @chain-extension func_id=43 pallets= status_codes=0,2
+        #[ink(message)]
+        pub fn check_runtime(&mut self) -> Result<u32, crate::ExtensionError> {
+            let versions = [
+                (
+                    self.env().extension().main.version(),
+                    crate::MAIN_EXTENSION_VERSION,
+                ),
+                (
+                    self.env().extension().scheduler.version(),
+                    crate::SCHEDULER_EXTENSION_VERSION,
+                ),
+            ];
+            for (version, expected) in versions {
+                match version {
+                    Ok(version) if version == expected => {}
+                    // Not knowing about `version()` at all means the runtime predates versions.
+                    Ok(_) | Err(crate::ExtensionError::UnknownFunction) => {
+                        self.capabilities = Some(0);
+                        self.more_capabilities = Some(0);
+                        return Err(crate::ExtensionError::VersionMismatch)
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let capabilities = self.env().extension().main.capabilities()?;
+            // Runtimes from before we ran out of room in `capabilities` don't have any of the
+            // functions in `more_capabilities` either.
+            let more_capabilities = match self.env().extension().main.more_capabilities() {
+                Err(crate::ExtensionError::UnknownFunction) => 0,
+                more_capabilities => more_capabilities?,
+            };
+            self.capabilities = Some(capabilities);
+            self.more_capabilities = Some(more_capabilities);
+            Ok(capabilities)
+        }
+
+        /// Fails with `ExtensionError::CapabilityMissing` if we know for sure that the runtime
+        /// doesn't support the given capability.
+        fn ensure_capability(&self, capability: u32) -> Result<(), crate::ExtensionError> {
+            match self.capabilities {
+                Some(capabilities) if capabilities & capability == 0 => {
+                    Err(crate::ExtensionError::CapabilityMissing)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Like `ensure_capability()`, for the flags in `more_capabilities`.
+        fn ensure_more_capability(&self, capability: u32) -> Result<(), crate::ExtensionError> {
+            match self.more_capabilities {
+                Some(capabilities) if capabilities & capability == 0 => {
+                    Err(crate::ExtensionError::CapabilityMissing)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Emits `ExtensionFailed` if `result` is an error, and passes `result` on either way.
+        ///
+        /// We only do this for messages which change state, which are the ones front-ends submit
+        /// as transactions and so can't just look at the return value of. Those return a
+        /// [`crate::Reported`] rather than a `Result`, or ink! would revert the event.
+        fn report<T>(
+            &self,
+            result: Result<T, crate::ExtensionError>,
+        ) -> Result<T, crate::ExtensionError> {
+            if let Err(e) = &result {
+                self.env().emit_event(ExtensionFailed {
+                    error: e.error_code().id(),
+                });
+            }
+            result
         }
 
         /// Note, we need to ensure we indicate that this call mutates state, otherwise it won't
         /// work.
+        ///
+        /// The extension doesn't hand back a status code for this one, if the write fails we
+        /// trap. So by the time we emit `ValueWritten` we know the value made it into storage.
+        ///
+        /// @chain-extension func_id=1 pallets=Template status_codes=0
         #[ink(message)]
         pub fn write_to_storage(&mut self, value: u32) {
-            self.env().extension().write_to_storage(value);
+            self.env().extension().main.write_to_storage(false, value);
+
+            self.env().emit_event(ValueWritten {
+                caller: self.env().caller(),
+                value,
+            });
         }
 
+        /// Reads the value last written with `write_to_storage()`. That may well have been
+        /// written by another contract, there's only the one value in `pallet_template`.
+        ///
+        /// @chain-extension func_id=39 pallets=Template status_codes=0,2
         #[ink(message)]
-        pub fn custom_type_with_result(
+        pub fn read_from_storage(&self) -> Result<Option<u32>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::READ_FROM_STORAGE)?;
+            Ok(self.env().extension().main.read_from_storage()?)
+        }
+
+        /// Stores `value` in the caller's own slot in runtime storage.
+        ///
+        /// @chain-extension func_id=11 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn write_for_caller(
             &mut self,
-            success: bool,
-        ) -> Result<(), crate::ExtensionError> {
-            let v = crate::Custom {
-                inner: if success {
-                    ink_prelude::vec![1, 2]
-                } else {
-                    ink_prelude::vec![1, 2, 3]
-                },
-            };
+            value: u32,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::ACCOUNT_VALUES)?;
+                self.report(self.env().extension().main.write_for_caller(false, value))?;
 
-            // Thanks to our `StatusCode` conversion we can easily handle the error using the `?`
-            // operator here.
-            Ok(self.env().extension().custom_type_with_result(v)?)
+                self.env().emit_event(ValueWritten {
+                    caller: self.env().caller(),
+                    value,
+                });
+                Ok(())
+            })
         }
 
+        /// Reads the value `account` stored with `write_for_caller()`.
+        ///
+        /// @chain-extension func_id=12 pallets=Template status_codes=0,2
         #[ink(message)]
-        pub fn schedule_call(&mut self, at: u32) -> Result<(), crate::ExtensionError> {
-            Ok(self.env().extension().schedule_call(at)?)
+        pub fn read_for(&self, account: AccountId) -> Result<Option<u32>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::ACCOUNT_VALUES)?;
+            Ok(self.env().extension().main.read_for(account)?)
         }
 
-        #[ink(message, selector = 0xC0FFEE)]
-        pub fn scheduler_handler(&mut self, arg: u32) {
-            Self::env().emit_event(SchedulerTriggered {
-                at: self.env().block_number(),
-                arg,
-            });
+        /// Transfers `amount` from this contract to `to`.
+        ///
+        /// This is payable, so the caller can send along the funds for the transfer in the same
+        /// call.
+        ///
+        /// @chain-extension func_id=13 pallets=Balances status_codes=0,2,6,7,8
+        #[ink(message, payable)]
+        pub fn transfer_native(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::TRANSFER_NATIVE)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .transfer_native(false, to, amount),
+                )
+            })
+        }
+
+        /// The same as `transfer_native`, but hands the runtime `amount` in its compact encoding.
+        ///
+        /// @chain-extension func_id=36 pallets=Balances status_codes=0,2,6,7,8
+        #[ink(message, payable)]
+        pub fn transfer_native_compact(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::TRANSFER_NATIVE_COMPACT)?;
+                let amount = scale::Compact(amount);
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .transfer_native_compact(false, to, amount),
+                )
+            })
+        }
+
+        /// How much `account` is free to spend, say to check whether it can cover an allowance
+        /// before taking it up on it.
+        ///
+        /// @chain-extension func_id=41 pallets=Balances status_codes=0,2
+        #[ink(message)]
+        pub fn free_balance(&self, account: AccountId) -> Result<Balance, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::FREE_BALANCE)?;
+            Ok(self.env().extension().main.free_balance(account)?)
+        }
+
+        /// How many transactions `account` has signed so far. Contracts can mix this into
+        /// identifiers which should differ for every transaction a user sends.
+        ///
+        /// @chain-extension func_id=42 pallets=System status_codes=0,2
+        #[ink(message)]
+        pub fn account_nonce(&self, account: AccountId) -> Result<u32, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::ACCOUNT_NONCE)?;
+            Ok(self.env().extension().main.account_nonce(account)?)
+        }
+
+        /// The code hash and storage deposit of the contract at `account`, if there is one.
+        ///
+        /// Other contracts can use this to only deal with contracts running code they trust.
+        ///
+        /// @chain-extension func_id=44 pallets=Contracts,Balances status_codes=0,2
+        #[ink(message)]
+        pub fn contract_info(
+            &self,
+            account: AccountId,
+        ) -> Result<Option<crate::ContractInfo<Hash, Balance>>, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::CONTRACT_INFO)?;
+            Ok(self.env().extension().main.contract_info(account)?)
+        }
+
+        /// Has the runtime call the contract at `dest` on our behalf, handing back the callee's
+        /// return flags and output.
+        ///
+        /// This is payable, so the caller can send along the funds for `value` in the same call.
+        ///
+        /// @chain-extension func_id=45 pallets=Contracts,Balances status_codes=0,2,3,8,44,45,70
+        #[ink(message, payable)]
+        pub fn call_contract(
+            &mut self,
+            dest: AccountId,
+            value: Balance,
+            gas_limit: u64,
+            input: Vec<u8>,
+        ) -> crate::Reported<(u32, Vec<u8>), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::CALL_CONTRACT)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .call_contract(false, dest, value, gas_limit, input),
+                )
+            })
+        }
+
+        /// Has the runtime instantiate a contract from the code with `code_hash` on our behalf,
+        /// handing back the new contract's address.
+        ///
+        /// Factories can use this to deploy contracts while leaving it to the runtime to decide
+        /// which code may be deployed this way.
+        ///
+        /// @chain-extension func_id=46 pallets=Contracts,Balances status_codes=0,2,8,45,46,47,48,70
+        #[ink(message, payable)]
+        pub fn instantiate_contract(
+            &mut self,
+            code_hash: Hash,
+            value: Balance,
+            gas_limit: u64,
+            input: Vec<u8>,
+            salt: Vec<u8>,
+        ) -> crate::Reported<AccountId, crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::INSTANTIATE_CONTRACT)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .instantiate_contract(false, code_hash, value, gas_limit, input, salt),
+                )
+            })
+        }
+
+        /// Has the runtime dispatch `call` on behalf of whoever called us.
+        ///
+        /// This saves us from waiting on a function of our own for every call we'd like to make,
+        /// as long as the runtime lets the call through.
+        ///
+        /// @chain-extension func_id=47 pallets=Template,Scheduler status_codes=0,2,3,71,30,49
+        #[ink(message)]
+        pub fn dispatch(&mut self, call: Vec<u8>) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::DISPATCH)?;
+                self.report(self.env().extension().main.dispatch(false, call))
+            })
+        }
+
+        /// Stores each of `values` in the caller's slot of the runtime's storage, one after the
+        /// other, leaving the last one in place.
+        ///
+        /// The runtime goes through with all of the writes or none of them.
+        ///
+        /// @chain-extension func_id=48 pallets=Utility,Template status_codes=0,2,35,49
+        #[ink(message)]
+        pub fn batch_write(
+            &mut self,
+            values: Vec<u32>,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::BATCH_WRITE)?;
+                self.report(self.env().extension().main.batch_write(false, values))
+            })
+        }
+
+        /// Approves the operation with `call_hash` of the 2-of-3 multisig between us and
+        /// `others`, leaving it to one of them to execute it.
+        ///
+        /// Leave out `timepoint` if we're the first to approve, in which case the deposit comes
+        /// out of our balance.
+        ///
+        /// @chain-extension func_id=50 pallets=Multisig status_codes=0,2,49,50,51,52,53,54
+        #[ink(message)]
+        pub fn approve_2_of_3(
+            &mut self,
+            others: [AccountId; 2],
+            timepoint: Option<crate::Timepoint<BlockNumber>>,
+            call_hash: [u8; 32],
+            max_weight: u64,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::MULTISIG)?;
+                self.report(self.env().extension().main.approve_as_multi(
+                    false,
+                    2,
+                    Self::sorted(others),
+                    timepoint,
+                    call_hash,
+                    max_weight,
+                ))
+            })
+        }
+
+        /// Gives the second approval to `call` of the 2-of-3 multisig between us and `others`,
+        /// which has the multisig account dispatch it.
+        ///
+        /// @chain-extension func_id=49 pallets=Multisig status_codes=0,2,49,50,51,54,55
+        #[ink(message)]
+        pub fn execute_2_of_3(
+            &mut self,
+            others: [AccountId; 2],
+            timepoint: crate::Timepoint<BlockNumber>,
+            call: Vec<u8>,
+            max_weight: u64,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::MULTISIG)?;
+                self.report(self.env().extension().main.as_multi(
+                    false,
+                    2,
+                    Self::sorted(others),
+                    Some(timepoint),
+                    call,
+                    max_weight,
+                ))
+            })
+        }
+
+        /// Lets `delegate` make the calls `proxy_type` allows on our behalf, without any delay.
+        ///
+        /// A proxy could pass itself off as one of our callbacks, so only the owner may do this.
+        ///
+        /// @chain-extension func_id=51 pallets=Proxy,Balances status_codes=0,2,3,49,57,58
+        #[ink(message)]
+        pub fn add_proxy(
+            &mut self,
+            delegate: AccountId,
+            proxy_type: crate::ProxyType,
+        ) -> crate::Reported<(), Error> {
+            crate::reported(|| {
+                self.only_owner()?;
+                self.ensure_more_capability(crate::more_capabilities::PROXY)?;
+                Ok(self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .add_proxy(false, delegate, proxy_type, 0),
+                )?)
+            })
+        }
+
+        /// Undoes `add_proxy()`.
+        ///
+        /// @chain-extension func_id=52 pallets=Proxy,Balances status_codes=0,2,49,59
+        #[ink(message)]
+        pub fn remove_proxy(
+            &mut self,
+            delegate: AccountId,
+            proxy_type: crate::ProxyType,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::PROXY)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .remove_proxy(false, delegate, proxy_type, 0),
+                )
+            })
+        }
+
+        /// Has the runtime dispatch `call` on behalf of `real`, who made us their proxy.
+        ///
+        /// That way users can have us make e.g. `pallet_template` writes for them, without having
+        /// to call us for each of them.
+        ///
+        /// @chain-extension func_id=53 pallets=Proxy,Template status_codes=0,2,3,4,49,56
+        #[ink(message)]
+        pub fn act_for(
+            &mut self,
+            real: AccountId,
+            call: Vec<u8>,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::PROXY)?;
+                self.report(self.env().extension().main.proxy(false, real, call))
+            })
+        }
+
+        /// The display name `account` set for itself, and what the registrars make of it.
+        ///
+        /// @chain-extension func_id=54 pallets=Identity status_codes=0,2
+        #[ink(message)]
+        pub fn identity_of(
+            &self,
+            account: AccountId,
+        ) -> Result<Option<crate::BoundedIdentityInfo>, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::IDENTITY)?;
+            Ok(self.env().extension().main.identity_of(account)?)
+        }
+
+        /// Just like `write_for_caller()`, but only for callers whose identity a registrar
+        /// vouches for.
+        ///
+        /// @chain-extension func_id=54 pallets=Identity status_codes=0,2
+        /// @chain-extension func_id=11 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn verified_write_for_caller(
+            &mut self,
+            value: u32,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                let verified = self
+                    .identity_of(self.env().caller())?
+                    .map_or(false, |identity| identity.judgement.is_verified());
+                if !verified {
+                    return Err(crate::ExtensionError::IdentityNotVerified)
+                }
+
+                self.write_for_caller(value).into()
+            })
+        }
+
+        /// Votes on referendum `ref_index` with `balance` out of our own funds.
+        ///
+        /// This is how a DAO would have its treasury take part in governance. We don't keep track
+        /// of who gets to decide how we vote here, so anyone can.
+        ///
+        /// @chain-extension func_id=55 pallets=Democracy status_codes=0,2,3,49,60,61,62
+        #[ink(message)]
+        pub fn vote(
+            &mut self,
+            ref_index: u32,
+            aye: bool,
+            conviction: crate::Conviction,
+            balance: Balance,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::GOVERNANCE)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .vote(false, ref_index, aye, conviction, balance),
+                )
+            })
+        }
+
+        /// The era the Staking pallet last planned, if it got around to planning any.
+        ///
+        /// @chain-extension func_id=56 pallets=Staking status_codes=0,2
+        #[ink(message)]
+        pub fn current_era(&self) -> Result<Option<crate::EraIndex>, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::STAKING)?;
+            Ok(self.env().extension().main.current_era()?)
+        }
+
+        /// How much backs `validator` in the active era.
+        ///
+        /// @chain-extension func_id=57 pallets=Staking status_codes=0,2
+        #[ink(message)]
+        pub fn validator_stake(
+            &self,
+            validator: AccountId,
+        ) -> Result<Balance, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::STAKING)?;
+            Ok(self.env().extension().main.validator_stake(validator)?)
+        }
+
+        /// Whether `stash` has funds bonded for staking.
+        ///
+        /// @chain-extension func_id=58 pallets=Staking status_codes=0,2
+        #[ink(message)]
+        pub fn is_bonded(&self, stash: AccountId) -> Result<bool, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::STAKING)?;
+            Ok(self.env().extension().main.is_bonded(stash)?)
+        }
+
+        /// Proposes that the treasury pays `beneficiary` `value`, e.g. for a grant we'd like to
+        /// hand out. We put up the bond, which we only get back if the proposal gets approved.
+        ///
+        /// @chain-extension func_id=59 pallets=Treasury,Balances status_codes=0,2,49,63
+        #[ink(message)]
+        pub fn propose_spend(
+            &mut self,
+            value: Balance,
+            beneficiary: AccountId,
+        ) -> crate::Reported<crate::ProposalIndex, crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::TREASURY)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .propose_spend(false, value, beneficiary),
+                )
+            })
+        }
+
+        /// Asks the Council, which we have to be a member of, to approve treasury proposal
+        /// `proposal_id`. With a `threshold` of one our own vote is enough.
+        ///
+        /// This is how a sub-DAO would put forward the grants it'd like the wider DAO to pay for.
+        ///
+        /// @chain-extension func_id=60 pallets=Council,Treasury status_codes=0,2,49,64,65,66
+        #[ink(message)]
+        pub fn propose_approval(
+            &mut self,
+            threshold: u32,
+            proposal_id: crate::ProposalIndex,
+        ) -> crate::Reported<Hash, crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::COUNCIL)?;
+                self.report(self.env().extension().main.propose_approval(
+                    false,
+                    threshold,
+                    proposal_id,
+                ))
+            })
+        }
+
+        /// Whether `account` is a member of the Council, as governance sees it.
+        ///
+        /// @chain-extension func_id=61 pallets=CouncilMembership status_codes=0,2
+        #[ink(message)]
+        pub fn is_member(&self, account: AccountId) -> Result<bool, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::MEMBERSHIP)?;
+            Ok(self.env().extension().main.is_member(account)?)
+        }
+
+        /// Just like `write_for_caller()`, but only for members of the Council. Who they are is
+        /// up to governance rather than to us.
+        ///
+        /// @chain-extension func_id=61 pallets=CouncilMembership status_codes=0,2
+        /// @chain-extension func_id=11 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn member_write_for_caller(
+            &mut self,
+            value: u32,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                if !self.is_member(self.env().caller())? {
+                    return Err(crate::ExtensionError::NotAMember)
+                }
+
+                self.write_for_caller(value).into()
+            })
+        }
+
+        /// Has `call`, encoded for the chain our runtime sends messages to, dispatched over there
+        /// as our account.
+        ///
+        /// @chain-extension func_id=62 pallets= status_codes=0,2,67,68,69
+        #[ink(message)]
+        pub fn xcm_transact(
+            &mut self,
+            call: Vec<u8>,
+            require_weight_at_most: u64,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_more_capability(crate::more_capabilities::XCM)?;
+                self.report(self.env().extension().main.xcm_transact(
+                    false,
+                    call,
+                    require_weight_at_most,
+                ))
+            })
+        }
+
+        /// What submitting `call`, an encoded runtime call, would cost in fees right now. Callers
+        /// sponsoring transactions can use this to see what they'd be paying for.
+        ///
+        /// @chain-extension func_id=63 pallets=TransactionPayment status_codes=0,2,4
+        #[ink(message)]
+        pub fn estimate_fee(
+            &self,
+            call: Vec<u8>,
+        ) -> Result<crate::FeeEstimate<Balance>, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::FEES)?;
+            Ok(self.env().extension().main.estimate_fee(call)?)
+        }
+
+        /// How much the runtime charges for the given amount of weight, e.g. to turn a gas budget
+        /// into a balance worth putting aside.
+        ///
+        /// @chain-extension func_id=64 pallets=TransactionPayment status_codes=0,2
+        #[ink(message)]
+        pub fn weight_to_fee(
+            &self,
+            ref_time: u64,
+            proof_size: u64,
+        ) -> Result<Balance, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::WEIGHT_TO_FEE)?;
+            Ok(self
+                .env()
+                .extension()
+                .main
+                .weight_to_fee(ref_time, proof_size)?)
+        }
+
+        /// The hash of block `number`, if it's one of the recent ones the runtime keeps around.
+        /// Contracts can tie commitments to it, so they're only good for that chain of blocks.
+        ///
+        /// @chain-extension func_id=65 pallets=System status_codes=0,2
+        #[ink(message)]
+        pub fn block_hash(
+            &self,
+            number: BlockNumber,
+        ) -> Result<Option<Hash>, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::BLOCK_HASH)?;
+            Ok(self.env().extension().main.block_hash(number)?)
+        }
+
+        /// Who authored the current block, e.g. to tip them. Chains whose blocks don't name an
+        /// author, like ones sealed without a consensus engine, give back `None`.
+        ///
+        /// @chain-extension func_id=66 pallets=Authorship status_codes=0,2
+        #[ink(message)]
+        pub fn block_author(&self) -> Result<Option<AccountId>, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::BLOCK_AUTHOR)?;
+            Ok(self.env().extension().main.block_author()?)
+        }
+
+        /// Where the chain is in its cadence of sessions and staking eras, for logic which should
+        /// happen once a session or era rather than every so many blocks.
+        ///
+        /// @chain-extension func_id=67 pallets=Session,Staking status_codes=0,2
+        #[ink(message)]
+        pub fn session_info(
+            &self,
+        ) -> Result<crate::SessionInfo<BlockNumber>, crate::ExtensionError> {
+            self.ensure_more_capability(crate::more_capabilities::SESSION)?;
+            Ok(self.env().extension().main.session_info()?)
+        }
+
+        /// The Multisig pallet wants the other signatories sorted, whichever order we got them in.
+        fn sorted(others: [AccountId; 2]) -> Vec<AccountId> {
+            let mut others = Vec::from(others);
+            others.sort();
+            others
+        }
+
+        /// Quotes how much gas the extension function `func_id` may use when given roughly
+        /// `approx_input_len` bytes of input.
+        ///
+        /// Contracts which call into us can use this to budget for the extension calls we make on
+        /// their behalf. Keep in mind that this only covers the runtime side of things, not the
+        /// execution of our messages.
+        ///
+        /// @chain-extension func_id=14 pallets= status_codes=0,2
+        #[ink(message)]
+        pub fn quote(
+            &self,
+            func_id: u32,
+            approx_input_len: u32,
+        ) -> Result<u64, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::QUOTE)?;
+            Ok(self
+                .env()
+                .extension()
+                .main
+                .quote(func_id, approx_input_len)?)
+        }
+
+        /// Simulates a call to the extension function `func_id`, which has to be one of
+        /// [`crate::STATE_CHANGING_FUNCS`], with the SCALE encoded `args` (minus the `simulate`
+        /// flag).
+        ///
+        /// We get back the error the call would have failed with, if any, while nothing the call
+        /// did sticks around. The runtime charges for a simulated call just like for a real one, so
+        /// dry-running this message also tells callers about how much gas the real thing will
+        /// take.
+        #[ink(message)]
+        pub fn probe(
+            &self,
+            func_id: u32,
+            args: ink_prelude::vec::Vec<u8>,
+        ) -> Result<(), crate::ExtensionError> {
+            if !crate::STATE_CHANGING_FUNCS.contains(&func_id) {
+                return Err(crate::ExtensionError::UnknownFunction)
+            }
+
+            // The functions we can probe have all sorts of different signatures, so we can't go
+            // through `MyChainExtension` here. We don't care about their output either way.
+            ink_env::chain_extension::ChainExtensionMethod::build(func_id)
+                .input::<(bool, crate::RawArgs)>()
+                .output::<()>()
+                .handle_error_code::<crate::ExtensionError>()
+                .call(&(true, crate::RawArgs(args)))
+        }
+
+        /// Has the runtime hold on to `ciphertext`, sealed for `public_key`, until block
+        /// `expires_at`.
+        ///
+        /// Use `chain_extension_primitives::keys` to derive the key pair off-chain.
+        ///
+        /// @chain-extension func_id=15 pallets=Template status_codes=0,2,9,36
+        #[ink(message)]
+        pub fn seal(
+            &mut self,
+            public_key: [u8; 32],
+            expires_at: BlockNumber,
+            ciphertext: ink_prelude::vec::Vec<u8>,
+        ) -> crate::Reported<u64, crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::ENVELOPES)?;
+                let result = self
+                    .env()
+                    .extension()
+                    .main
+                    .store_envelope(false, public_key, expires_at, ciphertext);
+                self.report(result)
+            })
+        }
+
+        /// Retrieves a sealed envelope, or `None` if there's no envelope with the given `id`.
+        ///
+        /// @chain-extension func_id=16 pallets=Template status_codes=0,2,9
+        #[ink(message)]
+        pub fn envelope(
+            &self,
+            id: u64,
+        ) -> Result<Option<crate::Envelope<AccountId, BlockNumber>>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::ENVELOPES)?;
+            Ok(self.env().extension().main.envelope(id)?)
+        }
+
+        /// How much of `asset` the account `who` holds.
+        ///
+        /// @chain-extension func_id=17 pallets=Assets status_codes=0,2
+        #[ink(message)]
+        pub fn asset_balance(
+            &self,
+            asset: crate::AssetId,
+            who: AccountId,
+        ) -> Result<Balance, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::ASSETS)?;
+            Ok(self.env().extension().main.asset_balance(asset, who)?)
+        }
+
+        /// Transfers `amount` of `asset` from this contract's own holdings to `to`.
+        ///
+        /// @chain-extension func_id=18 pallets=Assets status_codes=0,2,6,10,11,12,13
+        #[ink(message)]
+        pub fn asset_transfer(
+            &mut self,
+            asset: crate::AssetId,
+            to: AccountId,
+            amount: Balance,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::ASSETS)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .asset_transfer(false, asset, to, amount),
+                )
+            })
+        }
+
+        /// Mints `amount` of `asset` into the account `to`.
+        ///
+        /// The contract needs to have been made the issuer of `asset` for this to work, otherwise
+        /// we get `ExtensionError::NotPermitted`.
+        ///
+        /// @chain-extension func_id=19 pallets=Assets status_codes=0,2,3,6,11,13
+        #[ink(message)]
+        pub fn asset_mint(
+            &mut self,
+            asset: crate::AssetId,
+            to: AccountId,
+            amount: Balance,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::ASSETS)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .asset_mint(false, asset, to, amount),
+                )
+            })
+        }
+
+        /// Mints `item` of `collection` into the account `owner`.
+        ///
+        /// `metadata` usually points at where the NFT's actual content lives, e.g. an IPFS URI.
+        /// The contract needs to be the issuer and the owner of `collection` for this to work.
+        ///
+        /// @chain-extension func_id=24 pallets=Uniques status_codes=0,2,3,19,20,21,22
+        #[ink(message)]
+        pub fn nft_mint(
+            &mut self,
+            collection: crate::CollectionId,
+            item: crate::ItemId,
+            owner: AccountId,
+            metadata: ink_prelude::vec::Vec<u8>,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::NFTS)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .nft_mint(false, collection, item, owner, metadata),
+                )
+            })
+        }
+
+        /// Transfers `item` of `collection` to `to`.
+        ///
+        /// @chain-extension func_id=25 pallets=Uniques status_codes=0,2,3,19,21,22
+        #[ink(message)]
+        pub fn nft_transfer(
+            &mut self,
+            collection: crate::CollectionId,
+            item: crate::ItemId,
+            to: AccountId,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::NFTS)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .nft_transfer(false, collection, item, to),
+                )
+            })
+        }
+
+        /// Who holds `item` of `collection`, if it exists.
+        ///
+        /// @chain-extension func_id=26 pallets=Uniques status_codes=0,2
+        #[ink(message)]
+        pub fn nft_owner_of(
+            &self,
+            collection: crate::CollectionId,
+            item: crate::ItemId,
+        ) -> Result<Option<AccountId>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::NFTS)?;
+            Ok(self.env().extension().main.nft_owner_of(collection, item)?)
+        }
+
+        /// A random value for `subject`, which changes from block to block.
+        ///
+        /// Our dev chain gets its randomness from `pallet_randomness_collective_flip`, which block
+        /// authors can influence. Good enough for a demo, but don't bet any money on it.
+        ///
+        /// @chain-extension func_id=27 pallets=RandomnessCollectiveFlip status_codes=0
+        #[ink(message)]
+        pub fn random(&self, subject: ink_prelude::vec::Vec<u8>) -> Hash {
+            self.env()
+                .extension()
+                .main
+                .random(subject)
+                .expect("the runtime doesn't support randomness")
+        }
+
+        /// Whether `signature` is an sr25519 signature of `message` by `public`.
+        ///
+        /// The runtime does the actual work, which is a lot cheaper than doing it in Wasm.
+        ///
+        /// @chain-extension func_id=32 pallets= status_codes=0,2,27
+        #[ink(message)]
+        pub fn verify_sr25519(
+            &self,
+            signature: [u8; 64],
+            message: ink_prelude::vec::Vec<u8>,
+            public: [u8; 32],
+        ) -> Result<bool, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::SIGNATURES)?;
+            signature_check(
+                self.env()
+                    .extension()
+                    .main
+                    .verify_sr25519(signature, message, public),
+            )
+        }
+
+        /// Whether `signature` is an ecdsa signature of the BLAKE2-256 hash of `message` by
+        /// `public`.
+        ///
+        /// @chain-extension func_id=33 pallets= status_codes=0,2,27
+        #[ink(message)]
+        pub fn verify_ecdsa(
+            &self,
+            signature: [u8; 65],
+            message: ink_prelude::vec::Vec<u8>,
+            public: [u8; 33],
+        ) -> Result<bool, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::SIGNATURES)?;
+            signature_check(
+                self.env()
+                    .extension()
+                    .main
+                    .verify_ecdsa(signature, message, public),
+            )
+        }
+
+        /// Creates a counter called `name` which other contracts can be allowed to increment
+        /// through `set_counter_access`.
+        ///
+        /// @chain-extension func_id=28 pallets=Template status_codes=0,2,24,25
+        #[ink(message)]
+        pub fn create_counter(
+            &mut self,
+            name: ink_prelude::vec::Vec<u8>,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::COUNTERS)?;
+                self.report(self.env().extension().main.counter_create(false, name))
+            })
+        }
+
+        /// Takes the next number from the counter called `name`.
+        ///
+        /// No other contract sharing the counter will ever get the same number.
+        ///
+        /// @chain-extension func_id=29 pallets=Template status_codes=0,2,3,23
+        #[ink(message)]
+        pub fn next_in_sequence(
+            &mut self,
+            name: ink_prelude::vec::Vec<u8>,
+        ) -> crate::Reported<u64, crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::COUNTERS)?;
+                self.report(self.env().extension().main.counter_increment(false, name))
+            })
+        }
+
+        /// The current value of the counter called `name`, if there is one.
+        ///
+        /// @chain-extension func_id=30 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn counter(
+            &self,
+            name: ink_prelude::vec::Vec<u8>,
+        ) -> Result<Option<u64>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::COUNTERS)?;
+            Ok(self.env().extension().main.counter_value(name)?)
+        }
+
+        /// Lets the contract `who` take numbers from the counter called `name`, or stops letting
+        /// it if `allowed` isn't set. This only works for counters we created.
+        ///
+        /// @chain-extension func_id=31 pallets=Template status_codes=0,2,3,23,26
+        #[ink(message)]
+        pub fn set_counter_access(
+            &mut self,
+            name: ink_prelude::vec::Vec<u8>,
+            who: AccountId,
+            allowed: bool,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::COUNTERS)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .counter_set_access(false, name, who, allowed),
+                )
+            })
+        }
+
+        /// Commits to `hash` now, to be revealed in a window of `window` blocks starting
+        /// `opens_in` blocks from now.
+        ///
+        /// `hash` is the BLAKE2 256 hash of the preimage, which should be computed off-chain so
+        /// the preimage never shows up on-chain before it gets revealed.
+        ///
+        /// @chain-extension func_id=20 pallets=Template,Scheduler status_codes=0,2,5,31,32,37
+        #[ink(message)]
+        pub fn commit(
+            &mut self,
+            hash: Hash,
+            opens_in: u32,
+            window: u32,
+        ) -> crate::Reported<u64, crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::COMMIT_REVEAL)?;
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .commit(false, hash, opens_in, window),
+                )
+            })
+        }
+
+        /// Reveals the preimage of the commitment `id`.
+        ///
+        /// @chain-extension func_id=21 pallets=Template status_codes=0,2,3,14,15,16,17
+        #[ink(message)]
+        pub fn reveal(
+            &mut self,
+            id: u64,
+            preimage: ink_prelude::vec::Vec<u8>,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::COMMIT_REVEAL)?;
+                self.report(self.env().extension().main.reveal(false, id, preimage))
+            })
+        }
+
+        /// Stores the length of a `Custom` value, which only works if that length is a power of
+        /// two.
+        ///
+        /// @chain-extension func_id=2 pallets=Template status_codes=0,1,2
+        #[ink(message)]
+        pub fn custom_type_with_result(
+            &mut self,
+            success: bool,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::CUSTOM_TYPE_WITH_RESULT)?;
+
+                let v = crate::Custom {
+                    inner: if success {
+                        ink_prelude::vec![1, 2]
+                    } else {
+                        ink_prelude::vec![1, 2, 3]
+                    },
+                };
+
+                // Thanks to our `StatusCode` conversion we can easily handle the error using the
+                // `?` operator here.
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .custom_type_with_result(false, v),
+                )
+            })
+        }
+
+        /// The same as `custom_type_with_result`, but the runtime first checks that we agree on
+        /// what a `Custom` looks like.
+        ///
+        /// @chain-extension func_id=7 pallets=Template status_codes=0,1,2,4
+        #[ink(message)]
+        pub fn checked_custom_type_with_result(
+            &mut self,
+            success: bool,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::CHECKED_CUSTOM_TYPE)?;
+
+                let v = crate::Custom {
+                    inner: if success {
+                        ink_prelude::vec![1, 2]
+                    } else {
+                        ink_prelude::vec![1, 2, 3]
+                    },
+                };
+
+                let mut schema = crate::SchemaHash::default();
+                ink_env::hash_bytes::<ink_env::hash::Blake2x256>(
+                    &crate::type_descriptor::<crate::Custom>(),
+                    &mut schema,
+                );
+
+                self.report(
+                    self.env()
+                        .extension()
+                        .main
+                        .checked_custom_type(false, schema, v),
+                )
+            })
+        }
+
+        /// The same as `custom_type_with_result`, but goes through the runtime function which only
+        /// takes `Custom` values up to `MAX_BOUNDED_CUSTOM_LEN` bytes long.
+        ///
+        /// @chain-extension func_id=34 pallets=Template status_codes=0,1,2
+        #[ink(message)]
+        pub fn bounded_custom_type_with_result(
+            &mut self,
+            success: bool,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::BOUNDED_CUSTOM_TYPE)?;
+
+                let v = crate::Custom {
+                    inner: if success {
+                        ink_prelude::vec![1, 2]
+                    } else {
+                        ink_prelude::vec![1, 2, 3]
+                    },
+                };
+
+                self.report(self.env().extension().main.bounded_custom_type(false, v))
+            })
+        }
+
+        /// Stores what `measure` makes of `payload`, on the condition that the caller is `only_for`
+        /// if that's given.
+        ///
+        /// @chain-extension func_id=35 pallets=Template status_codes=0,1,2,3
+        #[ink(message)]
+        pub fn rich_custom_type_with_result(
+            &mut self,
+            measure: crate::Measure,
+            only_for: Option<AccountId>,
+            payload: Vec<u8>,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::RICH_CUSTOM_TYPE)?;
+
+                let custom = crate::RichCustom {
+                    measure,
+                    only_for,
+                    payload: crate::Custom { inner: payload },
+                };
+                self.report(self.env().extension().main.rich_custom_type(false, custom))
+            })
+        }
+
+        /// Asks the runtime to call `scheduler_handler` on this contract at block `at`, with up to
+        /// `gas_limit` gas. See `suggested_callback_gas_limit()` for picking one.
+        ///
+        /// The caller pays for whatever storage deposit the callback needs, so they get to bound it
+        /// with `storage_deposit_limit`.
+        ///
+        /// This is payable, and whatever the caller sends along is passed on to `scheduler_handler`
+        /// once it runs.
+        ///
+        /// Every callback takes up room in the runtime's agenda, so only the owner of the contract
+        /// may schedule them. They also have to put down the deposit from `callback_deposit()`,
+        /// which they get back once the callback has run.
+        ///
+        /// Returns where in the Scheduler's agenda the callback ended up: the block it's scheduled
+        /// for, and its index among that block's calls. Its handle, which is what its receipt can
+        /// be found under once it has run, is in `pending_schedules()` until then.
+        ///
+        /// @chain-extension func_id=65537 pallets=Scheduler,Contracts,Template,Balances status_codes=0,2,5,28,29,31,32,38,39,42
+        #[ink(message, payable)]
+        pub fn schedule_call(
+            &mut self,
+            at: u32,
+            gas_limit: u64,
+            storage_deposit_limit: Option<Balance>,
+        ) -> crate::Reported<(BlockNumber, u32), Error> {
+            self.schedule_call_to(
+                at,
+                crate::DEFAULT_CALLBACK_SELECTOR,
+                gas_limit,
+                storage_deposit_limit,
+            )
+        }
+
+        /// Asks the runtime to call the message with the given `selector` on this contract at
+        /// block `at`. The message gets handed a `u32` argument followed by the callback's handle,
+        /// along with whatever value the caller sends along, so it needs to be payable.
+        ///
+        /// Unlike callbacks to `scheduler_handler` these don't clear themselves once they've run,
+        /// see `clear_schedule()`.
+        ///
+        /// Like with `schedule_call()`, only the owner may do this.
+        ///
+        /// @chain-extension func_id=65537 pallets=Scheduler,Contracts,Template,Balances status_codes=0,2,5,28,29,31,32,38,39,42
+        #[ink(message, payable)]
+        pub fn schedule_call_to(
+            &mut self,
+            at: u32,
+            selector: [u8; 4],
+            gas_limit: u64,
+            storage_deposit_limit: Option<Balance>,
+        ) -> crate::Reported<(BlockNumber, u32), Error> {
+            crate::reported(|| {
+                self.only_owner()?;
+                self.ensure_capability(crate::capabilities::SCHEDULE_CALL)?;
+                let scheduled = self.schedule(at, selector, gas_limit, storage_deposit_limit)?;
+                Ok(scheduled.address)
+            })
+        }
+
+        /// Like `schedule_call()`, except that the callback is scheduled `blocks` blocks from now,
+        /// which saves the caller from looking up the current block first. `blocks` needs to be
+        /// at least one.
+        ///
+        /// @chain-extension func_id=65544 pallets=Scheduler,Contracts,Template,Balances status_codes=0,2,5,28,29,32,38,39,41,42
+        #[ink(message, payable)]
+        pub fn schedule_call_in(
+            &mut self,
+            blocks: BlockNumber,
+            gas_limit: u64,
+            storage_deposit_limit: Option<Balance>,
+        ) -> crate::Reported<(BlockNumber, u32), Error> {
+            crate::reported(|| {
+                self.only_owner()?;
+                self.ensure_capability(crate::capabilities::SCHEDULE_IN)?;
+                self.ensure_schedule_room()?;
+
+                let selector = crate::DEFAULT_CALLBACK_SELECTOR;
+                let scheduled = self.report(self.env().extension().scheduler.schedule_in(
+                    false,
+                    blocks,
+                    selector,
+                    gas_limit,
+                    storage_deposit_limit,
+                ))?;
+
+                // We only asked for a delay, so we go with the block the runtime picked.
+                self.track_schedule(scheduled.address.0, selector, &scheduled);
+                Ok(scheduled.address)
+            })
+        }
+
+        /// Pushes the pending call with the given `handle` back to block `at`, which needs to be
+        /// later than the one it's scheduled for now. If `at` is full the runtime may push it back a
+        /// little further, just like when it was scheduled.
+        ///
+        /// The call keeps its handle, and whoever scheduled it keeps their deposit down until it
+        /// runs. Like with `schedule_call()`, only the owner may do this.
+        ///
+        /// Returns the block the call ended up in.
+        ///
+        /// @chain-extension func_id=65543 pallets=Scheduler,Template status_codes=0,2,5,31,32,40
+        #[ink(message)]
+        pub fn reschedule_call(
+            &mut self,
+            handle: u64,
+            at: BlockNumber,
+        ) -> crate::Reported<BlockNumber, Error> {
+            crate::reported(|| {
+                self.only_owner()?;
+                self.ensure_capability(crate::capabilities::RESCHEDULE)?;
+
+                let mut info = self.schedules.get(handle).ok_or(Error::UnknownSchedule)?;
+                if at <= info.address.0 {
+                    return Err(Error::RescheduleTooEarly)
+                }
+
+                let address = self.report(self.env().extension().scheduler.reschedule_callback(
+                    false,
+                    info.handle,
+                    at,
+                ))?;
+                info.at = address.0;
+                info.address = address;
+                self.schedules.insert(handle, &info);
+                Ok(address.0)
+            })
+        }
+
+        /// Keeps calling `scheduler_handler` every `interval` blocks, starting `interval` blocks
+        /// from now, until `stop_heartbeat()` is called.
+        ///
+        /// Each beat schedules the next one from inside `scheduler_handler`, so nobody needs to
+        /// poke the contract in between. Each of them still takes a callback deposit though, from
+        /// the owner for the first beat and from the contract itself after that, and counts
+        /// towards the contract's scheduling quota. If scheduling the
+        /// next beat fails the heartbeat stops, with an `ExtensionFailed` event saying why.
+        ///
+        /// Starting a heartbeat while one is already running replaces it.
+        ///
+        /// @chain-extension func_id=65537 pallets=Scheduler,Contracts,Template,Balances status_codes=0,2,5,28,29,31,32,38,39,42
+        #[ink(message, payable)]
+        pub fn start_heartbeat(
+            &mut self,
+            interval: BlockNumber,
+            gas_limit: u64,
+            storage_deposit_limit: Option<Balance>,
+        ) -> crate::Reported<u64, Error> {
+            crate::reported(|| {
+                if interval == 0 {
+                    return Err(Error::InvalidInterval)
+                }
+
+                self.only_owner()?;
+                self.ensure_capability(crate::capabilities::SCHEDULE_CALL)?;
+
+                let at = self.env().block_number().saturating_add(interval);
+                let handle = self
+                    .schedule(
+                        at,
+                        crate::DEFAULT_CALLBACK_SELECTOR,
+                        gas_limit,
+                        storage_deposit_limit,
+                    )?
+                    .handle;
+                self.heartbeat = Some(Heartbeat {
+                    interval,
+                    gas_limit,
+                    storage_deposit_limit,
+                    handle,
+                });
+                Ok(handle)
+            })
+        }
+
+        /// Stops the heartbeat. The beat which is already scheduled still runs, it just won't
+        /// schedule another one.
+        #[ink(message)]
+        pub fn stop_heartbeat(&mut self) -> Result<(), Error> {
+            self.only_owner()?;
+            self.heartbeat = None;
+            Ok(())
+        }
+
+        /// How many blocks apart the beats of the heartbeat are, if there is one.
+        #[ink(message)]
+        pub fn heartbeat_interval(&self) -> Option<BlockNumber> {
+            self.heartbeat.as_ref().map(|heartbeat| heartbeat.interval)
+        }
+
+        /// Asks the runtime to call `selector` at block `at` and keeps track of the call until it
+        /// runs. Whether the caller may do this is up to our callers to check.
+        fn schedule(
+            &mut self,
+            at: BlockNumber,
+            selector: [u8; 4],
+            gas_limit: u64,
+            storage_deposit_limit: Option<Balance>,
+        ) -> Result<crate::ScheduledCallback<BlockNumber, Hash>, Error> {
+            self.ensure_schedule_room()?;
+            let scheduled = self.report(self.env().extension().scheduler.schedule_call(
+                false,
+                at,
+                selector,
+                gas_limit,
+                storage_deposit_limit,
+            ))?;
+
+            self.track_schedule(at, selector, &scheduled);
+            Ok(scheduled)
+        }
+
+        /// Makes sure we have room to keep track of another call before asking the runtime for it,
+        /// so that we never lose track of one.
+        fn ensure_schedule_room(&self) -> Result<(), Error> {
+            if self.pending_schedules.len() >= MAX_PENDING_SCHEDULES {
+                return Err(Error::TooManySchedules)
+            }
+            Ok(())
+        }
+
+        /// Keeps track of a call we just had scheduled for block `at` until it runs.
+        fn track_schedule(
+            &mut self,
+            at: BlockNumber,
+            selector: [u8; 4],
+            scheduled: &crate::ScheduledCallback<BlockNumber, Hash>,
+        ) {
+            self.schedules.insert(
+                scheduled.handle,
+                &ScheduleInfo {
+                    at,
+                    arg: CALLBACK_ARG,
+                    selector,
+                    handle: scheduled.handle,
+                    address: scheduled.address,
+                    preimage: scheduled.preimage,
+                },
+            );
+            self.pending_schedules.push(scheduled.handle);
+        }
+
+        /// The handles of the calls we've scheduled which haven't run yet, see `schedule_info()`
+        /// for the details of each one.
+        ///
+        /// Calls to `scheduler_handler` get cleared as they run, since the runtime tells it which
+        /// callback is running. Calls to any other selector stay in here until they're cleared
+        /// with `clear_schedule()`.
+        #[ink(message)]
+        pub fn pending_schedules(&self) -> Vec<u64> {
+            self.pending_schedules.clone()
+        }
+
+        /// What we know about the pending call with the given `handle`, if there is one.
+        #[ink(message)]
+        pub fn schedule_info(&self, handle: u64) -> Option<ScheduleInfo> {
+            self.schedules.get(handle)
+        }
+
+        /// Forgets about the pending call with the given `handle` once it has run, which makes room
+        /// for another one.
+        ///
+        /// Anyone may do this as long as the runtime still has a receipt for the call. Receipts
+        /// don't stick around forever though, so the owner may also do it without one.
+        ///
+        /// @chain-extension func_id=65540 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn clear_schedule(&mut self, handle: u64) -> Result<(), Error> {
+            if !self.schedules.contains(handle) {
+                return Err(Error::UnknownSchedule)
+            }
+
+            if self.only_owner().is_err() {
+                self.ensure_capability(crate::capabilities::CALLBACK_RECEIPTS)?;
+                let receipt = self.env().extension().scheduler.callback_receipt(handle)?;
+                if receipt.is_none() {
+                    return Err(Error::ScheduleStillPending)
+                }
+            }
+
+            self.forget_schedule(handle);
+            Ok(())
+        }
+
+        /// Stops keeping track of the call with the given `handle`.
+        fn forget_schedule(&mut self, handle: u64) {
+            self.schedules.remove(handle);
+            self.pending_schedules.retain(|pending| *pending != handle);
+        }
+
+        /// Schedules the beat after the one with the given handle, which just ran, if the
+        /// heartbeat is still going.
+        fn keep_heartbeat(&mut self, beat: u64) {
+            let mut heartbeat = match self.heartbeat.clone() {
+                Some(heartbeat) if heartbeat.handle == beat => heartbeat,
+                _ => return,
+            };
+
+            let at = self.env().block_number().saturating_add(heartbeat.interval);
+            self.heartbeat = match self.schedule(
+                at,
+                crate::DEFAULT_CALLBACK_SELECTOR,
+                heartbeat.gas_limit,
+                heartbeat.storage_deposit_limit,
+            ) {
+                Ok(scheduled) => {
+                    heartbeat.handle = scheduled.handle;
+                    Some(heartbeat)
+                }
+                Err(_) => None,
+            };
+        }
+
+        /// Suggests a gas limit for our next scheduled callback, based on how much weight our
+        /// previous callbacks actually used.
+        ///
+        /// The answer is a limit which at least `percent` percent of our past callbacks stayed
+        /// under, or `None` if none of our callbacks have run yet.
+        ///
+        /// @chain-extension func_id=65538 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn suggested_callback_gas_limit(
+            &self,
+            percent: u8,
+        ) -> Result<Option<u64>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::CALLBACK_WEIGHTS)?;
+            Ok(self
+                .env()
+                .extension()
+                .scheduler
+                .callback_weights()?
+                .percentile(percent))
+        }
+
+        /// How much weight is left in the current block's reservation for scheduled callbacks.
+        ///
+        /// @chain-extension func_id=65539 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn callback_reservation_left(&self) -> Result<u64, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::CALLBACK_RESERVATION)?;
+            Ok(self
+                .env()
+                .extension()
+                .scheduler
+                .callback_reservation()?
+                .remaining())
+        }
+
+        /// How much the owner has to put down for every call they schedule, until it has run.
+        ///
+        /// @chain-extension func_id=65541 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn callback_deposit(&self) -> Result<Balance, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::CALLBACK_DEPOSIT)?;
+            Ok(self.env().extension().scheduler.callback_deposit()?)
+        }
+
+        /// What came of the scheduled callback with the given handle.
+        ///
+        /// @chain-extension func_id=65540 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn callback_receipt(
+            &self,
+            handle: u64,
+        ) -> Result<Option<crate::CallbackReceipt<BlockNumber, Hash>>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::CALLBACK_RECEIPTS)?;
+            Ok(self.env().extension().scheduler.callback_receipt(handle)?)
+        }
+
+        /// Writes a raw key/value pair into runtime storage. This only works if governance made
+        /// this contract the system contract.
+        ///
+        /// @chain-extension func_id=6 pallets=Template status_codes=0,2,3,33,34
+        #[ink(message)]
+        pub fn write_raw(
+            &mut self,
+            key: ink_prelude::vec::Vec<u8>,
+            value: ink_prelude::vec::Vec<u8>,
+        ) -> crate::Reported<(), crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::WRITE_RAW)?;
+                self.report(self.env().extension().main.write_raw(false, key, value))
+            })
+        }
+
+        /// Reads a value which the system contract wrote into runtime storage.
+        ///
+        /// @chain-extension func_id=10 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn read_raw(
+            &self,
+            key: ink_prelude::vec::Vec<u8>,
+        ) -> Result<Option<ink_prelude::vec::Vec<u8>>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::READ_RAW)?;
+            Ok(self.env().extension().main.read_raw(key)?)
+        }
+
+        /// Reads the blob governance set in the runtime.
+        ///
+        /// @chain-extension func_id=37 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn blob(&self) -> Result<ink_prelude::vec::Vec<u8>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::BLOB)?;
+            Ok(self.env().extension().main.blob()?)
+        }
+
+        /// How often the chain extension function `func_id` has been called so far.
+        ///
+        /// @chain-extension func_id=38 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn call_counts(
+            &self,
+            func_id: u32,
+        ) -> Result<crate::CallCounts, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::CALL_COUNTS)?;
+            Ok(self.env().extension().main.call_counts(func_id)?)
+        }
+
+        /// How the chain this contract is deployed on is set up.
+        ///
+        /// @chain-extension func_id=40 pallets=System,Balances,Scheduler status_codes=0,2
+        #[ink(message)]
+        pub fn chain_constants(
+            &self,
+        ) -> Result<crate::ChainConstants<Balance>, crate::ExtensionError> {
+            self.ensure_capability(crate::capabilities::CHAIN_CONSTANTS)?;
+            Ok(self.env().extension().main.chain_constants()?)
+        }
+
+        /// Keeps `value` in contract storage and returns the key it can be looked up with.
+        #[ink(message)]
+        pub fn store_value(&mut self, value: u32) -> u32 {
+            let key = self.value_count;
+            self.values.insert(key, &value);
+            self.value_count += 1;
+            key
+        }
+
+        /// Moves up to `max` values out of contract storage and into the (cheaper) runtime
+        /// storage.
+        ///
+        /// Since we can only move `MAX_STORE_BATCH_LEN` values per call this may need to be
+        /// called a few times, we keep track of how far we got so each call picks up where the
+        /// last one left off. Returns `true` once everything has been moved.
+        ///
+        /// @chain-extension func_id=8 pallets=Template status_codes=0,2,35
+        #[ink(message)]
+        pub fn migrate(&mut self, max: u32) -> crate::Reported<bool, crate::ExtensionError> {
+            crate::reported(|| {
+                self.ensure_capability(crate::capabilities::CONTRACT_VALUES)?;
+
+                let start = self.migrated;
+                let end = self
+                    .value_count
+                    .min(start.saturating_add(max.min(crate::MAX_STORE_BATCH_LEN)));
+
+                let batch: ink_prelude::vec::Vec<(u32, u32)> = (start..end)
+                    .filter_map(|key| self.values.get(key).map(|value| (key, value)))
+                    .collect();
+                self.report(self.env().extension().main.store_batch(false, batch))?;
+
+                // Only clean up once the runtime has accepted the batch, otherwise we'd lose
+                // values.
+                for key in start..end {
+                    self.values.remove(key);
+                }
+                self.migrated = end;
+
+                Ok(self.migrated == self.value_count)
+            })
+        }
+
+        /// Looks up a value we stored with `store_value()`, wherever it currently lives.
+        ///
+        /// @chain-extension func_id=9 pallets=Template status_codes=0,2
+        #[ink(message)]
+        pub fn value(&self, key: u32) -> Result<Option<u32>, crate::ExtensionError> {
+            if key >= self.migrated {
+                return Ok(self.values.get(key))
+            }
+
+            self.ensure_capability(crate::capabilities::CONTRACT_VALUES)?;
+            Ok(self.env().extension().main.load_value(key)?)
+        }
+
+        /// Payable, since it gets sent whatever value came along with `schedule_call()`. Besides
+        /// `arg` the runtime hands us the `handle` of the callback which is running.
+        ///
+        /// Anyone could call this, but the runtime dispatches our callbacks on behalf of this
+        /// contract, which nobody else can sign for. So we only believe calls from our own account,
+        /// and only for a callback to this message which we're still waiting on. Everybody else,
+        /// including the owner who scheduled the callback, just gets a `CallbackRejected` event.
+        #[ink(message, payable, selector = 0xC0FFEE)]
+        pub fn scheduler_handler(&mut self, arg: u32, handle: u64) {
+            let caller = self.env().caller();
+            let callback = self
+                .schedules
+                .get(handle)
+                .filter(|info| info.selector == crate::DEFAULT_CALLBACK_SELECTOR);
+            if caller != self.env().account_id() || callback.is_none() {
+                self.env().emit_event(CallbackRejected { caller });
+                return
+            }
+            self.forget_schedule(handle);
+            self.keep_heartbeat(handle);
+
+            Self::env().emit_event(SchedulerTriggered {
+                at: self.env().block_number(),
+                caller,
+                arg,
+            });
+        }
+    }
+
+    /// Turns the outcome of verifying a signature into whether or not the signature is valid.
+    fn signature_check(
+        result: Result<(), crate::ExtensionError>,
+    ) -> Result<bool, crate::ExtensionError> {
+        match result {
+            Ok(()) => Ok(true),
+            Err(crate::ExtensionError::InvalidSignature) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::{
+            capabilities,
+            status,
+            ExtensionError,
+            Reported,
+            DEFAULT_CALLBACK_SELECTOR,
+        };
+        use ink_lang as ink;
+        use ink_prelude::vec::Vec;
+        use std::{
+            cell::RefCell,
+            rc::Rc,
+        };
+
+        /// A stand-in for one of the runtime's extension functions.
+        ///
+        /// It remembers the (encoded) input it was called with and answers with a fixed status
+        /// code and output.
+        struct MockExtension {
+            func_id: u32,
+            status: u32,
+            output: Vec<u8>,
+            input: Rc<RefCell<Option<Vec<u8>>>>,
+        }
+
+        impl ink_env::test::ChainExtension for MockExtension {
+            fn func_id(&self) -> u32 {
+                self.func_id
+            }
+
+            fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+                // The off-chain environment encodes the input once more before handing it to us,
+                // unlike the runtime which gets it as is.
+                let input = <Vec<u8> as scale::Decode>::decode(&mut &input[..])
+                    .expect("the off-chain environment encodes the input as a `Vec<u8>`; qed");
+                *self.input.borrow_mut() = Some(input);
+                output.extend_from_slice(&self.output);
+                self.status
+            }
+        }
+
+        /// Registers a mock for `func_id` and returns a handle to the input it ends up receiving.
+        fn register_mock(
+            func_id: u32,
+            status: u32,
+            output: Vec<u8>,
+        ) -> Rc<RefCell<Option<Vec<u8>>>> {
+            let input = Rc::new(RefCell::new(None));
+            ink_env::test::register_chain_extension(MockExtension {
+                func_id,
+                status,
+                output,
+                input: input.clone(),
+            });
+            input
+        }
+
+        /// What `schedule_call()` gives back for a callback which went into the agenda as is, as
+        /// the first call of block 10.
+        fn scheduled(handle: u64) -> Vec<u8> {
+            scale::Encode::encode(&crate::ScheduledCallback::<BlockNumber, Hash> {
+                handle,
+                address: (10, 0),
+                preimage: None,
+            })
+        }
+
+        #[ink::test]
+        fn write_to_storage_passes_value_through() {
+            let input = register_mock(1, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            contract.write_to_storage(42);
+
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, 42u32))));
+        }
+
+        #[ink::test]
+        fn write_to_storage_emits_event() {
+            register_mock(1, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let caller = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>().alice;
+
+            contract.write_to_storage(42);
+
+            assert!(matches!(
+                emitted_events()[..],
+                [Event::ValueWritten(ValueWritten { caller: c, value: 42 })] if c == caller
+            ));
+        }
+
+        #[ink::test]
+        fn read_from_storage_passes_value_through() {
+            register_mock(39, status::SUCCESS, scale::Encode::encode(&Some(42u32)));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.read_from_storage().ok(), Some(Some(42)));
+        }
+
+        #[ink::test]
+        fn custom_type_with_result_works() {
+            let input = register_mock(2, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract.custom_type_with_result(true).is_ok());
+
+            let expected = crate::Custom {
+                inner: ink_prelude::vec![1, 2],
+            };
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, expected))));
+        }
+
+        #[ink::test]
+        fn custom_type_with_result_surfaces_errors() {
+            let input = register_mock(2, status::CUSTOM_CALL_FAILED, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.custom_type_with_result(false),
+                Reported::Err(ExtensionError::CustomCallFailed)
+            ));
+
+            let expected = crate::Custom {
+                inner: ink_prelude::vec![1, 2, 3],
+            };
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, expected))));
+        }
+
+        #[ink::test]
+        fn bounded_custom_type_with_result_works() {
+            let input = register_mock(34, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract.bounded_custom_type_with_result(true).is_ok());
+
+            let expected = crate::Custom {
+                inner: ink_prelude::vec![1, 2],
+            };
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, expected))));
+        }
+
+        #[ink::test]
+        fn bounded_custom_type_with_result_surfaces_errors() {
+            register_mock(34, status::CUSTOM_CALL_FAILED, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.bounded_custom_type_with_result(false),
+                Reported::Err(ExtensionError::CustomCallFailed)
+            ));
+        }
+
+        #[ink::test]
+        fn rich_custom_type_with_result_passes_everything_through() {
+            let input = register_mock(35, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+
+            let result = contract.rich_custom_type_with_result(
+                crate::Measure::Nth(1),
+                Some(accounts.bob),
+                ink_prelude::vec![1, 2, 3],
+            );
+            assert!(result.is_ok());
+
+            let expected = crate::RichCustom {
+                measure: crate::Measure::Nth(1),
+                only_for: Some(accounts.bob),
+                payload: crate::Custom {
+                    inner: ink_prelude::vec![1, 2, 3],
+                },
+            };
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, expected))));
+        }
+
+        #[ink::test]
+        fn rich_custom_type_with_result_surfaces_errors() {
+            register_mock(35, status::NOT_PERMITTED, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.rich_custom_type_with_result(
+                    crate::Measure::Length,
+                    None,
+                    ink_prelude::vec![1]
+                ),
+                Reported::Err(ExtensionError::NotPermitted)
+            ));
+        }
+
+        #[ink::test]
+        fn schedule_call_passes_block_through() {
+            let input = register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(contract.schedule_call(10, 1_000, None).ok(), Some((10, 0)));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    10u32,
+                    DEFAULT_CALLBACK_SELECTOR,
+                    1_000u64,
+                    None::<Balance>
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn schedule_call_to_passes_selector_through() {
+            let input = register_mock(0x0001_0001, status::SUCCESS, scheduled(0));
+            let mut contract = ChainExtension::new();
+            let selector = [0xDE, 0xAD, 0xBE, 0xEF];
+
+            assert!(contract.schedule_call_to(10, selector, 1_000, Some(500)).is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, 10u32, selector, 1_000u64, Some(500u128))))
+            );
+        }
+
+        #[ink::test]
+        fn schedule_call_in_passes_delay_through() {
+            // Block 10 is full, so the runtime pushes the call back to block 12.
+            let output = scale::Encode::encode(&crate::ScheduledCallback::<BlockNumber, Hash> {
+                handle: 7,
+                address: (12, 1),
+                preimage: None,
+            });
+            let input = register_mock(0x0001_0008, status::SUCCESS, output);
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(
+                contract.schedule_call_in(10, 1_000, None).ok(),
+                Some((12, 1))
+            );
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    10u32,
+                    DEFAULT_CALLBACK_SELECTOR,
+                    1_000u64,
+                    None::<Balance>
+                )))
+            );
+            assert_eq!(contract.schedule_info(7).map(|info| info.at), Some(12));
+        }
+
+        #[ink::test]
+        fn invalid_delays_are_reported() {
+            register_mock(0x0001_0008, status::INVALID_SCHEDULE_DELAY, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.schedule_call_in(0, 1_000, None),
+                Reported::Err(Error::Extension(ExtensionError::InvalidScheduleDelay))
+            ));
+            assert!(contract.pending_schedules().is_empty());
+        }
+
+        #[ink::test]
+        fn contracts_governance_hasnt_allowed_cannot_schedule() {
+            register_mock(0x0001_0001, status::NOT_ALLOWED_TO_SCHEDULE, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.schedule_call(10, 1_000, None),
+                Reported::Err(Error::Extension(ExtensionError::NotAllowedToSchedule))
+            ));
+            assert!(contract.pending_schedules().is_empty());
+        }
+
+        #[ink::test]
+        fn disallowed_selectors_are_reported() {
+            register_mock(0x0001_0001, status::INVALID_CALLBACK_SELECTOR, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.schedule_call_to(10, [0xDE, 0xAD, 0xBE, 0xEF], 1_000, None),
+                Reported::Err(Error::Extension(ExtensionError::InvalidCallbackSelector))
+            ));
+        }
+
+        #[ink::test]
+        fn past_blocks_are_reported() {
+            register_mock(0x0001_0001, status::SCHEDULED_IN_PAST, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.schedule_call(0, 1_000, None),
+                Reported::Err(Error::Extension(ExtensionError::ScheduledInPast))
+            ));
+        }
+
+        #[ink::test]
+        fn unknown_function_is_reported() {
+            register_mock(0x0001_0001, status::UNKNOWN_FUNCTION, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.schedule_call(10, 1_000, None),
+                Reported::Err(Error::Extension(ExtensionError::UnknownFunction))
+            ));
+        }
+
+        #[ink::test]
+        fn unrecognized_status_codes_are_preserved() {
+            register_mock(2, 1337, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.custom_type_with_result(true),
+                Reported::Err(ExtensionError::Unknown(1337))
+            ));
+        }
+
+        /// Has both of our extensions report the given versions.
+        fn register_versions(main: u32, scheduler: u32) {
+            let main_id = crate::pack_func_id(crate::MAIN_EXTENSION_ID, crate::VERSION_FUNCTION);
+            register_mock(main_id, status::SUCCESS, scale::Encode::encode(&main));
+            let scheduler_id =
+                crate::pack_func_id(crate::SCHEDULER_EXTENSION_ID, crate::VERSION_FUNCTION);
+            register_mock(
+                scheduler_id,
+                status::SUCCESS,
+                scale::Encode::encode(&scheduler),
+            );
+        }
+
+        #[ink::test]
+        fn missing_capabilities_fail_fast() {
+            let caps = capabilities::WRITE_TO_STORAGE | capabilities::CUSTOM_TYPE_WITH_RESULT;
+            register_versions(
+                crate::MAIN_EXTENSION_VERSION,
+                crate::SCHEDULER_EXTENSION_VERSION,
+            );
+            register_mock(4, status::SUCCESS, scale::Encode::encode(&caps));
+            register_mock(43, status::SUCCESS, scale::Encode::encode(&0u32));
+            let schedule_input = register_mock(0x0001_0001, status::SUCCESS, scheduled(0));
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(contract.check_runtime().ok(), Some(caps));
+            assert!(matches!(
+                contract.schedule_call(10, 1_000, None),
+                Reported::Err(Error::Extension(ExtensionError::CapabilityMissing))
+            ));
+
+            // We should never have made it to the runtime.
+            assert_eq!(*schedule_input.borrow(), None);
+        }
+
+        #[ink::test]
+        fn version_mismatch_disables_everything() {
+            let newer = crate::SCHEDULER_EXTENSION_VERSION + 1;
+            register_versions(crate::MAIN_EXTENSION_VERSION, newer);
+            let capabilities_input = register_mock(4, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(contract.check_runtime(), Err(ExtensionError::VersionMismatch)));
+            assert!(matches!(
+                contract.write_for_caller(42),
+                Reported::Err(ExtensionError::CapabilityMissing)
+            ));
+
+            // There's no point in asking for capabilities we couldn't use anyways.
+            assert_eq!(*capabilities_input.borrow(), None);
+        }
+
+        #[ink::test]
+        fn runtimes_without_more_capabilities_have_none_of_them() {
+            register_versions(
+                crate::MAIN_EXTENSION_VERSION,
+                crate::SCHEDULER_EXTENSION_VERSION,
+            );
+            register_mock(4, status::SUCCESS, scale::Encode::encode(&u32::MAX));
+            register_mock(43, status::UNKNOWN_FUNCTION, Vec::new());
+            let contract_info_input = register_mock(44, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(contract.check_runtime().ok(), Some(u32::MAX));
+            assert!(matches!(
+                contract.contract_info(AccountId::from([2; 32])),
+                Err(ExtensionError::CapabilityMissing)
+            ));
+            assert_eq!(*contract_info_input.borrow(), None);
+        }
+
+        #[ink::test]
+        fn func_ids_can_be_unpacked() {
+            let func_id = crate::pack_func_id(crate::SCHEDULER_EXTENSION_ID, 1);
+
+            // This is the id `schedule_call()` is declared with.
+            assert_eq!(func_id, 0x0001_0001);
+            assert_eq!(crate::unpack_func_id(func_id), (crate::SCHEDULER_EXTENSION_ID, 1));
+        }
+
+        #[ink::test]
+        fn migrate_moves_values_in_chunks() {
+            let input = register_mock(8, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            for value in 0..5 {
+                contract.store_value(value * 10);
+            }
+
+            assert_eq!(contract.migrate(3).ok(), Some(false));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    ink_prelude::vec![(0u32, 0u32), (1, 10), (2, 20)]
+                )))
+            );
+
+            // The second call should pick up where the first one left off.
+            assert_eq!(contract.migrate(3).ok(), Some(true));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, ink_prelude::vec![(3u32, 30u32), (4, 40)])))
+            );
+
+            // Values which haven't been migrated yet are still served from contract storage.
+            contract.store_value(50);
+            assert_eq!(contract.value(5).ok(), Some(Some(50)));
+        }
+
+        #[ink::test]
+        fn failed_migrations_keep_values_around() {
+            register_mock(8, status::UNKNOWN_FUNCTION, Vec::new());
+            let mut contract = ChainExtension::new();
+            contract.store_value(42);
+
+            assert!(matches!(
+                contract.migrate(1),
+                Reported::Err(ExtensionError::UnknownFunction)
+            ));
+            assert_eq!(contract.value(0).ok(), Some(Some(42)));
+        }
+
+        #[ink::test]
+        fn migrated_values_are_loaded_from_the_runtime() {
+            register_mock(8, status::SUCCESS, Vec::new());
+            let input = register_mock(9, status::SUCCESS, scale::Encode::encode(&Some(42u32)));
+            let mut contract = ChainExtension::new();
+            contract.store_value(42);
+
+            assert_eq!(contract.migrate(1).ok(), Some(true));
+            assert_eq!(contract.value(0).ok(), Some(Some(42)));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&0u32)));
+        }
+
+        #[ink::test]
+        fn read_raw_decodes_runtime_output() {
+            let value = ink_prelude::vec![7u8; 100];
+            let input = register_mock(10, status::SUCCESS, scale::Encode::encode(&Some(&value)));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.read_raw(b"key".to_vec()).ok(), Some(Some(value)));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&b"key".to_vec())));
+        }
+
+        #[ink::test]
+        fn blob_passes_large_payloads_through() {
+            let value = ink_prelude::vec![7u8; 4096];
+            register_mock(37, status::SUCCESS, scale::Encode::encode(&value));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.blob().ok(), Some(value));
+        }
+
+        #[ink::test]
+        fn call_counts_are_passed_through() {
+            let counts = crate::CallCounts {
+                succeeded: 3,
+                failed: 1,
+            };
+            let input = register_mock(38, status::SUCCESS, scale::Encode::encode(&counts));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.call_counts(1).ok(), Some(counts));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&1u32)));
+        }
+
+        #[ink::test]
+        fn chain_constants_are_passed_through() {
+            let constants = crate::ChainConstants {
+                existential_deposit: 1_000,
+                ss58_prefix: 42,
+                token_decimals: 12,
+                maximum_scheduler_weight: 1_000_000_000,
+            };
+            register_mock(40, status::SUCCESS, scale::Encode::encode(&constants));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.chain_constants().ok(), Some(constants));
+        }
+
+        #[ink::test]
+        fn call_counts_of_unknown_functions_are_an_error() {
+            register_mock(38, status::UNKNOWN_FUNCTION, Vec::new());
+            let contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.call_counts(0xDEAD),
+                Err(crate::ExtensionError::UnknownFunction)
+            ));
+        }
+
+        #[ink::test]
+        fn write_for_caller_emits_event() {
+            let input = register_mock(11, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract.write_for_caller(42).is_ok());
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, 42u32))));
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn free_balance_passes_account_through() {
+            let input = register_mock(41, status::SUCCESS, scale::Encode::encode(&1_000u128));
+            let contract = ChainExtension::new();
+            let account = AccountId::from([2; 32]);
+
+            assert_eq!(contract.free_balance(account).ok(), Some(1_000));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&account)));
+        }
+
+        #[ink::test]
+        fn account_nonce_passes_account_through() {
+            let input = register_mock(42, status::SUCCESS, scale::Encode::encode(&7u32));
+            let contract = ChainExtension::new();
+            let account = AccountId::from([2; 32]);
+
+            assert_eq!(contract.account_nonce(account).ok(), Some(7));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&account)));
+        }
+
+        #[ink::test]
+        fn contract_info_passes_account_through() {
+            let info = crate::ContractInfo {
+                code_hash: Hash::from([1; 32]),
+                storage_deposit: 1_000,
+            };
+            let input = register_mock(44, status::SUCCESS, scale::Encode::encode(&Some(info)));
+            let contract = ChainExtension::new();
+            let account = AccountId::from([2; 32]);
+
+            assert_eq!(contract.contract_info(account).ok(), Some(Some(info)));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&account)));
+        }
+
+        #[ink::test]
+        fn call_contract_hands_back_the_callee_output() {
+            let input = register_mock(
+                45,
+                status::SUCCESS,
+                scale::Encode::encode(&(1u32, vec![4u8, 2])),
+            );
+            let mut contract = ChainExtension::new();
+            let dest = AccountId::from([2; 32]);
+
+            assert_eq!(
+                contract
+                    .call_contract(dest, 0, 0, vec![0xC0, 0xFF, 0xEE])
+                    .ok(),
+                Some((1, vec![4, 2]))
+            );
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    dest,
+                    0 as Balance,
+                    0u64,
+                    vec![0xC0u8, 0xFF, 0xEE]
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn instantiate_contract_hands_back_the_address() {
+            let address = AccountId::from([3; 32]);
+            let input = register_mock(46, status::SUCCESS, scale::Encode::encode(&address));
+            let mut contract = ChainExtension::new();
+            let code_hash = Hash::from([2; 32]);
+
+            assert_eq!(
+                contract
+                    .instantiate_contract(code_hash, 0, 0, Vec::new(), vec![1])
+                    .ok(),
+                Some(address)
+            );
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    code_hash,
+                    0 as Balance,
+                    0u64,
+                    Vec::<u8>::new(),
+                    vec![1u8]
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn constructors_which_revert_are_reported() {
+            register_mock(46, status::CONSTRUCTOR_REVERTED, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.instantiate_contract(Hash::from([2; 32]), 0, 0, Vec::new(), Vec::new()),
+                Reported::Err(crate::ExtensionError::ConstructorReverted)
+            ));
+        }
+
+        #[ink::test]
+        fn dispatch_passes_the_call_through() {
+            let input = register_mock(47, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract.dispatch(vec![1, 2, 3]).is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, vec![1u8, 2, 3])))
+            );
+        }
+
+        #[ink::test]
+        fn failed_dispatches_are_reported() {
+            register_mock(47, status::DISPATCH_FAILED, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.dispatch(Vec::new()),
+                Reported::Err(crate::ExtensionError::DispatchFailed)
+            ));
+        }
+
+        #[ink::test]
+        fn batch_write_passes_values_through() {
+            let input = register_mock(48, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract.batch_write(vec![1, 2, 3]).is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, vec![1u32, 2, 3])))
+            );
+        }
+
+        #[ink::test]
+        fn multisig_signatories_are_sorted() {
+            let input = register_mock(50, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let (first, second) = (AccountId::from([2; 32]), AccountId::from([3; 32]));
+
+            assert!(contract
+                .approve_2_of_3([second, first], None, [1; 32], 0)
+                .is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    2u16,
+                    vec![first, second],
+                    None::<crate::Timepoint<BlockNumber>>,
+                    [1u8; 32],
+                    0u64
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn executing_a_multisig_passes_the_timepoint_through() {
+            let input = register_mock(49, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let (first, second) = (AccountId::from([2; 32]), AccountId::from([3; 32]));
+            let timepoint = crate::Timepoint {
+                height: 5,
+                index: 1,
+            };
+
+            assert!(contract
+                .execute_2_of_3([first, second], timepoint, vec![7], 100)
+                .is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    2u16,
+                    vec![first, second],
+                    Some(timepoint),
+                    vec![7u8],
+                    100u64
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn repeated_multisig_approvals_are_reported() {
+            register_mock(50, status::ALREADY_APPROVED, Vec::new());
+            let mut contract = ChainExtension::new();
+            let others = [AccountId::from([2; 32]), AccountId::from([3; 32])];
+
+            assert!(matches!(
+                contract.approve_2_of_3(others, None, [1; 32], 0),
+                Reported::Err(crate::ExtensionError::AlreadyApproved)
+            ));
+        }
+
+        #[ink::test]
+        fn proxies_are_added_without_a_delay() {
+            let input = register_mock(51, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let delegate = AccountId::from([2; 32]);
+
+            assert!(contract
+                .add_proxy(delegate, crate::ProxyType::Template)
+                .is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    delegate,
+                    crate::ProxyType::Template,
+                    0 as BlockNumber
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn only_the_owner_can_add_proxies() {
+            let input = register_mock(51, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.bob);
+            assert!(matches!(
+                contract.add_proxy(accounts.bob, crate::ProxyType::Any),
+                Reported::Err(Error::NotOwner)
+            ));
+            assert_eq!(*input.borrow(), None);
+        }
+
+        #[ink::test]
+        fn act_for_passes_the_call_through() {
+            let input = register_mock(53, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let real = AccountId::from([2; 32]);
+
+            assert!(contract.act_for(real, vec![1, 2, 3]).is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, real, vec![1u8, 2, 3])))
+            );
+        }
+
+        #[ink::test]
+        fn acting_for_accounts_we_arent_a_proxy_of_is_reported() {
+            register_mock(53, status::NOT_A_PROXY, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.act_for(AccountId::from([2; 32]), Vec::new()),
+                Reported::Err(crate::ExtensionError::NotAProxy)
+            ));
+        }
+
+        #[ink::test]
+        fn identity_of_passes_account_through() {
+            let info = crate::BoundedIdentityInfo {
+                display: b"Alice".to_vec(),
+                judgement: crate::IdentityJudgement::KnownGood,
+            };
+            let input = register_mock(
+                54,
+                status::SUCCESS,
+                scale::Encode::encode(&Some(info.clone())),
+            );
+            let contract = ChainExtension::new();
+            let account = AccountId::from([2; 32]);
+
+            assert_eq!(contract.identity_of(account).ok(), Some(Some(info)));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&account)));
+        }
+
+        #[ink::test]
+        fn only_verified_callers_get_to_write() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            let identity = |judgement| {
+                Some(crate::BoundedIdentityInfo {
+                    display: b"Alice".to_vec(),
+                    judgement,
+                })
+            };
+            let mut contract = ChainExtension::new();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.alice);
+
+            // Paying a registrar to look at an identity doesn't make it verified yet.
+            register_mock(
+                54,
+                status::SUCCESS,
+                scale::Encode::encode(&identity(crate::IdentityJudgement::FeePaid)),
+            );
+            let write_input = register_mock(11, status::SUCCESS, Vec::new());
+            assert!(matches!(
+                contract.verified_write_for_caller(42),
+                Reported::Err(crate::ExtensionError::IdentityNotVerified)
+            ));
+            assert_eq!(*write_input.borrow(), None);
+
+            register_mock(
+                54,
+                status::SUCCESS,
+                scale::Encode::encode(&identity(crate::IdentityJudgement::Reasonable)),
+            );
+            assert!(contract.verified_write_for_caller(42).is_ok());
+            assert_eq!(
+                *write_input.borrow(),
+                Some(scale::Encode::encode(&(false, 42u32)))
+            );
+        }
+
+        #[ink::test]
+        fn votes_are_passed_through() {
+            let input = register_mock(55, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract
+                .vote(3, true, crate::Conviction::Locked2x, 1_000)
+                .is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    3u32,
+                    true,
+                    crate::Conviction::Locked2x,
+                    1_000 as Balance
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn votes_on_referenda_which_are_over_are_reported() {
+            register_mock(55, status::REFERENDUM_NOT_ONGOING, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.vote(3, false, crate::Conviction::None, 1_000),
+                Reported::Err(crate::ExtensionError::ReferendumNotOngoing)
+            ));
+        }
+
+        #[ink::test]
+        fn current_era_is_passed_through() {
+            register_mock(56, status::SUCCESS, scale::Encode::encode(&Some(3u32)));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.current_era().ok(), Some(Some(3)));
+        }
+
+        #[ink::test]
+        fn validator_stake_passes_validator_through() {
+            let input = register_mock(
+                57,
+                status::SUCCESS,
+                scale::Encode::encode(&(300 as Balance)),
+            );
+            let contract = ChainExtension::new();
+            let validator = AccountId::from([2; 32]);
+
+            assert_eq!(contract.validator_stake(validator).ok(), Some(300));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&validator)));
+        }
+
+        #[ink::test]
+        fn is_bonded_passes_stash_through() {
+            let input = register_mock(58, status::SUCCESS, scale::Encode::encode(&true));
+            let contract = ChainExtension::new();
+            let stash = AccountId::from([2; 32]);
+
+            assert_eq!(contract.is_bonded(stash).ok(), Some(true));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&stash)));
+        }
+
+        #[ink::test]
+        fn proposal_indices_are_passed_back() {
+            let input = register_mock(59, status::SUCCESS, scale::Encode::encode(&7u32));
+            let mut contract = ChainExtension::new();
+            let beneficiary = AccountId::from([2; 32]);
+
+            assert_eq!(contract.propose_spend(1_000, beneficiary).ok(), Some(7));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    1_000 as Balance,
+                    beneficiary
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn proposals_we_cant_afford_the_bond_for_are_reported() {
+            register_mock(59, status::INSUFFICIENT_PROPOSAL_BOND, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.propose_spend(1_000, AccountId::from([2; 32])),
+                Reported::Err(crate::ExtensionError::InsufficientProposalBond)
+            ));
+        }
+
+        #[ink::test]
+        fn motion_hashes_are_passed_back() {
+            let hash = Hash::from([7; 32]);
+            let input = register_mock(60, status::SUCCESS, scale::Encode::encode(&hash));
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(contract.propose_approval(2, 3).ok(), Some(hash));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, 2u32, 3u32)))
+            );
+        }
+
+        #[ink::test]
+        fn motions_from_outside_the_council_are_reported() {
+            register_mock(60, status::NOT_A_COUNCIL_MEMBER, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.propose_approval(2, 3),
+                Reported::Err(crate::ExtensionError::NotACouncilMember)
+            ));
+        }
+
+        #[ink::test]
+        fn is_member_passes_account_through() {
+            let input = register_mock(61, status::SUCCESS, scale::Encode::encode(&true));
+            let contract = ChainExtension::new();
+            let account = AccountId::from([2; 32]);
+
+            assert_eq!(contract.is_member(account).ok(), Some(true));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&account)));
+        }
+
+        #[ink::test]
+        fn only_members_get_to_write() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            let mut contract = ChainExtension::new();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.alice);
+
+            let member_input = register_mock(61, status::SUCCESS, scale::Encode::encode(&false));
+            let write_input = register_mock(11, status::SUCCESS, Vec::new());
+            assert!(matches!(
+                contract.member_write_for_caller(42),
+                Reported::Err(crate::ExtensionError::NotAMember)
+            ));
+            assert_eq!(
+                *member_input.borrow(),
+                Some(scale::Encode::encode(&accounts.alice))
+            );
+            assert_eq!(*write_input.borrow(), None);
+
+            register_mock(61, status::SUCCESS, scale::Encode::encode(&true));
+            assert!(contract.member_write_for_caller(42).is_ok());
+            assert_eq!(
+                *write_input.borrow(),
+                Some(scale::Encode::encode(&(false, 42u32)))
+            );
+        }
+
+        #[ink::test]
+        fn xcm_transacts_are_passed_through() {
+            let input = register_mock(62, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract.xcm_transact(vec![1, 2, 3], 1_000).is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, vec![1u8, 2, 3], 1_000u64)))
+            );
+        }
+
+        #[ink::test]
+        fn unroutable_xcms_are_reported() {
+            register_mock(62, status::XCM_UNROUTABLE, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.xcm_transact(vec![1, 2, 3], 1_000),
+                Reported::Err(crate::ExtensionError::XcmUnroutable)
+            ));
+        }
+
+        #[ink::test]
+        fn fee_estimates_are_passed_through() {
+            let estimate = crate::FeeEstimate {
+                weight: 1_000,
+                base_fee: 1,
+                len_fee: 2,
+                adjusted_weight_fee: 3,
+            };
+            let input = register_mock(63, status::SUCCESS, scale::Encode::encode(&estimate));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.estimate_fee(vec![1, 2, 3]).unwrap(), estimate);
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&vec![1u8, 2, 3]))
+            );
+        }
+
+        #[ink::test]
+        fn calls_which_dont_decode_get_no_fee_estimate() {
+            register_mock(63, status::SCHEMA_MISMATCH, Vec::new());
+            let contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.estimate_fee(vec![0xff]),
+                Err(crate::ExtensionError::SchemaMismatch)
+            ));
+        }
+
+        #[ink::test]
+        fn weight_to_fee_is_passed_through() {
+            let input = register_mock(64, status::SUCCESS, scale::Encode::encode(&7_000u128));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.weight_to_fee(5_000, 10).unwrap(), 7_000);
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(5_000u64, 10u64)))
+            );
+        }
+
+        #[ink::test]
+        fn block_hash_passes_number_through() {
+            let hash = Hash::from([7; 32]);
+            let input = register_mock(65, status::SUCCESS, scale::Encode::encode(&Some(hash)));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.block_hash(3).ok(), Some(Some(hash)));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&3u32)));
+        }
+
+        #[ink::test]
+        fn block_author_is_passed_through() {
+            let author = AccountId::from([3; 32]);
+            register_mock(66, status::SUCCESS, scale::Encode::encode(&Some(author)));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.block_author().ok(), Some(Some(author)));
+        }
+
+        #[ink::test]
+        fn session_info_is_passed_through() {
+            let info = crate::SessionInfo {
+                session_index: 14,
+                session_length: 100,
+                active_era: Some(2),
+                sessions_per_era: 6,
+            };
+            register_mock(67, status::SUCCESS, scale::Encode::encode(&info));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.session_info().ok(), Some(info));
+        }
+
+        #[ink::test]
+        fn read_for_passes_account_through() {
+            let input = register_mock(12, status::SUCCESS, scale::Encode::encode(&Some(42u32)));
+            let contract = ChainExtension::new();
+            let account = AccountId::from([2; 32]);
+
+            assert_eq!(contract.read_for(account).ok(), Some(Some(42)));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&account)));
+        }
+
+        #[ink::test]
+        fn full_agendas_are_reported() {
+            register_mock(0x0001_0001, status::AGENDA_FULL, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.schedule_call(10, 1_000, None),
+                Reported::Err(Error::Extension(ExtensionError::AgendaFull))
+            ));
+        }
+
+        #[ink::test]
+        fn only_the_owner_can_schedule_calls() {
+            let input = register_mock(0x0001_0001, status::SUCCESS, scheduled(0));
+            let mut contract = ChainExtension::new();
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            assert_eq!(contract.owner(), accounts.alice);
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.bob);
+            assert!(matches!(
+                contract.schedule_call(10, 1_000, None),
+                Reported::Err(Error::NotOwner)
+            ));
+            assert!(matches!(
+                contract.schedule_call_to(10, DEFAULT_CALLBACK_SELECTOR, 1_000, None),
+                Reported::Err(Error::NotOwner)
+            ));
+
+            // We should never have made it to the runtime.
+            assert_eq!(*input.borrow(), None);
+        }
+
+        #[test]
+        fn annotations_match_the_manifest() {
+            let mismatches = crate::manifest::annotation_mismatches(include_str!("lib.rs"));
+            assert!(mismatches.is_empty(), "{:#?}", mismatches);
+        }
+
+        type Event = <ChainExtension as ink_lang::reflect::ContractEventBase>::Type;
+
+        /// Every event the contract has emitted so far.
+        fn emitted_events() -> Vec<Event> {
+            ink_env::test::recorded_events()
+                .map(|event| scale::Decode::decode(&mut &event.data[..]).unwrap())
+                .collect()
+        }
+
+        /// Calls `scheduler_handler` the way the runtime does for our callbacks, on behalf of the
+        /// contract itself.
+        fn run_callback(contract: &mut ChainExtension, handle: u64) {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(ink_env::test::callee::<
+                ink_env::DefaultEnvironment,
+            >());
+            contract.scheduler_handler(CALLBACK_ARG, handle);
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.alice);
+        }
+
+        #[ink::test]
+        fn scheduler_handler_rejects_unscheduled_callers() {
+            let mut contract = ChainExtension::new();
+
+            contract.scheduler_handler(15663040, 0);
+
+            assert!(matches!(emitted_events()[..], [Event::CallbackRejected(_)]));
+        }
+
+        #[ink::test]
+        fn scheduler_handler_takes_one_call_per_callback() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(0));
+            let mut contract = ChainExtension::new();
+            assert!(contract.schedule_call(10, 1_000, None).is_ok());
+
+            run_callback(&mut contract, 0);
+            // The callback we scheduled has been used up, so this one must be fake.
+            run_callback(&mut contract, 0);
+
+            assert!(matches!(
+                emitted_events()[..],
+                [Event::SchedulerTriggered(_), Event::CallbackRejected(_)]
+            ));
+        }
+
+        #[ink::test]
+        fn scheduler_handler_rejects_the_owner_calling_early() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            ink_env::test::set_callee::<ink_env::DefaultEnvironment>(accounts.django);
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(0));
+            let mut contract = ChainExtension::new();
+            assert!(contract.schedule_call(10, 1_000, None).is_ok());
+
+            // The owner scheduled the callback, which doesn't make it theirs to run...
+            contract.scheduler_handler(CALLBACK_ARG, 0);
+            // ...so the actual callback still goes through.
+            run_callback(&mut contract, 0);
+
+            assert!(matches!(
+                emitted_events()[..],
+                [
+                    Event::CallbackRejected(CallbackRejected { caller: rejected }),
+                    Event::SchedulerTriggered(SchedulerTriggered { caller: triggered, .. }),
+                ] if rejected == accounts.alice && triggered == accounts.django
+            ));
+        }
+
+        #[ink::test]
+        fn schedules_are_tracked_until_their_callback_runs() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let mut contract = ChainExtension::new();
+            assert!(contract.schedule_call(1, 1_000, None).is_ok());
+
+            assert_eq!(contract.pending_schedules(), [7]);
+            assert_eq!(
+                contract.schedule_info(7),
+                Some(ScheduleInfo {
+                    at: 1,
+                    arg: CALLBACK_ARG,
+                    selector: DEFAULT_CALLBACK_SELECTOR,
+                    handle: 7,
+                    address: (10, 0),
+                    preimage: None,
+                })
+            );
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            run_callback(&mut contract, 7);
+
+            assert!(contract.pending_schedules().is_empty());
+            assert_eq!(contract.schedule_info(7), None);
+        }
+
+        #[ink::test]
+        fn schedules_remember_when_they_went_by_hash() {
+            let callback = crate::ScheduledCallback {
+                handle: 7,
+                address: (1, 0),
+                preimage: Some(Hash::from([9; 32])),
+            };
+            register_mock(
+                0x0001_0001,
+                status::SUCCESS,
+                scale::Encode::encode(&callback),
+            );
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(contract.schedule_call(1, 1_000, None).ok(), Some((1, 0)));
+            assert_eq!(
+                contract.schedule_info(7).map(|info| info.preimage),
+                Some(Some(Hash::from([9; 32])))
+            );
+        }
+
+        #[ink::test]
+        fn schedules_can_be_pushed_back() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let input = register_mock(
+                0x0001_0007,
+                status::SUCCESS,
+                scale::Encode::encode(&(12u32, 0u32)),
+            );
+            let mut contract = ChainExtension::new();
+            assert!(contract.schedule_call(5, 1_000, None).is_ok());
+
+            assert_eq!(contract.reschedule_call(7, 11).ok(), Some(12));
+
+            // The runtime finds the call by its handle, and we remember where it went.
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, 7u64, 11u32)))
+            );
+            assert_eq!(contract.schedule_info(7).map(|info| info.at), Some(12));
+            assert_eq!(
+                contract.schedule_info(7).map(|info| info.address),
+                Some((12, 0))
+            );
+        }
+
+        #[ink::test]
+        fn schedules_can_only_be_pushed_back_by_the_owner() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let input = register_mock(
+                0x0001_0007,
+                status::SUCCESS,
+                scale::Encode::encode(&(12u32, 0u32)),
+            );
+            let mut contract = ChainExtension::new();
+            assert!(contract.schedule_call(5, 1_000, None).is_ok());
+
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.bob);
+            assert!(matches!(
+                contract.reschedule_call(7, 11),
+                Reported::Err(Error::NotOwner)
+            ));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.alice);
+            assert!(matches!(
+                contract.reschedule_call(8, 11),
+                Reported::Err(Error::UnknownSchedule)
+            ));
+            assert!(matches!(
+                contract.reschedule_call(7, 5),
+                Reported::Err(Error::RescheduleTooEarly)
+            ));
+
+            // None of these made it to the runtime.
+            assert_eq!(*input.borrow(), None);
+            assert_eq!(contract.schedule_info(7).map(|info| info.at), Some(5));
+        }
+
+        #[ink::test]
+        fn callbacks_which_already_ran_cannot_be_pushed_back() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            register_mock(0x0001_0007, status::UNKNOWN_CALLBACK, Vec::new());
+            let mut contract = ChainExtension::new();
+            assert!(contract
+                .schedule_call_to(5, [1, 2, 3, 4], 1_000, None)
+                .is_ok());
+
+            assert!(matches!(
+                contract.reschedule_call(7, 11),
+                Reported::Err(Error::Extension(ExtensionError::UnknownCallback))
+            ));
+        }
+
+        #[ink::test]
+        fn only_the_callback_which_ran_gets_cleared() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let mut contract = ChainExtension::new();
+            assert!(contract.schedule_call(1, 1_000, None).is_ok());
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(8));
+            assert!(contract.schedule_call(1, 1_000, None).is_ok());
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            run_callback(&mut contract, 8);
+
+            // Both were due, but only the one we scheduled last has run.
+            assert_eq!(contract.pending_schedules(), [7]);
+            assert!(contract.schedule_info(7).is_some());
+            assert_eq!(contract.schedule_info(8), None);
+        }
+
+        #[ink::test]
+        fn scheduler_handler_rejects_callbacks_to_other_messages() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let mut contract = ChainExtension::new();
+            assert!(contract
+                .schedule_call_to(1, [1, 2, 3, 4], 1_000, None)
+                .is_ok());
+
+            run_callback(&mut contract, 7);
+
+            assert!(matches!(emitted_events()[..], [Event::CallbackRejected(_)]));
+            assert_eq!(contract.pending_schedules(), [7]);
+        }
+
+        #[ink::test]
+        fn pending_schedules_are_bounded() {
+            let mut contract = ChainExtension::new();
+            for handle in 0..MAX_PENDING_SCHEDULES as u64 {
+                register_mock(0x0001_0001, status::SUCCESS, scheduled(handle));
+                assert!(contract.schedule_call(10, 1_000, None).is_ok());
+            }
+            let input = register_mock(0x0001_0001, status::SUCCESS, scheduled(99));
+
+            assert!(matches!(
+                contract.schedule_call(10, 1_000, None),
+                Reported::Err(Error::TooManySchedules)
+            ));
+            assert!(matches!(
+                contract.schedule_call_in(10, 1_000, None),
+                Reported::Err(Error::TooManySchedules)
+            ));
+            // Neither of these made it to the runtime.
+            assert_eq!(*input.borrow(), None);
+
+            // Once one of them has run there's room again.
+            run_callback(&mut contract, 0);
+            assert!(contract.schedule_call(10, 1_000, None).is_ok());
+            assert_eq!(contract.pending_schedules().len(), MAX_PENDING_SCHEDULES);
+        }
+
+        #[ink::test]
+        fn schedules_are_cleared_once_they_have_a_receipt() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let input = register_mock(
+                0x0001_0004,
+                status::SUCCESS,
+                scale::Encode::encode(&None::<crate::CallbackReceipt<BlockNumber, Hash>>),
+            );
+            let mut contract = ChainExtension::new();
+            assert!(contract
+                .schedule_call_to(1, [1, 2, 3, 4], 1_000, None)
+                .is_ok());
+
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.bob);
+            assert!(matches!(
+                contract.clear_schedule(7),
+                Err(Error::ScheduleStillPending)
+            ));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&7u64)));
+
+            let receipt = crate::CallbackReceipt {
+                block: 1,
+                weight: 1_000,
+                success: true,
+                output_hash: Hash::from([3; 32]),
+            };
+            register_mock(
+                0x0001_0004,
+                status::SUCCESS,
+                scale::Encode::encode(&Some(&receipt)),
+            );
+            assert!(contract.clear_schedule(7).is_ok());
+            assert!(contract.pending_schedules().is_empty());
+            assert!(matches!(
+                contract.clear_schedule(7),
+                Err(Error::UnknownSchedule)
+            ));
+        }
+
+        #[ink::test]
+        fn the_owner_can_clear_schedules_without_a_receipt() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let input = register_mock(0x0001_0004, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            assert!(contract
+                .schedule_call_to(1, [1, 2, 3, 4], 1_000, None)
+                .is_ok());
+
+            assert!(contract.clear_schedule(7).is_ok());
+
+            assert_eq!(*input.borrow(), None);
+            assert!(contract.pending_schedules().is_empty());
+            assert_eq!(contract.schedule_info(7), None);
+        }
+
+        #[ink::test]
+        fn failed_schedules_are_not_tracked() {
+            register_mock(0x0001_0001, status::AGENDA_FULL, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract.schedule_call(10, 1_000, None).is_err());
+            assert!(contract.pending_schedules().is_empty());
+        }
+
+        #[ink::test]
+        fn heartbeats_reschedule_themselves() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let mut contract = ChainExtension::new();
+            assert!(contract.start_heartbeat(2, 1_000, None).is_ok());
+            assert_eq!(contract.heartbeat_interval(), Some(2));
+
+            let input = register_mock(0x0001_0001, status::SUCCESS, scheduled(8));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            run_callback(&mut contract, 7);
+
+            assert_eq!(contract.pending_schedules(), [8]);
+            assert_eq!(contract.schedule_info(8).map(|info| info.at), Some(4));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(
+                    false,
+                    4u32,
+                    DEFAULT_CALLBACK_SELECTOR,
+                    1_000u64,
+                    None::<Balance>
+                )))
+            );
+        }
+
+        #[ink::test]
+        fn stopped_heartbeats_die_out() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let mut contract = ChainExtension::new();
+            assert!(contract.start_heartbeat(1, 1_000, None).is_ok());
+            assert!(contract.stop_heartbeat().is_ok());
+            assert_eq!(contract.heartbeat_interval(), None);
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            run_callback(&mut contract, 7);
+
+            assert!(contract.pending_schedules().is_empty());
+        }
+
+        #[ink::test]
+        fn restarted_heartbeats_only_keep_the_new_beat() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let mut contract = ChainExtension::new();
+            assert!(contract.start_heartbeat(1, 1_000, None).is_ok());
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(8));
+            assert!(contract.start_heartbeat(5, 1_000, None).is_ok());
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            run_callback(&mut contract, 7);
+
+            // The beat from the first heartbeat ran, but didn't schedule another one.
+            assert_eq!(contract.pending_schedules(), [8]);
+        }
+
+        #[ink::test]
+        fn heartbeats_stop_when_rescheduling_fails() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(7));
+            let mut contract = ChainExtension::new();
+            assert!(contract.start_heartbeat(1, 1_000, None).is_ok());
+
+            register_mock(0x0001_0001, status::SCHEDULING_QUOTA_EXCEEDED, Vec::new());
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            run_callback(&mut contract, 7);
+
+            assert_eq!(contract.heartbeat_interval(), None);
+            assert!(contract.pending_schedules().is_empty());
+        }
+
+        #[ink::test]
+        fn heartbeats_need_an_interval() {
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.start_heartbeat(0, 1_000, None),
+                Reported::Err(Error::InvalidInterval)
+            ));
+        }
+
+        #[ink::test]
+        fn only_the_owner_can_stop_the_heartbeat() {
+            let mut contract = ChainExtension::new();
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.bob);
+            assert!(matches!(contract.stop_heartbeat(), Err(Error::NotOwner)));
+        }
+
+        #[ink::test]
+        fn scheduler_triggered_can_be_filtered_by_block_and_caller() {
+            let caller = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>().django;
+            ink_env::test::set_callee::<ink_env::DefaultEnvironment>(caller);
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(0));
+            let mut contract = ChainExtension::new();
+            assert!(contract.schedule_call(10, 1_000, None).is_ok());
+
+            run_callback(&mut contract, 0);
+
+            assert!(matches!(
+                emitted_events()[..],
+                [Event::SchedulerTriggered(SchedulerTriggered { caller: c, .. })] if c == caller
+            ));
+            // One topic for the event itself, plus one each for `at` and `caller`.
+            let event = ink_env::test::recorded_events().next().unwrap();
+            assert_eq!(event.topics.len(), 3);
+        }
+
+        #[ink::test]
+        fn ownership_can_be_transferred() {
+            register_mock(0x0001_0001, status::SUCCESS, scheduled(0));
+            let mut contract = ChainExtension::new();
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+
+            assert!(contract.transfer_ownership(accounts.bob).is_ok());
+            assert_eq!(contract.owner(), accounts.bob);
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+
+            // The previous owner gave up their rights along with the contract.
+            assert!(matches!(
+                contract.transfer_ownership(accounts.alice),
+                Err(Error::NotOwner)
+            ));
+            assert!(matches!(
+                contract.schedule_call(10, 1_000, None),
+                Reported::Err(Error::NotOwner)
+            ));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.bob);
+            assert!(contract.schedule_call(10, 1_000, None).is_ok());
+        }
+
+        #[ink::test]
+        fn transfer_native_maps_existential_deposit_errors() {
+            register_mock(13, status::WOULD_REAP_SENDER, Vec::new());
+            let mut contract = ChainExtension::new();
+            let to = AccountId::from([2; 32]);
+
+            assert!(matches!(
+                contract.transfer_native(to, 100),
+                Reported::Err(ExtensionError::WouldReapSender)
+            ));
+        }
+
+        #[ink::test]
+        fn transfer_native_passes_arguments_through() {
+            let input = register_mock(13, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let to = AccountId::from([2; 32]);
+
+            assert!(contract.transfer_native(to, 100).is_ok());
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, to, 100u128))));
+        }
+
+        #[ink::test]
+        fn transfer_native_compact_sends_compact_amounts() {
+            let input = register_mock(36, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let to = AccountId::from([2; 32]);
+
+            assert!(contract.transfer_native_compact(to, 42).is_ok());
+
+            let expected = scale::Encode::encode(&(false, to, scale::Compact(42u128)));
+            // A `bool`, an `AccountId` and a single byte for the amount, which is small enough to
+            // fit into one.
+            assert_eq!(expected.len(), 1 + 32 + 1);
+            assert_eq!(*input.borrow(), Some(expected));
+        }
+
+        #[ink::test]
+        fn quote_passes_weight_through() {
+            let input = register_mock(14, status::SUCCESS, scale::Encode::encode(&1_000u64));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.quote(2, 64).ok(), Some(1_000));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(2u32, 64u32))));
+        }
+
+        #[ink::test]
+        fn envelopes_round_trip() {
+            let envelope = crate::Envelope {
+                owner: AccountId::from([1; 32]),
+                public_key: [7; 32],
+                expires_at: 10,
+                ciphertext: ink_prelude::vec![1, 2, 3],
+            };
+            register_mock(15, status::SUCCESS, scale::Encode::encode(&0u64));
+            register_mock(16, status::SUCCESS, scale::Encode::encode(&Some(&envelope)));
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(contract.seal([7; 32], 10, ink_prelude::vec![1, 2, 3]).ok(), Some(0));
+            assert_eq!(contract.envelope(0).ok(), Some(Some(envelope)));
+        }
+
+        #[ink::test]
+        fn asset_transfer_passes_arguments_through() {
+            let input = register_mock(18, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let to = AccountId::from([2; 32]);
+
+            assert!(contract.asset_transfer(1, to, 100).is_ok());
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, 1u32, to, 100u128))));
+        }
+
+        #[ink::test]
+        fn asset_errors_are_mapped() {
+            register_mock(18, status::ASSET_BALANCE_LOW, Vec::new());
+            register_mock(19, status::NOT_PERMITTED, Vec::new());
+            let mut contract = ChainExtension::new();
+            let to = AccountId::from([2; 32]);
+
+            assert!(matches!(
+                contract.asset_transfer(1, to, 100),
+                Reported::Err(ExtensionError::AssetBalanceLow)
+            ));
+            assert!(matches!(
+                contract.asset_mint(1, to, 100),
+                Reported::Err(ExtensionError::NotPermitted)
+            ));
+        }
+
+        #[ink::test]
+        fn commit_passes_arguments_through() {
+            let input = register_mock(20, status::SUCCESS, scale::Encode::encode(&7u64));
+            let mut contract = ChainExtension::new();
+            let hash = Hash::from([1; 32]);
+
+            assert_eq!(contract.commit(hash, 5, 10).ok(), Some(7));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&(false, hash, 5u32, 10u32))));
+        }
+
+        #[ink::test]
+        fn early_reveals_are_reported() {
+            register_mock(21, status::REVEAL_TOO_EARLY, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.reveal(0, ink_prelude::vec![1, 2, 3]),
+                Reported::Err(ExtensionError::RevealTooEarly)
+            ));
+        }
+
+        #[ink::test]
+        fn probes_are_simulated() {
+            let input = register_mock(18, status::ASSET_BALANCE_LOW, Vec::new());
+            let contract = ChainExtension::new();
+            let args = scale::Encode::encode(&(1u32, AccountId::from([2; 32]), 100u128));
+
+            assert!(matches!(
+                contract.probe(18, args.clone()),
+                Err(ExtensionError::AssetBalanceLow)
+            ));
+            assert_eq!(*input.borrow(), Some([&[1u8][..], &args[..]].concat()));
+
+            // Functions which don't change state can't be simulated.
+            assert!(matches!(
+                contract.probe(17, args),
+                Err(ExtensionError::UnknownFunction)
+            ));
+        }
+
+        #[ink::test]
+        fn nft_mint_passes_metadata_through() {
+            let input = register_mock(24, status::SUCCESS, Vec::new());
+            let mut contract = ChainExtension::new();
+            let owner = AccountId::from([2; 32]);
+            let metadata = b"ipfs://nft".to_vec();
+
+            assert!(contract.nft_mint(1, 7, owner, metadata.clone()).is_ok());
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, 1u32, 7u32, owner, metadata)))
+            );
+        }
+
+        #[ink::test]
+        fn nft_owners_come_from_the_runtime() {
+            let owner = AccountId::from([2; 32]);
+            register_mock(26, status::SUCCESS, scale::Encode::encode(&Some(owner)));
+            register_mock(25, status::UNKNOWN_NFT, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(contract.nft_owner_of(1, 7).ok(), Some(Some(owner)));
+            assert!(matches!(
+                contract.nft_transfer(1, 8, owner),
+                Reported::Err(ExtensionError::UnknownNft)
+            ));
+        }
+
+        #[ink::test]
+        fn failures_are_reported_through_events() {
+            register_mock(18, status::ASSET_BALANCE_LOW, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(contract.asset_transfer(1, AccountId::from([2; 32]), 100).is_err());
+
+            let events: Vec<_> = ink_env::test::recorded_events().collect();
+            assert_eq!(events.len(), 1);
+            type Event = <ChainExtension as ink_lang::reflect::ContractEventBase>::Type;
+            match scale::Decode::decode(&mut &events[0].data[..]) {
+                Ok(Event::ExtensionFailed(ExtensionFailed { error })) => {
+                    assert_eq!(error, crate::ErrorCode::new(crate::domain::ASSETS, 1).id())
+                }
+                _ => panic!("expected an `ExtensionFailed` event"),
+            }
+        }
+
+        #[ink::test]
+        fn reported_encodes_like_a_result() {
+            assert_eq!(
+                scale::Encode::encode(&Reported::<u32, u8>::Ok(7)),
+                scale::Encode::encode(&Ok::<u32, u8>(7))
+            );
+            assert_eq!(
+                scale::Encode::encode(&Reported::<u32, u8>::Err(3)),
+                scale::Encode::encode(&Err::<u32, u8>(3))
+            );
+        }
+
+        #[ink::test]
+        fn error_codes_are_stable() {
+            assert_eq!(ExtensionError::AgendaFull.error_code().id(), 0x0101);
+            assert_eq!(ExtensionError::InvalidCallbackSelector.error_code().id(), 0x0102);
+            assert_eq!(ExtensionError::CallbackGasLimitTooHigh.error_code().id(), 0x0103);
+            assert_eq!(ExtensionError::ScheduledInPast.error_code().id(), 0x0104);
+            assert_eq!(
+                ExtensionError::SchedulingQuotaExceeded.error_code().id(),
+                0x0106
+            );
+            assert_eq!(ExtensionError::CodeNotApproved.error_code().id(), 0x0006);
+            assert_eq!(ExtensionError::BatchTooLarge.error_code().id(), 0x0903);
+            assert_eq!(ExtensionError::DispatchFailed.error_code().id(), 0x0007);
+            assert_eq!(
+                ExtensionError::SimulateNotSupported.error_code().id(),
+                0x0008
+            );
+            assert_eq!(ExtensionError::UndecodableCall.error_code().id(), 0x0009);
+            assert_eq!(ExtensionError::WrongTimepoint.error_code().id(), 0x0B05);
+            assert_eq!(ExtensionError::NotAProxy.error_code().id(), 0x0C01);
+            assert_eq!(
+                ExtensionError::ReferendumNotOngoing.error_code().id(),
+                0x0D01
+            );
+            assert_eq!(
+                ExtensionError::InsufficientProposalBond.error_code().id(),
+                0x0E01
+            );
+            assert_eq!(ExtensionError::DuplicateMotion.error_code().id(), 0x0F02);
+            assert_eq!(ExtensionError::XcmUnroutable.error_code().id(), 0x1002);
+            assert_eq!(ExtensionError::CalleeTrapped.error_code().id(), 0x0A02);
+            assert_eq!(ExtensionError::InvalidReveal.error_code().id(), 0x0504);
+            assert_eq!(ExtensionError::CapabilityMissing.error_code().id(), 0xFE02);
+            assert_eq!(
+                ExtensionError::IdentityNotVerified.error_code().id(),
+                0xFE04
+            );
+            assert_eq!(ExtensionError::NotAMember.error_code().id(), 0xFE05);
+            assert_eq!(ExtensionError::Unknown(1337).error_code().id(), 0xFF00);
+        }
+
+        #[ink::test]
+        fn random_passes_subject_through() {
+            let hash = Hash::from([7; 32]);
+            let input = register_mock(27, status::SUCCESS, scale::Encode::encode(&hash));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.random(b"lottery".to_vec()), hash);
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&b"lottery".to_vec())));
+        }
+
+        #[ink::test]
+        fn counters_hand_out_runtime_values() {
+            let input = register_mock(29, status::SUCCESS, scale::Encode::encode(&3u64));
+            register_mock(31, status::NOT_PERMITTED, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert_eq!(contract.next_in_sequence(b"orders".to_vec()).ok(), Some(3));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, b"orders".to_vec())))
+            );
+            assert!(matches!(
+                contract.set_counter_access(b"orders".to_vec(), AccountId::from([2; 32]), true),
+                Reported::Err(ExtensionError::NotPermitted)
+            ));
+        }
+
+        #[ink::test]
+        fn signatures_are_checked_by_the_runtime() {
+            let input = register_mock(32, status::SUCCESS, Vec::new());
+            register_mock(33, status::INVALID_SIGNATURE, Vec::new());
+            let contract = ChainExtension::new();
+            let message = b"pay bob 10 units".to_vec();
+
+            assert_eq!(contract.verify_sr25519([1; 64], message.clone(), [2; 32]).ok(), Some(true));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&([1u8; 64], message.clone(), [2u8; 32])))
+            );
+            assert_eq!(contract.verify_ecdsa([1; 65], message, [2; 33]).ok(), Some(false));
+        }
+
+        #[ink::test]
+        fn callback_reservation_comes_from_the_runtime() {
+            let usage = crate::ReservationUsage {
+                reserved: 100,
+                used: 30,
+            };
+            register_mock(0x0001_0003, status::SUCCESS, scale::Encode::encode(&usage));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.callback_reservation_left().ok(), Some(70));
+        }
+
+        #[ink::test]
+        fn callback_deposit_comes_from_the_runtime() {
+            register_mock(0x0001_0005, status::SUCCESS, scale::Encode::encode(&500u128));
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.callback_deposit().ok(), Some(500));
+        }
+
+        #[ink::test]
+        fn insufficient_deposits_are_reported() {
+            register_mock(0x0001_0001, status::INSUFFICIENT_DEPOSIT, Vec::new());
+            let mut contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.schedule_call(10, 1_000, None),
+                Reported::Err(Error::Extension(ExtensionError::InsufficientDeposit))
+            ));
+        }
+
+        #[ink::test]
+        fn callback_receipts_come_from_the_runtime() {
+            let receipt = crate::CallbackReceipt {
+                block: 5,
+                weight: 1_000,
+                success: true,
+                output_hash: Hash::from([3; 32]),
+            };
+            let input = register_mock(
+                0x0001_0004,
+                status::SUCCESS,
+                scale::Encode::encode(&Some(&receipt)),
+            );
+            let contract = ChainExtension::new();
+
+            assert_eq!(contract.callback_receipt(7).ok(), Some(Some(receipt)));
+            assert_eq!(*input.borrow(), Some(scale::Encode::encode(&7u64)));
+        }
+
+        #[ink::test]
+        fn expired_envelopes_are_reported() {
+            register_mock(16, status::ENVELOPE_EXPIRED, Vec::new());
+            let contract = ChainExtension::new();
+
+            assert!(matches!(
+                contract.envelope(0),
+                Err(ExtensionError::EnvelopeExpired)
+            ));
         }
     }
 }
@@ -0,0 +1,403 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A PSP22 facade for an asset which lives in the runtime's `pallet_assets`.
+//!
+//! Balances and the total supply aren't tracked by this contract at all, they're read straight from
+//! the runtime through our chain extension. The same goes for transfers, which move the asset
+//! between accounts in `pallet_assets`. That way the asset can be used both through this contract
+//! and through `pallet_assets` directly, without the two ever disagreeing.
+//!
+//! The one catch is that the runtime only lets us move assets out of an account which has approved
+//! this contract as a delegate through `pallet_assets::approve_transfer`. Users need to do that
+//! once, for at least as much as they intend to move through this contract, be it with `transfer`
+//! or by way of a PSP22 allowance and `transfer_from`.
+
+use ink_lang as ink;
+
+use chain_extension::ExtensionError;
+use ink_env::AccountId;
+use ink_prelude::{
+    string::String,
+    vec::Vec,
+};
+
+/// The runtime's balance type, which is what `pallet_assets` keeps track of assets in too.
+type Balance = <ink_env::DefaultEnvironment as ink_env::Environment>::Balance;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    /// Something went wrong which isn't covered by the standard errors.
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+impl From<ExtensionError> for PSP22Error {
+    fn from(e: ExtensionError) -> Self {
+        match e {
+            ExtensionError::AssetBalanceLow => Self::InsufficientBalance,
+            // The owner hasn't approved us through `pallet_assets`, which is different from them
+            // not having given the caller a PSP22 allowance.
+            ExtensionError::AssetUnapproved => {
+                Self::Custom(String::from("owner hasn't approved this contract in pallet_assets"))
+            }
+            e => Self::Custom(ink_prelude::format!("{:?}", e)),
+        }
+    }
+}
+
+/// The PSP22 fungible token standard.
+///
+/// The selectors of the messages are derived from the trait's name, so they match those of every
+/// other PSP22 implementation.
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// The total amount of the token in existence.
+    #[ink(message)]
+    fn total_supply(&self) -> Balance;
+
+    /// How much of the token `owner` holds.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    /// How much of `owner`'s tokens `spender` may still move with `transfer_from`.
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+    /// Moves `value` of the caller's tokens to `to`.
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Moves `value` of `from`'s tokens to `to`, using up some of the caller's allowance.
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Allows `spender` to move up to `value` of the caller's tokens.
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn increase_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: Balance,
+    ) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn decrease_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: Balance,
+    ) -> Result<(), PSP22Error>;
+}
+
+#[ink::contract(env = chain_extension::CustomEnvironment)]
+mod psp22_assets {
+    use super::{
+        PSP22Error,
+        PSP22,
+    };
+    use chain_extension::AssetId;
+    // Every contract using our `CustomEnvironment` can emit events through it, so we need to say
+    // which contract's events these are.
+    use ink_lang::codegen::EmitEvent;
+    use ink_prelude::vec::Vec;
+    use ink_storage::{
+        traits::SpreadAllocate,
+        Mapping,
+    };
+
+    #[ink(storage)]
+    #[derive(SpreadAllocate)]
+    pub struct Psp22Assets {
+        /// The asset in `pallet_assets` we're a facade for.
+        asset: AssetId,
+        /// PSP22 allowances, keyed by owner and then spender.
+        ///
+        /// These only exist in this contract, the runtime doesn't know about them.
+        allowances: Mapping<(AccountId, AccountId), Balance>,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    impl Psp22Assets {
+        #[ink(constructor)]
+        pub fn new(asset: AssetId) -> Self {
+            ink_lang::utils::initialize_contract(|contract: &mut Self| {
+                contract.asset = asset;
+            })
+        }
+
+        /// The asset in `pallet_assets` we're a facade for.
+        #[ink(message)]
+        pub fn asset(&self) -> AssetId {
+            self.asset
+        }
+
+        fn transfer_approved(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<(), PSP22Error> {
+            self.env()
+                .extension()
+                .main
+                .asset_transfer_approved(false, self.asset, from, to, value)?;
+
+            EmitEvent::<Psp22Assets>::emit_event(self.env(), Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        fn set_allowance(&mut self, owner: AccountId, spender: AccountId, value: Balance) {
+            self.allowances.insert((owner, spender), &value);
+            EmitEvent::<Psp22Assets>::emit_event(self.env(), Approval {
+                owner,
+                spender,
+                value,
+            });
+        }
+    }
+
+    impl PSP22 for Psp22Assets {
+        /// @chain-extension func_id=22 pallets=Assets status_codes=0,2
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.env()
+                .extension()
+                .main
+                .asset_total_supply(self.asset)
+                .expect("the runtime doesn't support assets")
+        }
+
+        /// @chain-extension func_id=17 pallets=Assets status_codes=0,2
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.env()
+                .extension()
+                .main
+                .asset_balance(self.asset, owner)
+                .expect("the runtime doesn't support assets")
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or_default()
+        }
+
+        /// @chain-extension func_id=23 pallets=Assets status_codes=0,2,6,10,11,12,13,18
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let from = self.env().caller();
+            self.transfer_approved(from, to, value)
+        }
+
+        /// @chain-extension func_id=23 pallets=Assets status_codes=0,2,6,10,11,12,13,18
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let spender = self.env().caller();
+            let allowance = self.allowance(from, spender);
+            if allowance < value {
+                return Err(PSP22Error::InsufficientAllowance)
+            }
+
+            self.transfer_approved(from, to, value)?;
+            self.set_allowance(from, spender, allowance - value);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self.allowance(owner, spender).saturating_add(delta_value);
+            self.set_allowance(owner, spender, allowance);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self
+                .allowance(owner, spender)
+                .checked_sub(delta_value)
+                .ok_or(PSP22Error::InsufficientAllowance)?;
+            self.set_allowance(owner, spender, allowance);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use chain_extension::status;
+        use ink_lang as ink;
+        use std::{
+            cell::RefCell,
+            rc::Rc,
+        };
+
+        /// A stand-in for one of the runtime's extension functions, see the tests of our main demo
+        /// contract.
+        struct MockExtension {
+            func_id: u32,
+            status: u32,
+            output: Vec<u8>,
+            input: Rc<RefCell<Option<Vec<u8>>>>,
+        }
+
+        impl ink_env::test::ChainExtension for MockExtension {
+            fn func_id(&self) -> u32 {
+                self.func_id
+            }
+
+            fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+                // The off-chain environment encodes the input once more before handing it to us,
+                // unlike the runtime which gets it as is.
+                let input = <Vec<u8> as scale::Decode>::decode(&mut &input[..])
+                    .expect("the off-chain environment encodes the input as a `Vec<u8>`; qed");
+                *self.input.borrow_mut() = Some(input);
+                output.extend_from_slice(&self.output);
+                self.status
+            }
+        }
+
+        fn register_mock(
+            func_id: u32,
+            status: u32,
+            output: Vec<u8>,
+        ) -> Rc<RefCell<Option<Vec<u8>>>> {
+            let input = Rc::new(RefCell::new(None));
+            ink_env::test::register_chain_extension(MockExtension {
+                func_id,
+                status,
+                output,
+                input: input.clone(),
+            });
+            input
+        }
+
+        fn accounts() -> ink_env::test::DefaultAccounts<ink_env::DefaultEnvironment> {
+            ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+        }
+
+        #[test]
+        fn annotations_match_the_manifest() {
+            let mismatches =
+                chain_extension::manifest::annotation_mismatches(include_str!("lib.rs"));
+            assert!(mismatches.is_empty(), "{:#?}", mismatches);
+        }
+
+        #[ink::test]
+        fn balances_come_from_the_runtime() {
+            let input = register_mock(17, status::SUCCESS, scale::Encode::encode(&42u128));
+            let contract = Psp22Assets::new(1);
+
+            assert_eq!(contract.balance_of(accounts().bob), 42);
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(1u32, accounts().bob)))
+            );
+        }
+
+        #[ink::test]
+        fn transfers_move_the_callers_assets() {
+            let input = register_mock(23, status::SUCCESS, Vec::new());
+            let mut contract = Psp22Assets::new(1);
+            let accounts = accounts();
+
+            assert_eq!(contract.transfer(accounts.bob, 10, Vec::new()), Ok(()));
+            assert_eq!(
+                *input.borrow(),
+                Some(scale::Encode::encode(&(false, 1u32, accounts.alice, accounts.bob, 10u128)))
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn low_balances_are_reported() {
+            register_mock(23, status::ASSET_BALANCE_LOW, Vec::new());
+            let mut contract = Psp22Assets::new(1);
+
+            assert_eq!(
+                contract.transfer(accounts().bob, 10, Vec::new()),
+                Err(PSP22Error::InsufficientBalance)
+            );
+        }
+
+        #[ink::test]
+        fn transfer_from_uses_up_allowances() {
+            register_mock(23, status::SUCCESS, Vec::new());
+            let mut contract = Psp22Assets::new(1);
+            let accounts = accounts();
+
+            assert_eq!(contract.approve(accounts.bob, 10), Ok(()));
+
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.transfer_from(accounts.alice, accounts.charlie, 11, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                contract.transfer_from(accounts.alice, accounts.charlie, 6, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(contract.allowance(accounts.alice, accounts.bob), 4);
+        }
+    }
+}
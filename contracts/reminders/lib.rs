@@ -0,0 +1,245 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A multi-user take on our scheduling demo.
+//!
+//! Any user can ask for a reminder at some future block. When that block comes around the runtime
+//! calls back into our `dispatch` message, which figures out whose reminders are due and emits a
+//! `ReminderDue` event for each of them. Since the owner is an indexed topic, each user can listen
+//! for just their own reminders.
+
+use ink_lang as ink;
+
+#[ink::contract(env = chain_extension::CustomEnvironment)]
+mod reminders {
+    use chain_extension::ExtensionError;
+    // Every contract using our `CustomEnvironment` can emit events through it, so we need to say
+    // which contract's events these are.
+    use ink_lang::codegen::EmitEvent;
+    use ink_prelude::vec::Vec;
+    use ink_storage::{
+        traits::{
+            PackedLayout,
+            SpreadAllocate,
+            SpreadLayout,
+        },
+        Mapping,
+    };
+
+    pub type ReminderId = u32;
+
+    /// The gas our callbacks get to use. This needs to cover every reminder which is due in the
+    /// same block.
+    const CALLBACK_GAS_LIMIT: u64 = 10_000_000_000;
+
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub struct Reminder {
+        owner: AccountId,
+        at: BlockNumber,
+        /// We only keep the hash of whatever the user wants to be reminded about, that way we
+        /// don't need to pay for storing arbitrarily large payloads.
+        payload_hash: Hash,
+    }
+
+    #[ink(storage)]
+    #[derive(SpreadAllocate)]
+    pub struct Reminders {
+        next_id: ReminderId,
+        reminders: Mapping<ReminderId, Reminder>,
+        /// The reminders which need to fire at a given block. This is how `dispatch` figures out
+        /// what to do, since the runtime doesn't tell it which reminder it's being called for.
+        due: Mapping<BlockNumber, Vec<ReminderId>>,
+        /// The reminders each user currently has pending.
+        owned: Mapping<AccountId, Vec<ReminderId>>,
+        /// The blocks in `due` which haven't been dispatched yet.
+        ///
+        /// The runtime may call us back a few blocks later than we asked for if the block we
+        /// wanted was full, so `dispatch` can't just look at the current block.
+        scheduled: Vec<BlockNumber>,
+    }
+
+    #[ink(event)]
+    pub struct ReminderScheduled {
+        #[ink(topic)]
+        owner: AccountId,
+        id: ReminderId,
+        at: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct ReminderCancelled {
+        #[ink(topic)]
+        owner: AccountId,
+        id: ReminderId,
+    }
+
+    #[ink(event)]
+    pub struct ReminderDue {
+        #[ink(topic)]
+        owner: AccountId,
+        id: ReminderId,
+        payload_hash: Hash,
+    }
+
+    #[derive(Debug, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// There's no pending reminder with the given ID.
+        NotFound,
+        /// Only the user who scheduled a reminder may cancel it.
+        NotOwner,
+        /// We've handed out every `ReminderId` there is.
+        TooManyReminders,
+        /// The chain extension wasn't able to schedule our callback.
+        Extension(ExtensionError),
+    }
+
+    impl From<ExtensionError> for Error {
+        fn from(e: ExtensionError) -> Self {
+            Self::Extension(e)
+        }
+    }
+
+    impl Reminders {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            ink_lang::utils::initialize_contract(|_: &mut Self| {})
+        }
+
+        /// Schedules a reminder for the caller at block `at`.
+        ///
+        /// @chain-extension func_id=65537 pallets=Scheduler,Contracts,Template,Balances status_codes=0,2,5,28,29,31,32,38,39,42
+        #[ink(message)]
+        pub fn schedule_reminder(
+            &mut self,
+            at: BlockNumber,
+            payload_hash: Hash,
+        ) -> Result<ReminderId, Error> {
+            let owner = self.env().caller();
+            let id = self.next_id;
+            self.next_id = id.checked_add(1).ok_or(Error::TooManyReminders)?;
+
+            // We only need one callback per block, no matter how many users want a reminder then.
+            let mut due = match self.due.get(at) {
+                Some(due) => due,
+                None => {
+                    self.env().extension().scheduler.schedule_call(
+                        false,
+                        at,
+                        chain_extension::DEFAULT_CALLBACK_SELECTOR,
+                        CALLBACK_GAS_LIMIT,
+                        None,
+                    )?;
+                    self.scheduled.push(at);
+                    Vec::new()
+                }
+            };
+            due.push(id);
+            self.due.insert(at, &due);
+
+            let mut owned = self.owned.get(owner).unwrap_or_default();
+            owned.push(id);
+            self.owned.insert(owner, &owned);
+
+            self.reminders.insert(
+                id,
+                &Reminder {
+                    owner,
+                    at,
+                    payload_hash,
+                },
+            );
+
+            EmitEvent::<Reminders>::emit_event(self.env(), ReminderScheduled { owner, id, at });
+            Ok(id)
+        }
+
+        /// Cancels one of the caller's pending reminders.
+        ///
+        /// The callback stays scheduled in the runtime, but once it fires it won't find this
+        /// reminder anymore.
+        #[ink(message)]
+        pub fn cancel_reminder(&mut self, id: ReminderId) -> Result<(), Error> {
+            let reminder = self.reminders.get(id).ok_or(Error::NotFound)?;
+            if reminder.owner != self.env().caller() {
+                return Err(Error::NotOwner)
+            }
+
+            self.remove(id, &reminder);
+            EmitEvent::<Reminders>::emit_event(self.env(), ReminderCancelled {
+                owner: reminder.owner,
+                id,
+            });
+            Ok(())
+        }
+
+        /// The reminders `owner` currently has pending.
+        #[ink(message)]
+        pub fn reminders_of(&self, owner: AccountId) -> Vec<ReminderId> {
+            self.owned.get(owner).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn reminder(&self, id: ReminderId) -> Option<Reminder> {
+            self.reminders.get(id)
+        }
+
+        /// This is what the runtime calls once a scheduled block is reached. It uses the default
+        /// callback selector, the same as the `scheduler_handler` of our main demo contract, so
+        /// it works without governance having to allow anything.
+        #[ink(message, selector = 0xC0FFEE)]
+        pub fn dispatch(&mut self, _arg: u32) {
+            let now = self.env().block_number();
+            let (ready, pending): (Vec<_>, Vec<_>) = core::mem::take(&mut self.scheduled)
+                .into_iter()
+                .partition(|at| *at <= now);
+            self.scheduled = pending;
+
+            for at in ready {
+                for id in self.due.get(at).unwrap_or_default() {
+                    // Cancelled reminders are already gone, so there's nothing to do for them.
+                    if let Some(reminder) = self.reminders.get(id) {
+                        self.remove(id, &reminder);
+                        EmitEvent::<Reminders>::emit_event(self.env(), ReminderDue {
+                            owner: reminder.owner,
+                            id,
+                            payload_hash: reminder.payload_hash,
+                        });
+                    }
+                }
+                self.due.remove(at);
+            }
+        }
+
+        fn remove(&mut self, id: ReminderId, reminder: &Reminder) {
+            self.reminders.remove(id);
+
+            let mut owned = self.owned.get(reminder.owner).unwrap_or_default();
+            owned.retain(|owned_id| *owned_id != id);
+            if owned.is_empty() {
+                self.owned.remove(reminder.owner);
+            } else {
+                self.owned.insert(reminder.owner, &owned);
+            }
+
+            // Note that we keep the (possibly empty) entry around, a callback for this block is
+            // still scheduled in the runtime after all.
+            let mut due = self.due.get(reminder.at).unwrap_or_default();
+            due.retain(|due_id| *due_id != id);
+            self.due.insert(reminder.at, &due);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        #[test]
+        fn annotations_match_the_manifest() {
+            let mismatches =
+                chain_extension::manifest::annotation_mismatches(include_str!("lib.rs"));
+            assert!(mismatches.is_empty(), "{:#?}", mismatches);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Deploys and drives our demo contract on a local node, as a scriptable alternative to the
+//! Contracts UI.
+//!
+//! ```bash
+//! cargo run -p demo-cli -- deploy
+//! cargo run -p demo-cli -- --contract <address> write 42
+//! ```
+
+use std::path::PathBuf;
+
+use chain_extension_primitives::Measure;
+use clap::Parser;
+use demo_client::{
+    DemoClient,
+    Result,
+    DEFAULT_URL,
+    GAS_LIMIT,
+};
+use subxt::sp_core::{
+    crypto::AccountId32,
+    sr25519,
+    Pair,
+    H256,
+};
+
+#[derive(Debug, clap::Parser)]
+#[clap(about)]
+struct Cli {
+    /// The websocket endpoint of the node to talk to.
+    #[clap(long, default_value = DEFAULT_URL)]
+    url: String,
+
+    /// The secret URI of the account to sign with.
+    #[clap(long, default_value = "//Alice")]
+    suri: String,
+
+    /// The address of the contract to call, as printed by `deploy`.
+    #[clap(long)]
+    contract: Option<AccountId32>,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Uploads and instantiates the demo contract, printing its address.
+    Deploy {
+        /// The contract's code. Defaults to the output of `cargo contract build`.
+        #[clap(long)]
+        wasm: Option<PathBuf>,
+    },
+
+    /// Has the contract write `value` to `pallet_template`.
+    Write { value: u32 },
+
+    /// Has the contract store what `measure` makes of `bytes`, given as hex.
+    Custom {
+        bytes: String,
+
+        #[clap(long, arg_enum, default_value = "length")]
+        measure: MeasureArg,
+    },
+
+    /// Has the contract schedule a call to itself at block `at`.
+    Schedule {
+        at: u32,
+
+        /// How much gas the scheduled call may use.
+        #[clap(long, default_value_t = GAS_LIMIT / 10)]
+        gas_limit: u64,
+    },
+}
+
+/// The parts of [`Measure`] which don't need an argument.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ArgEnum)]
+enum MeasureArg {
+    Length,
+    Sum,
+}
+
+impl From<MeasureArg> for Measure {
+    fn from(measure: MeasureArg) -> Self {
+        match measure {
+            MeasureArg::Length => Measure::Length,
+            MeasureArg::Sum => Measure::Sum,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let signer = sr25519::Pair::from_string(&cli.suri, None)
+        .map_err(|e| format!("invalid --suri: {:?}", e))?;
+    let client = DemoClient::connect(&cli.url, signer).await?;
+    let contract = || cli.contract.clone().ok_or("--contract is required");
+
+    match cli.command {
+        Command::Deploy { wasm } => {
+            let path = wasm.unwrap_or_else(|| {
+                PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("../contracts/chain_extension/target/ink/chain_extension.wasm")
+            });
+            let code = std::fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+            println!("{}", client.deploy(code).await?);
+        }
+        Command::Write { value } => {
+            let block = client.write_to_storage(&contract()?, value).await?;
+            report(&client, block).await?;
+        }
+        Command::Custom { bytes, measure } => {
+            let payload = hex::decode(bytes.trim_start_matches("0x"))?;
+            let block = client
+                .call(
+                    &contract()?,
+                    "rich_custom_type_with_result",
+                    (Measure::from(measure), None::<AccountId32>, payload),
+                )
+                .await?;
+            report(&client, block).await?;
+        }
+        Command::Schedule { at, gas_limit } => {
+            let block = client.schedule_call(&contract()?, at, gas_limit).await?;
+            println!("included in block {:?}, the call is due at block {}", block, at);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints which block a call made it into and what `pallet_template` holds as of that block.
+async fn report(client: &DemoClient, block: H256) -> Result<()> {
+    println!("included in block {:?}", block);
+    println!("stored value: {:?}", client.stored_value(Some(block)).await?);
+    Ok(())
+}
@@ -0,0 +1,393 @@
+//! Helpers for running end-to-end tests against a real `substrate-contracts-node`.
+//!
+//! The tests in `tests/` need two things which we can't build from here:
+//!
+//! - A node binary. By default we look for `target/release/substrate-contracts-node`, this can be
+//!   overridden with the `CONTRACTS_NODE` environment variable.
+//! - Our demo contract, built with `cargo contract build`. By default we look for
+//!   `contracts/chain_extension/target/ink/chain_extension.contract`, this can be overridden with
+//!   the `CHAIN_EXTENSION_CONTRACT` environment variable.
+
+use codec::{
+    Decode,
+    Encode,
+};
+use contracts_node_runtime as runtime;
+use jsonrpsee::{
+    core::client::{
+        ClientT,
+        SubscriptionClientT,
+    },
+    rpc_params,
+    ws_client::{
+        WsClient,
+        WsClientBuilder,
+    },
+};
+use runtime::{
+    AccountId,
+    BlockNumber,
+    Hash,
+};
+use sp_core::{
+    hashing::twox_128,
+    Pair,
+};
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::generic::Era;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    process::{
+        Child,
+        Command,
+        Stdio,
+    },
+    time::Duration,
+};
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub type EventRecord = frame_system::EventRecord<runtime::Event, Hash>;
+
+/// More than enough gas for any of our messages.
+pub const GAS_LIMIT: u64 = 100_000_000_000;
+
+fn workspace_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join(path)
+}
+
+/// A node running in `--dev` mode on a temporary database, which gets killed once this is dropped.
+pub struct Node {
+    process: Child,
+    client: WsClient,
+}
+
+impl Node {
+    /// Spawns a fresh node listening on `ws_port` and connects to it.
+    pub async fn spawn(ws_port: u16) -> Result<Self> {
+        let binary = std::env::var("CONTRACTS_NODE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| workspace_path("target/release/substrate-contracts-node"));
+
+        let process = Command::new(&binary)
+            .args(["--dev", "--tmp", "--ws-port", &ws_port.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("failed to spawn {}: {}", binary.display(), e))?;
+
+        // The node takes a moment before it starts accepting connections.
+        let url = format!("ws://127.0.0.1:{}", ws_port);
+        let mut attempts = 0;
+        let client = loop {
+            match WsClientBuilder::default().build(&url).await {
+                Ok(client) => break client,
+                Err(_) if attempts < 50 => {
+                    attempts += 1;
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        Ok(Self { process, client })
+    }
+
+    pub async fn block_hash(&self, number: Option<BlockNumber>) -> Result<Hash> {
+        Ok(self
+            .client
+            .request("chain_getBlockHash", rpc_params![number])
+            .await?)
+    }
+
+    pub async fn block_number(&self, hash: Hash) -> Result<BlockNumber> {
+        let header: runtime::Header = self
+            .client
+            .request("chain_getHeader", rpc_params![hash])
+            .await?;
+        Ok(header.number)
+    }
+
+    /// Signs and submits `call`, returning the hash of the block it was included in.
+    ///
+    /// Our node uses instant seal, so every extrinsic gets a block of its own.
+    pub async fn submit(&self, signer: Sr25519Keyring, call: runtime::Call) -> Result<Hash> {
+        let account: AccountId = signer.to_account_id();
+        let nonce: runtime::Index = self
+            .client
+            .request("system_accountNextIndex", rpc_params![account.to_string()])
+            .await?;
+        let genesis_hash = self.block_hash(Some(0)).await?;
+
+        let extra: runtime::SignedExtra = (
+            frame_system::CheckNonZeroSender::<runtime::Runtime>::new(),
+            frame_system::CheckSpecVersion::<runtime::Runtime>::new(),
+            frame_system::CheckTxVersion::<runtime::Runtime>::new(),
+            frame_system::CheckGenesis::<runtime::Runtime>::new(),
+            frame_system::CheckEra::<runtime::Runtime>::from(Era::Immortal),
+            frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
+            frame_system::CheckWeight::<runtime::Runtime>::new(),
+            pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+        );
+        let payload = runtime::SignedPayload::from_raw(
+            call.clone(),
+            extra.clone(),
+            (
+                (),
+                runtime::VERSION.spec_version,
+                runtime::VERSION.transaction_version,
+                genesis_hash,
+                genesis_hash,
+                (),
+                (),
+                (),
+            ),
+        );
+        let signature = payload.using_encoded(|e| signer.pair().sign(e));
+        let extrinsic = runtime::UncheckedExtrinsic::new_signed(
+            call,
+            account.into(),
+            runtime::Signature::Sr25519(signature),
+            extra,
+        );
+
+        let mut status = self
+            .client
+            .subscribe::<serde_json::Value>(
+                "author_submitAndWatchExtrinsic",
+                rpc_params![format!("0x{}", hex::encode(extrinsic.encode()))],
+                "author_unwatchExtrinsic",
+            )
+            .await?;
+
+        while let Some(update) = status.next().await {
+            if let Some(hash) = update?.get("inBlock") {
+                return Ok(serde_json::from_value(hash.clone())?)
+            }
+        }
+
+        Err("extrinsic was never included in a block".into())
+    }
+
+    /// Produces blocks until we're at block `number`.
+    pub async fn advance_to(&self, number: BlockNumber) -> Result<()> {
+        while self.block_number(self.block_hash(None).await?).await? < number {
+            self.submit(
+                Sr25519Keyring::Bob,
+                runtime::SystemCall::remark { remark: vec![] }.into(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Reads and decodes the value of a plain (i.e non-map) storage item.
+    pub async fn storage_value<T: Decode>(
+        &self,
+        pallet: &str,
+        item: &str,
+        at: Hash,
+    ) -> Result<Option<T>> {
+        let key = [twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat();
+        let value: Option<sp_core::Bytes> = self
+            .client
+            .request(
+                "state_getStorage",
+                rpc_params![sp_core::Bytes(key), at],
+            )
+            .await?;
+
+        value
+            .map(|value| T::decode(&mut &value[..]).map_err(Into::into))
+            .transpose()
+    }
+
+    /// What `pallet_template` stored as of the given block, read through the node's
+    /// `template_storedValue` RPC.
+    pub async fn stored_value(&self, at: Hash) -> Result<Option<u32>> {
+        Ok(self
+            .client
+            .request("template_storedValue", rpc_params![at])
+            .await?)
+    }
+
+    /// All of the events emitted in the given block.
+    pub async fn events(&self, at: Hash) -> Result<Vec<EventRecord>> {
+        Ok(self
+            .storage_value("System", "Events", at)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// The events emitted by `contract` in the given block, still SCALE encoded.
+    pub async fn contract_events(&self, contract: &AccountId, at: Hash) -> Result<Vec<Vec<u8>>> {
+        Ok(self
+            .events(at)
+            .await?
+            .into_iter()
+            .filter_map(|record| {
+                match record.event {
+                    runtime::Event::Contracts(pallet_contracts::Event::ContractEmitted {
+                        contract: emitter,
+                        data,
+                    }) if &emitter == contract => Some(data),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    /// Uploads and instantiates `bundle` using its `new` constructor, returning the address of
+    /// the new contract.
+    pub async fn deploy(&self, signer: Sr25519Keyring, bundle: &ContractBundle) -> Result<AccountId> {
+        let call = runtime::Call::Contracts(pallet_contracts::Call::instantiate_with_code {
+            value: 0,
+            gas_limit: GAS_LIMIT,
+            storage_deposit_limit: None,
+            code: bundle.wasm.clone(),
+            data: bundle.constructor("new")?.to_vec(),
+            salt: Vec::new(),
+        });
+        let block = self.submit(signer, call).await?;
+
+        self.events(block)
+            .await?
+            .into_iter()
+            .find_map(|record| {
+                match record.event {
+                    runtime::Event::Contracts(pallet_contracts::Event::Instantiated {
+                        contract,
+                        ..
+                    }) => Some(contract),
+                    _ => None,
+                }
+            })
+            .ok_or_else(|| "contract wasn't instantiated".into())
+    }
+
+    /// Calls the message with the given `selector` on `contract`, returning the hash of the block
+    /// the call was included in.
+    pub async fn call(
+        &self,
+        signer: Sr25519Keyring,
+        contract: &AccountId,
+        selector: [u8; 4],
+        args: impl Encode,
+    ) -> Result<Hash> {
+        let mut data = selector.to_vec();
+        args.encode_to(&mut data);
+
+        let call = runtime::Call::Contracts(pallet_contracts::Call::call {
+            dest: contract.clone().into(),
+            value: 0,
+            gas_limit: GAS_LIMIT,
+            storage_deposit_limit: None,
+            data,
+        });
+        self.submit(signer, call).await
+    }
+
+    /// Dry-runs the message with the given `selector` on `contract` through the `contracts_call`
+    /// RPC and returns the raw output of the contract.
+    pub async fn dry_run(
+        &self,
+        signer: Sr25519Keyring,
+        contract: &AccountId,
+        selector: [u8; 4],
+        args: impl Encode,
+    ) -> Result<Vec<u8>> {
+        let mut data = selector.to_vec();
+        args.encode_to(&mut data);
+
+        let request = serde_json::json!({
+            "origin": signer.to_account_id().to_string(),
+            "dest": contract.to_string(),
+            "value": 0,
+            "gasLimit": GAS_LIMIT,
+            "storageDepositLimit": null,
+            "inputData": format!("0x{}", hex::encode(data)),
+        });
+        let response: serde_json::Value = self
+            .client
+            .request("contracts_call", rpc_params![request])
+            .await?;
+
+        let output = response["result"]["Ok"]["data"]
+            .as_str()
+            .ok_or_else(|| format!("contract call failed: {}", response))?;
+        Ok(hex::decode(output.trim_start_matches("0x"))?)
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+    }
+}
+
+/// The parts of a `.contract` bundle produced by `cargo contract build` that we care about.
+pub struct ContractBundle {
+    pub wasm: Vec<u8>,
+    selectors: HashMap<String, [u8; 4]>,
+}
+
+impl ContractBundle {
+    /// Loads our demo contract's bundle.
+    pub fn chain_extension() -> Result<Self> {
+        let path = std::env::var("CHAIN_EXTENSION_CONTRACT")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                workspace_path("contracts/chain_extension/target/ink/chain_extension.contract")
+            });
+        Self::load(&path)
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let bundle: serde_json::Value = serde_json::from_slice(
+            &std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        )?;
+
+        let wasm = bundle["source"]["wasm"]
+            .as_str()
+            .ok_or("bundle is missing its wasm")?;
+        let wasm = hex::decode(wasm.trim_start_matches("0x"))?;
+
+        // Depending on the version of `cargo-contract` the spec may or may not be nested under a
+        // version key.
+        let spec = bundle.get("V3").unwrap_or(&bundle)["spec"].clone();
+
+        let mut selectors = HashMap::new();
+        for kind in ["constructors", "messages"] {
+            for entry in spec[kind].as_array().into_iter().flatten() {
+                let label = entry["label"].as_str().ok_or("entry without a label")?;
+                let selector = entry["selector"].as_str().ok_or("entry without a selector")?;
+                let selector = hex::decode(selector.trim_start_matches("0x"))?;
+                selectors.insert(
+                    format!("{}::{}", kind, label),
+                    selector
+                        .try_into()
+                        .map_err(|_| format!("bad selector for {}", label))?,
+                );
+            }
+        }
+
+        Ok(Self { wasm, selectors })
+    }
+
+    pub fn constructor(&self, label: &str) -> Result<[u8; 4]> {
+        self.selector("constructors", label)
+    }
+
+    pub fn message(&self, label: &str) -> Result<[u8; 4]> {
+        self.selector("messages", label)
+    }
+
+    fn selector(&self, kind: &str, label: &str) -> Result<[u8; 4]> {
+        self.selectors
+            .get(&format!("{}::{}", kind, label))
+            .copied()
+            .ok_or_else(|| format!("no {} called {}", kind, label).into())
+    }
+}
@@ -0,0 +1,89 @@
+//! These tests need a node binary and a built contract, see the crate docs for details. Since
+//! that's not something a plain `cargo test` can provide they're ignored by default, run them with
+//! `cargo test -p e2e-tests -- --ignored`.
+//!
+//! Each test spawns its own node, so they use different ports in order to be able to run in
+//! parallel.
+
+use codec::Encode;
+use contracts_node_runtime::BlockNumber;
+use e2e_tests::{
+    ContractBundle,
+    Node,
+    Result,
+    GAS_LIMIT,
+};
+use sp_keyring::Sr25519Keyring::Alice;
+
+/// The argument our runtime passes along to `scheduler_handler`.
+const MAGIC_NUMBER: u32 = 15663040;
+
+#[tokio::test]
+#[ignore = "requires a contracts node and a built contract"]
+async fn write_to_storage_works() -> Result<()> {
+    let node = Node::spawn(9950).await?;
+    let bundle = ContractBundle::chain_extension()?;
+    let contract = node.deploy(Alice, &bundle).await?;
+
+    let block = node
+        .call(Alice, &contract, bundle.message("write_to_storage")?, 42u32)
+        .await?;
+
+    let something = node.stored_value(block).await?;
+    assert_eq!(something, Some(42));
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires a contracts node and a built contract"]
+async fn custom_type_with_result_works() -> Result<()> {
+    let node = Node::spawn(9951).await?;
+    let bundle = ContractBundle::chain_extension()?;
+    let contract = node.deploy(Alice, &bundle).await?;
+    let selector = bundle.message("custom_type_with_result")?;
+
+    let block = node.call(Alice, &contract, selector, true).await?;
+    let something = node.stored_value(block).await?;
+    assert_eq!(something, Some(2));
+
+    // A `Custom` with a length that isn't a power of two gets rejected by the runtime, which the
+    // contract hands back as `Err(ExtensionError::CustomCallFailed)`.
+    let output = node.dry_run(Alice, &contract, selector, false).await?;
+    assert_eq!(output, Err::<(), u8>(0).encode());
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires a contracts node and a built contract"]
+async fn schedule_call_triggers_handler() -> Result<()> {
+    let node = Node::spawn(9952).await?;
+    let bundle = ContractBundle::chain_extension()?;
+    let contract = node.deploy(Alice, &bundle).await?;
+
+    let now = node.block_number(node.block_hash(None).await?).await?;
+    let at: BlockNumber = now + 3;
+    node.call(
+        Alice,
+        &contract,
+        bundle.message("schedule_call")?,
+        (at, GAS_LIMIT / 10, None::<u128>),
+    )
+    .await?;
+
+    node.advance_to(at).await?;
+
+    // `SchedulerTriggered` is the third event of our contract, so it's encoded as a `2` followed
+    // by its fields. Alice scheduled the call, but it runs on the contract's own behalf.
+    let expected = (2u8, at, contract.clone(), MAGIC_NUMBER).encode();
+    let events = node
+        .contract_events(&contract, node.block_hash(Some(at)).await?)
+        .await?;
+    assert_eq!(events, vec![expected]);
+
+    // And it shouldn't have fired any earlier.
+    let events = node
+        .contract_events(&contract, node.block_hash(Some(at - 1)).await?)
+        .await?;
+    assert!(events.is_empty());
+    Ok(())
+}
@@ -5,6 +5,7 @@ use contracts_node_runtime::{
     Signature,
     SudoConfig,
     SystemConfig,
+    TemplateConfig,
     WASM_BINARY,
 };
 use sc_service::ChainType;
@@ -41,7 +42,37 @@ where
     AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
+/// Where `cargo contract build` puts the demo contract, unless `DEMO_CONTRACT_WASM` says otherwise.
+const DEMO_CONTRACT_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../contracts/chain_extension/target/ink/chain_extension.wasm"
+);
+
+/// The selector of the demo contract's `new()` constructor.
+const DEMO_CONTRACT_CONSTRUCTOR: [u8; 4] = [0x9B, 0xAE, 0x9D, 0x5E];
+
 pub fn development_config() -> Result<ChainSpec, String> {
+    development_config_with(Vec::new())
+}
+
+/// The same as `development_config()`, except that Alice deploys the demo contract at genesis.
+/// `pallet_template::GenesisContracts` holds its address.
+pub fn demo_development_config() -> Result<ChainSpec, String> {
+    let path = std::env::var("DEMO_CONTRACT_WASM").unwrap_or_else(|_| DEMO_CONTRACT_PATH.into());
+    let code = std::fs::read(&path).map_err(|e| {
+        format!(
+            "Demo contract not available at {} ({}), build it with `cargo contract build` first",
+            path, e
+        )
+    })?;
+
+    let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+    development_config_with(vec![(alice, code, DEMO_CONTRACT_CONSTRUCTOR.to_vec())])
+}
+
+fn development_config_with(
+    contracts: Vec<(AccountId, Vec<u8>, Vec<u8>)>,
+) -> Result<ChainSpec, String> {
     let wasm_binary =
         WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
@@ -71,6 +102,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
                 ],
+                contracts.clone(),
                 true,
             )
         },
@@ -119,6 +151,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
                 ],
+                Vec::new(),
                 true,
             )
         },
@@ -142,6 +175,7 @@ fn testnet_genesis(
     wasm_binary: &[u8],
     root_key: AccountId,
     endowed_accounts: Vec<AccountId>,
+    contracts: Vec<(AccountId, Vec<u8>, Vec<u8>)>,
     _enable_println: bool,
 ) -> GenesisConfig {
     GenesisConfig {
@@ -157,10 +191,21 @@ fn testnet_genesis(
                 .map(|k| (k, 1 << 60))
                 .collect(),
         },
+        assets: Default::default(),
         sudo: SudoConfig {
             // Assign network admin rights.
             key: Some(root_key),
         },
         transaction_payment: Default::default(),
+        template: TemplateConfig {
+            // Built after `balances`, so their deployers can already pay for them.
+            contracts,
+        },
+        democracy: Default::default(),
+        // Nobody is staking at genesis, the first election happens once accounts bond.
+        staking: Default::default(),
+        treasury: Default::default(),
+        council: Default::default(),
+        council_membership: Default::default(),
     }
 }
@@ -7,6 +7,19 @@ pub struct Cli {
 
     #[clap(flatten)]
     pub run: RunCmd,
+
+    /// How the node authors blocks.
+    #[clap(long, arg_enum, default_value = "instant")]
+    pub sealing: Sealing,
+}
+
+/// The ways our node can author blocks. Either way there's no block time to wait for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ArgEnum)]
+pub enum Sealing {
+    /// Author a block as soon as a transaction enters the pool.
+    Instant,
+    /// Only author a block when asked to through the `engine_createBlock` RPC.
+    Manual,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -52,6 +52,7 @@ impl SubstrateCli for Cli {
     fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
         Ok(match id {
             "" | "dev" => Box::new(chain_spec::development_config()?),
+            "dev-demo" => Box::new(chain_spec::demo_development_config()?),
             "local" => Box::new(chain_spec::local_testnet_config()?),
             path => {
                 Box::new(chain_spec::ChainSpec::from_json_file(
@@ -218,8 +219,9 @@ pub fn run() -> sc_cli::Result<()> {
         }
         None => {
             let runner = cli.create_runner(&cli.run)?;
+            let sealing = cli.sealing;
             runner.run_node_until_exit(|config| {
-                async move { service::new_full(config).map_err(sc_cli::Error::Service) }
+                async move { service::new_full(config, sealing).map_err(sc_cli::Error::Service) }
             })
         }
     }
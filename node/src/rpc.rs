@@ -5,6 +5,8 @@
 
 #![warn(missing_docs)]
 
+mod template;
+
 use std::sync::Arc;
 
 use contracts_node_runtime::{
@@ -15,8 +17,10 @@ use contracts_node_runtime::{
     Hash,
     Index,
 };
+use futures::channel::mpsc::Sender;
 use jsonrpsee::RpcModule;
 use sc_client_api::BlockBackend;
+use sc_consensus_manual_seal::EngineCommand;
 use sc_rpc::dev::{
     Dev,
     DevApiServer,
@@ -40,6 +44,8 @@ pub struct FullDeps<C, P> {
     pub pool: Arc<P>,
     /// Whether to deny unsafe calls
     pub deny_unsafe: DenyUnsafe,
+    /// Where the `engine_*` RPCs send their requests for blocks, if we're sealing manually.
+    pub command_sink: Option<Sender<EngineCommand<Hash>>>,
 }
 
 /// Instantiate all full RPC extensions.
@@ -60,6 +66,7 @@ where
         Hash,
     >,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+    C::Api: contracts_node_runtime::TemplateApi<Block, AccountId>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
 {
@@ -71,16 +78,25 @@ where
         TransactionPayment,
         TransactionPaymentApiServer,
     };
+    use sc_consensus_manual_seal::rpc::{
+        ManualSeal,
+        ManualSealApiServer,
+    };
     use substrate_frame_rpc_system::{
         System,
         SystemApiServer,
     };
+    use template::{
+        Template,
+        TemplateApiServer,
+    };
 
     let mut module = RpcModule::new(());
     let FullDeps {
         client,
         pool,
         deny_unsafe,
+        command_sink,
     } = deps;
 
     module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
@@ -94,8 +110,16 @@ where
     // Contracts RPC API extension
     module.merge(Contracts::new(client.clone()).into_rpc())?;
 
+    // Lets frontends and tests read what contracts wrote to `pallet_template`
+    module.merge(Template::new(client.clone()).into_rpc())?;
+
     // Dev RPC API extension
     module.merge(Dev::new(client, deny_unsafe).into_rpc())?;
 
+    // Manual seal RPC API extension, without which there'd be no way to author blocks
+    if let Some(command_sink) = command_sink {
+        module.merge(ManualSeal::new(command_sink).into_rpc())?;
+    }
+
     Ok(module)
 }
@@ -0,0 +1,85 @@
+//! RPCs for reading what `pallet_template` holds, backed by our runtime's `TemplateApi`.
+
+use std::sync::Arc;
+
+use contracts_node_runtime::{
+    opaque::Block,
+    AccountId,
+    Hash,
+    TemplateApi as TemplateRuntimeApi,
+};
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::error::{
+        CallError,
+        ErrorObject,
+    },
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+
+/// The error code we hand back when calling into the runtime failed.
+const RUNTIME_ERROR: i32 = 1;
+
+/// What `pallet_template` holds, as of the block with the given hash or the best block if
+/// there's none.
+#[rpc(server)]
+pub trait TemplateApi {
+    /// The value last stored with `do_something`.
+    #[method(name = "template_storedValue")]
+    fn stored_value(&self, at: Option<Hash>) -> RpcResult<Option<u32>>;
+
+    /// The value `account` last stored in a slot of its own with `store_for_caller`.
+    #[method(name = "template_storedValueFor")]
+    fn stored_value_for(&self, account: AccountId, at: Option<Hash>) -> RpcResult<Option<u32>>;
+}
+
+/// Implements [`TemplateApiServer`] on top of a client whose runtime has a `TemplateApi`.
+pub struct Template<C> {
+    client: Arc<C>,
+}
+
+impl<C> Template<C> {
+    /// Creates a new instance of the `template_*` RPCs.
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+
+    fn at(&self, at: Option<Hash>) -> BlockId<Block>
+    where
+        C: HeaderBackend<Block>,
+    {
+        BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+    }
+}
+
+impl<C> TemplateApiServer for Template<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: TemplateRuntimeApi<Block, AccountId>,
+{
+    fn stored_value(&self, at: Option<Hash>) -> RpcResult<Option<u32>> {
+        self.client
+            .runtime_api()
+            .stored_value(&self.at(at))
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn stored_value_for(&self, account: AccountId, at: Option<Hash>) -> RpcResult<Option<u32>> {
+        self.client
+            .runtime_api()
+            .stored_value_for(&self.at(at), account)
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+    CallError::Custom(ErrorObject::owned(
+        RUNTIME_ERROR,
+        "Runtime error",
+        Some(format!("{:?}", err)),
+    ))
+    .into()
+}
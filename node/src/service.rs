@@ -1,5 +1,6 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
+use crate::cli::Sealing;
 use contracts_node_runtime::{
     self,
     opaque::Block,
@@ -131,7 +132,7 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(config: Configuration, sealing: Sealing) -> Result<TaskManager, ServiceError> {
     let sc_service::PartialComponents {
         client,
         backend,
@@ -175,6 +176,14 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
         );
     }
 
+    // When sealing manually, the `engine_createBlock` RPC hands its requests for blocks to the
+    // authorship task through this channel.
+    let (command_sink, commands_stream) = futures::channel::mpsc::channel(1024);
+    let command_sink = match sealing {
+        Sealing::Manual => Some(command_sink),
+        Sealing::Instant => None,
+    };
+
     let rpc_extensions_builder = {
         let client = client.clone();
         let pool = transaction_pool.clone();
@@ -184,6 +193,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
                 client: client.clone(),
                 pool: pool.clone(),
                 deny_unsafe,
+                command_sink: command_sink.clone(),
             };
             crate::rpc::create_full(deps).map_err(Into::into)
         })
@@ -211,25 +221,50 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
         telemetry.as_ref().map(|x| x.handle()),
     );
 
-    let params = sc_consensus_manual_seal::InstantSealParams {
-        block_import: client.clone(),
-        env: proposer,
-        client,
-        pool: transaction_pool,
-        select_chain,
-        consensus_data_provider: None,
-        create_inherent_data_providers: move |_, ()| {
-            async move { Ok(sp_timestamp::InherentDataProvider::from_system_time()) }
-        },
-    };
+    let create_inherent_data_providers =
+        move |_, ()| async move { Ok(sp_timestamp::InherentDataProvider::from_system_time()) };
+
+    match sealing {
+        Sealing::Instant => {
+            let params = sc_consensus_manual_seal::InstantSealParams {
+                block_import: client.clone(),
+                env: proposer,
+                client,
+                pool: transaction_pool,
+                select_chain,
+                consensus_data_provider: None,
+                create_inherent_data_providers,
+            };
 
-    let authorship_future = sc_consensus_manual_seal::run_instant_seal(params);
+            let authorship_future = sc_consensus_manual_seal::run_instant_seal(params);
 
-    task_manager.spawn_essential_handle().spawn_blocking(
-        "instant-seal",
-        None,
-        authorship_future,
-    );
+            task_manager.spawn_essential_handle().spawn_blocking(
+                "instant-seal",
+                None,
+                authorship_future,
+            );
+        }
+        Sealing::Manual => {
+            let params = sc_consensus_manual_seal::ManualSealParams {
+                block_import: client.clone(),
+                env: proposer,
+                client,
+                pool: transaction_pool,
+                commands_stream,
+                select_chain,
+                consensus_data_provider: None,
+                create_inherent_data_providers,
+            };
+
+            let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
+
+            task_manager.spawn_essential_handle().spawn_blocking(
+                "manual-seal",
+                None,
+                authorship_future,
+            );
+        }
+    }
 
     network_starter.start_network();
     Ok(task_manager)
@@ -19,5 +19,12 @@ benchmarks! {
         assert_eq!(Something::<T>::get(), Some(s));
     }
 
+    store_for_caller {
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller.clone()), 42)
+    verify {
+        assert_eq!(AccountValues::<T>::get(&caller), Some(42));
+    }
+
     impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
 }
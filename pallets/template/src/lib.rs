@@ -14,18 +14,323 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+/// Tells us which contract a callback dispatched through `dispatch_callback` is meant for.
+///
+/// The runtime knows what a contract call looks like, this pallet doesn't.
+pub trait CallbackTarget<AccountId, Call> {
+    /// Returns the contract `call` is calling into, or `None` if it isn't a contract call.
+    fn target(call: &Call) -> Option<AccountId>;
+
+    /// Whether there's a contract at `account`, which every scheduled callback should point at.
+    fn is_contract(account: &AccountId) -> bool;
+}
+
+impl<AccountId, Call> CallbackTarget<AccountId, Call> for () {
+    fn target(_: &Call) -> Option<AccountId> {
+        None
+    }
+
+    fn is_contract(_: &AccountId) -> bool {
+        false
+    }
+}
+
+/// Picks the events contracts emitted out of everything that happened in a block.
+///
+/// Just like with `CallbackTarget`, the runtime knows what a contract's event looks like, this
+/// pallet doesn't.
+pub trait ContractEvents<AccountId, Event> {
+    /// Returns the contract which emitted `event` along with the event's data, or `None` if it
+    /// isn't a contract's event.
+    fn contract_event(event: &Event) -> Option<(AccountId, sp_std::vec::Vec<u8>)>;
+}
+
+impl<AccountId, Event> ContractEvents<AccountId, Event> for () {
+    fn contract_event(_: &Event) -> Option<(AccountId, sp_std::vec::Vec<u8>)> {
+        None
+    }
+}
+
+/// The index of `SchedulerTriggered` among the demo contract's events, which is what the data of
+/// each of them starts with.
+pub const SCHEDULER_TRIGGERED: u8 = 2;
+
+/// Puts the contracts listed in our genesis config on chain.
+///
+/// Just like with `CallbackTarget`, the runtime knows how to deploy a contract and this pallet
+/// doesn't.
+pub trait ContractDeployer<AccountId> {
+    /// Uploads `code` and instantiates it on behalf of `origin`, handing `data` to its
+    /// constructor. Returns the address of the new contract.
+    fn deploy(
+        origin: AccountId,
+        code: sp_std::vec::Vec<u8>,
+        data: sp_std::vec::Vec<u8>,
+    ) -> Result<AccountId, sp_runtime::DispatchError>;
+}
+
+impl<AccountId> ContractDeployer<AccountId> for () {
+    fn deploy(
+        _: AccountId,
+        _: sp_std::vec::Vec<u8>,
+        _: sp_std::vec::Vec<u8>,
+    ) -> Result<AccountId, sp_runtime::DispatchError> {
+        Err(sp_runtime::DispatchError::Other("this runtime can't deploy contracts"))
+    }
+}
+
+/// Why a reveal wasn't accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevealError {
+    /// There's no commitment with the given id, or it has already been revealed or removed.
+    Unknown,
+    /// The commitment belongs to someone else.
+    NotOwner,
+    /// The reveal window hasn't opened yet.
+    TooEarly,
+    /// The reveal window has already closed.
+    TooLate,
+    /// The preimage doesn't hash to what was committed to.
+    Mismatch,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
-    use frame_support::pallet_prelude::*;
-    use frame_system::pallet_prelude::*;
+    use super::{
+        CallbackTarget,
+        ContractDeployer,
+        ContractEvents,
+        RevealError,
+        SCHEDULER_TRIGGERED,
+    };
+    use chain_extension_primitives::{
+        CallCounts,
+        CallbackReceipt,
+        Envelope,
+        ReservationUsage,
+        DEFAULT_CALLBACK_SELECTOR,
+        SchemaDescriptor,
+        WeightHistogram,
+    };
+    use frame_support::{
+        dispatch::{
+            Dispatchable,
+            PostDispatchInfo,
+        },
+        pallet_prelude::*,
+        traits::{
+            Currency,
+            ReservableCurrency,
+        },
+        weights::{
+            extract_actual_weight,
+            GetDispatchInfo,
+        },
+    };
+    use frame_system::{
+        offchain::{
+            SendTransactionTypes,
+            SubmitTransaction,
+        },
+        pallet_prelude::*,
+    };
+    use sp_runtime::{
+        traits::{
+            Hash,
+            Saturating,
+            Zero,
+        },
+        Perbill,
+    };
+    use sp_std::prelude::*;
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
         /// Because this pallet emits events, it depends on the runtime's definition of an event.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The calls we're able to dispatch as contract callbacks.
+        type Call: Parameter
+            + Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo>
+            + GetDispatchInfo;
+
+        /// Figures out which contract a callback is for.
+        type CallbackTarget: CallbackTarget<Self::AccountId, <Self as Config>::Call>;
+
+        /// Deploys the contracts from our genesis config.
+        type ContractDeployer: ContractDeployer<Self::AccountId>;
+
+        /// Finds the events contracts emitted, which our off-chain worker looks through.
+        type ContractEvents: ContractEvents<Self::AccountId, <Self as frame_system::Config>::Event>;
+
+        /// The currency in which value sent along with scheduled callbacks is held until they run.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// What whoever schedules a callback has to put down until it runs, for the room it takes
+        /// up in the agenda.
+        #[pallet::constant]
+        type CallbackDeposit: Get<BalanceOf<Self>>;
+
+        /// The longest key the system contract may write to `RawStorage`.
+        #[pallet::constant]
+        type MaxRawKeyLen: Get<u32>;
+
+        /// The longest value the system contract may write to `RawStorage`.
+        #[pallet::constant]
+        type MaxRawValueLen: Get<u32>;
+
+        /// The longest blob governance may put into `Blob`.
+        #[pallet::constant]
+        type MaxBlobLen: Get<u32>;
+
+        /// The largest number of values a contract may store in one go.
+        #[pallet::constant]
+        type MaxBatchLen: Get<u32>;
+
+        /// The most callbacks we'll schedule for any one block. This should be no larger than
+        /// the Scheduler's `MaxScheduledPerBlock`.
+        #[pallet::constant]
+        type MaxCallbacksPerBlock: Get<u32>;
+
+        /// The most callbacks a single contract may have scheduled for any one block, this keeps
+        /// one contract from taking up a whole block's worth of callbacks.
+        #[pallet::constant]
+        type MaxCallbacksPerContract: Get<u32>;
+
+        /// The most calls a single contract may schedule within any one `SchedulingPeriod`, no
+        /// matter which blocks they're for.
+        ///
+        /// Unlike `MaxCallbacksPerContract` this counts the calls when they get scheduled, so a
+        /// contract can't keep the agenda busy by spreading its callbacks out over many blocks.
+        #[pallet::constant]
+        type MaxSchedulesPerPeriod: Get<u32>;
+
+        /// The length, in blocks, of the periods `MaxSchedulesPerPeriod` applies to.
+        #[pallet::constant]
+        type SchedulingPeriod: Get<u32>;
+
+        /// How many blocks past the one it asked for a callback may get pushed back when that
+        /// block is full.
+        #[pallet::constant]
+        type MaxCallbackDelay: Get<u32>;
+
+        /// The most selectors, besides the default one, which governance may allow scheduled
+        /// callbacks to invoke.
+        #[pallet::constant]
+        type MaxCallbackSelectors: Get<u32>;
+
+        /// The most contracts governance may put on the `SchedulerAllowlist`.
+        #[pallet::constant]
+        type MaxAllowedSchedulers: Get<u32>;
+
+        /// The most code hashes governance may approve for any one extension function.
+        #[pallet::constant]
+        type MaxApprovedCodeHashes: Get<u32>;
+
+        /// The share of every block's maximum weight which is set aside for scheduled callbacks,
+        /// so that no amount of extrinsics can crowd them out.
+        #[pallet::constant]
+        type CallbackReservation: Get<Perbill>;
+
+        /// The largest gas limit a contract may give its scheduled callbacks.
+        #[pallet::constant]
+        type MaxCallbackGasLimit: Get<Weight>;
+
+        /// Callbacks whose encoding is longer than this are noted with the Preimage pallet, and
+        /// only their hash goes into the Scheduler's agenda.
+        #[pallet::constant]
+        type MaxInlineCallbackLen: Get<u32>;
+
+        /// The largest ciphertext an envelope may hold.
+        #[pallet::constant]
+        type MaxCiphertextLen: Get<u32>;
+
+        /// How many blocks a commitment sticks around for after its reveal window has closed.
+        ///
+        /// Reveals during that time are told that they're late, rather than that there's no such
+        /// commitment.
+        #[pallet::constant]
+        type CommitmentRetention: Get<u32>;
+
+        /// The longest name a shared counter may have.
+        #[pallet::constant]
+        type MaxCounterNameLen: Get<u32>;
+
+        /// The most accounts, besides its admin, which may increment any one shared counter.
+        #[pallet::constant]
+        type MaxCounterMembers: Get<u32>;
+
+        /// How many blocks the receipt of a callback sticks around for after the callback ran.
+        #[pallet::constant]
+        type ReceiptRetention: Get<u32>;
+    }
+
+    pub type EnvelopeOf<T> = Envelope<
+        <T as frame_system::Config>::AccountId,
+        <T as frame_system::Config>::BlockNumber,
+        BoundedVec<u8, <T as Config>::MaxCiphertextLen>,
+    >;
+
+    /// A hash a contract has committed to, along with the window in which it may be revealed.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+    pub struct Commitment<AccountId, BlockNumber, Hash> {
+        pub owner: AccountId,
+        pub hash: Hash,
+        /// The first block in which the commitment may be revealed.
+        pub opens_at: BlockNumber,
+        /// The first block in which it's too late to reveal the commitment.
+        pub closes_at: BlockNumber,
+    }
+
+    pub type CommitmentOf<T> = Commitment<
+        <T as frame_system::Config>::AccountId,
+        <T as frame_system::Config>::BlockNumber,
+        <T as frame_system::Config>::Hash,
+    >;
+
+    /// A sequence number shared by a group of cooperating contracts.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+    pub struct Counter<AccountId, Members> {
+        /// The account which created the counter. It gets to increment the counter and decides
+        /// who else may.
+        pub admin: AccountId,
+        /// The accounts, besides `admin`, which may increment the counter.
+        pub members: Members,
+        pub value: u64,
     }
 
+    pub type CounterOf<T> = Counter<
+        <T as frame_system::Config>::AccountId,
+        BoundedVec<<T as frame_system::Config>::AccountId, <T as Config>::MaxCounterMembers>,
+    >;
+
+    pub type CounterNameOf<T> = BoundedVec<u8, <T as Config>::MaxCounterNameLen>;
+
+    pub type BalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::Balance;
+
+    /// A scheduled callback which one of our genesis contracts told us about, by emitting
+    /// `SchedulerTriggered`.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+    pub struct Trigger<AccountId, BlockNumber> {
+        /// The block the callback ran in.
+        pub at: BlockNumber,
+        /// Who the callback was dispatched on behalf of, which is the contract itself.
+        pub caller: AccountId,
+        /// Whatever the callback was handed.
+        pub arg: u32,
+    }
+
+    pub type TriggerOf<T> =
+        Trigger<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+    pub type ReceiptOf<T> = CallbackReceipt<
+        <T as frame_system::Config>::BlockNumber,
+        <T as frame_system::Config>::Hash,
+    >;
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
     pub struct Pallet<T>(_);
@@ -38,6 +343,247 @@ pub mod pallet {
     // https://docs.substrate.io/v3/runtime/storage#declaring-storage-items
     pub type Something<T> = StorageValue<_, u32>;
 
+    /// A value for every account which has stored one, as opposed to the single global value in
+    /// `Something`.
+    #[pallet::storage]
+    #[pallet::getter(fn account_value)]
+    pub type AccountValues<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32>;
+
+    /// The weight actually used by the callbacks we've dispatched, per contract.
+    ///
+    /// Contracts can query this through the chain extension in order to pick a tighter gas limit
+    /// for their future callbacks.
+    #[pallet::storage]
+    #[pallet::getter(fn callback_weights)]
+    pub type CallbackWeights<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, WeightHistogram, ValueQuery>;
+
+    /// The number of callbacks scheduled for each (future) block.
+    #[pallet::storage]
+    #[pallet::getter(fn callbacks_in)]
+    pub type CallbacksPerBlock<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+    /// The number of callbacks each contract has scheduled for each (future) block.
+    #[pallet::storage]
+    #[pallet::getter(fn contract_callbacks_in)]
+    pub type CallbackSlots<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        Blake2_128Concat,
+        T::AccountId,
+        u32,
+        ValueQuery,
+    >;
+
+    /// How many calls each contract has scheduled, along with the `SchedulingPeriod` that count
+    /// is for. Counts for earlier periods are simply overwritten once the contract schedules
+    /// another call.
+    #[pallet::storage]
+    #[pallet::getter(fn schedule_count)]
+    pub type ScheduleCounts<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, u32), ValueQuery>;
+
+    /// The handle the next scheduled callback gets.
+    #[pallet::storage]
+    pub type NextCallbackHandle<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Receipts of the callbacks which ran during the last `ReceiptRetention` blocks, keyed by
+    /// their handle.
+    #[pallet::storage]
+    #[pallet::getter(fn receipt)]
+    pub type Receipts<T: Config> = StorageMap<_, Twox64Concat, u64, ReceiptOf<T>>;
+
+    /// The handles of the receipts to prune in each (future) block.
+    #[pallet::storage]
+    pub type ReceiptExpiry<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Twox64Concat, u64, ()>;
+
+    /// The value sent along with scheduling each callback which hasn't run yet, keyed by the
+    /// callback's handle, along with the account holding it in reserve.
+    #[pallet::storage]
+    #[pallet::getter(fn callback_escrow)]
+    pub type CallbackEscrow<T: Config> =
+        StorageMap<_, Twox64Concat, u64, (T::AccountId, BalanceOf<T>)>;
+
+    /// Where in the Scheduler's agenda each callback which hasn't run yet is, as a `(block,
+    /// index)` pair keyed by the callback's handle.
+    ///
+    /// This is what lets contracts move their callbacks around knowing only their handle.
+    #[pallet::storage]
+    #[pallet::getter(fn callback_address)]
+    pub type CallbackAddresses<T: Config> =
+        StorageMap<_, Twox64Concat, u64, (T::BlockNumber, u32)>;
+
+    /// The contract each callback which hasn't run yet calls into, along with the account it's
+    /// dispatched on behalf of, keyed by the callback's handle.
+    ///
+    /// `dispatch_callback` turns down anything which doesn't match what's in here.
+    #[pallet::storage]
+    #[pallet::getter(fn callback_origin)]
+    pub type CallbackOrigins<T: Config> =
+        StorageMap<_, Twox64Concat, u64, (T::AccountId, T::AccountId)>;
+
+    /// The deposit put down for each callback which hasn't run yet, keyed by the callback's
+    /// handle, along with the account which put it down.
+    #[pallet::storage]
+    #[pallet::getter(fn callback_deposit)]
+    pub type CallbackDeposits<T: Config> =
+        StorageMap<_, Twox64Concat, u64, (T::AccountId, BalanceOf<T>)>;
+
+    /// How much of the weight reserved for callbacks they've used up in the current block.
+    #[pallet::storage]
+    #[pallet::getter(fn callback_weight_used)]
+    pub type CallbackWeightUsed<T> = StorageValue<_, Weight, ValueQuery>;
+
+    /// The selectors of the messages scheduled callbacks may invoke, on top of
+    /// `DEFAULT_CALLBACK_SELECTOR`.
+    ///
+    /// Governance keeps this short so that contracts can't have the runtime call into arbitrary
+    /// messages of theirs later on.
+    #[pallet::storage]
+    #[pallet::getter(fn callback_selectors)]
+    pub type CallbackSelectors<T: Config> =
+        StorageValue<_, BoundedVec<[u8; 4], T::MaxCallbackSelectors>, ValueQuery>;
+
+    /// The contracts which may schedule callbacks, if governance has restricted that at all.
+    ///
+    /// As long as this isn't set any contract may fill up the Scheduler's agenda, within the
+    /// limits of its quota.
+    #[pallet::storage]
+    #[pallet::getter(fn scheduler_allowlist)]
+    pub type SchedulerAllowlist<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxAllowedSchedulers>>;
+
+    /// The code hashes a contract needs to have been instantiated from to call the extension
+    /// function `func_id`, for the functions governance has restricted this way.
+    ///
+    /// Unlike `SchedulerAllowlist` this trusts code rather than addresses, e.g. code which has
+    /// been audited, so every contract deployed from it gets in.
+    #[pallet::storage]
+    #[pallet::getter(fn approved_code_hashes)]
+    pub type ApprovedCodeHashes<T: Config> =
+        StorageMap<_, Twox64Concat, u32, BoundedVec<T::Hash, T::MaxApprovedCodeHashes>>;
+
+    /// Values which contracts have moved out of their own storage, keyed by contract and then by
+    /// whatever key the contract uses for them.
+    ///
+    /// Keeping these in the runtime is cheaper than keeping them in contract storage.
+    #[pallet::storage]
+    #[pallet::getter(fn contract_value)]
+    pub type ContractValues<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, u32, u32>;
+
+    /// The schemas of the payloads our chain extension accepts, keyed by `func_id`.
+    ///
+    /// Extension functions which check schemas compare against the descriptor registered here,
+    /// falling back to the one they were compiled with if there isn't one.
+    #[pallet::storage]
+    #[pallet::getter(fn schema)]
+    pub type Schemas<T> = StorageMap<_, Twox64Concat, u32, SchemaDescriptor>;
+
+    /// The id the next envelope will be stored under.
+    #[pallet::storage]
+    pub type NextEnvelopeId<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Encrypted blobs held on behalf of contracts. They stay here after they expire, until
+    /// someone calls `remove_expired_envelope`.
+    #[pallet::storage]
+    #[pallet::getter(fn envelope)]
+    pub type Envelopes<T: Config> = StorageMap<_, Twox64Concat, u64, EnvelopeOf<T>>;
+
+    /// The id the next commitment will be stored under.
+    #[pallet::storage]
+    pub type NextCommitmentId<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Commitments which haven't been revealed yet. Unrevealed commitments get removed
+    /// `CommitmentRetention` blocks after their reveal window closes.
+    #[pallet::storage]
+    #[pallet::getter(fn commitment)]
+    pub type Commitments<T: Config> = StorageMap<_, Twox64Concat, u64, CommitmentOf<T>>;
+
+    /// Counters shared between contracts, keyed by name.
+    ///
+    /// Names are first come, first served.
+    #[pallet::storage]
+    #[pallet::getter(fn counter)]
+    pub type Counters<T: Config> = StorageMap<_, Blake2_128Concat, CounterNameOf<T>, CounterOf<T>>;
+
+    /// The contract which governance has trusted with writing to `RawStorage`.
+    #[pallet::storage]
+    #[pallet::getter(fn system_contract)]
+    pub type SystemContract<T: Config> = StorageValue<_, T::AccountId>;
+
+    /// Arbitrary key/value pairs written by the system contract.
+    ///
+    /// This is the only place the system contract gets to write to, everything else stays off
+    /// limits no matter which keys it comes up with.
+    #[pallet::storage]
+    #[pallet::getter(fn raw_storage)]
+    pub type RawStorage<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        BoundedVec<u8, T::MaxRawKeyLen>,
+        BoundedVec<u8, T::MaxRawValueLen>,
+    >;
+
+    /// A blob of bytes governance wants every contract to be able to read.
+    ///
+    /// Unlike with most of our storage, contracts don't know how long this is before reading it.
+    #[pallet::storage]
+    #[pallet::getter(fn blob)]
+    pub type Blob<T: Config> = StorageValue<_, BoundedVec<u8, T::MaxBlobLen>, ValueQuery>;
+
+    /// The addresses of the contracts deployed at genesis, in the order the genesis config lists
+    /// them in.
+    #[pallet::storage]
+    #[pallet::getter(fn genesis_contracts)]
+    pub type GenesisContracts<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+    /// How often each of our chain extension functions has been called, keyed by func_id.
+    ///
+    /// Only func_ids our runtime knows about get counted, so contracts can't grow this at will.
+    #[pallet::storage]
+    #[pallet::getter(fn extension_calls)]
+    pub type ExtensionCalls<T> = StorageMap<_, Twox64Concat, u32, CallCounts, ValueQuery>;
+
+    /// The last scheduled callback each of our genesis contracts told us about, as our off-chain
+    /// worker reported it.
+    #[pallet::storage]
+    #[pallet::getter(fn last_trigger)]
+    pub type LastTriggers<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, TriggerOf<T>>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// Contracts to deploy at genesis, as the account deploying each of them, its code and
+        /// the input to its constructor. This saves developers from deploying the contracts
+        /// they're working against every time they start a dev chain.
+        pub contracts: Vec<(T::AccountId, Vec<u8>, Vec<u8>)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self { contracts: Vec::new() }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            let contracts = self
+                .contracts
+                .iter()
+                .map(|(origin, code, data)| {
+                    T::ContractDeployer::deploy(origin.clone(), code.clone(), data.clone())
+                        .expect("genesis contracts can be deployed; qed")
+                })
+                .collect::<Vec<_>>();
+            <GenesisContracts<T>>::put(contracts);
+        }
+    }
+
     // Pallets use events to inform users when important changes are made.
     // https://docs.substrate.io/v3/runtime/events-and-errors
     #[pallet::event]
@@ -46,6 +592,54 @@ pub mod pallet {
         /// Event documentation should end with an array that provides descriptive names for event
         /// parameters. [something, who]
         SomethingStored(u32, T::AccountId),
+        /// An account stored a value in its own slot. [value, who]
+        AccountValueStored(u32, T::AccountId),
+        /// A contract callback was dispatched. [handle, contract, actual_weight, result]
+        CallbackDispatched(u64, T::AccountId, Weight, DispatchResult),
+        /// Governance changed which selectors scheduled callbacks may invoke. [selectors]
+        CallbackSelectorsSet(BoundedVec<[u8; 4], T::MaxCallbackSelectors>),
+        /// Governance changed which contracts may schedule callbacks, `None` meaning all of them.
+        /// [contracts]
+        SchedulerAllowlistSet(Option<BoundedVec<T::AccountId, T::MaxAllowedSchedulers>>),
+        /// Governance changed which code may call an extension function, `None` meaning any.
+        /// [func_id, code_hashes]
+        CodeHashesApproved(u32, Option<BoundedVec<T::Hash, T::MaxApprovedCodeHashes>>),
+        /// Governance changed which contract is the system contract. [contract]
+        SystemContractSet(Option<T::AccountId>),
+        /// The system contract wrote to `RawStorage`. [key]
+        RawValueWritten(BoundedVec<u8, T::MaxRawKeyLen>),
+        /// Governance changed the registered schema of an extension function. [func_id, schema]
+        SchemaRegistered(u32, Option<SchemaDescriptor>),
+        /// A contract stored a batch of values. [contract, count]
+        BatchStored(T::AccountId, u32),
+        /// A contract stored an envelope. [owner, id, expires_at]
+        EnvelopeStored(T::AccountId, u64, T::BlockNumber),
+        /// An expired envelope was removed. [id]
+        EnvelopeRemoved(u64),
+        /// A contract committed to a hash. [owner, id, opens_at, closes_at]
+        CommitmentStored(T::AccountId, u64, T::BlockNumber, T::BlockNumber),
+        /// A commitment was revealed. [id]
+        CommitmentRevealed(u64),
+        /// A commitment which was never revealed was removed. [id]
+        CommitmentRemoved(u64),
+        /// A shared counter was created. [admin, name]
+        CounterCreated(T::AccountId, CounterNameOf<T>),
+        /// A shared counter was incremented. [name, value]
+        CounterIncremented(CounterNameOf<T>, u64),
+        /// The admin of a shared counter let an account increment it, or stopped letting it.
+        /// [name, who, allowed]
+        CounterAccessChanged(CounterNameOf<T>, T::AccountId, bool),
+        /// Governance replaced the blob. [len]
+        BlobSet(u32),
+        /// A contract stored a value through our chain extension, on behalf of the account which
+        /// called it. [something, who, contract]
+        SomethingStoredByContract(u32, T::AccountId, T::AccountId),
+        /// A contract scheduled a call to itself through our chain extension, on behalf of the
+        /// account which called it. [contract, caller, at]
+        ContractScheduled(T::AccountId, T::AccountId, T::BlockNumber),
+        /// Our off-chain worker saw one of our genesis contracts report that a scheduled callback
+        /// ran. [contract, caller, at]
+        TriggerNoted(T::AccountId, T::AccountId, T::BlockNumber),
     }
 
     // Errors inform users that something went wrong.
@@ -55,6 +649,123 @@ pub mod pallet {
         NoneValue,
         /// Errors should have helpful documentation associated with them.
         StorageOverflow,
+        /// The call given to `dispatch_callback` isn't a call into a contract.
+        NotACallback,
+        /// There's no callback with this handle waiting to run.
+        UnknownCallback,
+        /// The callback isn't due to run before the block it's scheduled for.
+        CallbackNotDue,
+        /// The call given to `dispatch_callback` isn't for the contract the callback was scheduled
+        /// for, or isn't dispatched on behalf of the account it was scheduled for.
+        CallbackMismatch,
+        /// More than `MaxCallbackSelectors` selectors were given to `set_callback_selectors`.
+        TooManyCallbackSelectors,
+        /// More than `MaxAllowedSchedulers` contracts were given to `set_scheduler_allowlist`.
+        TooManyAllowedSchedulers,
+        /// More than `MaxApprovedCodeHashes` code hashes were given to `set_approved_code_hashes`.
+        TooManyApprovedCodeHashes,
+        /// Only the system contract is allowed to write to `RawStorage`.
+        NotSystemContract,
+        /// The key is longer than `MaxRawKeyLen`.
+        RawKeyTooLong,
+        /// The value is longer than `MaxRawValueLen`.
+        RawValueTooLong,
+        /// More than `MaxBatchLen` values were given to `store_batch`.
+        BatchTooLarge,
+        /// The ciphertext is longer than `MaxCiphertextLen`.
+        CiphertextTooLong,
+        /// The envelope would have expired before it was even stored.
+        AlreadyExpired,
+        /// There's no envelope with the given id.
+        UnknownEnvelope,
+        /// The envelope or commitment hasn't expired yet, so it can't be removed.
+        NotExpired,
+        /// The reveal window of a commitment needs to be at least one block long.
+        EmptyRevealWindow,
+        /// There's no commitment with the given id.
+        UnknownCommitment,
+        /// The counter name is longer than `MaxCounterNameLen`.
+        CounterNameTooLong,
+        /// There already is a counter with the given name.
+        CounterExists,
+        /// There's no counter with the given name.
+        UnknownCounter,
+        /// Only the admin and members of a counter may increment it, and only its admin may
+        /// change who its members are.
+        NotCounterMember,
+        /// The counter already has `MaxCounterMembers` members.
+        TooManyCounterMembers,
+        /// The account scheduling a callback can't afford its `CallbackDeposit`.
+        InsufficientDeposit,
+        /// The blob is longer than `MaxBlobLen`.
+        BlobTooLong,
+        /// Only the events of the contracts deployed at genesis get noted.
+        NotAGenesisContract,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            // Claiming the reserved weight up front means extrinsics only get whatever is left
+            // over. Callbacks draw from it in `dispatch_callback`, and `on_finalize` needs a write
+            // to reset how much of it they used.
+            let reserved = Self::reserved_callback_weight()
+                .saturating_add(T::DbWeight::get().writes(1))
+                .saturating_add(Self::prune_receipts(n));
+
+            // Callbacks can't be scheduled for the current block, so the counts for it are of no
+            // use anymore.
+            let scheduled = <CallbacksPerBlock<T>>::take(n);
+            if scheduled == 0 {
+                return reserved.saturating_add(T::DbWeight::get().reads(1))
+            }
+
+            <CallbackSlots<T>>::remove_prefix(n, None);
+            reserved.saturating_add(T::DbWeight::get().reads_writes(1, 1 + scheduled as Weight))
+        }
+
+        fn on_finalize(_n: T::BlockNumber) {
+            <CallbackWeightUsed<T>>::kill();
+        }
+
+        /// Looks through the events of the block we just imported for scheduled callbacks our
+        /// genesis contracts report, and hands each of them back to the runtime in a transaction.
+        ///
+        /// This is the other way around from scheduled callbacks, which have the runtime call
+        /// into a contract: here a contract's event ends up in the runtime.
+        fn offchain_worker(_n: T::BlockNumber) {
+            let watched = Self::genesis_contracts();
+            if watched.is_empty() {
+                return
+            }
+
+            for record in <frame_system::Pallet<T>>::read_events_no_consensus() {
+                let (contract, data) = match T::ContractEvents::contract_event(&record.event) {
+                    Some(event) => event,
+                    None => continue,
+                };
+                if !watched.contains(&contract) {
+                    continue
+                }
+                if let Some(trigger) = Self::decode_trigger(&data) {
+                    let call = Call::note_trigger { contract, trigger };
+                    // The pool turns it down if it already has the same one, there's nothing more
+                    // to do about it either way.
+                    let _ =
+                        SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into());
+                }
+            }
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<(), &'static str> {
+            Self::do_try_state()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            Self::do_try_state()
+        }
     }
 
     // Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -98,5 +809,808 @@ pub mod pallet {
                 }
             }
         }
+
+        /// Like `do_something`, except that the value is stored in a slot of the caller's own
+        /// rather than in one shared by everyone.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn store_for_caller(origin: OriginFor<T>, value: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            <AccountValues<T>>::insert(&who, value);
+
+            Self::deposit_event(Event::AccountValueStored(value, who));
+            Ok(())
+        }
+
+        /// Dispatches a contract callback and records how much weight it actually used, along
+        /// with a receipt which can be looked up by the callback's `handle`.
+        ///
+        /// The Scheduler calls this for every callback scheduled through our chain extension.
+        /// Whether or not the callback itself succeeds, this dispatchable doesn't fail, so the
+        /// recorded weight and receipt are kept around either way.
+        ///
+        /// Anybody could submit this themselves though, so it only goes through for a callback
+        /// which is still waiting to run and is due by now, with the call and origin it was
+        /// scheduled with. Everything else is turned down before anything is touched.
+        ///
+        /// The weight used by the callback comes out of the block's reservation for callbacks
+        /// first, which was already accounted for in `on_initialize`. Only whatever doesn't fit in
+        /// there anymore counts against the block.
+        ///
+        /// Any value escrowed for the callback is released right before it's dispatched, so that
+        /// the callback can pass it on to the message it calls. The deposit put down for the
+        /// callback is handed back at the same time.
+        #[pallet::weight({
+            let dispatch_info = call.get_dispatch_info();
+            (
+                dispatch_info.weight
+                    .saturating_add(10_000)
+                    .saturating_add(T::DbWeight::get().reads_writes(7, 9)),
+                dispatch_info.class,
+            )
+        })]
+        pub fn dispatch_callback(
+            origin: OriginFor<T>,
+            handle: u64,
+            call: Box<<T as Config>::Call>,
+        ) -> DispatchResultWithPostInfo {
+            let dispatcher = ensure_signed(origin.clone())?;
+            let contract = T::CallbackTarget::target(&call).ok_or(Error::<T>::NotACallback)?;
+
+            let (at, _) = <CallbackAddresses<T>>::get(handle).ok_or(Error::<T>::UnknownCallback)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(at <= now, Error::<T>::CallbackNotDue);
+            ensure!(
+                <CallbackOrigins<T>>::get(handle) == Some((contract.clone(), dispatcher)),
+                Error::<T>::CallbackMismatch
+            );
+
+            // If the callback fails the value simply stays with the contract.
+            if let Some((owner, value)) = <CallbackEscrow<T>>::take(handle) {
+                T::Currency::unreserve(&owner, value);
+            }
+            if let Some((depositor, deposit)) = <CallbackDeposits<T>>::take(handle) {
+                T::Currency::unreserve(&depositor, deposit);
+            }
+            <CallbackAddresses<T>>::remove(handle);
+            <CallbackOrigins<T>>::remove(handle);
+
+            let info = call.get_dispatch_info();
+            let result = call.dispatch(origin);
+            let actual_weight = extract_actual_weight(&result, &info);
+
+            <CallbackWeights<T>>::mutate(&contract, |histogram| histogram.note(actual_weight));
+            let covered = Self::draw_from_reservation(actual_weight);
+
+            let outcome = result.map(|_| ()).map_err(|e| e.error);
+            <Receipts<T>>::insert(
+                handle,
+                CallbackReceipt {
+                    block: now,
+                    weight: actual_weight,
+                    success: outcome.is_ok(),
+                    output_hash: T::Hashing::hash_of(&outcome),
+                },
+            );
+            let expires_at = now.saturating_add(T::ReceiptRetention::get().into());
+            <ReceiptExpiry<T>>::insert(expires_at, handle, ());
+
+            Self::deposit_event(Event::CallbackDispatched(
+                handle,
+                contract,
+                actual_weight,
+                outcome,
+            ));
+
+            Ok(Some(
+                actual_weight
+                    .saturating_sub(covered)
+                    .saturating_add(10_000)
+                    .saturating_add(T::DbWeight::get().reads_writes(7, 9)),
+            )
+            .into())
+        }
+
+        /// Removes an envelope which has expired. Anyone may do this, since nobody can read an
+        /// expired envelope anymore anyways.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn remove_expired_envelope(origin: OriginFor<T>, id: u64) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let envelope = Self::envelope(id).ok_or(Error::<T>::UnknownEnvelope)?;
+            ensure!(
+                envelope.expires_at <= <frame_system::Pallet<T>>::block_number(),
+                Error::<T>::NotExpired
+            );
+
+            <Envelopes<T>>::remove(id);
+            Self::deposit_event(Event::EnvelopeRemoved(id));
+            Ok(())
+        }
+
+        /// Removes a commitment which was never revealed, once `CommitmentRetention` blocks have
+        /// passed since its reveal window closed. Just like with envelopes anyone may do this.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn remove_expired_commitment(origin: OriginFor<T>, id: u64) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let commitment = Self::commitment(id).ok_or(Error::<T>::UnknownCommitment)?;
+            let expires_at = commitment
+                .closes_at
+                .saturating_add(T::CommitmentRetention::get().into());
+            ensure!(
+                expires_at <= <frame_system::Pallet<T>>::block_number(),
+                Error::<T>::NotExpired
+            );
+
+            <Commitments<T>>::remove(id);
+            Self::deposit_event(Event::CommitmentRemoved(id));
+            Ok(())
+        }
+
+        /// Replaces the selectors scheduled callbacks may invoke, on top of the default one. Only
+        /// governance gets to decide this.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_callback_selectors(
+            origin: OriginFor<T>,
+            selectors: Vec<[u8; 4]>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let selectors: BoundedVec<_, T::MaxCallbackSelectors> =
+                selectors.try_into().map_err(|_| Error::<T>::TooManyCallbackSelectors)?;
+            <CallbackSelectors<T>>::put(&selectors);
+            Self::deposit_event(Event::CallbackSelectorsSet(selectors));
+            Ok(())
+        }
+
+        /// Restricts scheduling callbacks to the given contracts, or lifts the restriction if
+        /// `None` is given. Only governance gets to decide this.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_scheduler_allowlist(
+            origin: OriginFor<T>,
+            contracts: Option<Vec<T::AccountId>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let contracts: Option<BoundedVec<_, T::MaxAllowedSchedulers>> = contracts
+                .map(|contracts| contracts.try_into())
+                .transpose()
+                .map_err(|_| Error::<T>::TooManyAllowedSchedulers)?;
+            <SchedulerAllowlist<T>>::set(contracts.clone());
+            Self::deposit_event(Event::SchedulerAllowlistSet(contracts));
+            Ok(())
+        }
+
+        /// Restricts the extension function `func_id` to contracts instantiated from one of the
+        /// given code hashes, or lifts the restriction if `None` is given. Only governance gets
+        /// to decide this.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_approved_code_hashes(
+            origin: OriginFor<T>,
+            func_id: u32,
+            code_hashes: Option<Vec<T::Hash>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let code_hashes: Option<BoundedVec<_, T::MaxApprovedCodeHashes>> = code_hashes
+                .map(|code_hashes| code_hashes.try_into())
+                .transpose()
+                .map_err(|_| Error::<T>::TooManyApprovedCodeHashes)?;
+            <ApprovedCodeHashes<T>>::set(func_id, code_hashes.clone());
+            Self::deposit_event(Event::CodeHashesApproved(func_id, code_hashes));
+            Ok(())
+        }
+
+        /// Designates the contract which may write to `RawStorage`, or removes the current one if
+        /// `None` is given. Only governance gets to decide this.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_system_contract(
+            origin: OriginFor<T>,
+            contract: Option<T::AccountId>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            <SystemContract<T>>::set(contract.clone());
+            Self::deposit_event(Event::SystemContractSet(contract));
+            Ok(())
+        }
+
+        /// Registers the schema of the payload accepted by the extension function `func_id`, or
+        /// removes it if `None` is given. Only governance gets to decide this.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn register_schema(
+            origin: OriginFor<T>,
+            func_id: u32,
+            schema: Option<SchemaDescriptor>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            <Schemas<T>>::set(func_id, schema);
+            Self::deposit_event(Event::SchemaRegistered(func_id, schema));
+            Ok(())
+        }
+
+        /// Replaces the blob contracts can read. Only governance gets to decide this.
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_blob(origin: OriginFor<T>, blob: Vec<u8>) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let blob: BoundedVec<u8, T::MaxBlobLen> =
+                blob.try_into().map_err(|_| Error::<T>::BlobTooLong)?;
+            let len = blob.len() as u32;
+            <Blob<T>>::put(blob);
+            Self::deposit_event(Event::BlobSet(len));
+            Ok(())
+        }
+
+        /// Records a scheduled callback one of our genesis contracts reported in an event.
+        ///
+        /// Only our off-chain worker submits this, as an unsigned transaction. See
+        /// `validate_unsigned` for who we take it from.
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn note_trigger(
+            origin: OriginFor<T>,
+            contract: T::AccountId,
+            trigger: TriggerOf<T>,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            ensure!(
+                Self::genesis_contracts().contains(&contract),
+                Error::<T>::NotAGenesisContract
+            );
+
+            let (caller, at) = (trigger.caller.clone(), trigger.at);
+            <LastTriggers<T>>::insert(&contract, trigger);
+            Self::deposit_event(Event::TriggerNoted(contract, caller, at));
+            Ok(())
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let (contract, trigger) = match call {
+                Call::note_trigger { contract, trigger } => (contract, trigger),
+                _ => return InvalidTransaction::Call.into(),
+            };
+
+            // Nothing proves that a contract really emitted the event, so we only take these from
+            // our own off-chain worker, and from whoever authored the blocks we import. That's
+            // good enough for a development chain with a single author.
+            if !matches!(source, TransactionSource::Local | TransactionSource::InBlock) {
+                return InvalidTransaction::Call.into()
+            }
+            if !Self::genesis_contracts().contains(contract) {
+                return InvalidTransaction::Call.into()
+            }
+
+            ValidTransaction::with_tag_prefix("TemplateTrigger")
+                .and_provides((contract, trigger))
+                // Events only stick around for a block, so this is a note about a block which
+                // has only just passed.
+                .longevity(5)
+                .propagate(false)
+                .build()
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Does what `do_something` does on behalf of `who`, except that the event also records
+        /// the `contract` which `who` went through.
+        ///
+        /// This isn't a dispatchable, it's only reachable through our chain extension. Contracts
+        /// can't sign anything, so going through `do_something` the event would only name `who`
+        /// and look just like `who` had sent an extrinsic themselves.
+        pub fn store_something_for_contract(
+            contract: T::AccountId,
+            who: T::AccountId,
+            something: u32,
+        ) {
+            <Something<T>>::put(something);
+            Self::deposit_event(Event::SomethingStoredByContract(something, who, contract));
+        }
+
+        /// Turns the data of a `SchedulerTriggered` event into a trigger, or `None` if it's the
+        /// data of any other event.
+        pub fn decode_trigger(data: &[u8]) -> Option<TriggerOf<T>> {
+            match data.split_first() {
+                Some((&SCHEDULER_TRIGGERED, mut fields)) => {
+                    let (at, caller, arg) = Decode::decode(&mut fields).ok()?;
+                    Some(Trigger { at, caller, arg })
+                }
+                _ => None,
+            }
+        }
+
+        /// Checks that what we keep about scheduled callbacks and contracts' events adds up.
+        ///
+        /// `try-runtime` runs this before and after every runtime upgrade it tries out, so that
+        /// a migration which leaves any of it in a mess gets caught before it's enacted.
+        #[cfg(any(feature = "try-runtime", test))]
+        pub fn do_try_state() -> Result<(), &'static str> {
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            // `on_initialize` clears the slots of each block as it starts, so whatever is left is
+            // for callbacks still to come. Those are all for contracts which are still around.
+            for (block, count) in <CallbacksPerBlock<T>>::iter() {
+                ensure!(block > now, "callback slots are left over from a past block");
+
+                let mut slots = 0u32;
+                for (contract, taken) in <CallbackSlots<T>>::iter_prefix(block) {
+                    ensure!(
+                        T::CallbackTarget::is_contract(&contract),
+                        "a callback is scheduled for an account which isn't a contract"
+                    );
+                    slots = slots.saturating_add(taken);
+                }
+                ensure!(slots == count, "callback slots don't add up to the callbacks in a block");
+            }
+            ensure!(
+                <CallbackSlots<T>>::iter_keys()
+                    .all(|(block, _)| <CallbacksPerBlock<T>>::contains_key(block)),
+                "a contract holds callback slots in a block without any callbacks"
+            );
+
+            // Whatever is held for a callback is handed back as it runs, which is also when its
+            // address gets forgotten.
+            ensure!(
+                <CallbackEscrow<T>>::iter_keys()
+                    .chain(<CallbackDeposits<T>>::iter_keys())
+                    .all(|handle| <CallbackAddresses<T>>::contains_key(handle)),
+                "value or a deposit is held for a callback which isn't scheduled"
+            );
+            ensure!(
+                <CallbackAddresses<T>>::iter_keys()
+                    .all(|handle| <CallbackOrigins<T>>::contains_key(handle)),
+                "a scheduled callback doesn't say who it's for"
+            );
+
+            let genesis = Self::genesis_contracts();
+            ensure!(
+                <LastTriggers<T>>::iter_keys().all(|contract| genesis.contains(&contract)),
+                "a trigger was noted for a contract which wasn't deployed at genesis"
+            );
+
+            Ok(())
+        }
+
+        /// Counts a call to the chain extension function `func_id`, as having succeeded or not.
+        ///
+        /// This isn't a dispatchable, it's only reachable through our chain extension.
+        pub fn note_extension_call(func_id: u32, succeeded: bool) {
+            <ExtensionCalls<T>>::mutate(func_id, |counts| {
+                if succeeded {
+                    counts.succeeded = counts.succeeded.saturating_add(1);
+                } else {
+                    counts.failed = counts.failed.saturating_add(1);
+                }
+            });
+        }
+
+        /// Writes `value` under `key` in `RawStorage` on behalf of `who`, which needs to be the
+        /// system contract.
+        ///
+        /// This isn't a dispatchable, it's only reachable through our chain extension.
+        pub fn write_raw(who: &T::AccountId, key: Vec<u8>, value: Vec<u8>) -> DispatchResult {
+            ensure!(
+                Self::system_contract().as_ref() == Some(who),
+                Error::<T>::NotSystemContract
+            );
+
+            let key: BoundedVec<_, _> = key.try_into().map_err(|_| Error::<T>::RawKeyTooLong)?;
+            let value: BoundedVec<_, _> =
+                value.try_into().map_err(|_| Error::<T>::RawValueTooLong)?;
+
+            <RawStorage<T>>::insert(&key, value);
+            Self::deposit_event(Event::RawValueWritten(key));
+            Ok(())
+        }
+
+        /// Reads the value stored under `key` in `RawStorage`.
+        ///
+        /// Unlike writing, anyone may do this.
+        pub fn read_raw(key: Vec<u8>) -> Option<Vec<u8>> {
+            // A key which is too long can't have been written in the first place.
+            let key: BoundedVec<u8, T::MaxRawKeyLen> = key.try_into().ok()?;
+            <RawStorage<T>>::get(&key).map(BoundedVec::into_inner)
+        }
+
+        /// Stores a batch of `(key, value)` pairs on behalf of `contract`.
+        ///
+        /// This isn't a dispatchable, it's only reachable through our chain extension.
+        pub fn store_batch(contract: &T::AccountId, values: Vec<(u32, u32)>) -> DispatchResult {
+            ensure!(
+                values.len() as u32 <= T::MaxBatchLen::get(),
+                Error::<T>::BatchTooLarge
+            );
+
+            let count = values.len() as u32;
+            for (key, value) in values {
+                <ContractValues<T>>::insert(contract, key, value);
+            }
+
+            Self::deposit_event(Event::BatchStored(contract.clone(), count));
+            Ok(())
+        }
+
+        /// Reserves a slot for a callback from `contract`, as close to block `at` as possible.
+        ///
+        /// Blocks which already have `MaxCallbacksPerBlock` callbacks, or which already have
+        /// `MaxCallbacksPerContract` callbacks from `contract`, are skipped. Returns the block the
+        /// slot is in, or `None` if none of the blocks up to `MaxCallbackDelay` past `at` had any
+        /// room left.
+        pub fn reserve_callback(
+            contract: &T::AccountId,
+            at: T::BlockNumber,
+        ) -> Option<T::BlockNumber> {
+            (0..=T::MaxCallbackDelay::get())
+                .map(|delay| at.saturating_add(delay.into()))
+                .find(|block| {
+                    Self::callbacks_in(block) < T::MaxCallbacksPerBlock::get()
+                        && Self::contract_callbacks_in(block, contract)
+                            < T::MaxCallbacksPerContract::get()
+                })
+                .map(|block| {
+                    <CallbacksPerBlock<T>>::mutate(block, |count| *count += 1);
+                    <CallbackSlots<T>>::mutate(block, contract, |count| *count += 1);
+                    block
+                })
+        }
+
+        /// Gives back a slot which `contract` reserved in block `at` with `reserve_callback`, say
+        /// because its callback has been moved elsewhere.
+        pub fn release_callback(contract: &T::AccountId, at: T::BlockNumber) {
+            <CallbacksPerBlock<T>>::mutate_exists(at, |count| {
+                *count = count.map(|n| n.saturating_sub(1)).filter(|n| *n > 0);
+            });
+            <CallbackSlots<T>>::mutate_exists(at, contract, |count| {
+                *count = count.map(|n| n.saturating_sub(1)).filter(|n| *n > 0);
+            });
+        }
+
+        /// How many more calls `contract` may schedule during the current `SchedulingPeriod`.
+        pub fn schedule_quota_left(contract: &T::AccountId) -> u32 {
+            let (period, count) = Self::schedule_count(contract);
+            if period != Self::current_scheduling_period() {
+                return T::MaxSchedulesPerPeriod::get()
+            }
+            T::MaxSchedulesPerPeriod::get().saturating_sub(count)
+        }
+
+        /// Counts a call `contract` just scheduled against its quota for the current
+        /// `SchedulingPeriod`.
+        pub fn use_schedule_quota(contract: &T::AccountId) {
+            let current = Self::current_scheduling_period();
+            <ScheduleCounts<T>>::mutate(contract, |(period, count)| {
+                if *period != current {
+                    *period = current;
+                    *count = 0;
+                }
+                *count += 1;
+            });
+        }
+
+        /// The `SchedulingPeriod` we're in right now.
+        fn current_scheduling_period() -> T::BlockNumber {
+            let now = <frame_system::Pallet<T>>::block_number();
+            now / T::SchedulingPeriod::get().max(1).into()
+        }
+
+        /// Hands out the handle for a callback which is about to be scheduled.
+        pub fn next_callback_handle() -> u64 {
+            <NextCallbackHandle<T>>::mutate(|next| {
+                let handle = *next;
+                *next = next.wrapping_add(1);
+                handle
+            })
+        }
+
+        /// Holds on to the `value` which `contract` was sent when scheduling the callback with the
+        /// given `handle`, until the callback runs.
+        ///
+        /// The callback is dispatched on behalf of the contract itself, so the value is held in
+        /// reserve by the contract and paid from its account to itself again.
+        pub fn escrow_callback_value(
+            handle: u64,
+            contract: &T::AccountId,
+            value: BalanceOf<T>,
+        ) -> DispatchResult {
+            if value.is_zero() {
+                return Ok(())
+            }
+
+            T::Currency::reserve(contract, value)?;
+            <CallbackEscrow<T>>::insert(handle, (contract.clone(), value));
+            Ok(())
+        }
+
+        /// Remembers that the callback with the given `handle` is at `index` of the Scheduler's
+        /// agenda for block `at`.
+        pub fn note_callback_address(handle: u64, at: T::BlockNumber, index: u32) {
+            <CallbackAddresses<T>>::insert(handle, (at, index));
+        }
+
+        /// Remembers that the callback with the given `handle` calls into `contract`, on behalf of
+        /// `dispatcher`.
+        pub fn note_callback_origin(
+            handle: u64,
+            contract: &T::AccountId,
+            dispatcher: &T::AccountId,
+        ) {
+            <CallbackOrigins<T>>::insert(handle, (contract.clone(), dispatcher.clone()));
+        }
+
+        /// Lets everyone know that `contract` scheduled a call to itself for block `at`, on
+        /// behalf of `caller`.
+        ///
+        /// Contracts may emit events of their own about this, but block explorers can't be
+        /// expected to decode those.
+        pub fn note_contract_scheduled(
+            contract: T::AccountId,
+            caller: T::AccountId,
+            at: T::BlockNumber,
+        ) {
+            Self::deposit_event(Event::ContractScheduled(contract, caller, at));
+        }
+
+        /// Has `caller` put down the `CallbackDeposit` for the callback with the given `handle`,
+        /// until the callback runs.
+        ///
+        /// `extra` goes on top of that, for whatever else the callback keeps in storage until
+        /// then, e.g. its preimage.
+        pub fn reserve_callback_deposit(
+            handle: u64,
+            caller: &T::AccountId,
+            extra: BalanceOf<T>,
+        ) -> DispatchResult {
+            let deposit = T::CallbackDeposit::get().saturating_add(extra);
+            if deposit.is_zero() {
+                return Ok(())
+            }
+
+            T::Currency::reserve(caller, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+            <CallbackDeposits<T>>::insert(handle, (caller.clone(), deposit));
+            Ok(())
+        }
+
+        /// Removes the receipts which expire in block `n`, returning the weight this took.
+        fn prune_receipts(n: T::BlockNumber) -> Weight {
+            let mut pruned: Weight = 0;
+            for (handle, ()) in <ReceiptExpiry<T>>::drain_prefix(n) {
+                <Receipts<T>>::remove(handle);
+                pruned += 1;
+            }
+            T::DbWeight::get().reads_writes(1 + pruned, 2 * pruned)
+        }
+
+        /// The weight set aside for scheduled callbacks in every block.
+        pub fn reserved_callback_weight() -> Weight {
+            T::CallbackReservation::get() * T::BlockWeights::get().max_block
+        }
+
+        /// How much of the weight reserved for callbacks has been used up in the current block.
+        pub fn reservation_usage() -> ReservationUsage {
+            ReservationUsage {
+                reserved: Self::reserved_callback_weight(),
+                used: Self::callback_weight_used(),
+            }
+        }
+
+        /// Takes as much of `weight` out of the current block's reservation for callbacks as it
+        /// has left, returning how much that was.
+        fn draw_from_reservation(weight: Weight) -> Weight {
+            <CallbackWeightUsed<T>>::mutate(|used| {
+                let covered = Self::reserved_callback_weight().saturating_sub(*used).min(weight);
+                *used = used.saturating_add(covered);
+                covered
+            })
+        }
+
+        /// Whether scheduled callbacks may invoke the message with the given selector.
+        pub fn is_callback_selector_allowed(selector: [u8; 4]) -> bool {
+            selector == DEFAULT_CALLBACK_SELECTOR || Self::callback_selectors().contains(&selector)
+        }
+
+        /// Whether `contract` may schedule callbacks, see `SchedulerAllowlist`.
+        pub fn is_scheduler_allowed(contract: &T::AccountId) -> bool {
+            Self::scheduler_allowlist().map_or(true, |allowed| allowed.contains(contract))
+        }
+
+        /// Whether contracts instantiated from `code_hash` may call the extension function
+        /// `func_id`, see `ApprovedCodeHashes`.
+        pub fn is_code_hash_approved(func_id: u32, code_hash: &T::Hash) -> bool {
+            Self::approved_code_hashes(func_id)
+                .map_or(true, |approved| approved.contains(code_hash))
+        }
+
+        /// Stores an envelope on behalf of `owner`, returning the id it can be retrieved with.
+        ///
+        /// This isn't a dispatchable, it's only reachable through our chain extension.
+        pub fn store_envelope(
+            owner: &T::AccountId,
+            public_key: [u8; 32],
+            expires_at: T::BlockNumber,
+            ciphertext: Vec<u8>,
+        ) -> Result<u64, DispatchError> {
+            ensure!(
+                expires_at > <frame_system::Pallet<T>>::block_number(),
+                Error::<T>::AlreadyExpired
+            );
+            let ciphertext: BoundedVec<_, _> =
+                ciphertext.try_into().map_err(|_| Error::<T>::CiphertextTooLong)?;
+
+            let id = <NextEnvelopeId<T>>::mutate(|next| {
+                let id = *next;
+                *next = next.wrapping_add(1);
+                id
+            });
+            <Envelopes<T>>::insert(
+                id,
+                Envelope {
+                    owner: owner.clone(),
+                    public_key,
+                    expires_at,
+                    ciphertext,
+                },
+            );
+
+            Self::deposit_event(Event::EnvelopeStored(owner.clone(), id, expires_at));
+            Ok(id)
+        }
+
+        /// Stores a commitment to `hash` on behalf of `owner`, which may be revealed from block
+        /// `opens_at` up until (but not including) block `closes_at`. Returns the id of the
+        /// commitment.
+        ///
+        /// This isn't a dispatchable, it's only reachable through our chain extension.
+        pub fn commit(
+            owner: &T::AccountId,
+            hash: T::Hash,
+            opens_at: T::BlockNumber,
+            closes_at: T::BlockNumber,
+        ) -> Result<u64, DispatchError> {
+            ensure!(opens_at < closes_at, Error::<T>::EmptyRevealWindow);
+
+            let id = <NextCommitmentId<T>>::mutate(|next| {
+                let id = *next;
+                *next = next.wrapping_add(1);
+                id
+            });
+            <Commitments<T>>::insert(
+                id,
+                Commitment {
+                    owner: owner.clone(),
+                    hash,
+                    opens_at,
+                    closes_at,
+                },
+            );
+
+            Self::deposit_event(Event::CommitmentStored(owner.clone(), id, opens_at, closes_at));
+            Ok(id)
+        }
+
+        /// Checks `preimage` against the commitment `id` made by `owner`, removing the commitment
+        /// if it matches.
+        ///
+        /// A mismatched preimage leaves the commitment in place, so `owner` may try again as long
+        /// as the reveal window is open.
+        pub fn reveal(owner: &T::AccountId, id: u64, preimage: &[u8]) -> Result<(), RevealError> {
+            let commitment = Self::commitment(id).ok_or(RevealError::Unknown)?;
+            if &commitment.owner != owner {
+                return Err(RevealError::NotOwner)
+            }
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            if now < commitment.opens_at {
+                return Err(RevealError::TooEarly)
+            }
+            if now >= commitment.closes_at {
+                return Err(RevealError::TooLate)
+            }
+            if T::Hashing::hash(preimage) != commitment.hash {
+                return Err(RevealError::Mismatch)
+            }
+
+            <Commitments<T>>::remove(id);
+            Self::deposit_event(Event::CommitmentRevealed(id));
+            Ok(())
+        }
+
+        /// Creates a counter called `name`, starting at zero, with `admin` as its admin and no
+        /// other members.
+        ///
+        /// This isn't a dispatchable, it's only reachable through our chain extension.
+        pub fn create_counter(admin: &T::AccountId, name: Vec<u8>) -> DispatchResult {
+            let name: CounterNameOf<T> =
+                name.try_into().map_err(|_| Error::<T>::CounterNameTooLong)?;
+            ensure!(!<Counters<T>>::contains_key(&name), Error::<T>::CounterExists);
+
+            <Counters<T>>::insert(
+                &name,
+                Counter {
+                    admin: admin.clone(),
+                    members: Default::default(),
+                    value: 0,
+                },
+            );
+
+            Self::deposit_event(Event::CounterCreated(admin.clone(), name));
+            Ok(())
+        }
+
+        /// Increments the counter called `name` on behalf of `who`, which needs to be either its
+        /// admin or one of its members. Returns the new value of the counter.
+        ///
+        /// Every call gets a value of its own, so members can use these as sequence numbers
+        /// without having to coordinate amongst themselves.
+        pub fn increment_counter(who: &T::AccountId, name: Vec<u8>) -> Result<u64, DispatchError> {
+            // A name which is too long can't have been used to create a counter.
+            let name: CounterNameOf<T> =
+                name.try_into().map_err(|_| Error::<T>::UnknownCounter)?;
+
+            let value = <Counters<T>>::try_mutate(&name, |counter| {
+                let counter = counter.as_mut().ok_or(Error::<T>::UnknownCounter)?;
+                ensure!(
+                    &counter.admin == who || counter.members.contains(who),
+                    Error::<T>::NotCounterMember
+                );
+
+                counter.value = counter.value.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
+                Ok::<_, DispatchError>(counter.value)
+            })?;
+
+            Self::deposit_event(Event::CounterIncremented(name, value));
+            Ok(value)
+        }
+
+        /// The current value of the counter called `name`.
+        ///
+        /// Anyone may read any counter.
+        pub fn counter_value(name: Vec<u8>) -> Option<u64> {
+            let name: CounterNameOf<T> = name.try_into().ok()?;
+            <Counters<T>>::get(&name).map(|counter| counter.value)
+        }
+
+        /// Lets `who` increment the counter called `name` if `allowed` is set, and stops letting
+        /// it otherwise. Only the counter's admin may do this.
+        ///
+        /// This isn't a dispatchable, it's only reachable through our chain extension.
+        pub fn set_counter_access(
+            admin: &T::AccountId,
+            name: Vec<u8>,
+            who: T::AccountId,
+            allowed: bool,
+        ) -> DispatchResult {
+            let name: CounterNameOf<T> =
+                name.try_into().map_err(|_| Error::<T>::UnknownCounter)?;
+
+            <Counters<T>>::try_mutate(&name, |counter| {
+                let counter = counter.as_mut().ok_or(Error::<T>::UnknownCounter)?;
+                ensure!(&counter.admin == admin, Error::<T>::NotCounterMember);
+
+                let position = counter.members.iter().position(|member| member == &who);
+                match (allowed, position) {
+                    (true, None) => {
+                        counter
+                            .members
+                            .try_push(who.clone())
+                            .map_err(|_| Error::<T>::TooManyCounterMembers)?;
+                    }
+                    (false, Some(position)) => {
+                        counter.members.remove(position);
+                    }
+                    // Nothing to do, `who` already has the access it's meant to have.
+                    _ => {}
+                }
+                Ok::<_, DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::CounterAccessChanged(name, who, allowed));
+            Ok(())
+        }
     }
 }
@@ -1,16 +1,25 @@
 use crate as pallet_template;
-use frame_support::traits::{
-    ConstU16,
-    ConstU64,
+use frame_support::{
+    parameter_types,
+    traits::{
+        ConstU16,
+        ConstU32,
+        ConstU64,
+    },
 };
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
-    testing::Header,
+    testing::{
+        Header,
+        TestXt,
+    },
     traits::{
         BlakeTwo256,
         IdentityLookup,
     },
+    DispatchError,
+    Perbill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -24,7 +33,15 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-        TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
+        TemplateModule: pallet_template::{
+            Pallet,
+            Call,
+            Storage,
+            Config<T>,
+            Event<T>,
+            ValidateUnsigned,
+        },
     }
 );
 
@@ -46,7 +63,7 @@ impl system::Config for Test {
     type BlockHashCount = ConstU64<250>;
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u64>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
@@ -55,8 +72,102 @@ impl system::Config for Test {
     type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+}
+
+/// The account our mock treats as the target of every callback.
+pub const CALLBACK_TARGET: u64 = 7;
+
+/// We don't have contracts in our mock, so we pretend that remarks are contract callbacks.
+pub struct RemarkIsCallback;
+
+impl pallet_template::CallbackTarget<u64, Call> for RemarkIsCallback {
+    fn target(call: &Call) -> Option<u64> {
+        match call {
+            Call::System(frame_system::Call::remark { .. }) => Some(CALLBACK_TARGET),
+            _ => None,
+        }
+    }
+
+    fn is_contract(account: &u64) -> bool {
+        *account == CALLBACK_TARGET
+    }
+}
+
+/// Nor can we deploy any, so every contract ends up at its deployer's account plus the length
+/// of its code.
+pub struct CodeLenDeployer;
+
+impl pallet_template::ContractDeployer<u64> for CodeLenDeployer {
+    fn deploy(origin: u64, code: Vec<u8>, _data: Vec<u8>) -> Result<u64, DispatchError> {
+        Ok(origin + code.len() as u64)
+    }
+}
+
+/// Nor do we have their events, so we pretend that remarks are too. The remark's sender is the
+/// contract, and the hash of the remark is the event's data.
+pub struct RemarkIsContractEvent;
+
+impl pallet_template::ContractEvents<u64, Event> for RemarkIsContractEvent {
+    fn contract_event(event: &Event) -> Option<(u64, Vec<u8>)> {
+        match event {
+            Event::System(frame_system::Event::Remarked { sender, hash }) => {
+                Some((*sender, hash.as_bytes().to_vec()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = TestXt<Call, ()>;
+}
+
+parameter_types! {
+    pub const CallbackReservation: Perbill = Perbill::from_percent(10);
+}
+
 impl pallet_template::Config for Test {
     type Event = Event;
+    type Call = Call;
+    type CallbackTarget = RemarkIsCallback;
+    type ContractDeployer = CodeLenDeployer;
+    type ContractEvents = RemarkIsContractEvent;
+    type Currency = Balances;
+    type CallbackDeposit = ConstU64<5>;
+    type MaxRawKeyLen = ConstU32<32>;
+    type MaxRawValueLen = ConstU32<64>;
+    type MaxBlobLen = ConstU32<64>;
+    type MaxBatchLen = ConstU32<4>;
+    type MaxCallbacksPerBlock = ConstU32<4>;
+    type MaxCallbacksPerContract = ConstU32<2>;
+    type MaxSchedulesPerPeriod = ConstU32<3>;
+    type SchedulingPeriod = ConstU32<10>;
+    type MaxCallbackDelay = ConstU32<2>;
+    type MaxCallbackSelectors = ConstU32<2>;
+    type MaxAllowedSchedulers = ConstU32<2>;
+    type MaxApprovedCodeHashes = ConstU32<2>;
+    type CallbackReservation = CallbackReservation;
+    type MaxCallbackGasLimit = ConstU64<1_000_000>;
+    type MaxInlineCallbackLen = ConstU32<128>;
+    type MaxCiphertextLen = ConstU32<64>;
+    type CommitmentRetention = ConstU32<2>;
+    type MaxCounterNameLen = ConstU32<8>;
+    type MaxCounterMembers = ConstU32<2>;
+    type ReceiptRetention = ConstU32<2>;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -1,10 +1,50 @@
 use crate::{
     mock::*,
     Error,
+    RevealError,
+    Trigger,
+    SCHEDULER_TRIGGERED,
+};
+use chain_extension_primitives::{
+    SchemaDescriptor,
+    DEFAULT_CALLBACK_SELECTOR,
+};
+use codec::{
+    Decode,
+    Encode,
 };
 use frame_support::{
     assert_noop,
     assert_ok,
+    traits::{
+        ConstU32,
+        Currency,
+        GenesisBuild,
+        Hooks,
+        ReservableCurrency,
+    },
+    unsigned::ValidateUnsigned,
+    weights::GetDispatchInfo,
+    BoundedVec,
+};
+use sp_core::{
+    offchain::{
+        testing::TestTransactionPoolExt,
+        TransactionPoolExt,
+    },
+    H256,
+};
+use sp_runtime::{
+    testing::TestXt,
+    traits::{
+        BadOrigin,
+        BlakeTwo256,
+        Hash,
+    },
+    transaction_validity::{
+        InvalidTransaction,
+        TransactionSource,
+    },
 };
 
 #[test]
@@ -27,3 +67,1123 @@ fn correct_error_for_none_value() {
         );
     });
 }
+
+#[test]
+fn every_account_gets_its_own_value() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::store_for_caller(Origin::signed(1), 42));
+        assert_ok!(TemplateModule::store_for_caller(Origin::signed(2), 7));
+
+        assert_eq!(TemplateModule::account_value(1), Some(42));
+        assert_eq!(TemplateModule::account_value(2), Some(7));
+        assert_eq!(TemplateModule::account_value(3), None);
+        // The global value is left alone.
+        assert_eq!(TemplateModule::something(), None);
+    });
+}
+
+/// Has the callback with the given `handle` wait for the current block, calling into
+/// `CALLBACK_TARGET` on its own behalf, just like our chain extension does when scheduling it.
+fn note_callback(handle: u64) {
+    TemplateModule::note_callback_address(handle, System::block_number(), 0);
+    TemplateModule::note_callback_origin(handle, &CALLBACK_TARGET, &CALLBACK_TARGET);
+}
+
+#[test]
+fn dispatch_callback_records_weight() {
+    new_test_ext().execute_with(|| {
+        note_callback(0);
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        assert_ok!(TemplateModule::dispatch_callback(
+            Origin::signed(CALLBACK_TARGET),
+            0,
+            call
+        ));
+
+        let histogram = TemplateModule::callback_weights(CALLBACK_TARGET);
+        assert_eq!(histogram.count, 1);
+        assert_eq!(histogram.buckets.iter().sum::<u32>(), 1);
+    });
+}
+
+#[test]
+fn callbacks_draw_from_the_reservation() {
+    new_test_ext().execute_with(|| {
+        let reserved = TemplateModule::reserved_callback_weight();
+        assert!(reserved > 0);
+        assert!(TemplateModule::on_initialize(1) >= reserved);
+
+        note_callback(0);
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        let weight = call.get_dispatch_info().weight;
+        let info =
+            TemplateModule::dispatch_callback(Origin::signed(CALLBACK_TARGET), 0, call).unwrap();
+
+        // The callback fit into the reservation, so only our own overhead is left to count.
+        let usage = TemplateModule::reservation_usage();
+        assert_eq!(usage.reserved, reserved);
+        assert_eq!(usage.used, weight);
+        assert_eq!(info.actual_weight, Some(10_000));
+
+        TemplateModule::on_finalize(1);
+        assert_eq!(TemplateModule::callback_weight_used(), 0);
+    });
+}
+
+#[test]
+fn callbacks_past_the_reservation_count_against_the_block() {
+    new_test_ext().execute_with(|| {
+        let reserved = TemplateModule::reserved_callback_weight();
+        crate::CallbackWeightUsed::<Test>::put(reserved);
+
+        note_callback(0);
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        let weight = call.get_dispatch_info().weight;
+        let info =
+            TemplateModule::dispatch_callback(Origin::signed(CALLBACK_TARGET), 0, call).unwrap();
+
+        assert_eq!(TemplateModule::callback_weight_used(), reserved);
+        assert_eq!(info.actual_weight, Some(weight + 10_000));
+    });
+}
+
+#[test]
+fn callbacks_get_handles_in_order() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(TemplateModule::next_callback_handle(), 0);
+        assert_eq!(TemplateModule::next_callback_handle(), 1);
+    });
+}
+
+#[test]
+fn dispatched_callbacks_leave_receipts_for_a_while() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        note_callback(3);
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        let weight = call.get_dispatch_info().weight;
+        assert_ok!(TemplateModule::dispatch_callback(
+            Origin::signed(CALLBACK_TARGET),
+            3,
+            call
+        ));
+
+        let ok: sp_runtime::DispatchResult = Ok(());
+        let receipt = TemplateModule::receipt(3).unwrap();
+        assert_eq!(receipt.block, 1);
+        assert_eq!(receipt.weight, weight);
+        assert!(receipt.success);
+        assert_eq!(receipt.output_hash, BlakeTwo256::hash_of(&ok));
+
+        // Receipts stick around for `ReceiptRetention` blocks.
+        TemplateModule::on_initialize(2);
+        assert!(TemplateModule::receipt(3).is_some());
+        TemplateModule::on_initialize(3);
+        assert_eq!(TemplateModule::receipt(3), None);
+    });
+}
+
+#[test]
+fn forged_callbacks_leave_no_receipts() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let call = || {
+            Box::new(Call::System(frame_system::Call::remark {
+                remark: vec![0; 32],
+            }))
+        };
+
+        // Neither for callbacks which were never scheduled...
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(1), 3, call()),
+            Error::<Test>::UnknownCallback
+        );
+        assert_eq!(TemplateModule::receipt(3), None);
+
+        // ...nor for ones which were, but are dispatched by the account which scheduled them.
+        note_callback(3);
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(1), 3, call()),
+            Error::<Test>::CallbackMismatch
+        );
+        assert_eq!(TemplateModule::receipt(3), None);
+    });
+}
+
+#[test]
+fn escrowed_values_are_released_when_the_callback_runs() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&CALLBACK_TARGET, 100);
+
+        assert_ok!(TemplateModule::escrow_callback_value(
+            0,
+            &CALLBACK_TARGET,
+            30
+        ));
+        assert_eq!(Balances::free_balance(CALLBACK_TARGET), 70);
+        assert_eq!(Balances::reserved_balance(CALLBACK_TARGET), 30);
+        assert_eq!(
+            TemplateModule::callback_escrow(0),
+            Some((CALLBACK_TARGET, 30))
+        );
+
+        note_callback(0);
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        assert_ok!(TemplateModule::dispatch_callback(
+            Origin::signed(CALLBACK_TARGET),
+            0,
+            call
+        ));
+
+        assert_eq!(Balances::reserved_balance(CALLBACK_TARGET), 0);
+        assert_eq!(Balances::free_balance(CALLBACK_TARGET), 100);
+        assert_eq!(TemplateModule::callback_escrow(0), None);
+    });
+}
+
+#[test]
+fn escrowed_values_stay_put_for_forged_callbacks() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&CALLBACK_TARGET, 100);
+        assert_ok!(TemplateModule::escrow_callback_value(
+            0,
+            &CALLBACK_TARGET,
+            30
+        ));
+        note_callback(0);
+
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(1), 0, call),
+            Error::<Test>::CallbackMismatch
+        );
+
+        assert_eq!(Balances::reserved_balance(CALLBACK_TARGET), 30);
+        assert_eq!(
+            TemplateModule::callback_escrow(0),
+            Some((CALLBACK_TARGET, 30))
+        );
+    });
+}
+
+#[test]
+fn callback_deposits_are_returned_when_the_callback_runs() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&1, 10);
+
+        assert_ok!(TemplateModule::reserve_callback_deposit(0, &1, 0));
+        assert_eq!(Balances::reserved_balance(1), 5);
+        assert_eq!(TemplateModule::callback_deposit(0), Some((1, 5)));
+
+        note_callback(0);
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        assert_ok!(TemplateModule::dispatch_callback(
+            Origin::signed(CALLBACK_TARGET),
+            0,
+            call
+        ));
+
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 10);
+        assert_eq!(TemplateModule::callback_deposit(0), None);
+    });
+}
+
+#[test]
+fn callback_deposits_stay_put_for_forged_callbacks() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&1, 10);
+        assert_ok!(TemplateModule::reserve_callback_deposit(0, &1, 0));
+        note_callback(0);
+
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(1), 0, call),
+            Error::<Test>::CallbackMismatch
+        );
+
+        assert_eq!(Balances::reserved_balance(1), 5);
+        assert_eq!(TemplateModule::callback_deposit(0), Some((1, 5)));
+    });
+}
+
+#[test]
+fn callback_addresses_are_forgotten_when_the_callback_runs() {
+    new_test_ext().execute_with(|| {
+        TemplateModule::note_callback_address(0, 10, 2);
+        TemplateModule::note_callback_origin(0, &CALLBACK_TARGET, &CALLBACK_TARGET);
+        assert_eq!(TemplateModule::callback_address(0), Some((10, 2)));
+
+        System::set_block_number(10);
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        assert_ok!(TemplateModule::dispatch_callback(
+            Origin::signed(CALLBACK_TARGET),
+            0,
+            call
+        ));
+
+        assert_eq!(TemplateModule::callback_address(0), None);
+        assert_eq!(TemplateModule::callback_origin(0), None);
+    });
+}
+
+#[test]
+fn callbacks_can_put_down_more_than_the_deposit() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&1, 10);
+
+        assert_ok!(TemplateModule::reserve_callback_deposit(0, &1, 3));
+        assert_eq!(Balances::reserved_balance(1), 8);
+        assert_eq!(TemplateModule::callback_deposit(0), Some((1, 8)));
+    });
+}
+
+#[test]
+fn callback_deposits_must_be_affordable() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&1, 4);
+
+        assert_noop!(
+            TemplateModule::reserve_callback_deposit(0, &1, 0),
+            Error::<Test>::InsufficientDeposit
+        );
+    });
+}
+
+#[test]
+fn only_values_the_contract_has_can_be_escrowed() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&CALLBACK_TARGET, 10);
+
+        assert_noop!(
+            TemplateModule::escrow_callback_value(0, &CALLBACK_TARGET, 30),
+            pallet_balances::Error::<Test>::InsufficientBalance
+        );
+
+        // Callbacks which weren't sent anything don't need an escrow at all.
+        assert_ok!(TemplateModule::escrow_callback_value(
+            0,
+            &CALLBACK_TARGET,
+            0
+        ));
+        assert_eq!(TemplateModule::callback_escrow(0), None);
+    });
+}
+
+#[test]
+fn dispatch_callback_rejects_non_callbacks() {
+    new_test_ext().execute_with(|| {
+        note_callback(0);
+        let call = Box::new(Call::TemplateModule(crate::Call::do_something {
+            something: 42,
+        }));
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(CALLBACK_TARGET), 0, call),
+            Error::<Test>::NotACallback
+        );
+    });
+}
+
+#[test]
+fn dispatch_callback_only_takes_callbacks_which_are_scheduled() {
+    new_test_ext().execute_with(|| {
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(1), 0, call),
+            Error::<Test>::UnknownCallback
+        );
+        assert_eq!(TemplateModule::callback_weights(CALLBACK_TARGET).count, 0);
+    });
+}
+
+#[test]
+fn dispatch_callback_turns_down_callbacks_which_are_not_due() {
+    new_test_ext().execute_with(|| {
+        TemplateModule::note_callback_address(0, 10, 0);
+        TemplateModule::note_callback_origin(0, &CALLBACK_TARGET, &CALLBACK_TARGET);
+
+        System::set_block_number(9);
+        let call = Box::new(Call::System(frame_system::Call::remark {
+            remark: vec![0; 32],
+        }));
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(CALLBACK_TARGET), 0, call),
+            Error::<Test>::CallbackNotDue
+        );
+        assert_eq!(TemplateModule::callback_address(0), Some((10, 0)));
+    });
+}
+
+#[test]
+fn dispatch_callback_turns_down_forged_callbacks() {
+    new_test_ext().execute_with(|| {
+        note_callback(0);
+        let call = || {
+            Box::new(Call::System(frame_system::Call::remark {
+                remark: vec![0; 32],
+            }))
+        };
+
+        // Not even the account which scheduled the callback, it's dispatched by the contract
+        // itself.
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(1), 0, call()),
+            Error::<Test>::CallbackMismatch
+        );
+
+        // The right origin, but a callback meant for another contract.
+        TemplateModule::note_callback_origin(0, &(CALLBACK_TARGET + 1), &CALLBACK_TARGET);
+        assert_noop!(
+            TemplateModule::dispatch_callback(Origin::signed(CALLBACK_TARGET), 0, call()),
+            Error::<Test>::CallbackMismatch
+        );
+
+        // Nothing about the callback was touched, so it can still run as scheduled.
+        assert_eq!(TemplateModule::callback_weights(CALLBACK_TARGET).count, 0);
+        assert_eq!(TemplateModule::callback_weight_used(), 0);
+        assert_eq!(TemplateModule::callback_address(0), Some((0, 0)));
+        TemplateModule::note_callback_origin(0, &CALLBACK_TARGET, &CALLBACK_TARGET);
+        assert_ok!(TemplateModule::dispatch_callback(
+            Origin::signed(CALLBACK_TARGET),
+            0,
+            call()
+        ));
+    });
+}
+
+#[test]
+fn only_root_sets_system_contract() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TemplateModule::set_system_contract(Origin::signed(1), Some(1)),
+            BadOrigin
+        );
+        assert_ok!(TemplateModule::set_system_contract(Origin::root(), Some(1)));
+        assert_eq!(TemplateModule::system_contract(), Some(1));
+    });
+}
+
+#[test]
+fn system_contract_writes_raw_values() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::set_system_contract(Origin::root(), Some(1)));
+        assert_ok!(TemplateModule::write_raw(&1, b"key".to_vec(), b"value".to_vec()));
+
+        let key: BoundedVec<u8, ConstU32<32>> = b"key".to_vec().try_into().unwrap();
+        assert_eq!(
+            TemplateModule::raw_storage(key).map(|v| v.into_inner()),
+            Some(b"value".to_vec())
+        );
+    });
+}
+
+#[test]
+fn other_contracts_cannot_write_raw_values() {
+    new_test_ext().execute_with(|| {
+        // Nobody is the system contract by default.
+        assert_noop!(
+            TemplateModule::write_raw(&1, b"key".to_vec(), b"value".to_vec()),
+            Error::<Test>::NotSystemContract
+        );
+
+        assert_ok!(TemplateModule::set_system_contract(Origin::root(), Some(1)));
+        assert_noop!(
+            TemplateModule::write_raw(&2, b"key".to_vec(), b"value".to_vec()),
+            Error::<Test>::NotSystemContract
+        );
+    });
+}
+
+#[test]
+fn raw_writes_are_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::set_system_contract(Origin::root(), Some(1)));
+        assert_noop!(
+            TemplateModule::write_raw(&1, vec![0; 33], b"value".to_vec()),
+            Error::<Test>::RawKeyTooLong
+        );
+        assert_noop!(
+            TemplateModule::write_raw(&1, b"key".to_vec(), vec![0; 65]),
+            Error::<Test>::RawValueTooLong
+        );
+    });
+}
+
+#[test]
+fn only_governance_sets_the_blob() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(TemplateModule::set_blob(Origin::signed(1), vec![1, 2, 3]), BadOrigin);
+        assert_eq!(TemplateModule::blob().into_inner(), Vec::<u8>::new());
+
+        assert_ok!(TemplateModule::set_blob(Origin::root(), vec![1, 2, 3]));
+        assert_eq!(TemplateModule::blob().into_inner(), vec![1, 2, 3]);
+    });
+}
+
+#[test]
+fn blobs_are_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TemplateModule::set_blob(Origin::root(), vec![0; 65]),
+            Error::<Test>::BlobTooLong
+        );
+    });
+}
+
+#[test]
+fn raw_values_can_be_read_back() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::set_system_contract(Origin::root(), Some(1)));
+        assert_ok!(TemplateModule::write_raw(&1, b"key".to_vec(), b"value".to_vec()));
+
+        assert_eq!(TemplateModule::read_raw(b"key".to_vec()), Some(b"value".to_vec()));
+        assert_eq!(TemplateModule::read_raw(b"other".to_vec()), None);
+        assert_eq!(TemplateModule::read_raw(vec![0; 33]), None);
+    });
+}
+
+#[test]
+fn only_root_registers_schemas() {
+    new_test_ext().execute_with(|| {
+        let schema = SchemaDescriptor {
+            version: 1,
+            hash: [1; 32],
+        };
+        assert_noop!(
+            TemplateModule::register_schema(Origin::signed(1), 7, Some(schema)),
+            BadOrigin
+        );
+        assert_ok!(TemplateModule::register_schema(Origin::root(), 7, Some(schema)));
+        assert_eq!(TemplateModule::schema(7), Some(schema));
+
+        assert_ok!(TemplateModule::register_schema(Origin::root(), 7, None));
+        assert_eq!(TemplateModule::schema(7), None);
+    });
+}
+
+#[test]
+fn store_batch_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::store_batch(&1, vec![(0, 10), (1, 11)]));
+
+        assert_eq!(TemplateModule::contract_value(1, 0), Some(10));
+        assert_eq!(TemplateModule::contract_value(1, 1), Some(11));
+        // Every contract gets its own set of keys.
+        assert_eq!(TemplateModule::contract_value(2, 0), None);
+    });
+}
+
+#[test]
+fn store_batch_is_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TemplateModule::store_batch(&1, (0..5).map(|i| (i, i)).collect()),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn callbacks_fill_up_blocks_in_order() {
+    new_test_ext().execute_with(|| {
+        // Each contract may only have two callbacks per block, so it takes two of them to fill
+        // one up.
+        for contract in [1, 1, 2, 2] {
+            assert_eq!(TemplateModule::reserve_callback(&contract, 10), Some(10));
+        }
+        assert_eq!(TemplateModule::callbacks_in(10), 4);
+
+        // Once a block is full, everyone gets pushed back to the next one.
+        assert_eq!(TemplateModule::reserve_callback(&3, 10), Some(11));
+        assert_eq!(TemplateModule::callbacks_in(11), 1);
+    });
+}
+
+#[test]
+fn no_contract_can_take_up_a_whole_block() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(10));
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(10));
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(11));
+
+        // Which leaves room for others.
+        assert_eq!(TemplateModule::reserve_callback(&2, 10), Some(10));
+        assert_eq!(TemplateModule::contract_callbacks_in(10, 1), 2);
+        assert_eq!(TemplateModule::contract_callbacks_in(10, 2), 1);
+    });
+}
+
+#[test]
+fn schedule_quotas_are_per_contract_and_period() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        for left in [3, 2, 1] {
+            assert_eq!(TemplateModule::schedule_quota_left(&1), left);
+            TemplateModule::use_schedule_quota(&1);
+        }
+        assert_eq!(TemplateModule::schedule_quota_left(&1), 0);
+
+        // Other contracts have a quota of their own.
+        assert_eq!(TemplateModule::schedule_quota_left(&2), 3);
+
+        // Once the next period starts everyone gets their whole quota back.
+        System::set_block_number(10);
+        assert_eq!(TemplateModule::schedule_quota_left(&1), 3);
+        TemplateModule::use_schedule_quota(&1);
+        assert_eq!(TemplateModule::schedule_count(1), (1, 1));
+    });
+}
+
+#[test]
+fn callbacks_are_only_pushed_back_so_far() {
+    new_test_ext().execute_with(|| {
+        // Blocks 10, 11 and 12 each have room for two of our callbacks.
+        for expected in [10, 10, 11, 11, 12, 12] {
+            assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(expected));
+        }
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), None);
+
+        // Nothing changed by the failed attempt.
+        assert_eq!(TemplateModule::contract_callbacks_in(13, 1), 0);
+    });
+}
+
+#[test]
+fn released_callback_slots_can_be_taken_again() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(10));
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(10));
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(11));
+
+        TemplateModule::release_callback(&1, 10);
+        assert_eq!(TemplateModule::callbacks_in(10), 1);
+        assert_eq!(TemplateModule::contract_callbacks_in(10, 1), 1);
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(10));
+
+        // Releasing a block's last slot doesn't leave anything behind.
+        TemplateModule::release_callback(&1, 11);
+        assert!(!crate::CallbacksPerBlock::<Test>::contains_key(11));
+        assert!(!crate::CallbackSlots::<Test>::contains_key(11, 1));
+    });
+}
+
+#[test]
+fn callback_counts_are_cleared_once_a_block_starts() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(TemplateModule::reserve_callback(&1, 10), Some(10));
+
+        TemplateModule::on_initialize(10);
+
+        assert_eq!(TemplateModule::callbacks_in(10), 0);
+        assert_eq!(TemplateModule::contract_callbacks_in(10, 1), 0);
+    });
+}
+
+#[test]
+fn only_root_allows_callback_selectors() {
+    new_test_ext().execute_with(|| {
+        let selector = [0xDE, 0xAD, 0xBE, 0xEF];
+        assert!(TemplateModule::is_callback_selector_allowed(DEFAULT_CALLBACK_SELECTOR));
+        assert!(!TemplateModule::is_callback_selector_allowed(selector));
+
+        assert_noop!(
+            TemplateModule::set_callback_selectors(Origin::signed(1), vec![selector]),
+            BadOrigin
+        );
+        assert_ok!(TemplateModule::set_callback_selectors(Origin::root(), vec![selector]));
+        assert!(TemplateModule::is_callback_selector_allowed(selector));
+
+        // The default selector can't be taken away.
+        assert_ok!(TemplateModule::set_callback_selectors(Origin::root(), vec![]));
+        assert!(!TemplateModule::is_callback_selector_allowed(selector));
+        assert!(TemplateModule::is_callback_selector_allowed(DEFAULT_CALLBACK_SELECTOR));
+    });
+}
+
+#[test]
+fn callback_selectors_are_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TemplateModule::set_callback_selectors(Origin::root(), vec![[1; 4], [2; 4], [3; 4]]),
+            Error::<Test>::TooManyCallbackSelectors
+        );
+    });
+}
+
+#[test]
+fn only_root_restricts_who_may_schedule() {
+    new_test_ext().execute_with(|| {
+        // Anyone may schedule until governance says otherwise.
+        assert!(TemplateModule::is_scheduler_allowed(&1));
+        assert!(TemplateModule::is_scheduler_allowed(&2));
+
+        assert_noop!(
+            TemplateModule::set_scheduler_allowlist(Origin::signed(1), Some(vec![1])),
+            BadOrigin
+        );
+        assert_ok!(TemplateModule::set_scheduler_allowlist(
+            Origin::root(),
+            Some(vec![1])
+        ));
+        assert!(TemplateModule::is_scheduler_allowed(&1));
+        assert!(!TemplateModule::is_scheduler_allowed(&2));
+
+        // An empty allowlist keeps everyone out, lifting it lets everyone back in.
+        assert_ok!(TemplateModule::set_scheduler_allowlist(
+            Origin::root(),
+            Some(vec![])
+        ));
+        assert!(!TemplateModule::is_scheduler_allowed(&1));
+        assert_ok!(TemplateModule::set_scheduler_allowlist(
+            Origin::root(),
+            None
+        ));
+        assert!(TemplateModule::is_scheduler_allowed(&2));
+    });
+}
+
+#[test]
+fn scheduler_allowlist_is_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TemplateModule::set_scheduler_allowlist(Origin::root(), Some(vec![1, 2, 3])),
+            Error::<Test>::TooManyAllowedSchedulers
+        );
+    });
+}
+
+#[test]
+fn only_root_restricts_which_code_may_call_a_function() {
+    new_test_ext().execute_with(|| {
+        let audited = H256::repeat_byte(1);
+        let unaudited = H256::repeat_byte(2);
+        assert!(TemplateModule::is_code_hash_approved(7, &unaudited));
+
+        assert_noop!(
+            TemplateModule::set_approved_code_hashes(Origin::signed(1), 7, Some(vec![audited])),
+            BadOrigin
+        );
+        assert_ok!(TemplateModule::set_approved_code_hashes(
+            Origin::root(),
+            7,
+            Some(vec![audited])
+        ));
+        assert!(TemplateModule::is_code_hash_approved(7, &audited));
+        assert!(!TemplateModule::is_code_hash_approved(7, &unaudited));
+        // Other functions aren't affected.
+        assert!(TemplateModule::is_code_hash_approved(8, &unaudited));
+
+        assert_ok!(TemplateModule::set_approved_code_hashes(
+            Origin::root(),
+            7,
+            None
+        ));
+        assert!(TemplateModule::is_code_hash_approved(7, &unaudited));
+    });
+}
+
+#[test]
+fn approved_code_hashes_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let code_hashes = (1..=3).map(H256::repeat_byte).collect();
+        assert_noop!(
+            TemplateModule::set_approved_code_hashes(Origin::root(), 7, Some(code_hashes)),
+            Error::<Test>::TooManyApprovedCodeHashes
+        );
+    });
+}
+
+#[test]
+fn envelopes_can_be_stored() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        assert_eq!(TemplateModule::store_envelope(&1, [7; 32], 10, vec![1, 2, 3]), Ok(0));
+        assert_eq!(TemplateModule::store_envelope(&1, [7; 32], 10, vec![4, 5, 6]), Ok(1));
+
+        let envelope = TemplateModule::envelope(0).unwrap();
+        assert_eq!(envelope.owner, 1);
+        assert_eq!(envelope.public_key, [7; 32]);
+        assert_eq!(envelope.expires_at, 10);
+        assert_eq!(envelope.ciphertext.into_inner(), vec![1, 2, 3]);
+    });
+}
+
+#[test]
+fn envelopes_are_validated() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+
+        assert_noop!(
+            TemplateModule::store_envelope(&1, [7; 32], 5, vec![1, 2, 3]),
+            Error::<Test>::AlreadyExpired
+        );
+        assert_noop!(
+            TemplateModule::store_envelope(&1, [7; 32], 10, vec![0; 65]),
+            Error::<Test>::CiphertextTooLong
+        );
+    });
+}
+
+#[test]
+fn only_expired_envelopes_can_be_removed() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(TemplateModule::store_envelope(&1, [7; 32], 10, vec![1, 2, 3]));
+
+        assert_noop!(
+            TemplateModule::remove_expired_envelope(Origin::signed(2), 0),
+            Error::<Test>::NotExpired
+        );
+
+        System::set_block_number(10);
+        assert_ok!(TemplateModule::remove_expired_envelope(Origin::signed(2), 0));
+        assert_eq!(TemplateModule::envelope(0), None);
+        assert_noop!(
+            TemplateModule::remove_expired_envelope(Origin::signed(2), 0),
+            Error::<Test>::UnknownEnvelope
+        );
+    });
+}
+
+#[test]
+fn commitments_can_only_be_revealed_during_their_window() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let hash = BlakeTwo256::hash(b"secret");
+        assert_eq!(TemplateModule::commit(&1, hash, 5, 7), Ok(0));
+
+        assert_eq!(TemplateModule::reveal(&1, 0, b"secret"), Err(RevealError::TooEarly));
+
+        System::set_block_number(7);
+        assert_eq!(TemplateModule::reveal(&1, 0, b"secret"), Err(RevealError::TooLate));
+
+        System::set_block_number(6);
+        assert_eq!(TemplateModule::reveal(&2, 0, b"secret"), Err(RevealError::NotOwner));
+        assert_eq!(TemplateModule::reveal(&1, 0, b"guess"), Err(RevealError::Mismatch));
+        assert_eq!(TemplateModule::reveal(&1, 0, b"secret"), Ok(()));
+
+        // Commitments can only be revealed once.
+        assert_eq!(TemplateModule::commitment(0), None);
+        assert_eq!(TemplateModule::reveal(&1, 0, b"secret"), Err(RevealError::Unknown));
+    });
+}
+
+#[test]
+fn reveal_windows_cannot_be_empty() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TemplateModule::commit(&1, BlakeTwo256::hash(b"secret"), 5, 5),
+            Error::<Test>::EmptyRevealWindow
+        );
+    });
+}
+
+#[test]
+fn unrevealed_commitments_are_kept_around_for_a_while() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(TemplateModule::commit(&1, BlakeTwo256::hash(b"secret"), 5, 7));
+
+        // `CommitmentRetention` is two blocks in our mock.
+        System::set_block_number(8);
+        assert_noop!(
+            TemplateModule::remove_expired_commitment(Origin::signed(2), 0),
+            Error::<Test>::NotExpired
+        );
+
+        System::set_block_number(9);
+        assert_ok!(TemplateModule::remove_expired_commitment(Origin::signed(2), 0));
+        assert_eq!(TemplateModule::commitment(0), None);
+    });
+}
+
+#[test]
+fn counters_are_first_come_first_served() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::create_counter(&1, b"orders".to_vec()));
+        assert_eq!(TemplateModule::counter_value(b"orders".to_vec()), Some(0));
+
+        assert_noop!(
+            TemplateModule::create_counter(&2, b"orders".to_vec()),
+            Error::<Test>::CounterExists
+        );
+        // `MaxCounterNameLen` is eight bytes in our mock.
+        assert_noop!(
+            TemplateModule::create_counter(&2, b"invoices".to_vec().repeat(2)),
+            Error::<Test>::CounterNameTooLong
+        );
+    });
+}
+
+#[test]
+fn only_members_can_increment_counters() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::create_counter(&1, b"orders".to_vec()));
+
+        assert_eq!(TemplateModule::increment_counter(&1, b"orders".to_vec()), Ok(1));
+        assert_noop!(
+            TemplateModule::increment_counter(&2, b"orders".to_vec()),
+            Error::<Test>::NotCounterMember
+        );
+
+        assert_ok!(TemplateModule::set_counter_access(&1, b"orders".to_vec(), 2, true));
+        assert_eq!(TemplateModule::increment_counter(&2, b"orders".to_vec()), Ok(2));
+        assert_eq!(TemplateModule::increment_counter(&1, b"orders".to_vec()), Ok(3));
+
+        assert_ok!(TemplateModule::set_counter_access(&1, b"orders".to_vec(), 2, false));
+        assert_noop!(
+            TemplateModule::increment_counter(&2, b"orders".to_vec()),
+            Error::<Test>::NotCounterMember
+        );
+        assert_eq!(TemplateModule::counter_value(b"orders".to_vec()), Some(3));
+
+        assert_noop!(
+            TemplateModule::increment_counter(&1, b"invoices".to_vec()),
+            Error::<Test>::UnknownCounter
+        );
+    });
+}
+
+#[test]
+fn only_admins_manage_counter_access() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::create_counter(&1, b"orders".to_vec()));
+        assert_ok!(TemplateModule::set_counter_access(&1, b"orders".to_vec(), 2, true));
+
+        // Being a member doesn't make you an admin.
+        assert_noop!(
+            TemplateModule::set_counter_access(&2, b"orders".to_vec(), 3, true),
+            Error::<Test>::NotCounterMember
+        );
+
+        // `MaxCounterMembers` is two in our mock.
+        assert_ok!(TemplateModule::set_counter_access(&1, b"orders".to_vec(), 3, true));
+        assert_noop!(
+            TemplateModule::set_counter_access(&1, b"orders".to_vec(), 4, true),
+            Error::<Test>::TooManyCounterMembers
+        );
+
+        let name: BoundedVec<u8, ConstU32<8>> = b"orders".to_vec().try_into().unwrap();
+        let counter = TemplateModule::counter(name).unwrap();
+        assert_eq!(counter.members.into_inner(), vec![2, 3]);
+    });
+}
+
+#[test]
+fn genesis_contracts_are_deployed_in_order() {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    crate::GenesisConfig::<Test> {
+        contracts: vec![(1, vec![0; 10], Vec::new()), (2, vec![0; 20], vec![1, 2])],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    sp_io::TestExternalities::from(storage).execute_with(|| {
+        assert_eq!(TemplateModule::genesis_contracts(), vec![11, 22]);
+    });
+}
+
+// Our mock's contract events are remark hashes, so this pads `data` out to one.
+fn remark_as_contract_event(contract: u64, data: &[u8]) {
+    let mut hash = [0u8; 32];
+    hash[..data.len()].copy_from_slice(data);
+    System::deposit_event(frame_system::Event::Remarked {
+        sender: contract,
+        hash: H256::from(hash),
+    });
+}
+
+#[test]
+fn only_scheduler_triggered_events_are_triggers() {
+    new_test_ext().execute_with(|| {
+        let data = (SCHEDULER_TRIGGERED, 5u64, 3u64, 42u32).encode();
+        assert_eq!(
+            TemplateModule::decode_trigger(&data),
+            Some(Trigger {
+                at: 5,
+                caller: 3,
+                arg: 42
+            })
+        );
+
+        // `ValueWritten`, the contract's first event.
+        assert_eq!(
+            TemplateModule::decode_trigger(&(0u8, 3u64, 42u32).encode()),
+            None
+        );
+        assert_eq!(
+            TemplateModule::decode_trigger(&[SCHEDULER_TRIGGERED, 1]),
+            None
+        );
+        assert_eq!(TemplateModule::decode_trigger(&[]), None);
+    });
+}
+
+#[test]
+fn offchain_worker_reports_triggers_of_genesis_contracts() {
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    let mut ext = new_test_ext();
+    ext.register_extension(TransactionPoolExt::new(pool));
+
+    ext.execute_with(|| {
+        // Nothing gets deposited in the genesis block.
+        System::set_block_number(1);
+        crate::GenesisContracts::<Test>::put(vec![CALLBACK_TARGET]);
+        let data = (SCHEDULER_TRIGGERED, 1u64, 3u64, 42u32).encode();
+        remark_as_contract_event(CALLBACK_TARGET, &data);
+        // Anyone can emit an event which looks like it, but we only listen to our own contracts.
+        remark_as_contract_event(CALLBACK_TARGET + 1, &data);
+
+        TemplateModule::offchain_worker(1);
+
+        let transactions = pool_state.read().transactions.clone();
+        assert_eq!(transactions.len(), 1);
+        let transaction = TestXt::<Call, ()>::decode(&mut &transactions[0][..]).unwrap();
+        assert_eq!(transaction.signature, None);
+        assert_eq!(
+            transaction.call,
+            Call::TemplateModule(crate::Call::note_trigger {
+                contract: CALLBACK_TARGET,
+                trigger: Trigger {
+                    at: 1,
+                    caller: 3,
+                    arg: 42
+                },
+            })
+        );
+    });
+}
+
+#[test]
+fn triggers_are_only_noted_for_genesis_contracts() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let trigger = Trigger {
+            at: 1,
+            caller: 3,
+            arg: 42,
+        };
+        assert_noop!(
+            TemplateModule::note_trigger(Origin::none(), CALLBACK_TARGET, trigger.clone()),
+            Error::<Test>::NotAGenesisContract
+        );
+
+        crate::GenesisContracts::<Test>::put(vec![CALLBACK_TARGET]);
+        assert_noop!(
+            TemplateModule::note_trigger(Origin::signed(3), CALLBACK_TARGET, trigger.clone()),
+            BadOrigin
+        );
+        assert_ok!(TemplateModule::note_trigger(
+            Origin::none(),
+            CALLBACK_TARGET,
+            trigger.clone()
+        ));
+        assert_eq!(TemplateModule::last_trigger(CALLBACK_TARGET), Some(trigger));
+    });
+}
+
+#[test]
+fn triggers_are_only_taken_from_our_own_offchain_worker() {
+    new_test_ext().execute_with(|| {
+        crate::GenesisContracts::<Test>::put(vec![CALLBACK_TARGET]);
+        let call = crate::Call::note_trigger {
+            contract: CALLBACK_TARGET,
+            trigger: Trigger {
+                at: 1,
+                caller: 3,
+                arg: 42,
+            },
+        };
+
+        assert!(TemplateModule::validate_unsigned(TransactionSource::Local, &call).is_ok());
+        assert!(TemplateModule::validate_unsigned(TransactionSource::InBlock, &call).is_ok());
+        assert_eq!(
+            TemplateModule::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Call.into()
+        );
+
+        let other = crate::Call::note_trigger {
+            contract: CALLBACK_TARGET + 1,
+            trigger: Trigger {
+                at: 1,
+                caller: 3,
+                arg: 42,
+            },
+        };
+        assert_eq!(
+            TemplateModule::validate_unsigned(TransactionSource::Local, &other),
+            InvalidTransaction::Call.into()
+        );
+    });
+}
+
+#[test]
+fn try_state_holds_for_scheduled_callbacks() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&1, 10);
+
+        assert_eq!(
+            TemplateModule::reserve_callback(&CALLBACK_TARGET, 10),
+            Some(10)
+        );
+        assert_ok!(TemplateModule::reserve_callback_deposit(0, &1, 0));
+        TemplateModule::note_callback_address(0, 10, 0);
+        TemplateModule::note_callback_origin(0, &CALLBACK_TARGET, &CALLBACK_TARGET);
+        assert_ok!(TemplateModule::do_try_state());
+
+        // Once the block starts its slots are gone, and they're no longer needed either.
+        System::set_block_number(10);
+        assert_eq!(
+            TemplateModule::do_try_state(),
+            Err("callback slots are left over from a past block")
+        );
+        TemplateModule::on_initialize(10);
+        assert_ok!(TemplateModule::do_try_state());
+    });
+}
+
+#[test]
+fn try_state_catches_callbacks_which_dont_add_up() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            TemplateModule::reserve_callback(&(CALLBACK_TARGET + 1), 10),
+            Some(10)
+        );
+        assert_eq!(
+            TemplateModule::do_try_state(),
+            Err("a callback is scheduled for an account which isn't a contract")
+        );
+        TemplateModule::release_callback(&(CALLBACK_TARGET + 1), 10);
+
+        // A deposit which nothing will ever hand back.
+        Balances::make_free_balance_be(&1, 10);
+        assert_ok!(TemplateModule::reserve_callback_deposit(0, &1, 0));
+        assert_eq!(
+            TemplateModule::do_try_state(),
+            Err("value or a deposit is held for a callback which isn't scheduled")
+        );
+    });
+}
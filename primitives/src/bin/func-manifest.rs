@@ -0,0 +1,10 @@
+//! Prints the JSON manifest of our chain extensions' functions, see
+//! `chain_extension_primitives::manifest`.
+
+fn main() {
+    let manifest = chain_extension_primitives::manifest::to_json();
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&manifest).expect("JSON values always serialize")
+    );
+}
@@ -0,0 +1,1395 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Everything that both sides of our chain extension need to agree on.
+//!
+//! The ink! contract and the runtime are compiled separately, so if we define these things twice
+//! it's very easy for them to drift apart. Instead both of them depend on this crate.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+/// When we have a custom type we need to make sure that it can be encoded and decoded.
+///
+/// Since this type is shared, the contract and the runtime are guaranteed to agree on its encoding.
+#[derive(scale::Encode, scale::Decode, scale_info::TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub struct Custom {
+    /// We want to demonstrate how to read dynamically sized types from a chain extension, so we'll
+    /// use a type, `Vec`, whose size we cannot necessarily calculate at compile time.
+    pub inner: Vec<u8>,
+}
+
+/// A payload shaped more like what real pallets take than [`Custom`] is: it has an enum, an
+/// `Option` and a nested struct in it.
+///
+/// As long as both sides use this type their encodings can't drift apart, however it's laid out.
+/// `AccountId` is left generic since the contract and the runtime have types of their own for it,
+/// which encode the same way.
+#[derive(scale::Encode, scale::Decode, scale_info::TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub struct RichCustom<AccountId> {
+    /// Which number we get out of `payload`.
+    pub measure: Measure,
+    /// If set, the runtime only goes through with the call for this caller.
+    pub only_for: Option<AccountId>,
+    pub payload: Custom,
+}
+
+/// The ways a [`RichCustom`] can be turned into a number.
+#[derive(scale::Encode, scale::Decode, scale_info::TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub enum Measure {
+    /// The length of the payload.
+    Length,
+    /// The sum of all bytes in the payload.
+    Sum,
+    /// The byte at the given index of the payload.
+    Nth(u32),
+}
+
+impl Measure {
+    /// Applies this measure to `bytes`, if it can be.
+    pub fn apply(&self, bytes: &[u8]) -> Option<u32> {
+        match self {
+            Self::Length => Some(bytes.len() as u32),
+            Self::Sum => Some(bytes.iter().map(|byte| *byte as u32).sum()),
+            Self::Nth(index) => bytes.get(*index as usize).map(|byte| *byte as u32),
+        }
+    }
+}
+
+/// Puts together the `func_id` of `function` within the extension `extension_id`.
+///
+/// The extension goes into the upper 16 bits and the function into the lower ones, which is how
+/// the runtime tells our extensions apart.
+pub const fn pack_func_id(extension_id: u16, function: u16) -> u32 {
+    ((extension_id as u32) << 16) | function as u32
+}
+
+/// Splits a `func_id` back up into the extension it belongs to and the function within it, see
+/// [`pack_func_id`].
+pub const fn unpack_func_id(func_id: u32) -> (u16, u16) {
+    ((func_id >> 16) as u16, func_id as u16)
+}
+
+/// The extension for everything but scheduling callbacks, i.e. `MyChainExtension` on the ink!
+/// side.
+///
+/// Its id is zero, so its functions kept the ids they had before we had more than one extension.
+pub const MAIN_EXTENSION_ID: u16 = 0;
+/// The extension for scheduling callbacks, i.e. `SchedulerExtension` on the ink! side.
+pub const SCHEDULER_EXTENSION_ID: u16 = 1;
+
+/// Function 0 of every extension is `version()`, which tells contracts which version of that
+/// extension the runtime speaks.
+pub const VERSION_FUNCTION: u16 = 0;
+
+/// The version of `MyChainExtension` described by this crate.
+///
+/// This gets bumped whenever one of its functions changes in a way which would break contracts
+/// built against an older version, e.g. by expecting a different input. Newly added functions
+/// don't need a bump, contracts find out about those through `capabilities()`.
+pub const MAIN_EXTENSION_VERSION: u32 = 1;
+/// The version of `SchedulerExtension` described by this crate, see [`MAIN_EXTENSION_VERSION`].
+pub const SCHEDULER_EXTENSION_VERSION: u32 = 3;
+
+/// Corresponds to `version()` of `MyChainExtension`.
+pub const FUNC_VERSION: u32 = pack_func_id(MAIN_EXTENSION_ID, VERSION_FUNCTION);
+/// Corresponds to `write_to_storage()`.
+pub const FUNC_WRITE_TO_STORAGE: u32 = pack_func_id(MAIN_EXTENSION_ID, 1);
+/// Corresponds to `custom_type_with_result()`.
+pub const FUNC_CUSTOM_TYPE_WITH_RESULT: u32 = pack_func_id(MAIN_EXTENSION_ID, 2);
+/// Corresponds to `capabilities()`.
+pub const FUNC_CAPABILITIES: u32 = pack_func_id(MAIN_EXTENSION_ID, 4);
+/// Corresponds to `write_raw()`.
+pub const FUNC_WRITE_RAW: u32 = pack_func_id(MAIN_EXTENSION_ID, 6);
+/// Corresponds to `checked_custom_type()`.
+pub const FUNC_CHECKED_CUSTOM_TYPE: u32 = pack_func_id(MAIN_EXTENSION_ID, 7);
+/// Corresponds to `store_batch()`.
+pub const FUNC_STORE_BATCH: u32 = pack_func_id(MAIN_EXTENSION_ID, 8);
+/// Corresponds to `load_value()`.
+pub const FUNC_LOAD_VALUE: u32 = pack_func_id(MAIN_EXTENSION_ID, 9);
+/// Corresponds to `read_raw()`.
+pub const FUNC_READ_RAW: u32 = pack_func_id(MAIN_EXTENSION_ID, 10);
+/// Corresponds to `write_for_caller()`.
+pub const FUNC_WRITE_FOR_CALLER: u32 = pack_func_id(MAIN_EXTENSION_ID, 11);
+/// Corresponds to `read_for()`.
+pub const FUNC_READ_FOR: u32 = pack_func_id(MAIN_EXTENSION_ID, 12);
+/// Corresponds to `transfer_native()`.
+pub const FUNC_TRANSFER_NATIVE: u32 = pack_func_id(MAIN_EXTENSION_ID, 13);
+/// Corresponds to `quote()`.
+pub const FUNC_QUOTE: u32 = pack_func_id(MAIN_EXTENSION_ID, 14);
+/// Corresponds to `store_envelope()`.
+pub const FUNC_STORE_ENVELOPE: u32 = pack_func_id(MAIN_EXTENSION_ID, 15);
+/// Corresponds to `envelope()`.
+pub const FUNC_ENVELOPE: u32 = pack_func_id(MAIN_EXTENSION_ID, 16);
+/// Corresponds to `asset_balance()`.
+pub const FUNC_ASSET_BALANCE: u32 = pack_func_id(MAIN_EXTENSION_ID, 17);
+/// Corresponds to `asset_transfer()`.
+pub const FUNC_ASSET_TRANSFER: u32 = pack_func_id(MAIN_EXTENSION_ID, 18);
+/// Corresponds to `asset_mint()`.
+pub const FUNC_ASSET_MINT: u32 = pack_func_id(MAIN_EXTENSION_ID, 19);
+/// Corresponds to `commit()`.
+pub const FUNC_COMMIT: u32 = pack_func_id(MAIN_EXTENSION_ID, 20);
+/// Corresponds to `reveal()`.
+pub const FUNC_REVEAL: u32 = pack_func_id(MAIN_EXTENSION_ID, 21);
+/// Corresponds to `asset_total_supply()`.
+pub const FUNC_ASSET_TOTAL_SUPPLY: u32 = pack_func_id(MAIN_EXTENSION_ID, 22);
+/// Corresponds to `asset_transfer_approved()`.
+pub const FUNC_ASSET_TRANSFER_APPROVED: u32 = pack_func_id(MAIN_EXTENSION_ID, 23);
+/// Corresponds to `nft_mint()`.
+pub const FUNC_NFT_MINT: u32 = pack_func_id(MAIN_EXTENSION_ID, 24);
+/// Corresponds to `nft_transfer()`.
+pub const FUNC_NFT_TRANSFER: u32 = pack_func_id(MAIN_EXTENSION_ID, 25);
+/// Corresponds to `nft_owner_of()`.
+pub const FUNC_NFT_OWNER_OF: u32 = pack_func_id(MAIN_EXTENSION_ID, 26);
+/// Corresponds to `random()`.
+pub const FUNC_RANDOM: u32 = pack_func_id(MAIN_EXTENSION_ID, 27);
+/// Corresponds to `counter_create()`.
+pub const FUNC_COUNTER_CREATE: u32 = pack_func_id(MAIN_EXTENSION_ID, 28);
+/// Corresponds to `counter_increment()`.
+pub const FUNC_COUNTER_INCREMENT: u32 = pack_func_id(MAIN_EXTENSION_ID, 29);
+/// Corresponds to `counter_value()`.
+pub const FUNC_COUNTER_VALUE: u32 = pack_func_id(MAIN_EXTENSION_ID, 30);
+/// Corresponds to `counter_set_access()`.
+pub const FUNC_COUNTER_SET_ACCESS: u32 = pack_func_id(MAIN_EXTENSION_ID, 31);
+/// Corresponds to `verify_sr25519()`.
+pub const FUNC_VERIFY_SR25519: u32 = pack_func_id(MAIN_EXTENSION_ID, 32);
+/// Corresponds to `verify_ecdsa()`.
+pub const FUNC_VERIFY_ECDSA: u32 = pack_func_id(MAIN_EXTENSION_ID, 33);
+/// Corresponds to `bounded_custom_type()`.
+pub const FUNC_BOUNDED_CUSTOM_TYPE: u32 = pack_func_id(MAIN_EXTENSION_ID, 34);
+/// Corresponds to `rich_custom_type()`.
+pub const FUNC_RICH_CUSTOM_TYPE: u32 = pack_func_id(MAIN_EXTENSION_ID, 35);
+/// Corresponds to `transfer_native_compact()`.
+pub const FUNC_TRANSFER_NATIVE_COMPACT: u32 = pack_func_id(MAIN_EXTENSION_ID, 36);
+/// Corresponds to `blob()`.
+pub const FUNC_BLOB: u32 = pack_func_id(MAIN_EXTENSION_ID, 37);
+/// Corresponds to `call_counts()`.
+pub const FUNC_CALL_COUNTS: u32 = pack_func_id(MAIN_EXTENSION_ID, 38);
+/// Corresponds to `read_from_storage()`.
+pub const FUNC_READ_FROM_STORAGE: u32 = pack_func_id(MAIN_EXTENSION_ID, 39);
+/// Corresponds to `chain_constants()`.
+pub const FUNC_CHAIN_CONSTANTS: u32 = pack_func_id(MAIN_EXTENSION_ID, 40);
+/// Corresponds to `free_balance()`.
+pub const FUNC_FREE_BALANCE: u32 = pack_func_id(MAIN_EXTENSION_ID, 41);
+/// Corresponds to `account_nonce()`.
+pub const FUNC_ACCOUNT_NONCE: u32 = pack_func_id(MAIN_EXTENSION_ID, 42);
+/// Corresponds to `more_capabilities()`.
+pub const FUNC_MORE_CAPABILITIES: u32 = pack_func_id(MAIN_EXTENSION_ID, 43);
+/// Corresponds to `contract_info()`.
+pub const FUNC_CONTRACT_INFO: u32 = pack_func_id(MAIN_EXTENSION_ID, 44);
+/// Corresponds to `call_contract()`.
+pub const FUNC_CALL_CONTRACT: u32 = pack_func_id(MAIN_EXTENSION_ID, 45);
+/// Corresponds to `instantiate_contract()`.
+pub const FUNC_INSTANTIATE_CONTRACT: u32 = pack_func_id(MAIN_EXTENSION_ID, 46);
+/// Corresponds to `dispatch()`.
+pub const FUNC_DISPATCH: u32 = pack_func_id(MAIN_EXTENSION_ID, 47);
+/// Corresponds to `batch_write()`.
+pub const FUNC_BATCH_WRITE: u32 = pack_func_id(MAIN_EXTENSION_ID, 48);
+/// Corresponds to `as_multi()`.
+pub const FUNC_AS_MULTI: u32 = pack_func_id(MAIN_EXTENSION_ID, 49);
+/// Corresponds to `approve_as_multi()`.
+pub const FUNC_APPROVE_AS_MULTI: u32 = pack_func_id(MAIN_EXTENSION_ID, 50);
+/// Corresponds to `add_proxy()`.
+pub const FUNC_ADD_PROXY: u32 = pack_func_id(MAIN_EXTENSION_ID, 51);
+/// Corresponds to `remove_proxy()`.
+pub const FUNC_REMOVE_PROXY: u32 = pack_func_id(MAIN_EXTENSION_ID, 52);
+/// Corresponds to `proxy()`.
+pub const FUNC_PROXY: u32 = pack_func_id(MAIN_EXTENSION_ID, 53);
+/// Corresponds to `identity_of()`.
+pub const FUNC_IDENTITY_OF: u32 = pack_func_id(MAIN_EXTENSION_ID, 54);
+/// Corresponds to `vote()`.
+pub const FUNC_VOTE: u32 = pack_func_id(MAIN_EXTENSION_ID, 55);
+/// Corresponds to `current_era()`.
+pub const FUNC_CURRENT_ERA: u32 = pack_func_id(MAIN_EXTENSION_ID, 56);
+/// Corresponds to `validator_stake()`.
+pub const FUNC_VALIDATOR_STAKE: u32 = pack_func_id(MAIN_EXTENSION_ID, 57);
+/// Corresponds to `is_bonded()`.
+pub const FUNC_IS_BONDED: u32 = pack_func_id(MAIN_EXTENSION_ID, 58);
+/// Corresponds to `propose_spend()`.
+pub const FUNC_PROPOSE_SPEND: u32 = pack_func_id(MAIN_EXTENSION_ID, 59);
+/// Corresponds to `propose_approval()`.
+pub const FUNC_PROPOSE_APPROVAL: u32 = pack_func_id(MAIN_EXTENSION_ID, 60);
+/// Corresponds to `is_member()`.
+pub const FUNC_IS_MEMBER: u32 = pack_func_id(MAIN_EXTENSION_ID, 61);
+/// Corresponds to `xcm_transact()`.
+pub const FUNC_XCM_TRANSACT: u32 = pack_func_id(MAIN_EXTENSION_ID, 62);
+/// Corresponds to `estimate_fee()`.
+pub const FUNC_ESTIMATE_FEE: u32 = pack_func_id(MAIN_EXTENSION_ID, 63);
+/// Corresponds to `weight_to_fee()`.
+pub const FUNC_WEIGHT_TO_FEE: u32 = pack_func_id(MAIN_EXTENSION_ID, 64);
+/// Corresponds to `block_hash()`.
+pub const FUNC_BLOCK_HASH: u32 = pack_func_id(MAIN_EXTENSION_ID, 65);
+/// Corresponds to `block_author()`.
+pub const FUNC_BLOCK_AUTHOR: u32 = pack_func_id(MAIN_EXTENSION_ID, 66);
+/// Corresponds to `session_info()`.
+pub const FUNC_SESSION_INFO: u32 = pack_func_id(MAIN_EXTENSION_ID, 67);
+
+/// Corresponds to `version()` of `SchedulerExtension`.
+pub const FUNC_SCHEDULER_VERSION: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, VERSION_FUNCTION);
+/// Corresponds to `schedule_call()`.
+pub const FUNC_SCHEDULE_CALL: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, 1);
+/// Corresponds to `callback_weights()`.
+pub const FUNC_CALLBACK_WEIGHTS: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, 2);
+/// Corresponds to `callback_reservation()`.
+pub const FUNC_CALLBACK_RESERVATION: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, 3);
+/// Corresponds to `callback_receipt()`.
+pub const FUNC_CALLBACK_RECEIPT: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, 4);
+/// Corresponds to `callback_deposit()`.
+pub const FUNC_CALLBACK_DEPOSIT: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, 5);
+/// Corresponds to `reschedule()`.
+pub const FUNC_RESCHEDULE: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, 6);
+/// Corresponds to `reschedule_callback()`.
+pub const FUNC_RESCHEDULE_CALLBACK: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, 7);
+/// Corresponds to `schedule_in()`.
+pub const FUNC_SCHEDULE_IN: u32 = pack_func_id(SCHEDULER_EXTENSION_ID, 8);
+
+/// The functions which change state.
+///
+/// Their input starts with a `simulate: bool` flag. When it's set the runtime goes through with
+/// the call, charging for it and returning its status code and output as usual, but then rolls
+/// back any changes it made. Contracts can use this to find out whether something would work
+/// before committing to it.
+pub const STATE_CHANGING_FUNCS: &[u32] = &[
+    FUNC_WRITE_TO_STORAGE,
+    FUNC_CUSTOM_TYPE_WITH_RESULT,
+    FUNC_SCHEDULE_CALL,
+    FUNC_WRITE_RAW,
+    FUNC_CHECKED_CUSTOM_TYPE,
+    FUNC_STORE_BATCH,
+    FUNC_WRITE_FOR_CALLER,
+    FUNC_TRANSFER_NATIVE,
+    FUNC_STORE_ENVELOPE,
+    FUNC_ASSET_TRANSFER,
+    FUNC_ASSET_MINT,
+    FUNC_COMMIT,
+    FUNC_REVEAL,
+    FUNC_ASSET_TRANSFER_APPROVED,
+    FUNC_NFT_MINT,
+    FUNC_NFT_TRANSFER,
+    FUNC_COUNTER_CREATE,
+    FUNC_COUNTER_INCREMENT,
+    FUNC_COUNTER_SET_ACCESS,
+    FUNC_BOUNDED_CUSTOM_TYPE,
+    FUNC_RICH_CUSTOM_TYPE,
+    FUNC_TRANSFER_NATIVE_COMPACT,
+    FUNC_RESCHEDULE,
+    FUNC_RESCHEDULE_CALLBACK,
+    FUNC_SCHEDULE_IN,
+    FUNC_DISPATCH,
+    FUNC_BATCH_WRITE,
+    FUNC_AS_MULTI,
+    FUNC_APPROVE_AS_MULTI,
+    FUNC_ADD_PROXY,
+    FUNC_REMOVE_PROXY,
+    FUNC_PROXY,
+    FUNC_VOTE,
+    FUNC_PROPOSE_SPEND,
+    FUNC_PROPOSE_APPROVAL,
+    FUNC_XCM_TRANSACT,
+];
+
+/// Functions which change state, and take a leading `simulate` flag just like the ones in
+/// [`STATE_CHANGING_FUNCS`], but which the runtime can't simulate. They turn down calls with the
+/// flag set with [`status::SIMULATE_NOT_SUPPORTED`].
+///
+/// These run other contracts, be it one which is already there or the constructor of a new one.
+/// `pallet_contracts` settles what those owe in storage deposits once the outermost call is done,
+/// from what it keeps track of outside of storage. Rolling back what they did to storage would
+/// still leave their deposits to be charged.
+pub const UNSIMULATABLE_FUNCS: &[u32] = &[FUNC_CALL_CONTRACT, FUNC_INSTANTIATE_CONTRACT];
+
+/// How assets managed by `pallet_assets` are identified.
+pub type AssetId = u32;
+
+/// How collections of NFTs managed by `pallet_uniques` are identified.
+pub type CollectionId = u32;
+
+/// How NFTs are identified within their collection.
+pub type ItemId = u32;
+
+/// How the eras of `pallet_staking` are numbered.
+pub type EraIndex = u32;
+
+/// How spending proposals of `pallet_treasury` are numbered.
+pub type ProposalIndex = u32;
+
+/// The largest number of values a contract may hand to `store_batch()` in one go.
+pub const MAX_STORE_BATCH_LEN: u32 = 32;
+
+/// The largest number of values a contract may hand to `batch_write()` in one go.
+pub const MAX_BATCH_WRITE_LEN: u32 = 32;
+
+/// The longest `Custom` value `bounded_custom_type()` accepts.
+///
+/// Knowing this up front is what lets the runtime decode the value with `read_as()`, see
+/// `BoundedCustom` in the runtime. Anything longer doesn't decode, and traps the contract.
+pub const MAX_BOUNDED_CUSTOM_LEN: u32 = 64;
+
+/// The longest display name `identity_of()` hands back.
+///
+/// This is as much as `pallet_identity` lets anyone put into a field as raw bytes, so no display
+/// name ever gets cut short.
+pub const MAX_IDENTITY_DISPLAY_LEN: u32 = 32;
+
+/// The longest encoded call a contract may have `xcm_transact()` send to another chain.
+pub const MAX_XCM_CALL_LEN: u32 = 256;
+
+/// The selector of the message scheduled callbacks invoke unless the runtime has been told about
+/// others, i.e. `scheduler_handler` in our demo contract.
+///
+/// If you're unsure about what a message's selector is, go check out the `metadata.json` file of
+/// the contract.
+pub const DEFAULT_CALLBACK_SELECTOR: [u8; 4] = [0x00, 0xC0, 0xFF, 0xEE];
+
+/// The status codes returned by the runtime through `RetVal::Converging`.
+///
+/// On the ink! side these get turned into an `ExtensionError` by its `FromStatusCode`
+/// implementation.
+pub mod status {
+    /// Everything went fine.
+    pub const SUCCESS: u32 = 0;
+    /// The runtime wasn't happy with the `Custom` value it was given.
+    pub const CUSTOM_CALL_FAILED: u32 = 1;
+    /// The runtime didn't recognize the `func_id` it was called with.
+    pub const UNKNOWN_FUNCTION: u32 = 2;
+    /// The calling contract isn't allowed to use this function.
+    pub const NOT_PERMITTED: u32 = 3;
+    /// The contract and the runtime disagree on the shape of the payload.
+    pub const SCHEMA_MISMATCH: u32 = 4;
+    /// None of the blocks a callback could have been scheduled for had any room left.
+    pub const AGENDA_FULL: u32 = 5;
+    /// The recipient of a transfer doesn't exist yet, and the amount isn't enough to cover the
+    /// existential deposit.
+    pub const BELOW_EXISTENTIAL_DEPOSIT: u32 = 6;
+    /// A transfer would leave the sending contract with less than the existential deposit.
+    pub const WOULD_REAP_SENDER: u32 = 7;
+    /// A transfer failed for any other reason, e.g. because some of the funds are locked.
+    pub const TRANSFER_FAILED: u32 = 8;
+    /// The envelope has expired, or would have expired before it was even stored.
+    pub const ENVELOPE_EXPIRED: u32 = 9;
+    /// The account doesn't hold enough of the asset.
+    pub const ASSET_BALANCE_LOW: u32 = 10;
+    /// There's no asset with the given id.
+    pub const UNKNOWN_ASSET: u32 = 11;
+    /// The asset, or the account's holding of it, is frozen.
+    pub const ASSET_FROZEN: u32 = 12;
+    /// An asset operation failed for any other reason.
+    pub const ASSET_OPERATION_FAILED: u32 = 13;
+    /// There's no commitment with the given id, or it has already been revealed.
+    pub const UNKNOWN_COMMITMENT: u32 = 14;
+    /// The commitment's reveal window hasn't opened yet.
+    pub const REVEAL_TOO_EARLY: u32 = 15;
+    /// The commitment's reveal window has already closed.
+    pub const REVEAL_TOO_LATE: u32 = 16;
+    /// The revealed preimage doesn't match the commitment.
+    pub const INVALID_REVEAL: u32 = 17;
+    /// The owner of the asset hasn't approved the calling contract to move (this much of) it.
+    pub const ASSET_UNAPPROVED: u32 = 18;
+    /// There's no NFT collection, or no item within it, with the given id.
+    pub const UNKNOWN_NFT: u32 = 19;
+    /// There already is an NFT with the given id.
+    pub const NFT_ALREADY_EXISTS: u32 = 20;
+    /// The NFT, or its whole collection, is frozen.
+    pub const NFT_FROZEN: u32 = 21;
+    /// An NFT operation failed for any other reason, e.g. because its metadata was too long.
+    pub const NFT_OPERATION_FAILED: u32 = 22;
+    /// There's no shared counter with the given name.
+    pub const UNKNOWN_COUNTER: u32 = 23;
+    /// There already is a shared counter with the given name.
+    pub const COUNTER_EXISTS: u32 = 24;
+    /// The name of a shared counter is longer than the runtime allows.
+    pub const COUNTER_NAME_TOO_LONG: u32 = 25;
+    /// The shared counter can't have any more members.
+    pub const TOO_MANY_COUNTER_MEMBERS: u32 = 26;
+    /// The signature isn't valid for the given message and public key.
+    pub const INVALID_SIGNATURE: u32 = 27;
+    /// Scheduled callbacks aren't allowed to invoke the message with the given selector.
+    pub const INVALID_CALLBACK_SELECTOR: u32 = 28;
+    /// The gas limit for a scheduled callback is larger than the runtime allows.
+    pub const CALLBACK_GAS_LIMIT_TOO_HIGH: u32 = 29;
+    /// The runtime wouldn't act on behalf of the calling account.
+    pub const BAD_ORIGIN: u32 = 30;
+    /// A call was to be scheduled for a block which has already passed.
+    pub const SCHEDULED_IN_PAST: u32 = 31;
+    /// The Scheduler couldn't schedule a call for any other reason.
+    pub const SCHEDULING_FAILED: u32 = 32;
+    /// The key is longer than the runtime allows for raw storage.
+    pub const RAW_KEY_TOO_LONG: u32 = 33;
+    /// The value is longer than the runtime allows for raw storage.
+    pub const RAW_VALUE_TOO_LONG: u32 = 34;
+    /// The batch holds more values than the runtime stores in one go.
+    pub const BATCH_TOO_LARGE: u32 = 35;
+    /// The ciphertext is longer than the runtime allows for an envelope.
+    pub const CIPHERTEXT_TOO_LONG: u32 = 36;
+    /// The reveal window of a commitment needs to be at least one block long.
+    pub const EMPTY_REVEAL_WINDOW: u32 = 37;
+    /// The contract has already scheduled as many calls as it may for the time being.
+    pub const SCHEDULING_QUOTA_EXCEEDED: u32 = 38;
+    /// Whoever is scheduling a callback can't afford to put down the deposit for it.
+    pub const INSUFFICIENT_DEPOSIT: u32 = 39;
+    /// The calling contract has no pending callback with the given address or handle.
+    pub const UNKNOWN_CALLBACK: u32 = 40;
+    /// A call was to be scheduled a number of blocks from now which doesn't put it in a future
+    /// block, i.e. zero or so many that the block number would overflow.
+    pub const INVALID_SCHEDULE_DELAY: u32 = 41;
+    /// Governance hasn't put the calling contract on the list of those which may schedule calls.
+    pub const NOT_ALLOWED_TO_SCHEDULE: u32 = 42;
+    /// Governance has restricted the function to contracts instantiated from code it approved,
+    /// and the calling contract's code isn't among it.
+    pub const CODE_NOT_APPROVED: u32 = 43;
+    /// There's no contract at the account which was to be called.
+    pub const NOT_A_CONTRACT: u32 = 44;
+    /// The called contract trapped, so everything it did was rolled back.
+    pub const CALLEE_TRAPPED: u32 = 45;
+    /// There's no code with the given hash to instantiate a contract from.
+    pub const UNKNOWN_CODE_HASH: u32 = 46;
+    /// There already is a contract at the address a new one would get, i.e. the same contract
+    /// already instantiated the same code with the same salt.
+    pub const CONTRACT_EXISTS: u32 = 47;
+    /// The constructor of the new contract reverted, so it wasn't instantiated after all.
+    pub const CONSTRUCTOR_REVERTED: u32 = 48;
+    /// A runtime call dispatched on behalf of a contract failed, for a reason there's no more
+    /// specific status code for.
+    pub const DISPATCH_FAILED: u32 = 49;
+    /// The threshold or signatories of a multisig don't add up, e.g. because the other
+    /// signatories aren't sorted or include the contract itself.
+    pub const INVALID_SIGNATORIES: u32 = 50;
+    /// The contract already approved this multisig operation.
+    pub const ALREADY_APPROVED: u32 = 51;
+    /// The multisig operation has all the approvals it needs, so it has to be executed with
+    /// `as_multi()` rather than approved.
+    pub const NO_APPROVALS_NEEDED: u32 = 52;
+    /// There's no multisig operation for this call which is waiting on approvals.
+    pub const UNKNOWN_MULTISIG: u32 = 53;
+    /// The timepoint is missing, or doesn't match the one of the multisig operation's first
+    /// approval.
+    pub const WRONG_TIMEPOINT: u32 = 54;
+    /// The multisig operation's call needs more weight than was set aside for it.
+    pub const MAX_WEIGHT_TOO_LOW: u32 = 55;
+    /// The contract isn't a proxy of the account it wanted to act for, at least not one which may
+    /// make this call without announcing it first.
+    pub const NOT_A_PROXY: u32 = 56;
+    /// The account already has as many proxies as it may have.
+    pub const TOO_MANY_PROXIES: u32 = 57;
+    /// The account already has this proxy.
+    pub const DUPLICATE_PROXY: u32 = 58;
+    /// The account doesn't have the proxy which was to be removed.
+    pub const UNKNOWN_PROXY: u32 = 59;
+    /// There's no referendum with this index which is still being voted on.
+    pub const REFERENDUM_NOT_ONGOING: u32 = 60;
+    /// The contract voted with more than it has.
+    pub const INSUFFICIENT_VOTING_BALANCE: u32 = 61;
+    /// The contract is already voting on as many referenda as it may.
+    pub const TOO_MANY_VOTES: u32 = 62;
+    /// The contract can't afford the bond which a spending proposal has to come with.
+    pub const INSUFFICIENT_PROPOSAL_BOND: u32 = 63;
+    /// Only members of the Council may propose motions.
+    pub const NOT_A_COUNCIL_MEMBER: u32 = 64;
+    /// The Council is already voting on this very motion.
+    pub const DUPLICATE_MOTION: u32 = 65;
+    /// The Council is already voting on as many motions as it may.
+    pub const TOO_MANY_MOTIONS: u32 = 66;
+    /// The call to send to another chain is longer than `MAX_XCM_CALL_LEN`.
+    pub const XCM_CALL_TOO_LONG: u32 = 67;
+    /// There's no way for messages to reach the chain they're meant for.
+    pub const XCM_UNROUTABLE: u32 = 68;
+    /// The message could be routed, but sending it failed anyway.
+    pub const XCM_SEND_FAILED: u32 = 69;
+    /// The `simulate` flag was set for a function which can't be simulated, see
+    /// [`crate::UNSIMULATABLE_FUNCS`].
+    pub const SIMULATE_NOT_SUPPORTED: u32 = 70;
+    /// The runtime call a contract handed over doesn't decode, be it because it's nested too
+    /// deeply or because it was encoded for another version of the runtime.
+    pub const UNDECODABLE_CALL: u32 = 71;
+
+    /// A short, human-readable explanation of `status_code`, or `None` if we don't know it.
+    pub fn describe(status_code: u32) -> Option<&'static str> {
+        let description = match status_code {
+            SUCCESS => "success",
+            CUSTOM_CALL_FAILED => "the runtime wasn't happy with the value it was given",
+            UNKNOWN_FUNCTION => "the runtime doesn't know this function",
+            NOT_PERMITTED => "the calling contract isn't allowed to do this",
+            SCHEMA_MISMATCH => "the contract and the runtime disagree on the payload's schema",
+            AGENDA_FULL => "none of the blocks the call could go into had any room left",
+            BELOW_EXISTENTIAL_DEPOSIT => "the amount doesn't cover the existential deposit",
+            WOULD_REAP_SENDER => "the transfer would reap the sending contract",
+            TRANSFER_FAILED => "the transfer failed",
+            ENVELOPE_EXPIRED => "the envelope has expired",
+            ASSET_BALANCE_LOW => "the account doesn't hold enough of the asset",
+            UNKNOWN_ASSET => "there's no asset with this id",
+            ASSET_FROZEN => "the asset is frozen",
+            ASSET_OPERATION_FAILED => "the asset operation failed",
+            UNKNOWN_COMMITMENT => "there's no unrevealed commitment with this id",
+            REVEAL_TOO_EARLY => "the reveal window hasn't opened yet",
+            REVEAL_TOO_LATE => "the reveal window has already closed",
+            INVALID_REVEAL => "the preimage doesn't match the commitment",
+            ASSET_UNAPPROVED => "the owner hasn't approved the contract to move this much",
+            UNKNOWN_NFT => "there's no NFT with this id",
+            NFT_ALREADY_EXISTS => "there already is an NFT with this id",
+            NFT_FROZEN => "the NFT is frozen",
+            NFT_OPERATION_FAILED => "the NFT operation failed",
+            UNKNOWN_COUNTER => "there's no counter with this name",
+            COUNTER_EXISTS => "there already is a counter with this name",
+            COUNTER_NAME_TOO_LONG => "the counter's name is too long",
+            TOO_MANY_COUNTER_MEMBERS => "the counter can't have any more members",
+            INVALID_SIGNATURE => "the signature isn't valid",
+            INVALID_CALLBACK_SELECTOR => "callbacks aren't allowed to invoke this selector",
+            CALLBACK_GAS_LIMIT_TOO_HIGH => "the callback's gas limit is too high",
+            BAD_ORIGIN => "the runtime won't act on behalf of the calling account",
+            SCHEDULED_IN_PAST => "the block to schedule for has already passed",
+            SCHEDULING_FAILED => "the Scheduler couldn't schedule the call",
+            RAW_KEY_TOO_LONG => "the raw storage key is too long",
+            RAW_VALUE_TOO_LONG => "the raw storage value is too long",
+            BATCH_TOO_LARGE => "the batch holds too many values",
+            CIPHERTEXT_TOO_LONG => "the envelope's ciphertext is too long",
+            EMPTY_REVEAL_WINDOW => "the reveal window needs to be at least one block long",
+            SCHEDULING_QUOTA_EXCEEDED => "the contract can't schedule any more calls for now",
+            INSUFFICIENT_DEPOSIT => "the caller can't afford the callback deposit",
+            UNKNOWN_CALLBACK => "the contract has no pending callback there",
+            INVALID_SCHEDULE_DELAY => "the delay doesn't put the call into a future block",
+            NOT_ALLOWED_TO_SCHEDULE => "the contract isn't allowed to schedule calls",
+            CODE_NOT_APPROVED => "the contract's code isn't approved for this function",
+            NOT_A_CONTRACT => "there's no contract at this account",
+            CALLEE_TRAPPED => "the called contract trapped",
+            UNKNOWN_CODE_HASH => "there's no code with this hash",
+            CONTRACT_EXISTS => "there already is a contract at the new contract's address",
+            CONSTRUCTOR_REVERTED => "the new contract's constructor reverted",
+            DISPATCH_FAILED => "the dispatched call failed",
+            INVALID_SIGNATORIES => "the multisig's threshold or signatories are invalid",
+            ALREADY_APPROVED => "the multisig operation was already approved by this account",
+            NO_APPROVALS_NEEDED => "the multisig operation doesn't need any more approvals",
+            UNKNOWN_MULTISIG => "there's no such multisig operation",
+            WRONG_TIMEPOINT => "the timepoint doesn't match the multisig operation's",
+            MAX_WEIGHT_TOO_LOW => "the multisig operation's call needs more weight",
+            NOT_A_PROXY => "the contract isn't a proxy of the account",
+            TOO_MANY_PROXIES => "the account has too many proxies",
+            DUPLICATE_PROXY => "the account already has this proxy",
+            UNKNOWN_PROXY => "the account doesn't have this proxy",
+            REFERENDUM_NOT_ONGOING => "there's no ongoing referendum with this index",
+            INSUFFICIENT_VOTING_BALANCE => "the contract voted with more than it has",
+            TOO_MANY_VOTES => "the contract is already voting on too many referenda",
+            INSUFFICIENT_PROPOSAL_BOND => "the contract can't afford the proposal's bond",
+            NOT_A_COUNCIL_MEMBER => "the contract isn't a member of the council",
+            DUPLICATE_MOTION => "the council is already voting on this motion",
+            TOO_MANY_MOTIONS => "the council is already voting on too many motions",
+            XCM_CALL_TOO_LONG => "the call to send to another chain is too long",
+            XCM_UNROUTABLE => "there's no route to the chain the message is meant for",
+            XCM_SEND_FAILED => "sending the message to another chain failed",
+            SIMULATE_NOT_SUPPORTED => "this function can't be simulated",
+            UNDECODABLE_CALL => "the runtime call doesn't decode",
+            _ => return None,
+        };
+
+        Some(description)
+    }
+}
+
+/// The areas our status codes are grouped into, see [`ErrorCode`].
+pub mod domain {
+    /// Problems with the call itself, e.g. an unknown function or a malformed payload.
+    pub const GENERAL: u8 = 0;
+    /// Scheduling callbacks.
+    pub const SCHEDULER: u8 = 1;
+    /// Moving the chain's native token around.
+    pub const BALANCES: u8 = 2;
+    /// Storing and reading envelopes.
+    pub const ENVELOPES: u8 = 3;
+    /// Anything to do with `pallet_assets`.
+    pub const ASSETS: u8 = 4;
+    /// Committing and revealing.
+    pub const COMMIT_REVEAL: u8 = 5;
+    /// Anything to do with `pallet_uniques`.
+    pub const NFTS: u8 = 6;
+    /// Counters shared between contracts.
+    pub const COUNTERS: u8 = 7;
+    /// Verifying signatures.
+    pub const SIGNATURES: u8 = 8;
+    /// Values contracts keep in the runtime's storage, be it raw or keyed by contract.
+    pub const STORAGE: u8 = 9;
+    /// Calling and instantiating other contracts.
+    pub const CONTRACTS: u8 = 10;
+    /// Anything to do with `pallet_multisig`.
+    pub const MULTISIG: u8 = 11;
+    /// Anything to do with `pallet_proxy`.
+    pub const PROXY: u8 = 12;
+    /// Voting on referenda of `pallet_democracy`.
+    pub const GOVERNANCE: u8 = 13;
+    /// Spending proposals of `pallet_treasury`.
+    pub const TREASURY: u8 = 14;
+    /// Motions of the Council, an instance of `pallet_collective`.
+    pub const COUNCIL: u8 = 15;
+    /// Messages to other chains.
+    pub const XCM: u8 = 16;
+    /// Errors raised by a contract before it even got to call into the runtime.
+    pub const CONTRACT: u8 = 0xFE;
+    /// Status codes which this version of the primitives doesn't know about.
+    pub const UNKNOWN: u8 = 0xFF;
+}
+
+/// A status code, or any other error a contract runs into when using our extension, as a compact
+/// `(domain, code)` pair.
+///
+/// Status codes are allocated in the order functions get added to the extension, which doesn't
+/// say much about what went wrong. Front-ends can instead look at the domain to figure out which
+/// area an error belongs to, and at the code to pick a message for it. Once assigned, a pair never
+/// changes meaning.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct ErrorCode {
+    /// One of the constants in [`domain`].
+    pub domain: u8,
+    /// What went wrong, numbered from 1 within each domain.
+    pub code: u8,
+}
+
+impl ErrorCode {
+    pub const fn new(domain: u8, code: u8) -> Self {
+        Self { domain, code }
+    }
+
+    /// The pair for a status code returned by the runtime, or `None` for `SUCCESS`.
+    pub fn from_status(status_code: u32) -> Option<Self> {
+        use domain::*;
+
+        let (domain, code) = match status_code {
+            status::SUCCESS => return None,
+            status::CUSTOM_CALL_FAILED => (GENERAL, 1),
+            status::UNKNOWN_FUNCTION => (GENERAL, 2),
+            status::NOT_PERMITTED => (GENERAL, 3),
+            status::SCHEMA_MISMATCH => (GENERAL, 4),
+            status::BAD_ORIGIN => (GENERAL, 5),
+            status::CODE_NOT_APPROVED => (GENERAL, 6),
+            status::DISPATCH_FAILED => (GENERAL, 7),
+            status::SIMULATE_NOT_SUPPORTED => (GENERAL, 8),
+            status::UNDECODABLE_CALL => (GENERAL, 9),
+            status::AGENDA_FULL => (SCHEDULER, 1),
+            status::INVALID_CALLBACK_SELECTOR => (SCHEDULER, 2),
+            status::CALLBACK_GAS_LIMIT_TOO_HIGH => (SCHEDULER, 3),
+            status::SCHEDULED_IN_PAST => (SCHEDULER, 4),
+            status::SCHEDULING_FAILED => (SCHEDULER, 5),
+            status::SCHEDULING_QUOTA_EXCEEDED => (SCHEDULER, 6),
+            status::INSUFFICIENT_DEPOSIT => (SCHEDULER, 7),
+            status::UNKNOWN_CALLBACK => (SCHEDULER, 8),
+            status::INVALID_SCHEDULE_DELAY => (SCHEDULER, 9),
+            status::NOT_ALLOWED_TO_SCHEDULE => (SCHEDULER, 10),
+            status::BELOW_EXISTENTIAL_DEPOSIT => (BALANCES, 1),
+            status::WOULD_REAP_SENDER => (BALANCES, 2),
+            status::TRANSFER_FAILED => (BALANCES, 3),
+            status::ENVELOPE_EXPIRED => (ENVELOPES, 1),
+            status::CIPHERTEXT_TOO_LONG => (ENVELOPES, 2),
+            status::ASSET_BALANCE_LOW => (ASSETS, 1),
+            status::UNKNOWN_ASSET => (ASSETS, 2),
+            status::ASSET_FROZEN => (ASSETS, 3),
+            status::ASSET_OPERATION_FAILED => (ASSETS, 4),
+            status::ASSET_UNAPPROVED => (ASSETS, 5),
+            status::UNKNOWN_COMMITMENT => (COMMIT_REVEAL, 1),
+            status::REVEAL_TOO_EARLY => (COMMIT_REVEAL, 2),
+            status::REVEAL_TOO_LATE => (COMMIT_REVEAL, 3),
+            status::INVALID_REVEAL => (COMMIT_REVEAL, 4),
+            status::EMPTY_REVEAL_WINDOW => (COMMIT_REVEAL, 5),
+            status::UNKNOWN_NFT => (NFTS, 1),
+            status::NFT_ALREADY_EXISTS => (NFTS, 2),
+            status::NFT_FROZEN => (NFTS, 3),
+            status::NFT_OPERATION_FAILED => (NFTS, 4),
+            status::UNKNOWN_COUNTER => (COUNTERS, 1),
+            status::COUNTER_EXISTS => (COUNTERS, 2),
+            status::COUNTER_NAME_TOO_LONG => (COUNTERS, 3),
+            status::TOO_MANY_COUNTER_MEMBERS => (COUNTERS, 4),
+            status::INVALID_SIGNATURE => (SIGNATURES, 1),
+            status::RAW_KEY_TOO_LONG => (STORAGE, 1),
+            status::RAW_VALUE_TOO_LONG => (STORAGE, 2),
+            status::BATCH_TOO_LARGE => (STORAGE, 3),
+            status::NOT_A_CONTRACT => (CONTRACTS, 1),
+            status::CALLEE_TRAPPED => (CONTRACTS, 2),
+            status::UNKNOWN_CODE_HASH => (CONTRACTS, 3),
+            status::CONTRACT_EXISTS => (CONTRACTS, 4),
+            status::CONSTRUCTOR_REVERTED => (CONTRACTS, 5),
+            status::INVALID_SIGNATORIES => (MULTISIG, 1),
+            status::ALREADY_APPROVED => (MULTISIG, 2),
+            status::NO_APPROVALS_NEEDED => (MULTISIG, 3),
+            status::UNKNOWN_MULTISIG => (MULTISIG, 4),
+            status::WRONG_TIMEPOINT => (MULTISIG, 5),
+            status::MAX_WEIGHT_TOO_LOW => (MULTISIG, 6),
+            status::NOT_A_PROXY => (PROXY, 1),
+            status::TOO_MANY_PROXIES => (PROXY, 2),
+            status::DUPLICATE_PROXY => (PROXY, 3),
+            status::UNKNOWN_PROXY => (PROXY, 4),
+            status::REFERENDUM_NOT_ONGOING => (GOVERNANCE, 1),
+            status::INSUFFICIENT_VOTING_BALANCE => (GOVERNANCE, 2),
+            status::TOO_MANY_VOTES => (GOVERNANCE, 3),
+            status::INSUFFICIENT_PROPOSAL_BOND => (TREASURY, 1),
+            status::NOT_A_COUNCIL_MEMBER => (COUNCIL, 1),
+            status::DUPLICATE_MOTION => (COUNCIL, 2),
+            status::TOO_MANY_MOTIONS => (COUNCIL, 3),
+            status::XCM_CALL_TOO_LONG => (XCM, 1),
+            status::XCM_UNROUTABLE => (XCM, 2),
+            status::XCM_SEND_FAILED => (XCM, 3),
+            // We can't squeeze the status code into a `u8`, so all unknown ones look the same.
+            _ => (UNKNOWN, 0),
+        };
+
+        Some(Self::new(domain, code))
+    }
+
+    /// The pair as a single number, with the domain in the upper byte.
+    pub const fn id(self) -> u16 {
+        ((self.domain as u16) << 8) | self.code as u16
+    }
+}
+
+/// The flags making up the bitmap returned by the `capabilities()` function.
+pub mod capabilities {
+    pub const WRITE_TO_STORAGE: u32 = 1 << 0;
+    pub const CUSTOM_TYPE_WITH_RESULT: u32 = 1 << 1;
+    pub const SCHEDULE_CALL: u32 = 1 << 2;
+    pub const CALLBACK_WEIGHTS: u32 = 1 << 3;
+    pub const WRITE_RAW: u32 = 1 << 4;
+    pub const CHECKED_CUSTOM_TYPE: u32 = 1 << 5;
+    pub const CONTRACT_VALUES: u32 = 1 << 6;
+    pub const READ_RAW: u32 = 1 << 7;
+    pub const ACCOUNT_VALUES: u32 = 1 << 8;
+    pub const TRANSFER_NATIVE: u32 = 1 << 9;
+    pub const QUOTE: u32 = 1 << 10;
+    pub const ENVELOPES: u32 = 1 << 11;
+    pub const ASSETS: u32 = 1 << 12;
+    pub const COMMIT_REVEAL: u32 = 1 << 13;
+    pub const NFTS: u32 = 1 << 14;
+    pub const RANDOMNESS: u32 = 1 << 15;
+    pub const COUNTERS: u32 = 1 << 16;
+    pub const SIGNATURES: u32 = 1 << 17;
+    pub const CALLBACK_RESERVATION: u32 = 1 << 18;
+    pub const CALLBACK_RECEIPTS: u32 = 1 << 19;
+    pub const CALLBACK_DEPOSIT: u32 = 1 << 20;
+    pub const BOUNDED_CUSTOM_TYPE: u32 = 1 << 21;
+    pub const RICH_CUSTOM_TYPE: u32 = 1 << 22;
+    pub const TRANSFER_NATIVE_COMPACT: u32 = 1 << 23;
+    pub const BLOB: u32 = 1 << 24;
+    pub const RESCHEDULE: u32 = 1 << 25;
+    pub const SCHEDULE_IN: u32 = 1 << 26;
+    pub const CALL_COUNTS: u32 = 1 << 27;
+    pub const READ_FROM_STORAGE: u32 = 1 << 28;
+    pub const CHAIN_CONSTANTS: u32 = 1 << 29;
+    pub const FREE_BALANCE: u32 = 1 << 30;
+    pub const ACCOUNT_NONCE: u32 = 1 << 31;
+}
+
+/// The flags making up the bitmap returned by the `more_capabilities()` function.
+///
+/// We ran out of room in [`capabilities`], so everything added since gets a flag in here instead.
+/// Runtimes from before then don't know about `more_capabilities()`, which is the same as them
+/// not having any of these.
+pub mod more_capabilities {
+    pub const CONTRACT_INFO: u32 = 1 << 0;
+    pub const CALL_CONTRACT: u32 = 1 << 1;
+    pub const INSTANTIATE_CONTRACT: u32 = 1 << 2;
+    pub const DISPATCH: u32 = 1 << 3;
+    pub const BATCH_WRITE: u32 = 1 << 4;
+    pub const MULTISIG: u32 = 1 << 5;
+    pub const PROXY: u32 = 1 << 6;
+    pub const IDENTITY: u32 = 1 << 7;
+    pub const GOVERNANCE: u32 = 1 << 8;
+    pub const STAKING: u32 = 1 << 9;
+    pub const TREASURY: u32 = 1 << 10;
+    pub const COUNCIL: u32 = 1 << 11;
+    pub const MEMBERSHIP: u32 = 1 << 12;
+    pub const XCM: u32 = 1 << 13;
+    pub const FEES: u32 = 1 << 14;
+    pub const WEIGHT_TO_FEE: u32 = 1 << 15;
+    pub const BLOCK_HASH: u32 = 1 << 16;
+    pub const BLOCK_AUTHOR: u32 = 1 << 17;
+    pub const SESSION: u32 = 1 << 18;
+}
+
+/// The runtime's notion of weight. This has to match `frame_support::weights::Weight`.
+pub type Weight = u64;
+
+/// How much of a block's weight is reserved for scheduled callbacks, and how much of that they've
+/// used up so far.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+pub struct ReservationUsage {
+    pub reserved: Weight,
+    pub used: Weight,
+}
+
+impl ReservationUsage {
+    /// How much of the reservation is still up for grabs.
+    pub fn remaining(&self) -> Weight {
+        self.reserved.saturating_sub(self.used)
+    }
+}
+
+/// How often one of our extension functions has been called, as handed out by `call_counts()`.
+///
+/// Calls which return [`status::SUCCESS`] count as having succeeded, any other status code as
+/// having failed. Calls which trap are rolled back along with everything else the contract did,
+/// so they don't show up at all.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+pub struct CallCounts {
+    pub succeeded: u64,
+    pub failed: u64,
+}
+
+/// How the chain a contract finds itself on is set up, as handed out by `chain_constants()`.
+///
+/// None of these change without a runtime upgrade, but they do differ between chains. A contract
+/// (or its UI) which is meant to be deployed on more than one of them shouldn't hard-code them.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct ChainConstants<Balance> {
+    /// The least an account has to hold on to for it to keep existing.
+    pub existential_deposit: Balance,
+    /// What SS58 addresses on this chain get prefixed with.
+    pub ss58_prefix: u16,
+    /// How many decimal places the native token has.
+    pub token_decimals: u8,
+    /// The most weight the Scheduler spends on calls in any one block, callbacks included.
+    pub maximum_scheduler_weight: Weight,
+}
+
+/// What `contract_info()` hands back for an account which belongs to a contract.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct ContractInfo<Hash, Balance> {
+    /// The code the contract runs.
+    pub code_hash: Hash,
+    /// What the contract's owner put down for the storage the contract is using.
+    pub storage_deposit: Balance,
+}
+
+/// Where the first approval of a multisig operation ended up on chain, which is what identifies
+/// the operation from then on. This has to match `pallet_multisig::Timepoint`.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct Timepoint<BlockNumber> {
+    /// The block the first approval was made in.
+    pub height: BlockNumber,
+    /// The index of the extrinsic which made it within that block.
+    pub index: u32,
+}
+
+/// What a proxy may do on behalf of the account which added it. This is the `ProxyType` of our
+/// runtime's `pallet_proxy`.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub enum ProxyType {
+    /// Anything the account itself could do.
+    Any,
+    /// Only calls of `pallet_template`.
+    Template,
+}
+
+impl Default for ProxyType {
+    fn default() -> Self {
+        Self::Any
+    }
+}
+
+/// What the registrars of `pallet_identity` make of an account's identity, boiled down to a
+/// single judgement. These go from least to most trustworthy.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub enum IdentityJudgement {
+    /// The identity is wrong, and most likely on purpose. Once a registrar says so, this is the
+    /// judgement no matter what the others say.
+    Erroneous,
+    /// The identity is unlikely to be right.
+    LowQuality,
+    /// The identity used to be right, but isn't anymore.
+    OutOfDate,
+    /// Nobody has looked at the identity yet.
+    Unknown,
+    /// A registrar has been paid to look at the identity, but hasn't gotten around to it yet.
+    FeePaid,
+    /// The identity looks right, but nobody checked it in depth.
+    Reasonable,
+    /// The identity has been checked and is right.
+    KnownGood,
+}
+
+impl IdentityJudgement {
+    /// Whether a registrar vouches for the identity, which is what contracts should go by when
+    /// gating features on verified identities.
+    pub fn is_verified(&self) -> bool {
+        matches!(self, Self::Reasonable | Self::KnownGood)
+    }
+}
+
+/// What `identity_of()` hands back for an account which has set an identity.
+///
+/// The runtime keeps the display name in a bounded vector of at most
+/// [`MAX_IDENTITY_DISPLAY_LEN`] bytes, which encodes just like the `Vec` we use on the contract
+/// side.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct BoundedIdentityInfo<Display = Vec<u8>> {
+    /// The account's display name. Identities can also hold just the hash of their display name,
+    /// in which case there's nothing to hand back and this is empty.
+    pub display: Display,
+    /// What the registrars make of the identity, see [`IdentityJudgement`].
+    pub judgement: IdentityJudgement,
+}
+
+/// How long a vote on a referendum keeps the balance it was cast with locked up, in multiples of
+/// the enactment period, and in return how much more the vote counts for. This has to match
+/// `pallet_democracy::Conviction`.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub enum Conviction {
+    /// The balance isn't locked up past the end of the referendum, and counts for a tenth.
+    None,
+    /// Locked up for one enactment period, counts once.
+    Locked1x,
+    /// Locked up for two enactment periods, counts twice.
+    Locked2x,
+    /// Locked up for four enactment periods, counts three times.
+    Locked3x,
+    /// Locked up for eight enactment periods, counts four times.
+    Locked4x,
+    /// Locked up for 16 enactment periods, counts five times.
+    Locked5x,
+    /// Locked up for 32 enactment periods, counts six times.
+    Locked6x,
+}
+
+impl Default for Conviction {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// What `estimate_fee()` hands back, i.e. what `pallet_transaction_payment` would charge for a
+/// call submitted as an extrinsic of its own, tip aside.
+///
+/// This is the breakdown `query_fee_details` gives, along with the call's weight. Calls which
+/// don't pay fees cost nothing.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+pub struct FeeEstimate<Balance> {
+    /// How much the call may use up at most.
+    pub weight: Weight,
+    /// What every extrinsic pays, regardless of what it does.
+    pub base_fee: Balance,
+    /// What the extrinsic pays for its length.
+    pub len_fee: Balance,
+    /// What the extrinsic pays for its weight, adjusted for how busy the chain has been lately.
+    pub adjusted_weight_fee: Balance,
+}
+
+impl FeeEstimate<u128> {
+    /// Everything put together, which is what `query_info` calls the partial fee.
+    pub fn partial_fee(&self) -> u128 {
+        self.base_fee
+            .saturating_add(self.len_fee)
+            .saturating_add(self.adjusted_weight_fee)
+    }
+}
+
+/// What `session_info()` hands back, i.e. where the chain is in its cadence of sessions and eras.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+pub struct SessionInfo<BlockNumber> {
+    /// The session we're in, counting from zero at genesis.
+    pub session_index: u32,
+    /// How many blocks a session lasts.
+    pub session_length: BlockNumber,
+    /// The era stakers are currently being paid for, if staking has gotten around to one yet.
+    pub active_era: Option<EraIndex>,
+    /// How many sessions make up an era.
+    pub sessions_per_era: u32,
+}
+
+/// The number of buckets in a [`WeightHistogram`].
+pub const WEIGHT_HISTOGRAM_BUCKETS: usize = 16;
+
+/// Bucket `i` of a [`WeightHistogram`] counts the weights below `2^(WEIGHT_HISTOGRAM_BASE + i)`
+/// which didn't fit into any of the earlier buckets.
+pub const WEIGHT_HISTOGRAM_BASE: u32 = 26;
+
+/// A summary of how much weight a contract's scheduled callbacks actually ended up using.
+///
+/// The buckets grow exponentially, so we get a decent resolution for cheap callbacks without
+/// needing an unbounded amount of storage for expensive ones. Anything too large for the last
+/// bucket gets counted in it anyways.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+pub struct WeightHistogram {
+    /// How many callbacks have been recorded.
+    pub count: u32,
+    /// The largest weight we've seen so far.
+    pub max: Weight,
+    pub buckets: [u32; WEIGHT_HISTOGRAM_BUCKETS],
+}
+
+impl WeightHistogram {
+    /// The index of the bucket which `weight` gets counted in.
+    pub fn bucket_of(weight: Weight) -> usize {
+        // `weight` is strictly smaller than `2^bits`.
+        let bits = Weight::BITS - weight.leading_zeros();
+        (bits.saturating_sub(WEIGHT_HISTOGRAM_BASE) as usize).min(WEIGHT_HISTOGRAM_BUCKETS - 1)
+    }
+
+    /// The (exclusive) upper bound of the weights counted in the given bucket.
+    pub fn upper_bound(bucket: usize) -> Weight {
+        if bucket >= WEIGHT_HISTOGRAM_BUCKETS - 1 {
+            Weight::MAX
+        } else {
+            1 << (WEIGHT_HISTOGRAM_BASE + bucket as u32)
+        }
+    }
+
+    /// Records the weight used by a single callback.
+    pub fn note(&mut self, weight: Weight) {
+        self.count = self.count.saturating_add(1);
+        self.max = self.max.max(weight);
+
+        let bucket = &mut self.buckets[Self::bucket_of(weight)];
+        *bucket = bucket.saturating_add(1);
+    }
+
+    /// A weight which at least `percent` of the recorded callbacks stayed below.
+    ///
+    /// This is what a contract would want to use as a gas limit for its next callback. Returns
+    /// `None` if nothing has been recorded yet.
+    pub fn percentile(&self, percent: u8) -> Option<Weight> {
+        if self.count == 0 {
+            return None
+        }
+
+        let percent = percent.min(100) as u64;
+        let threshold = (self.count as u64 * percent + 99) / 100;
+
+        let mut seen = 0u64;
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += *count as u64;
+            if seen >= threshold {
+                return Some(Self::upper_bound(bucket).min(self.max))
+            }
+        }
+
+        Some(self.max)
+    }
+}
+
+/// What `schedule_call()` gives back.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct ScheduledCallback<BlockNumber, Hash> {
+    /// Identifies the callback, e.g. when looking up its receipt.
+    pub handle: u64,
+    /// Where the callback went in the Scheduler's agenda: the block it's scheduled for, and its
+    /// index among that block's calls. This is what `reschedule()` takes.
+    pub address: (BlockNumber, u32),
+    /// The hash the callback was noted under with the Preimage pallet, if it was too long to go
+    /// into the Scheduler's agenda as is.
+    pub preimage: Option<Hash>,
+}
+
+/// What the runtime remembers about a scheduled callback once it has run.
+///
+/// Receipts are kept around for a while after the callback ran, so contracts and auditors can
+/// check what came of it using the handle `schedule_call()` gave back.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct CallbackReceipt<BlockNumber, Hash> {
+    /// The block the callback ran in.
+    pub block: BlockNumber,
+    /// The weight the callback actually used.
+    pub weight: Weight,
+    /// Whether the callback went through without an error.
+    pub success: bool,
+    /// The hash of the callback's encoded outcome, i.e. a `Result<(), DispatchError>`.
+    pub output_hash: Hash,
+}
+
+/// An opaque, encrypted blob which the runtime holds on to until it expires.
+///
+/// The runtime never looks inside, it only makes sure nobody gets to read the ciphertext past
+/// `expires_at`. This is enough for commit-reveal and sealed-bid schemes: bidders seal their bids
+/// now and reveal the plaintext later, at which point contracts can check it against the
+/// ciphertext they've seen.
+///
+/// The runtime keeps the ciphertext in a bounded vector, which encodes just like the `Vec` we use
+/// on the contract side.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct Envelope<AccountId, BlockNumber, Ciphertext = Vec<u8>> {
+    /// The contract which stored this envelope.
+    pub owner: AccountId,
+    /// The x25519 public key the ciphertext was sealed for, see [`keys`].
+    pub public_key: [u8; 32],
+    /// The first block at which the envelope can't be read anymore.
+    pub expires_at: BlockNumber,
+    pub ciphertext: Ciphertext,
+}
+
+/// Helpers for the off-chain parties which seal and open [`Envelope`]s.
+#[cfg(feature = "std")]
+pub mod manifest;
+
+#[cfg(feature = "std")]
+pub mod keys {
+    use blake2::{
+        digest::consts::U32,
+        Blake2b,
+        Digest,
+    };
+    pub use x25519_dalek::{
+        PublicKey,
+        StaticSecret,
+    };
+
+    /// Keeps the keys we derive from ever coinciding with anything else derived from the same
+    /// seed.
+    const CONTEXT: &[u8] = b"chain-extension-primitives/envelope/x25519";
+
+    /// Derives an x25519 key pair from the 32 byte seed (i.e. the mini secret key) of an sr25519
+    /// account.
+    ///
+    /// sr25519 keys can't be used for encryption directly, but this way users don't need to keep
+    /// track of a second secret either. The `label` allows deriving different key pairs for
+    /// different purposes, e.g. one per auction.
+    pub fn derive_x25519(seed: &[u8; 32], label: &[u8]) -> (StaticSecret, PublicKey) {
+        let mut hasher = Blake2b::<U32>::new();
+        hasher.update(CONTEXT);
+        hasher.update(seed);
+        hasher.update(label);
+
+        let secret: [u8; 32] = hasher.finalize().into();
+        let secret = StaticSecret::from(secret);
+        let public = PublicKey::from(&secret);
+        (secret, public)
+    }
+}
+
+/// The Blake2-256 hash of a [`type_descriptor`].
+pub type SchemaHash = [u8; 32];
+
+/// A versioned entry of the runtime's schema registry.
+#[derive(
+    scale::Encode,
+    scale::Decode,
+    scale::MaxEncodedLen,
+    scale_info::TypeInfo,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub struct SchemaDescriptor {
+    /// Bumped every time the payload changes shape, so humans can tell descriptors apart.
+    pub version: u32,
+    pub hash: SchemaHash,
+}
+
+/// A SCALE encoded description of `T`'s type information, including everything it refers to.
+///
+/// Two types with the same descriptor encode the same way, so by comparing (the hashes of)
+/// descriptors the contract and the runtime can find out if they've drifted apart before trying
+/// to decode anything.
+///
+/// Docs are left out since whether or not they're included depends on the features `scale-info`
+/// was compiled with, and those may well differ between the contract and the runtime.
+pub fn type_descriptor<T: scale_info::TypeInfo + 'static>() -> Vec<u8> {
+    use scale::Encode;
+    use scale_info::{
+        form::PortableForm,
+        Field,
+        PortableRegistry,
+        Registry,
+        TypeDef,
+    };
+
+    fn encode_fields(fields: &[Field<PortableForm>], out: &mut Vec<u8>) {
+        fields
+            .iter()
+            .map(|field| (field.name(), field.ty(), field.type_name()))
+            .collect::<Vec<_>>()
+            .encode_to(out);
+    }
+
+    let mut registry = Registry::new();
+    registry.register_type(&scale_info::meta_type::<T>());
+    let registry: PortableRegistry = registry.into();
+
+    let mut out = Vec::new();
+    for ty in registry.types() {
+        ty.id().encode_to(&mut out);
+        ty.ty().path().segments().encode_to(&mut out);
+        ty.ty().type_params().encode_to(&mut out);
+
+        match ty.ty().type_def() {
+            TypeDef::Composite(composite) => {
+                0u8.encode_to(&mut out);
+                encode_fields(composite.fields(), &mut out);
+            }
+            TypeDef::Variant(variant) => {
+                1u8.encode_to(&mut out);
+                (variant.variants().len() as u32).encode_to(&mut out);
+                for variant in variant.variants() {
+                    (variant.name(), variant.index()).encode_to(&mut out);
+                    encode_fields(variant.fields(), &mut out);
+                }
+            }
+            // None of the other kinds of types carry any docs.
+            other => {
+                2u8.encode_to(&mut out);
+                other.encode_to(&mut out);
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,895 @@
+//! A machine-readable description of every function our chain extensions offer.
+//!
+//! Tooling and frontends which want to know what a `func_id` takes, hands back or can fail with
+//! can use the JSON from [`to_json()`] instead of reading through the runtime. The
+//! `func-manifest` binary prints it:
+//!
+//! ```bash
+//! cargo run -p chain-extension-primitives --bin func-manifest > manifest.json
+//! ```
+//!
+//! Types are named the way the ink! side sees them, e.g. `AccountId` or `Balance`.
+
+use crate::{
+    status::*,
+    *,
+};
+
+/// What a single function of one of our chain extensions looks like from the outside.
+pub struct FuncSpec {
+    pub func_id: u32,
+    pub name: &'static str,
+    /// The function's arguments and their types, in the order they're encoded in.
+    ///
+    /// This leaves out the leading `simulate: bool` of functions in [`STATE_CHANGING_FUNCS`] and
+    /// [`UNSIMULATABLE_FUNCS`], [`to_json()`] adds it back.
+    pub args: &'static [(&'static str, &'static str)],
+    /// What's written to the output buffer if the call succeeds.
+    pub returns: &'static str,
+    /// The status codes the runtime may hand back, see [`status`].
+    ///
+    /// This leaves out [`CODE_NOT_APPROVED`], which any function may hand back once governance
+    /// restricts it to approved code. [`to_json()`] adds it.
+    pub status_codes: &'static [u32],
+}
+
+/// Every function of [`MAIN_EXTENSION_ID`] and [`SCHEDULER_EXTENSION_ID`], in `func_id` order.
+pub const FUNCS: &[FuncSpec] = &[
+    FuncSpec {
+        func_id: FUNC_VERSION,
+        name: "version",
+        args: &[],
+        returns: "u32",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_WRITE_TO_STORAGE,
+        name: "write_to_storage",
+        args: &[("value", "u32")],
+        returns: "()",
+        status_codes: &[SUCCESS],
+    },
+    FuncSpec {
+        func_id: FUNC_CUSTOM_TYPE_WITH_RESULT,
+        name: "custom_type_with_result",
+        args: &[("custom", "Custom")],
+        returns: "()",
+        status_codes: &[SUCCESS, CUSTOM_CALL_FAILED, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_CAPABILITIES,
+        name: "capabilities",
+        args: &[],
+        returns: "u32",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_WRITE_RAW,
+        name: "write_raw",
+        args: &[("key", "Vec<u8>"), ("value", "Vec<u8>")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            RAW_KEY_TOO_LONG,
+            RAW_VALUE_TOO_LONG,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_CHECKED_CUSTOM_TYPE,
+        name: "checked_custom_type",
+        args: &[("schema", "SchemaHash"), ("custom", "Custom")],
+        returns: "()",
+        status_codes: &[SUCCESS, CUSTOM_CALL_FAILED, UNKNOWN_FUNCTION, SCHEMA_MISMATCH],
+    },
+    FuncSpec {
+        func_id: FUNC_STORE_BATCH,
+        name: "store_batch",
+        args: &[("values", "Vec<(u32, u32)>")],
+        returns: "()",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, BATCH_TOO_LARGE],
+    },
+    FuncSpec {
+        func_id: FUNC_LOAD_VALUE,
+        name: "load_value",
+        args: &[("key", "u32")],
+        returns: "Option<u32>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_READ_RAW,
+        name: "read_raw",
+        args: &[("key", "Vec<u8>")],
+        returns: "Option<Vec<u8>>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_WRITE_FOR_CALLER,
+        name: "write_for_caller",
+        args: &[("value", "u32")],
+        returns: "()",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_READ_FOR,
+        name: "read_for",
+        args: &[("account", "AccountId")],
+        returns: "Option<u32>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_TRANSFER_NATIVE,
+        name: "transfer_native",
+        args: &[("to", "AccountId"), ("amount", "Balance")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            BELOW_EXISTENTIAL_DEPOSIT,
+            WOULD_REAP_SENDER,
+            TRANSFER_FAILED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_QUOTE,
+        name: "quote",
+        args: &[("func_id", "u32"), ("input_len", "u32")],
+        returns: "u64",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_STORE_ENVELOPE,
+        name: "store_envelope",
+        args: &[
+            ("public_key", "[u8; 32]"),
+            ("expires_at", "BlockNumber"),
+            ("ciphertext", "Vec<u8>"),
+        ],
+        returns: "u64",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, ENVELOPE_EXPIRED, CIPHERTEXT_TOO_LONG],
+    },
+    FuncSpec {
+        func_id: FUNC_ENVELOPE,
+        name: "envelope",
+        args: &[("id", "u64")],
+        returns: "Option<Envelope<AccountId, BlockNumber>>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, ENVELOPE_EXPIRED],
+    },
+    FuncSpec {
+        func_id: FUNC_ASSET_BALANCE,
+        name: "asset_balance",
+        args: &[("asset", "AssetId"), ("who", "AccountId")],
+        returns: "Balance",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_ASSET_TRANSFER,
+        name: "asset_transfer",
+        args: &[("asset", "AssetId"), ("to", "AccountId"), ("amount", "Balance")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            BELOW_EXISTENTIAL_DEPOSIT,
+            ASSET_BALANCE_LOW,
+            UNKNOWN_ASSET,
+            ASSET_FROZEN,
+            ASSET_OPERATION_FAILED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_ASSET_MINT,
+        name: "asset_mint",
+        args: &[("asset", "AssetId"), ("to", "AccountId"), ("amount", "Balance")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            BELOW_EXISTENTIAL_DEPOSIT,
+            UNKNOWN_ASSET,
+            ASSET_OPERATION_FAILED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_COMMIT,
+        name: "commit",
+        args: &[("hash", "Hash"), ("opens_in", "u32"), ("window", "u32")],
+        returns: "u64",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            AGENDA_FULL,
+            SCHEDULED_IN_PAST,
+            SCHEDULING_FAILED,
+            EMPTY_REVEAL_WINDOW,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_REVEAL,
+        name: "reveal",
+        args: &[("id", "u64"), ("preimage", "Vec<u8>")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            UNKNOWN_COMMITMENT,
+            REVEAL_TOO_EARLY,
+            REVEAL_TOO_LATE,
+            INVALID_REVEAL,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_ASSET_TOTAL_SUPPLY,
+        name: "asset_total_supply",
+        args: &[("asset", "AssetId")],
+        returns: "Balance",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_ASSET_TRANSFER_APPROVED,
+        name: "asset_transfer_approved",
+        args: &[
+            ("asset", "AssetId"),
+            ("owner", "AccountId"),
+            ("to", "AccountId"),
+            ("amount", "Balance"),
+        ],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            BELOW_EXISTENTIAL_DEPOSIT,
+            ASSET_BALANCE_LOW,
+            UNKNOWN_ASSET,
+            ASSET_FROZEN,
+            ASSET_OPERATION_FAILED,
+            ASSET_UNAPPROVED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_NFT_MINT,
+        name: "nft_mint",
+        args: &[
+            ("collection", "CollectionId"),
+            ("item", "ItemId"),
+            ("owner", "AccountId"),
+            ("metadata", "Vec<u8>"),
+        ],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            UNKNOWN_NFT,
+            NFT_ALREADY_EXISTS,
+            NFT_FROZEN,
+            NFT_OPERATION_FAILED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_NFT_TRANSFER,
+        name: "nft_transfer",
+        args: &[("collection", "CollectionId"), ("item", "ItemId"), ("to", "AccountId")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            UNKNOWN_NFT,
+            NFT_FROZEN,
+            NFT_OPERATION_FAILED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_NFT_OWNER_OF,
+        name: "nft_owner_of",
+        args: &[("collection", "CollectionId"), ("item", "ItemId")],
+        returns: "Option<AccountId>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_RANDOM,
+        name: "random",
+        args: &[("subject", "Vec<u8>")],
+        returns: "Hash",
+        status_codes: &[SUCCESS],
+    },
+    FuncSpec {
+        func_id: FUNC_COUNTER_CREATE,
+        name: "counter_create",
+        args: &[("name", "Vec<u8>")],
+        returns: "()",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, COUNTER_EXISTS, COUNTER_NAME_TOO_LONG],
+    },
+    FuncSpec {
+        func_id: FUNC_COUNTER_INCREMENT,
+        name: "counter_increment",
+        args: &[("name", "Vec<u8>")],
+        returns: "u64",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, NOT_PERMITTED, UNKNOWN_COUNTER],
+    },
+    FuncSpec {
+        func_id: FUNC_COUNTER_VALUE,
+        name: "counter_value",
+        args: &[("name", "Vec<u8>")],
+        returns: "Option<u64>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_COUNTER_SET_ACCESS,
+        name: "counter_set_access",
+        args: &[("name", "Vec<u8>"), ("who", "AccountId"), ("allowed", "bool")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            UNKNOWN_COUNTER,
+            TOO_MANY_COUNTER_MEMBERS,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_VERIFY_SR25519,
+        name: "verify_sr25519",
+        args: &[("signature", "[u8; 64]"), ("message", "Vec<u8>"), ("public", "[u8; 32]")],
+        returns: "()",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, INVALID_SIGNATURE],
+    },
+    FuncSpec {
+        func_id: FUNC_VERIFY_ECDSA,
+        name: "verify_ecdsa",
+        args: &[("signature", "[u8; 65]"), ("message", "Vec<u8>"), ("public", "[u8; 33]")],
+        returns: "()",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, INVALID_SIGNATURE],
+    },
+    FuncSpec {
+        func_id: FUNC_BOUNDED_CUSTOM_TYPE,
+        name: "bounded_custom_type",
+        args: &[("custom", "Custom")],
+        returns: "()",
+        status_codes: &[SUCCESS, CUSTOM_CALL_FAILED, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_RICH_CUSTOM_TYPE,
+        name: "rich_custom_type",
+        args: &[("custom", "RichCustom<AccountId>")],
+        returns: "()",
+        status_codes: &[SUCCESS, CUSTOM_CALL_FAILED, UNKNOWN_FUNCTION, NOT_PERMITTED],
+    },
+    FuncSpec {
+        func_id: FUNC_TRANSFER_NATIVE_COMPACT,
+        name: "transfer_native_compact",
+        args: &[("to", "AccountId"), ("amount", "Compact<Balance>")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            BELOW_EXISTENTIAL_DEPOSIT,
+            WOULD_REAP_SENDER,
+            TRANSFER_FAILED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_BLOB,
+        name: "blob",
+        args: &[],
+        returns: "Vec<u8>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_CALL_COUNTS,
+        name: "call_counts",
+        args: &[("func_id", "u32")],
+        returns: "CallCounts",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_READ_FROM_STORAGE,
+        name: "read_from_storage",
+        args: &[],
+        returns: "Option<u32>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_CHAIN_CONSTANTS,
+        name: "chain_constants",
+        args: &[],
+        returns: "ChainConstants<Balance>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_FREE_BALANCE,
+        name: "free_balance",
+        args: &[("account", "AccountId")],
+        returns: "Balance",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_ACCOUNT_NONCE,
+        name: "account_nonce",
+        args: &[("account", "AccountId")],
+        returns: "u32",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_MORE_CAPABILITIES,
+        name: "more_capabilities",
+        args: &[],
+        returns: "u32",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_CONTRACT_INFO,
+        name: "contract_info",
+        args: &[("account", "AccountId")],
+        returns: "Option<ContractInfo<Hash, Balance>>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_CALL_CONTRACT,
+        name: "call_contract",
+        args: &[
+            ("dest", "AccountId"),
+            ("value", "Balance"),
+            ("gas_limit", "u64"),
+            ("input", "Vec<u8>"),
+        ],
+        returns: "(u32, Vec<u8>)",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            TRANSFER_FAILED,
+            NOT_A_CONTRACT,
+            CALLEE_TRAPPED,
+            SIMULATE_NOT_SUPPORTED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_INSTANTIATE_CONTRACT,
+        name: "instantiate_contract",
+        args: &[
+            ("code_hash", "Hash"),
+            ("value", "Balance"),
+            ("gas_limit", "u64"),
+            ("input", "Vec<u8>"),
+            ("salt", "Vec<u8>"),
+        ],
+        returns: "AccountId",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            TRANSFER_FAILED,
+            CALLEE_TRAPPED,
+            UNKNOWN_CODE_HASH,
+            CONTRACT_EXISTS,
+            CONSTRUCTOR_REVERTED,
+            SIMULATE_NOT_SUPPORTED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_DISPATCH,
+        name: "dispatch",
+        args: &[("call", "Vec<u8>")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            UNDECODABLE_CALL,
+            BAD_ORIGIN,
+            DISPATCH_FAILED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_BATCH_WRITE,
+        name: "batch_write",
+        args: &[("values", "Vec<u32>")],
+        returns: "()",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, BATCH_TOO_LARGE, DISPATCH_FAILED],
+    },
+    FuncSpec {
+        func_id: FUNC_AS_MULTI,
+        name: "as_multi",
+        args: &[
+            ("threshold", "u16"),
+            ("other_signatories", "Vec<AccountId>"),
+            ("timepoint", "Option<Timepoint>"),
+            ("call", "Vec<u8>"),
+            ("max_weight", "Weight"),
+        ],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            DISPATCH_FAILED,
+            INVALID_SIGNATORIES,
+            ALREADY_APPROVED,
+            WRONG_TIMEPOINT,
+            MAX_WEIGHT_TOO_LOW,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_APPROVE_AS_MULTI,
+        name: "approve_as_multi",
+        args: &[
+            ("threshold", "u16"),
+            ("other_signatories", "Vec<AccountId>"),
+            ("timepoint", "Option<Timepoint>"),
+            ("call_hash", "[u8; 32]"),
+            ("max_weight", "Weight"),
+        ],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            DISPATCH_FAILED,
+            INVALID_SIGNATORIES,
+            ALREADY_APPROVED,
+            NO_APPROVALS_NEEDED,
+            UNKNOWN_MULTISIG,
+            WRONG_TIMEPOINT,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_ADD_PROXY,
+        name: "add_proxy",
+        args: &[
+            ("delegate", "AccountId"),
+            ("proxy_type", "ProxyType"),
+            ("delay", "BlockNumber"),
+        ],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            DISPATCH_FAILED,
+            TOO_MANY_PROXIES,
+            DUPLICATE_PROXY,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_REMOVE_PROXY,
+        name: "remove_proxy",
+        args: &[
+            ("delegate", "AccountId"),
+            ("proxy_type", "ProxyType"),
+            ("delay", "BlockNumber"),
+        ],
+        returns: "()",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, DISPATCH_FAILED, UNKNOWN_PROXY],
+    },
+    FuncSpec {
+        func_id: FUNC_PROXY,
+        name: "proxy",
+        args: &[("real", "AccountId"), ("call", "Vec<u8>")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            SCHEMA_MISMATCH,
+            DISPATCH_FAILED,
+            NOT_A_PROXY,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_IDENTITY_OF,
+        name: "identity_of",
+        args: &[("account", "AccountId")],
+        returns: "Option<BoundedIdentityInfo>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_VOTE,
+        name: "vote",
+        args: &[
+            ("ref_index", "u32"),
+            ("aye", "bool"),
+            ("conviction", "Conviction"),
+            ("balance", "Balance"),
+        ],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            NOT_PERMITTED,
+            DISPATCH_FAILED,
+            REFERENDUM_NOT_ONGOING,
+            INSUFFICIENT_VOTING_BALANCE,
+            TOO_MANY_VOTES,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_CURRENT_ERA,
+        name: "current_era",
+        args: &[],
+        returns: "Option<EraIndex>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_VALIDATOR_STAKE,
+        name: "validator_stake",
+        args: &[("validator", "AccountId")],
+        returns: "Balance",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_IS_BONDED,
+        name: "is_bonded",
+        args: &[("stash", "AccountId")],
+        returns: "bool",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_PROPOSE_SPEND,
+        name: "propose_spend",
+        args: &[("value", "Balance"), ("beneficiary", "AccountId")],
+        returns: "ProposalIndex",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            DISPATCH_FAILED,
+            INSUFFICIENT_PROPOSAL_BOND,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_PROPOSE_APPROVAL,
+        name: "propose_approval",
+        args: &[("threshold", "u32"), ("proposal_id", "ProposalIndex")],
+        returns: "Hash",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            DISPATCH_FAILED,
+            NOT_A_COUNCIL_MEMBER,
+            DUPLICATE_MOTION,
+            TOO_MANY_MOTIONS,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_IS_MEMBER,
+        name: "is_member",
+        args: &[("account", "AccountId")],
+        returns: "bool",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_XCM_TRANSACT,
+        name: "xcm_transact",
+        args: &[("call", "Vec<u8>"), ("require_weight_at_most", "u64")],
+        returns: "()",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            XCM_CALL_TOO_LONG,
+            XCM_UNROUTABLE,
+            XCM_SEND_FAILED,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_ESTIMATE_FEE,
+        name: "estimate_fee",
+        args: &[("call", "Vec<u8>")],
+        returns: "FeeEstimate<Balance>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION, SCHEMA_MISMATCH],
+    },
+    FuncSpec {
+        func_id: FUNC_WEIGHT_TO_FEE,
+        name: "weight_to_fee",
+        args: &[("ref_time", "u64"), ("proof_size", "u64")],
+        returns: "Balance",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_BLOCK_HASH,
+        name: "block_hash",
+        args: &[("number", "BlockNumber")],
+        returns: "Option<Hash>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_BLOCK_AUTHOR,
+        name: "block_author",
+        args: &[],
+        returns: "Option<AccountId>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_SESSION_INFO,
+        name: "session_info",
+        args: &[],
+        returns: "SessionInfo<BlockNumber>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_SCHEDULER_VERSION,
+        name: "version",
+        args: &[],
+        returns: "u32",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_SCHEDULE_CALL,
+        name: "schedule_call",
+        args: &[
+            ("at", "u32"),
+            ("selector", "[u8; 4]"),
+            ("gas_limit", "u64"),
+            ("storage_deposit_limit", "Option<Balance>"),
+        ],
+        returns: "ScheduledCallback<BlockNumber, Hash>",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            AGENDA_FULL,
+            INVALID_CALLBACK_SELECTOR,
+            CALLBACK_GAS_LIMIT_TOO_HIGH,
+            SCHEDULED_IN_PAST,
+            SCHEDULING_FAILED,
+            SCHEDULING_QUOTA_EXCEEDED,
+            INSUFFICIENT_DEPOSIT,
+            NOT_ALLOWED_TO_SCHEDULE,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_CALLBACK_WEIGHTS,
+        name: "callback_weights",
+        args: &[],
+        returns: "WeightHistogram",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_CALLBACK_RESERVATION,
+        name: "callback_reservation",
+        args: &[],
+        returns: "ReservationUsage",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_CALLBACK_RECEIPT,
+        name: "callback_receipt",
+        args: &[("handle", "u64")],
+        returns: "Option<CallbackReceipt<BlockNumber, Hash>>",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_CALLBACK_DEPOSIT,
+        name: "callback_deposit",
+        args: &[],
+        returns: "Balance",
+        status_codes: &[SUCCESS, UNKNOWN_FUNCTION],
+    },
+    FuncSpec {
+        func_id: FUNC_RESCHEDULE,
+        name: "reschedule",
+        args: &[("when", "u32"), ("index", "u32"), ("new_when", "u32")],
+        returns: "(u32, u32)",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            AGENDA_FULL,
+            SCHEDULED_IN_PAST,
+            SCHEDULING_FAILED,
+            UNKNOWN_CALLBACK,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_RESCHEDULE_CALLBACK,
+        name: "reschedule_callback",
+        args: &[("handle", "u64"), ("new_when", "u32")],
+        returns: "(u32, u32)",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            AGENDA_FULL,
+            SCHEDULED_IN_PAST,
+            SCHEDULING_FAILED,
+            UNKNOWN_CALLBACK,
+        ],
+    },
+    FuncSpec {
+        func_id: FUNC_SCHEDULE_IN,
+        name: "schedule_in",
+        args: &[
+            ("blocks", "u32"),
+            ("selector", "[u8; 4]"),
+            ("gas_limit", "u64"),
+            ("storage_deposit_limit", "Option<Balance>"),
+        ],
+        returns: "ScheduledCallback<BlockNumber, Hash>",
+        status_codes: &[
+            SUCCESS,
+            UNKNOWN_FUNCTION,
+            AGENDA_FULL,
+            INVALID_CALLBACK_SELECTOR,
+            CALLBACK_GAS_LIMIT_TOO_HIGH,
+            SCHEDULING_FAILED,
+            SCHEDULING_QUOTA_EXCEEDED,
+            INSUFFICIENT_DEPOSIT,
+            INVALID_SCHEDULE_DELAY,
+            NOT_ALLOWED_TO_SCHEDULE,
+        ],
+    },
+];
+
+/// The name of the extension with the given id, as the contract calls it.
+fn extension_name(extension_id: u16) -> &'static str {
+    match extension_id {
+        MAIN_EXTENSION_ID => "MyChainExtension",
+        SCHEDULER_EXTENSION_ID => "SchedulerExtension",
+        _ => "unknown",
+    }
+}
+
+/// [`FUNCS`] as a JSON array, with one object per function.
+pub fn to_json() -> serde_json::Value {
+    let funcs = FUNCS
+        .iter()
+        .map(|func| {
+            let (extension_id, function) = unpack_func_id(func.func_id);
+            let state_changing = STATE_CHANGING_FUNCS.contains(&func.func_id)
+                || UNSIMULATABLE_FUNCS.contains(&func.func_id);
+            let simulate = state_changing.then_some(&("simulate", "bool"));
+            let args: Vec<_> = simulate
+                .into_iter()
+                .chain(func.args)
+                .map(|(name, ty)| serde_json::json!({ "name": name, "type": ty }))
+                .collect();
+            let status_codes: Vec<_> = func
+                .status_codes
+                .iter()
+                .chain(&[CODE_NOT_APPROVED])
+                .collect();
+
+            serde_json::json!({
+                "func_id": func.func_id,
+                "extension": extension_name(extension_id),
+                "extension_id": extension_id,
+                "function": function,
+                "name": func.name,
+                "args": args,
+                "returns": func.returns,
+                "status_codes": status_codes,
+                "state_changing": state_changing,
+            })
+        })
+        .collect();
+
+    serde_json::Value::Array(funcs)
+}
+
+/// Checks the `@chain-extension` annotations in the docs of a contract's `source` against
+/// [`FUNCS`].
+///
+/// Every annotation has to name one of our functions and list the same status codes we do for
+/// it, in the same order. Returns the annotations which don't, along with what they should list.
+pub fn annotation_mismatches(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("/// @chain-extension "))
+        .filter_map(|annotation| {
+            let field = |name: &str| {
+                annotation
+                    .split_whitespace()
+                    .find_map(|field| field.strip_prefix(name)?.strip_prefix('='))
+            };
+            let func = field("func_id")
+                .and_then(|func_id| func_id.parse::<u32>().ok())
+                .and_then(|func_id| FUNCS.iter().find(|func| func.func_id == func_id));
+            let func = match func {
+                Some(func) => func,
+                None => return Some(format!("`{}` isn't one of our functions", annotation)),
+            };
+
+            let status_codes: Vec<_> = func.status_codes.iter().map(u32::to_string).collect();
+            let status_codes = status_codes.join(",");
+            (field("status_codes") != Some(&status_codes))
+                .then(|| format!("`{}` should have status_codes={}", annotation, status_codes))
+        })
+        .collect()
+}
@@ -0,0 +1,47 @@
+;; Forwards its input to our chain extension and hands back whatever the extension returned.
+;;
+;; The first four bytes of the input are the `func_id`, the rest gets passed to the extension
+;; as-is. The output is the extension's status code (four bytes) followed by its output buffer.
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 16 16))
+
+	;; [0, 4) len of the input buffer
+	(data (i32.const 0) "\00\80")
+
+	;; [4, 32772) input buffer: func_id followed by the arguments
+
+	;; [32772, 32776) len of the output buffer
+	(data (i32.const 32772) "\00\80")
+
+	;; [32776, 32780) status code returned by the extension
+
+	;; [32780, 65548) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		(i32.store
+			(i32.const 32776)
+			(call $seal_call_chain_extension
+				(i32.load (i32.const 4))					;; func_id
+				(i32.const 8)								;; input_ptr
+				(i32.sub (i32.load (i32.const 0)) (i32.const 4))	;; input_len
+				(i32.const 32780)							;; output_ptr
+				(i32.const 32772)							;; output_len_ptr
+			)
+		)
+
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 32776)
+			(i32.add (i32.load (i32.const 32772)) (i32.const 4))
+		)
+	)
+)
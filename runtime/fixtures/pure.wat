@@ -0,0 +1,71 @@
+;; Does the same work as our chain extension functions, but using nothing other than the host
+;; functions every contract has access to. This is what an ink! contract which doesn't use our
+;; extension would end up doing.
+;;
+;; The first four bytes of the input select the task, the rest are its arguments:
+;;
+;; 1. Store a `u32` in contract storage.
+;; 2. Store a sequence of `(u32 key, u32 value)` pairs in contract storage.
+;; 3. Hash the arguments with BLAKE2b-256.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_hash_blake2_256" (func $seal_hash_blake2_256 (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) len of the input buffer
+	(data (i32.const 0) "\00\04")
+
+	;; [4, 1028) input buffer: task followed by the arguments
+
+	;; [1028, 1060) storage key, only the first four bytes are ever set
+
+	;; [1060, 1092) hash output
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(local $task i32)
+		(local $end i32)
+		(local $offset i32)
+
+		(call $seal_input (i32.const 4) (i32.const 0))
+		(local.set $task (i32.load (i32.const 4)))
+		(local.set $end (i32.add (i32.const 4) (i32.load (i32.const 0))))
+
+		(if (i32.eq (local.get $task) (i32.const 1))
+			(then
+				(call $seal_set_storage (i32.const 1028) (i32.const 8) (i32.const 4))
+			)
+		)
+
+		(if (i32.eq (local.get $task) (i32.const 2))
+			(then
+				(local.set $offset (i32.const 8))
+				(block $done
+					(loop $next
+						(br_if $done (i32.ge_u (local.get $offset) (local.get $end)))
+						(i32.store (i32.const 1028) (i32.load (local.get $offset)))
+						(call $seal_set_storage
+							(i32.const 1028)							;; key_ptr
+							(i32.add (local.get $offset) (i32.const 4))	;; value_ptr
+							(i32.const 4)								;; value_len
+						)
+						(local.set $offset (i32.add (local.get $offset) (i32.const 8)))
+						(br $next)
+					)
+				)
+			)
+		)
+
+		(if (i32.eq (local.get $task) (i32.const 3))
+			(then
+				(call $seal_hash_blake2_256
+					(i32.const 8)								;; input_ptr
+					(i32.sub (local.get $end) (i32.const 8))	;; input_len
+					(i32.const 1060)							;; output_ptr
+				)
+			)
+		)
+	)
+)
@@ -1,49 +1,290 @@
-use frame_support::pallet_prelude::{Decode, Encode};
+use frame_support::pallet_prelude::Decode;
+use frame_support::weights::Weight;
 use frame_support::traits::tokens::currency::Currency;
-use frame_support::traits::Get;
-use frame_system::RawOrigin;
+use frame_support::storage::{with_transaction, TransactionOutcome};
 use pallet_contracts::chain_extension::{
-    ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+    BufInBufOutState, ChainExtension, Environment, Ext, InitState, RetVal, SysConfig,
+    UncheckedFrom,
 };
 use sp_runtime::DispatchError;
+use sp_std::marker::PhantomData;
 
-/// This is the definition of the `Custom` type from our ink! contract. We need this type to match
-/// what we have in ink! in order for it to be correctly deserialized when we're reading it out of
-/// the buffer.
-#[derive(Encode, Decode)]
-struct CustomDef {
-    inner: sp_std::vec::Vec<u8>,
-}
+#[cfg(feature = "runtime-benchmarks")]
+pub(crate) mod benchmarking;
+pub mod weights;
 
-pub struct MyExtension;
+// Every group of related functions gets a module of its own, see `HandleFunc`.
+mod assets;
+mod authorship;
+mod balances;
+mod commit_reveal;
+mod contracts;
+mod council;
+mod counters;
+mod cross_chain;
+mod crypto;
+mod custom;
+mod envelopes;
+mod fees;
+mod governance;
+mod identity;
+mod introspection;
+mod membership;
+mod multisig;
+mod nfts;
+mod proxy;
+mod runtime_calls;
+mod scheduler;
+mod session;
+mod staking;
+mod storage;
+mod system;
+mod treasury;
 
-// Note, we don't need to have the `SysConfig` trait bound here since the Contract pallet
-// Config is required to implement it
-//
-// Just like when writing runtime code, if we want to access code from specific pallets we need to
-// specify that in our generic parameters.
-impl<T> ChainExtension<T> for MyExtension
+#[cfg(test)]
+mod gas_golf;
+#[cfg(test)]
+mod tests;
+#[cfg(test)]
+mod weight_snapshot;
+
+use self::{
+    assets::AssetFuncs,
+    authorship::AuthorshipFuncs,
+    balances::BalanceFuncs,
+    commit_reveal::CommitRevealFuncs,
+    contracts::ContractFuncs,
+    council::CouncilFuncs,
+    counters::CounterFuncs,
+    cross_chain::CrossChainFuncs,
+    crypto::CryptoFuncs,
+    custom::CustomFuncs,
+    envelopes::EnvelopeFuncs,
+    fees::FeeFuncs,
+    governance::GovernanceFuncs,
+    identity::IdentityFuncs,
+    introspection::IntrospectionFuncs,
+    membership::MembershipFuncs,
+    multisig::MultisigFuncs,
+    nfts::NftFuncs,
+    proxy::ProxyFuncs,
+    runtime_calls::RuntimeCallFuncs,
+    scheduler::SchedulerFuncs,
+    session::SessionFuncs,
+    staking::StakingFuncs,
+    storage::StorageFuncs,
+    system::SystemFuncs,
+    treasury::TreasuryFuncs,
+};
+
+/// The weights we charge for each of our extension functions.
+type ExtensionWeightInfo<T> = weights::SubstrateWeight<T>;
+
+/// What our extensions log under. Start the node with `-lchain-extension=debug` to see every call
+/// they handle.
+pub const LOG_TARGET: &str = "chain-extension";
+
+// These are shared with our ink! contract, so we're guaranteed to agree on how things are encoded
+// and on what each `func_id` and status code means.
+use chain_extension_primitives::{
+    status,
+    unpack_func_id,
+    CallCounts,
+    MAIN_EXTENSION_ID,
+    SCHEDULER_EXTENSION_ID,
+    STATE_CHANGING_FUNCS,
+};
+
+/// Everything our extension needs from the runtime it's a part of.
+///
+/// Just like when writing runtime code, if we want to access code from specific pallets we need to
+/// specify that in our generic parameters. Rather than have every handler repeat all of that, they
+/// only ask for `T: Config`. The bounds which can't be expressed as supertraits are spelled out
+/// once, on the blanket implementation below.
+pub trait Config:
+    pallet_contracts::Config
+    // The value sent along with scheduling a callback is escrowed by `pallet_template`, so it had
+    // better be using the same currency as contracts do.
+    + pallet_template::Config<Currency = <Self as pallet_contracts::Config>::Currency>
+    + pallet_scheduler::Config
+    // Same goes for the deposit we take for the preimages of callbacks.
+    + pallet_preimage::Config<Currency = <Self as pallet_contracts::Config>::Currency>
+    + pallet_assets::Config
+    + pallet_uniques::Config
+    + pallet_multisig::Config
+    + pallet_proxy::Config
+    + pallet_identity::Config
+    + pallet_democracy::Config
+    + pallet_staking::Config
+    + pallet_treasury::Config
+    + pallet_collective::Config<pallet_collective::Instance1>
+    + pallet_membership::Config<pallet_membership::Instance1>
+{
+    /// Turns one of our runtime's calls into one the Scheduler can dispatch.
+    fn scheduler_call(call: crate::Call) -> <Self as pallet_scheduler::Config>::Call;
+
+    /// Turns a call the Scheduler has in its agenda back into one of our runtime's calls.
+    fn runtime_call(call: <Self as pallet_scheduler::Config>::Call) -> crate::Call;
+
+    /// Turns `account` into an address our runtime's calls accept.
+    fn runtime_address(
+        account: <Self as SysConfig>::AccountId,
+    ) -> sp_runtime::MultiAddress<sp_runtime::AccountId32, ()>;
+
+    /// Turns `balance` into one our runtime's calls accept.
+    fn runtime_balance(balance: BalanceOf<Self>) -> crate::Balance;
+
+    /// Whether contracts may `dispatch()` `call`, see [`crate::ContractDispatchFilter`].
+    fn is_dispatchable(call: &<Self as pallet_contracts::Config>::Call) -> bool;
+
+    /// Turns one of our runtime's calls into one we can dispatch on behalf of a contract.
+    fn contracts_call(call: crate::Call) -> <Self as pallet_contracts::Config>::Call;
+}
+
+impl<T> Config for T
 where
-    T: pallet_contracts::Config + pallet_template::Config + pallet_scheduler::Config,
+    T: pallet_contracts::Config
+        + pallet_template::Config<Currency = <T as pallet_contracts::Config>::Currency>
+        + pallet_scheduler::Config
+        + pallet_preimage::Config<Currency = <T as pallet_contracts::Config>::Currency>
+        + pallet_assets::Config
+        + pallet_uniques::Config
+        + pallet_multisig::Config
+        + pallet_proxy::Config
+        + pallet_identity::Config
+        + pallet_democracy::Config
+        + pallet_staking::Config
+        + pallet_treasury::Config
+        + pallet_collective::Config<pallet_collective::Instance1>
+        + pallet_membership::Config<pallet_membership::Instance1>,
 
     // The Scheduler pallet's `schedule()` dispatchable expects a Scheduler pallet Call. We can't
     // construct this directly from a Contract pallet Call, but we can construct it from a Runtime
     // call (which itself wraps a Contract pallet Call), so we express that requirement here.
-    <T as pallet_scheduler::Config>::Call: From<crate::Call>,
+    // We also need to be able to look inside the calls the Scheduler hands back, to make sure
+    // contracts only get to move their own callbacks around.
+    <T as pallet_scheduler::Config>::Call: From<crate::Call> + Into<crate::Call>,
 
     // Yeah, this is kinda ugly but if we want to use the `value` transferred by the smart
     // contract while building our call we need to have this.
-    u128: From<
-        <<T as pallet_contracts::Config>::Currency as Currency<
-            <T as SysConfig>::AccountId,
-        >>::Balance,
-    >,
+    u128: From<BalanceOf<T>>,
 
     // `pallet_contracts::Call::call()` expects a `MultiAddress`, so we need to make sure this
     // conversion can be done
     sp_runtime::MultiAddress<sp_runtime::AccountId32, ()>:
         From<<T as SysConfig>::AccountId>,
+
+    // Which calls contracts may dispatch is up to our runtime, but the calls we decode are those
+    // of `pallet_contracts`.
+    crate::ContractDispatchFilter:
+        frame_support::traits::Contains<<T as pallet_contracts::Config>::Call>,
+
+    // The calls we put together ourselves, e.g. for `batch_write()`, are our runtime's.
+    <T as pallet_contracts::Config>::Call: From<crate::Call>,
+{
+    fn scheduler_call(call: crate::Call) -> <T as pallet_scheduler::Config>::Call {
+        call.into()
+    }
+
+    fn runtime_call(call: <T as pallet_scheduler::Config>::Call) -> crate::Call {
+        call.into()
+    }
+
+    fn runtime_address(
+        account: <T as SysConfig>::AccountId,
+    ) -> sp_runtime::MultiAddress<sp_runtime::AccountId32, ()> {
+        account.into()
+    }
+
+    fn runtime_balance(balance: BalanceOf<T>) -> crate::Balance {
+        balance.into()
+    }
+
+    fn is_dispatchable(call: &<T as pallet_contracts::Config>::Call) -> bool {
+        <crate::ContractDispatchFilter as frame_support::traits::Contains<_>>::contains(call)
+    }
+
+    fn contracts_call(call: crate::Call) -> <T as pallet_contracts::Config>::Call {
+        call.into()
+    }
+}
+
+/// A group of related extension functions, which live in a module of their own.
+///
+/// Our extensions only look at `FUNCS` to figure out which handler a call goes to, everything
+/// else is up to the handler.
+trait HandleFunc {
+    /// The `func_id`s this takes care of.
+    const FUNCS: &'static [u32];
+
+    /// Does the actual work for `func_id`, which is always one of `FUNCS`.
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>;
+
+    /// The most weight a call to `func_id` with `input_len` bytes of input can be charged, see
+    /// [`quote`].
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight>;
+}
+
+/// Whether `func_id` is taken care of by `H`.
+fn handles<H: HandleFunc>(func_id: u32) -> bool {
+    H::FUNCS.contains(&func_id)
+}
+
+/// A chain extension which takes care of the `func_id`s carrying its `ID` in their upper 16 bits.
+///
+/// Newer versions of `pallet_contracts` come with a trait like this, until we upgrade we make do
+/// with our own.
+pub trait RegisteredChainExtension<T: pallet_contracts::Config>: ChainExtension<T> {
+    /// Which extension this is, see `MAIN_EXTENSION_ID` and friends.
+    const ID: u16;
+}
+
+/// Several chain extensions bundled up into one, with each call going to the extension its
+/// `func_id` belongs to.
+///
+/// `pallet_contracts` only lets us hand it a single `ChainExtension`, this is how we get more than
+/// one in there. Neither the trait nor tuples are ours, so we can't implement it for `(A, B)`
+/// directly and need this wrapper instead.
+pub struct ChainExtensions<Exts>(PhantomData<Exts>);
+
+impl<T, A, B> ChainExtension<T> for ChainExtensions<(A, B)>
+where
+    T: pallet_contracts::Config,
+    A: RegisteredChainExtension<T>,
+    B: RegisteredChainExtension<T>,
 {
+    fn call<E>(
+        func_id: u32,
+        env: Environment<'_, '_, E, InitState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        // The extension gets the whole `func_id`, that way it doesn't matter whether it's being
+        // used on its own or as part of a bundle.
+        match unpack_func_id(func_id).0 {
+            id if id == A::ID => A::call(func_id, env),
+            id if id == B::ID => B::call(func_id, env),
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+}
+
+/// Everything but scheduling callbacks, i.e. `MyChainExtension` on the ink! side.
+pub struct MyExtension;
+
+// Note, we don't need to have the `SysConfig` trait bound here since the Contract pallet
+// Config is required to implement it
+impl<T: Config> ChainExtension<T> for MyExtension {
     // We will use the `Environment` to get access to the current execution context. What this
     // gives us access to are things like: function arguments and weight information.
     fn call<E>(
@@ -55,126 +296,384 @@ where
         <E::T as SysConfig>::AccountId:
             UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
     {
-        // When working with chain extensions we "communicate" between our contract and the runtime
-        // using a memory buffer.
-        //
-        // We can read encoded method arguments from this buffer. We can also write the result of
-        // our computations into this buffer, which can then get used by ink!.
-        let mut env = env.buf_in_buf_out();
-
-        // This is the implementation block of the methods we declared on the ink! side of things.
-        //
-        // At this point we're writing runtime code, not smart contract code, so we need to be more
-        // careful! For instance, we now need to manually track our weight (i.e gas) usage.
+        handle_call::<T, E>(func_id, env, Self::dispatch::<T, E>)
+    }
+}
+
+impl<T: Config> RegisteredChainExtension<T> for MyExtension {
+    const ID: u16 = MAIN_EXTENSION_ID;
+}
+
+impl MyExtension {
+    /// Hands the call off to whichever handler takes care of `func_id`.
+    ///
+    /// The handlers are where we implement the methods we declared on the ink! side of things. At
+    /// that point we're writing runtime code, not smart contract code, so we need to be more
+    /// careful! For instance, we now need to manually track our weight (i.e gas) usage.
+    fn dispatch<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
         match func_id {
-            // Corresponds to `write_to_storage()`
-            1 => {
-                // This will read some bytes from the memory buffer mentioned above and try to
-                // decode them into the specified type. This method should only be used if the size
-                // of the type is known ahead of time.
-                let something: u32 = env.read_as()?;
-
-                // We need to ensure that we're charging weight to account for the amount of compute
-                // used by the call to our pallet. This is something we typically don't have to
-                // worry about in the context of smart contracts since they're gas metered.
-                //
-                // Ideally we should be using benchmarked results here
-                // (i.e `T::WeightInfo::do_something()`), but since we know how to calculate the
-                // weight manually we're gonna cheat for now.
-                let weight = 10_000 + T::DbWeight::get().writes(1);
-                env.charge_weight(weight)?;
-
-                // Using `env.ext()` we can access all sorts of info about the execution
-                // environment. You can this of this as equivalent to `self.env()` in an ink!
-                // contract.
-                let caller = env.ext().caller().clone();
-                pallet_template::Pallet::<T>::do_something(
-                    RawOrigin::Signed(caller).into(),
-                    something,
-                )?;
+            f if handles::<StorageFuncs>(f) => StorageFuncs::handle::<T, E>(f, env),
+            f if handles::<CustomFuncs>(f) => CustomFuncs::handle::<T, E>(f, env),
+            f if handles::<IntrospectionFuncs>(f) => IntrospectionFuncs::handle::<T, E>(f, env),
+            f if handles::<EnvelopeFuncs>(f) => EnvelopeFuncs::handle::<T, E>(f, env),
+            f if handles::<BalanceFuncs>(f) => BalanceFuncs::handle::<T, E>(f, env),
+            f if handles::<AssetFuncs>(f) => AssetFuncs::handle::<T, E>(f, env),
+            f if handles::<NftFuncs>(f) => NftFuncs::handle::<T, E>(f, env),
+            f if handles::<CryptoFuncs>(f) => CryptoFuncs::handle::<T, E>(f, env),
+            f if handles::<CounterFuncs>(f) => CounterFuncs::handle::<T, E>(f, env),
+            f if handles::<CommitRevealFuncs>(f) => CommitRevealFuncs::handle::<T, E>(f, env),
+            f if handles::<SystemFuncs>(f) => SystemFuncs::handle::<T, E>(f, env),
+            f if handles::<ContractFuncs>(f) => ContractFuncs::handle::<T, E>(f, env),
+            f if handles::<RuntimeCallFuncs>(f) => RuntimeCallFuncs::handle::<T, E>(f, env),
+            f if handles::<MultisigFuncs>(f) => MultisigFuncs::handle::<T, E>(f, env),
+            f if handles::<ProxyFuncs>(f) => ProxyFuncs::handle::<T, E>(f, env),
+            f if handles::<IdentityFuncs>(f) => IdentityFuncs::handle::<T, E>(f, env),
+            f if handles::<GovernanceFuncs>(f) => GovernanceFuncs::handle::<T, E>(f, env),
+            f if handles::<StakingFuncs>(f) => StakingFuncs::handle::<T, E>(f, env),
+            f if handles::<TreasuryFuncs>(f) => TreasuryFuncs::handle::<T, E>(f, env),
+            f if handles::<CouncilFuncs>(f) => CouncilFuncs::handle::<T, E>(f, env),
+            f if handles::<MembershipFuncs>(f) => MembershipFuncs::handle::<T, E>(f, env),
+            f if handles::<CrossChainFuncs>(f) => CrossChainFuncs::handle::<T, E>(f, env),
+            f if handles::<FeeFuncs>(f) => FeeFuncs::handle::<T, E>(f, env),
+            f if handles::<AuthorshipFuncs>(f) => AuthorshipFuncs::handle::<T, E>(f, env),
+            f if handles::<SessionFuncs>(f) => SessionFuncs::handle::<T, E>(f, env),
+            _ => {
+                // Panicking here would trap the whole contract call, which isn't very friendly.
+                // Instead we hand back a status code which maps to
+                // `ExtensionError::UnknownFunction` on the ink! side.
+                Ok(RetVal::Converging(status::UNKNOWN_FUNCTION))
             }
-            // Corresponds to `custom_type_with_result()`
-            2 => {
-                // Since our type interally uses a `Vec` we don't know what the size of it will be
-                // ahead of time. This means we can't use `read_as()` which requires the size of the
-                // type to be known at compile time (put another way, `read_as()` requires
-                // `T: scale::MaxEncodedLen`.
-                //
-                // We must instead read only the amount of bytes we have gotten as an input.
-                let custom: CustomDef = env.read_as_unbounded(env.in_len())?;
-
-                // As mentioned before, we're cheating with this, but it'll do.
-                let weight = 10_000 + T::DbWeight::get().writes(1);
-                env.charge_weight(weight)?;
-
-                if !custom.inner.len().is_power_of_two() {
-                    // Remember that we have a `FromStatusCode` implementation in our contract which
-                    // will know to to handle this `RetVal` correctly.
-                    //
-                    // In our case this maps to our `ExtensionError::CustomCallFailed` error.
-                    return Ok(RetVal::Converging(1));
-                }
-
-                // Here we don't do anything useful, we just store the length of our vector in
-                // storage.
-                let caller = env.ext().caller().clone();
-                pallet_template::Pallet::<T>::do_something(
-                    RawOrigin::Signed(caller).into(),
-                    custom.inner.len() as u32,
-                )?;
+        }
+    }
+}
+
+/// Scheduling callbacks and finding out how they went, i.e. `SchedulerExtension` on the ink! side.
+///
+/// None of this has much to do with the rest of our functions, so it gets an extension of its own.
+pub struct SchedulerExtension;
+
+impl<T: Config> ChainExtension<T> for SchedulerExtension {
+    fn call<E>(
+        func_id: u32,
+        env: Environment<'_, '_, E, InitState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        // There's only the one handler, which reports anything it doesn't know about itself.
+        handle_call::<T, E>(func_id, env, SchedulerFuncs::handle::<T, E>)
+    }
+}
+
+impl<T: Config> RegisteredChainExtension<T> for SchedulerExtension {
+    const ID: u16 = SCHEDULER_EXTENSION_ID;
+}
+
+/// What every one of our extensions does with a call, `dispatch` being the part that differs.
+fn handle_call<T: Config, E>(
+    func_id: u32,
+    env: Environment<'_, '_, E, InitState>,
+    dispatch: impl FnOnce(
+        u32,
+        &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>,
+) -> Result<RetVal, DispatchError>
+where
+    E: Ext<T = T>,
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    // When working with chain extensions we "communicate" between our contract and the runtime
+    // using a memory buffer.
+    //
+    // We can read encoded method arguments from this buffer. We can also write the result of our
+    // computations into this buffer, which can then get used by ink!.
+    let mut env = env.buf_in_buf_out();
+    let gas_left = env.ext().gas_meter().gas_left();
+    let input_len = env.in_len();
+    log::debug!(
+        target: LOG_TARGET,
+        "func_id {:#x} called by {:?} with {} bytes of input",
+        func_id,
+        env.ext().address(),
+        input_len,
+    );
+
+    // Functions which change state take a leading `simulate` flag. When it's set we still do (and
+    // charge for) all of the work, but we roll back whatever it did to storage afterwards. That
+    // way contracts can find out what status code and output they'd get, and what it would cost
+    // them, without committing to anything. The ones we can't simulate like that (see
+    // `UNSIMULATABLE_FUNCS`) read the flag themselves, and turn it down.
+    let simulate = STATE_CHANGING_FUNCS.contains(&func_id) && {
+        let flag = env.read(1)?;
+        bool::decode(&mut &flag[..]).map_err(|_| pallet_contracts::Error::<T>::DecodingFailed)?
+    };
+
+    // Calls to functions we know about get counted, see `pallet_template::ExtensionCalls`, and
+    // may be restricted to approved code. We charge for both up front, the same way for every
+    // function.
+    let counted = quote::<T>(func_id, 0).is_some();
+    if counted {
+        env.charge_weight(call_overhead::<T>())?;
+    }
+
+    // Governance may only trust code it has approved, say because it has been audited, with
+    // some of our functions. Every contract instantiated from that code gets in, no matter its
+    // address.
+    let approved = !counted || {
+        let code_hash = *env.ext().own_code_hash();
+        pallet_template::Pallet::<T>::is_code_hash_approved(func_id, &code_hash)
+    };
+
+    let result = if approved {
+        with_transaction(|| {
+            let result = dispatch(func_id, &mut env);
+            if simulate || result.is_err() {
+                TransactionOutcome::Rollback(result)
+            } else {
+                TransactionOutcome::Commit(result)
             }
-            // Corresponds to `schedule_call()`
-            3 => {
-                let at: u32 = env.read_as()?;
-
-                // We got this weight info by looking at the `schedule` dispatchable in the
-                // Scheduler pallet and using that.
-                use pallet_scheduler::WeightInfo;
-                let max_weight_per_block = T::MaxScheduledPerBlock::get();
-                let weight = <T as pallet_scheduler::Config>::WeightInfo::schedule(
-                    max_weight_per_block,
-                );
-                env.charge_weight(weight)?;
-
-                let caller = env.ext().caller().clone();
-                let dest = env.ext().address().clone().into();
-                let value = env.ext().value_transferred().into();
-
-                // NOTE: About 5% of block weight worked for me here
-                let gas_limit = env.ext().gas_meter().gas_left();
-
-                let mut data = crate::Vec::new();
-
-                // If you're unsure about what the selector is, go check out the `metadata.json`
-                // file of the contract.
-                let mut selector: crate::Vec<u8> = [0x00, 0xC0, 0xFF, 0xEE].into();
-                let magic_number = 15663040;
-                data.append(&mut selector);
-                data.append(&mut magic_number.encode());
-
-                let call = crate::Call::Contracts(pallet_contracts::Call::call {
-                    dest,
-                    value,
-                    gas_limit,
-                    storage_deposit_limit: None,
-                    data,
-                })
-                .into();
-
-                use frame_support::traits::schedule::MaybeHashed;
-                let call = crate::Box::new(MaybeHashed::Value(call));
-
-                pallet_scheduler::Pallet::<T>::schedule(
-                    RawOrigin::Signed(caller).into(),
-                    at.into(),
-                    None,
-                    Default::default(),
-                    call,
-                )?;
+        })
+    } else {
+        Ok(RetVal::Converging(status::CODE_NOT_APPROVED))
+    };
+
+    // Handlers are free to use `?` on whatever the pallets they call into return. Errors which
+    // contracts can do something about get turned into a status code here, once whatever the
+    // handler did has been rolled back. Everything else still traps.
+    let result = result.or_else(|e| dispatch_error_status::<T>(e).map(RetVal::Converging).ok_or(e));
+
+    // This happens outside of the transaction above so simulated calls get counted too. Calls
+    // which trap take the counters down with them, so there's no point in counting those.
+    if let (true, Ok(RetVal::Converging(code))) = (counted, &result) {
+        pallet_template::Pallet::<T>::note_extension_call(func_id, *code == status::SUCCESS);
+    }
+
+    // Whatever the handler charged, minus anything it refunded.
+    let charged = gas_left.saturating_sub(env.ext().gas_meter().gas_left());
+    match &result {
+        Ok(RetVal::Converging(code)) => {
+            log::debug!(
+                target: LOG_TARGET,
+                "func_id {:#x} returned status {} after charging {} weight{}",
+                func_id,
+                code,
+                charged,
+                if simulate { ", rolled back since it was simulated" } else { "" },
+            );
+
+            // A status code on its own doesn't tell contract developers much, so when they're
+            // dry-running a call with debugging enabled we also tell them what it means.
+            if *code != status::SUCCESS {
+                let description = status::describe(*code).unwrap_or("unknown status code");
+                env.ext().append_debug_buffer(&alloc::format!(
+                    "{}: func_id {:#x} returned status {}: {}\n",
+                    LOG_TARGET,
+                    func_id,
+                    code,
+                    description,
+                ));
             }
-            _ => panic!("Unrecognized function ID."),
         }
+        Ok(RetVal::Diverging { .. }) => {
+            log::debug!(target: LOG_TARGET, "func_id {:#x} diverged", func_id)
+        }
+        Err(e) => {
+            log::debug!(
+                target: LOG_TARGET,
+                "func_id {:#x} trapped with {:?} after charging {} weight",
+                func_id,
+                e,
+                charged,
+            );
+            env.ext().append_debug_buffer(&alloc::format!(
+                "{}: func_id {:#x} trapped with {:?}\n",
+                LOG_TARGET,
+                func_id,
+                e,
+            ));
+        }
+    }
+
+    result
+}
+
+/// The most weight a call to `func_id` with `input_len` bytes of input can be charged.
+///
+/// Functions which refund unused weight may end up costing less. Returns `None` for functions we
+/// don't know about.
+pub fn quote<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+    // This needs to route calls the same way our extensions do.
+    let weight = match func_id {
+        f if handles::<StorageFuncs>(f) => StorageFuncs::weight::<T>(f, input_len),
+        f if handles::<CustomFuncs>(f) => CustomFuncs::weight::<T>(f, input_len),
+        f if handles::<SchedulerFuncs>(f) => SchedulerFuncs::weight::<T>(f, input_len),
+        f if handles::<IntrospectionFuncs>(f) => IntrospectionFuncs::weight::<T>(f, input_len),
+        f if handles::<EnvelopeFuncs>(f) => EnvelopeFuncs::weight::<T>(f, input_len),
+        f if handles::<BalanceFuncs>(f) => BalanceFuncs::weight::<T>(f, input_len),
+        f if handles::<AssetFuncs>(f) => AssetFuncs::weight::<T>(f, input_len),
+        f if handles::<NftFuncs>(f) => NftFuncs::weight::<T>(f, input_len),
+        f if handles::<CryptoFuncs>(f) => CryptoFuncs::weight::<T>(f, input_len),
+        f if handles::<CounterFuncs>(f) => CounterFuncs::weight::<T>(f, input_len),
+        f if handles::<CommitRevealFuncs>(f) => CommitRevealFuncs::weight::<T>(f, input_len),
+        f if handles::<SystemFuncs>(f) => SystemFuncs::weight::<T>(f, input_len),
+        f if handles::<ContractFuncs>(f) => ContractFuncs::weight::<T>(f, input_len),
+        f if handles::<RuntimeCallFuncs>(f) => RuntimeCallFuncs::weight::<T>(f, input_len),
+        f if handles::<MultisigFuncs>(f) => MultisigFuncs::weight::<T>(f, input_len),
+        f if handles::<ProxyFuncs>(f) => ProxyFuncs::weight::<T>(f, input_len),
+        f if handles::<IdentityFuncs>(f) => IdentityFuncs::weight::<T>(f, input_len),
+        f if handles::<GovernanceFuncs>(f) => GovernanceFuncs::weight::<T>(f, input_len),
+        f if handles::<StakingFuncs>(f) => StakingFuncs::weight::<T>(f, input_len),
+        f if handles::<TreasuryFuncs>(f) => TreasuryFuncs::weight::<T>(f, input_len),
+        f if handles::<CouncilFuncs>(f) => CouncilFuncs::weight::<T>(f, input_len),
+        f if handles::<MembershipFuncs>(f) => MembershipFuncs::weight::<T>(f, input_len),
+        f if handles::<CrossChainFuncs>(f) => CrossChainFuncs::weight::<T>(f, input_len),
+        f if handles::<FeeFuncs>(f) => FeeFuncs::weight::<T>(f, input_len),
+        f if handles::<AuthorshipFuncs>(f) => AuthorshipFuncs::weight::<T>(f, input_len),
+        f if handles::<SessionFuncs>(f) => SessionFuncs::weight::<T>(f, input_len),
+        _ => None,
+    }?;
+
+    // On top of whatever the function itself costs, `handle_call` charges some overhead.
+    Some(weight.saturating_add(call_overhead::<T>()))
+}
+
+/// What `handle_call` charges for every call to a function we know about, before it's dispatched:
+/// counting the call and checking whether the calling contract's code is approved for it.
+fn call_overhead<T: Config>() -> Weight {
+    type W<T> = ExtensionWeightInfo<T>;
 
-        Ok(RetVal::Converging(0))
+    W::<T>::note_call().saturating_add(W::<T>::check_code_hash())
+}
+
+sp_api::decl_runtime_apis! {
+    /// Lets clients find out how much gas our extension functions may use, without having to go
+    /// through a contract.
+    ///
+    /// Version 2 added `call_counts`.
+    #[api_version(2)]
+    pub trait ChainExtensionApi {
+        /// See [`quote`].
+        fn quote(func_id: u32, input_len: u32) -> Option<Weight>;
+
+        /// How often `func_id` has been called, see `pallet_template::ExtensionCalls`.
+        fn call_counts(func_id: u32) -> CallCounts;
     }
 }
+
+type BalanceOf<T> = <<T as pallet_contracts::Config>::Currency as Currency<
+    <T as SysConfig>::AccountId,
+>>::Balance;
+
+/// Charges `weight` for the whole of the input, and only then decodes it.
+///
+/// This is for inputs which don't have a known size ahead of time, say because they contain a
+/// `Vec`. We can't use `read_as()` for those since it requires the size of the type to be known at
+/// compile time (put another way, `read_as()` requires `T: scale::MaxEncodedLen`). We must instead
+/// read only the amount of bytes we have gotten as an input.
+///
+/// Decoding gets more expensive the more bytes we're given, so we make sure to charge for that
+/// _before_ doing any work.
+fn charge_and_read<V: Decode, E: Ext>(
+    env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    weight: impl FnOnce(u32) -> Weight,
+) -> Result<V, DispatchError>
+where
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    let len = env.in_len();
+    env.charge_weight(weight(len))?;
+    env.read_as_unbounded(len)
+}
+
+/// The status code for an error which bubbled up out of one of our handlers, if it's one
+/// contracts can reasonably do something about.
+///
+/// Unlike with [`assets::asset_status`] and [`nfts::nft_status`] there's no catch-all, anything we
+/// don't know about (running out of gas, say) keeps trapping the contract.
+fn dispatch_error_status<T>(error: DispatchError) -> Option<u32>
+where
+    T: pallet_template::Config
+        + pallet_scheduler::Config
+        + pallet_multisig::Config
+        + pallet_proxy::Config
+        + pallet_democracy::Config
+        + pallet_treasury::Config
+        + pallet_collective::Config<pallet_collective::Instance1>,
+{
+    use pallet_democracy::Error as DemocracyError;
+    use pallet_multisig::Error as MultisigError;
+    use pallet_proxy::Error as ProxyError;
+    use pallet_scheduler::Error as SchedulerError;
+    use pallet_template::Error;
+    use pallet_treasury::Error as TreasuryError;
+    type CouncilError<T> = pallet_collective::Error<T, pallet_collective::Instance1>;
+
+    let status = match error {
+        DispatchError::BadOrigin => status::BAD_ORIGIN,
+        e if e == SchedulerError::<T>::TargetBlockNumberInPast.into() => status::SCHEDULED_IN_PAST,
+        e if e == SchedulerError::<T>::FailedToSchedule.into() => status::SCHEDULING_FAILED,
+        e if e == SchedulerError::<T>::RescheduleNoChange.into() => status::SCHEDULING_FAILED,
+        e if e == SchedulerError::<T>::NotFound.into() => status::UNKNOWN_CALLBACK,
+        e if e == Error::<T>::NotSystemContract.into() => status::NOT_PERMITTED,
+        e if e == Error::<T>::RawKeyTooLong.into() => status::RAW_KEY_TOO_LONG,
+        e if e == Error::<T>::RawValueTooLong.into() => status::RAW_VALUE_TOO_LONG,
+        e if e == Error::<T>::BatchTooLarge.into() => status::BATCH_TOO_LARGE,
+        e if e == Error::<T>::CiphertextTooLong.into() => status::CIPHERTEXT_TOO_LONG,
+        e if e == Error::<T>::AlreadyExpired.into() => status::ENVELOPE_EXPIRED,
+        e if e == Error::<T>::EmptyRevealWindow.into() => status::EMPTY_REVEAL_WINDOW,
+        e if e == Error::<T>::UnknownCounter.into() => status::UNKNOWN_COUNTER,
+        e if e == Error::<T>::CounterExists.into() => status::COUNTER_EXISTS,
+        e if e == Error::<T>::CounterNameTooLong.into() => status::COUNTER_NAME_TOO_LONG,
+        e if e == Error::<T>::NotCounterMember.into() => status::NOT_PERMITTED,
+        e if e == Error::<T>::TooManyCounterMembers.into() => status::TOO_MANY_COUNTER_MEMBERS,
+        e if e == Error::<T>::InsufficientDeposit.into() => status::INSUFFICIENT_DEPOSIT,
+        e if e == MultisigError::<T>::MinimumThreshold.into() => status::INVALID_SIGNATORIES,
+        e if e == MultisigError::<T>::TooFewSignatories.into() => status::INVALID_SIGNATORIES,
+        e if e == MultisigError::<T>::TooManySignatories.into() => status::INVALID_SIGNATORIES,
+        e if e == MultisigError::<T>::SignatoriesOutOfOrder.into() => status::INVALID_SIGNATORIES,
+        e if e == MultisigError::<T>::SenderInSignatories.into() => status::INVALID_SIGNATORIES,
+        e if e == MultisigError::<T>::AlreadyApproved.into() => status::ALREADY_APPROVED,
+        e if e == MultisigError::<T>::NoApprovalsNeeded.into() => status::NO_APPROVALS_NEEDED,
+        e if e == MultisigError::<T>::NotFound.into() => status::UNKNOWN_MULTISIG,
+        e if e == MultisigError::<T>::NoTimepoint.into() => status::WRONG_TIMEPOINT,
+        e if e == MultisigError::<T>::WrongTimepoint.into() => status::WRONG_TIMEPOINT,
+        e if e == MultisigError::<T>::UnexpectedTimepoint.into() => status::WRONG_TIMEPOINT,
+        e if e == MultisigError::<T>::MaxWeightTooLow.into() => status::MAX_WEIGHT_TOO_LOW,
+        e if e == ProxyError::<T>::NotProxy.into() => status::NOT_A_PROXY,
+        // Proxies with a delay have to announce their calls first, which contracts can't do
+        // through us. As far as they're concerned, they aren't proxies (yet).
+        e if e == ProxyError::<T>::Unannounced.into() => status::NOT_A_PROXY,
+        e if e == ProxyError::<T>::TooMany.into() => status::TOO_MANY_PROXIES,
+        e if e == ProxyError::<T>::Duplicate.into() => status::DUPLICATE_PROXY,
+        e if e == ProxyError::<T>::NotFound.into() => status::UNKNOWN_PROXY,
+        e if e == ProxyError::<T>::NoPermission.into() => status::NOT_PERMITTED,
+        e if e == ProxyError::<T>::Unproxyable.into() => status::NOT_PERMITTED,
+        e if e == ProxyError::<T>::NoSelfProxy.into() => status::NOT_PERMITTED,
+        e if e == DemocracyError::<T>::ReferendumInvalid.into() => status::REFERENDUM_NOT_ONGOING,
+        e if e == DemocracyError::<T>::InsufficientFunds.into() => {
+            status::INSUFFICIENT_VOTING_BALANCE
+        }
+        e if e == DemocracyError::<T>::MaxVotesReached.into() => status::TOO_MANY_VOTES,
+        // Accounts which delegated their votes can't vote themselves until they undelegate.
+        e if e == DemocracyError::<T>::AlreadyDelegating.into() => status::NOT_PERMITTED,
+        e if e == TreasuryError::<T>::InsufficientProposersBalance.into() => {
+            status::INSUFFICIENT_PROPOSAL_BOND
+        }
+        e if e == CouncilError::<T>::NotMember.into() => status::NOT_A_COUNCIL_MEMBER,
+        e if e == CouncilError::<T>::DuplicateProposal.into() => status::DUPLICATE_MOTION,
+        e if e == CouncilError::<T>::TooManyProposals.into() => status::TOO_MANY_MOTIONS,
+        _ => return None,
+    };
+
+    Some(status)
+}
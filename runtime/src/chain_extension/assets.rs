@@ -0,0 +1,188 @@
+//! Functions for working with the fungible assets of `pallet_assets`.
+
+use super::{
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_ASSET_BALANCE,
+    FUNC_ASSET_MINT,
+    FUNC_ASSET_TOTAL_SUPPLY,
+    FUNC_ASSET_TRANSFER,
+    FUNC_ASSET_TRANSFER_APPROVED,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::Weight,
+};
+use frame_system::RawOrigin;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::{
+    traits::StaticLookup,
+    DispatchError,
+    DispatchResult,
+    TokenError,
+};
+
+pub(super) type AssetIdOf<T> = <T as pallet_assets::Config>::AssetId;
+pub(super) type AssetBalanceOf<T> = <T as pallet_assets::Config>::Balance;
+
+pub(super) struct AssetFuncs;
+
+impl HandleFunc for AssetFuncs {
+    const FUNCS: &'static [u32] = &[
+        FUNC_ASSET_BALANCE,
+        FUNC_ASSET_TRANSFER,
+        FUNC_ASSET_TOTAL_SUPPLY,
+        FUNC_ASSET_TRANSFER_APPROVED,
+        FUNC_ASSET_MINT,
+    ];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `asset_balance()`
+            FUNC_ASSET_BALANCE => {
+                let (asset, who): (AssetIdOf<T>, <T as SysConfig>::AccountId) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::asset_balance())?;
+
+                let balance = pallet_assets::Pallet::<T>::balance(asset, who);
+                env.write(&balance.encode(), false, None)?;
+            }
+            // Corresponds to `asset_transfer()`
+            FUNC_ASSET_TRANSFER => {
+                let (_, asset, to, amount): (
+                    bool,
+                    AssetIdOf<T>,
+                    <T as SysConfig>::AccountId,
+                    AssetBalanceOf<T>,
+                ) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::asset_transfer())?;
+
+                // Just like with `transfer_native()` the assets come out of the contract's own
+                // account.
+                let contract = env.ext().address().clone();
+                let result = pallet_assets::Pallet::<T>::transfer(
+                    RawOrigin::Signed(contract).into(),
+                    asset,
+                    <T as SysConfig>::Lookup::unlookup(to),
+                    amount,
+                );
+                return Ok(asset_status::<T>(result))
+            }
+            // Corresponds to `asset_total_supply()`
+            FUNC_ASSET_TOTAL_SUPPLY => {
+                let asset: AssetIdOf<T> = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::asset_total_supply())?;
+
+                let supply = pallet_assets::Pallet::<T>::total_supply(asset);
+                env.write(&supply.encode(), false, None)?;
+            }
+            // Corresponds to `asset_transfer_approved()`
+            FUNC_ASSET_TRANSFER_APPROVED => {
+                let (_, asset, owner, to, amount): (
+                    bool,
+                    AssetIdOf<T>,
+                    <T as SysConfig>::AccountId,
+                    <T as SysConfig>::AccountId,
+                    AssetBalanceOf<T>,
+                ) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::asset_transfer_approved())?;
+
+                // This moves assets out of someone else's account, which only works if `owner`
+                // has approved our contract as a delegate through `pallet_assets` beforehand.
+                let contract = env.ext().address().clone();
+                let result = pallet_assets::Pallet::<T>::transfer_approved(
+                    RawOrigin::Signed(contract).into(),
+                    asset,
+                    <T as SysConfig>::Lookup::unlookup(owner),
+                    <T as SysConfig>::Lookup::unlookup(to),
+                    amount,
+                );
+                return Ok(asset_status::<T>(result))
+            }
+            // Corresponds to `asset_mint()`
+            FUNC_ASSET_MINT => {
+                let (_, asset, to, amount): (
+                    bool,
+                    AssetIdOf<T>,
+                    <T as SysConfig>::AccountId,
+                    AssetBalanceOf<T>,
+                ) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::asset_mint())?;
+
+                // `pallet_assets` only lets an asset's issuer mint, so this only works for
+                // contracts which have been made the issuer of `asset`.
+                let contract = env.ext().address().clone();
+                let result = pallet_assets::Pallet::<T>::mint(
+                    RawOrigin::Signed(contract).into(),
+                    asset,
+                    <T as SysConfig>::Lookup::unlookup(to),
+                    amount,
+                );
+                return Ok(asset_status::<T>(result))
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_ASSET_BALANCE => W::<T>::asset_balance(),
+            FUNC_ASSET_TRANSFER => W::<T>::asset_transfer(),
+            FUNC_ASSET_TOTAL_SUPPLY => W::<T>::asset_total_supply(),
+            FUNC_ASSET_TRANSFER_APPROVED => W::<T>::asset_transfer_approved(),
+            FUNC_ASSET_MINT => W::<T>::asset_mint(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// Turns the outcome of a `pallet_assets` dispatchable into a status code.
+///
+/// Errors which contracts can reasonably do something about get a status code of their own,
+/// everything else is lumped together.
+fn asset_status<T: pallet_assets::Config>(result: DispatchResult) -> RetVal {
+    use pallet_assets::Error;
+
+    let status = match result {
+        Ok(()) => status::SUCCESS,
+        Err(DispatchError::Token(TokenError::NoFunds)) => status::ASSET_BALANCE_LOW,
+        Err(DispatchError::Token(TokenError::UnknownAsset)) => status::UNKNOWN_ASSET,
+        Err(DispatchError::Token(TokenError::BelowMinimum)) => status::BELOW_EXISTENTIAL_DEPOSIT,
+        Err(e) if e == Error::<T>::BalanceLow.into() => status::ASSET_BALANCE_LOW,
+        // As far as contracts are concerned, not holding an asset at all is the same as holding
+        // none of it.
+        Err(e) if e == Error::<T>::NoAccount.into() => status::ASSET_BALANCE_LOW,
+        Err(e) if e == Error::<T>::Unknown.into() => status::UNKNOWN_ASSET,
+        Err(e) if e == Error::<T>::Frozen.into() => status::ASSET_FROZEN,
+        Err(e) if e == Error::<T>::NoPermission.into() => status::NOT_PERMITTED,
+        Err(e) if e == Error::<T>::Unapproved.into() => status::ASSET_UNAPPROVED,
+        Err(_) => status::ASSET_OPERATION_FAILED,
+    };
+
+    RetVal::Converging(status)
+}
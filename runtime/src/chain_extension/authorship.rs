@@ -0,0 +1,68 @@
+//! Functions which tell contracts who authored the current block, as `pallet_authorship` finds
+//! out from its digest.
+
+use super::{
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_BLOCK_AUTHOR,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct AuthorshipFuncs;
+
+impl HandleFunc for AuthorshipFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_BLOCK_AUTHOR];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `block_author()`
+            FUNC_BLOCK_AUTHOR => {
+                env.charge_weight(ExtensionWeightInfo::<T>::block_author())?;
+
+                // Our blocks are sealed without a consensus engine, so there's no digest to find
+                // an author in and this is always `None`. Chains with Aura or BABE would plug
+                // theirs into `FindAuthor`.
+                let author = crate::Authorship::author();
+                env.write(&author.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_BLOCK_AUTHOR => W::<T>::block_author(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
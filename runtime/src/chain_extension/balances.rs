@@ -0,0 +1,142 @@
+//! Functions for moving the chain's native token around.
+
+use super::{
+    weights::WeightInfo,
+    BalanceOf,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_FREE_BALANCE,
+    FUNC_TRANSFER_NATIVE,
+    FUNC_TRANSFER_NATIVE_COMPACT,
+};
+use codec::{
+    Compact,
+    Encode,
+};
+use frame_support::{
+    traits::{
+        tokens::currency::Currency,
+        ExistenceRequirement,
+    },
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::{
+    traits::{
+        Saturating,
+        Zero,
+    },
+    DispatchError,
+};
+
+pub(super) struct BalanceFuncs;
+
+impl HandleFunc for BalanceFuncs {
+    const FUNCS: &'static [u32] = &[
+        FUNC_TRANSFER_NATIVE,
+        FUNC_TRANSFER_NATIVE_COMPACT,
+        FUNC_FREE_BALANCE,
+    ];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `transfer_native()`
+            FUNC_TRANSFER_NATIVE => {
+                let (_, to, amount): (bool, <T as SysConfig>::AccountId, BalanceOf<T>) =
+                    env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::transfer_native())?;
+
+                // The funds always come out of the contract's own account, not the caller's.
+                let contract = env.ext().address().clone();
+                transfer_native::<T>(&contract, &to, amount)
+            }
+            // Corresponds to `transfer_native_compact()`
+            FUNC_TRANSFER_NATIVE_COMPACT => {
+                // Just like the dispatchables of `pallet_balances`, this takes the amount in its
+                // compact encoding, which saves contracts most of its 16 bytes for typical
+                // amounts. `Compact<u128>` still has a known maximum size, so `read_as()` works.
+                let (_, to, amount): (bool, <T as SysConfig>::AccountId, Compact<u128>) =
+                    env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::transfer_native())?;
+
+                // We don't know that our balance type is a `u128`, only that it can hold one. An
+                // amount which doesn't fit couldn't have been encoded by a contract on this chain.
+                let amount = BalanceOf::<T>::try_from(amount.0)
+                    .map_err(|_| pallet_contracts::Error::<T>::DecodingFailed)?;
+
+                let contract = env.ext().address().clone();
+                transfer_native::<T>(&contract, &to, amount)
+            }
+            // Corresponds to `free_balance()`
+            FUNC_FREE_BALANCE => {
+                let account: <T as SysConfig>::AccountId = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::free_balance())?;
+
+                // ink! only tells contracts about their own balance, this works for any account.
+                // Whatever the account has reserved isn't included, it can't spend that anyway.
+                let balance = <T as pallet_contracts::Config>::Currency::free_balance(&account);
+                env.write(&balance.encode(), false, None)?;
+                Ok(RetVal::Converging(status::SUCCESS))
+            }
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_TRANSFER_NATIVE | FUNC_TRANSFER_NATIVE_COMPACT => W::<T>::transfer_native(),
+            FUNC_FREE_BALANCE => W::<T>::free_balance(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// Moves `amount` from `contract` to `to`.
+///
+/// The Currency only tells us that a transfer failed, not why, so we check for the existential
+/// deposit problems ourselves beforehand. That way contracts get a status code they can act on.
+pub(super) fn transfer_native<T: pallet_contracts::Config>(
+    contract: &T::AccountId,
+    to: &T::AccountId,
+    amount: BalanceOf<T>,
+) -> Result<RetVal, DispatchError> {
+    type Balances<T> = <T as pallet_contracts::Config>::Currency;
+
+    let minimum_balance = Balances::<T>::minimum_balance();
+    if Balances::<T>::total_balance(to).is_zero() && amount < minimum_balance {
+        return Ok(RetVal::Converging(status::BELOW_EXISTENTIAL_DEPOSIT))
+    }
+
+    // Contract accounts must never be reaped while they still exist.
+    if Balances::<T>::free_balance(contract).saturating_sub(amount) < minimum_balance {
+        return Ok(RetVal::Converging(status::WOULD_REAP_SENDER))
+    }
+
+    match Balances::<T>::transfer(contract, to, amount, ExistenceRequirement::KeepAlive) {
+        Ok(()) => Ok(RetVal::Converging(status::SUCCESS)),
+        Err(_) => Ok(RetVal::Converging(status::TRANSFER_FAILED)),
+    }
+}
@@ -0,0 +1,1583 @@
+//! Benchmarking setup for our chain extension.
+//!
+//! The extension isn't a pallet, so just like `frame-system-benchmarking` we introduce a stand-in
+//! `Pallet` and `Config` for the benchmarks to hang off of.
+//!
+//! We can't easily call into the extension without a contract, so instead we benchmark the work
+//! the runtime does on behalf of each function: decoding the input and dispatching into the
+//! relevant pallet.
+
+use super::{
+    assets::{
+        AssetBalanceOf,
+        AssetIdOf,
+    },
+    cross_chain::transact_message,
+    custom::BoundedCustom,
+    governance::democracy_conviction,
+    identity::{
+        display_name,
+        judgement_of,
+        IdentityBalanceOf,
+        IdentityInfoOf,
+        RegistrationOf,
+    },
+    nfts::{
+        CollectionIdOf,
+        ItemIdOf,
+        NftMetadataOf,
+    },
+    *,
+};
+use chain_extension_primitives::{
+    capabilities,
+    type_descriptor,
+    CallCounts,
+    Conviction,
+    Custom,
+    Measure,
+    ProposalIndex,
+    ProxyType,
+    RichCustom,
+    SchemaHash,
+    SessionInfo,
+    FUNC_CHECKED_CUSTOM_TYPE,
+    FUNC_READ_RAW,
+    FUNC_WRITE_TO_STORAGE,
+    MAIN_EXTENSION_VERSION,
+    MAX_BATCH_WRITE_LEN,
+    MAX_BOUNDED_CUSTOM_LEN,
+    MAX_IDENTITY_DISPLAY_LEN,
+    MAX_XCM_CALL_LEN,
+};
+use codec::DecodeLimit;
+use frame_benchmarking::{
+    account,
+    benchmarks,
+    whitelisted_caller,
+};
+use frame_support::{
+    pallet_prelude::{
+        Decode,
+        Encode,
+    },
+    traits::{
+        schedule::{
+            v2::Anon,
+            DispatchTime,
+            MaybeHashed,
+        },
+        tokens::nonfungibles,
+        Contains,
+        ExistenceRequirement,
+        Get,
+        PreimageProvider,
+        PreimageRecipient,
+        Randomness,
+        ReservableCurrency,
+    },
+    weights::{
+        GetDispatchInfo,
+        WeightToFee,
+    },
+};
+use frame_system::RawOrigin;
+use pallet_identity::{
+    Data,
+    IdentityInfo,
+    Judgement,
+};
+use sp_core::{
+    ecdsa,
+    sr25519,
+};
+use sp_runtime::traits::{
+    Bounded,
+    Hash,
+    Saturating,
+    StaticLookup,
+    Zero,
+};
+use sp_std::prelude::*;
+
+/// The largest `Custom` value we benchmark with.
+const MAX_CUSTOM_LEN: u32 = 16 * 1024;
+
+/// The longest preimage we benchmark `reveal` with.
+const MAX_PREIMAGE_LEN: u32 = 16 * 1024;
+
+/// The longest subject we benchmark `random` with.
+const MAX_SUBJECT_LEN: u32 = 16 * 1024;
+
+/// The longest message we benchmark signature verification with.
+const MAX_MESSAGE_LEN: u32 = 16 * 1024;
+
+/// The longest input we benchmark `call_contract` and `instantiate_contract` with.
+const MAX_CALL_INPUT_LEN: u32 = 16 * 1024;
+
+/// The key type we sign messages with for the signature verification benchmarks. It doesn't
+/// matter which one it is, the keys only ever live in the benchmark's keystore.
+const BENCHMARK_KEY_TYPE: sp_core::crypto::KeyTypeId = sp_core::crypto::KeyTypeId(*b"bnch");
+
+pub struct Pallet<T: Config>(sp_std::marker::PhantomData<T>);
+
+pub trait Config: super::Config {}
+
+/// Creates an asset owned, and so also issued, by `contract` and hands `contract` some of it.
+fn create_asset<T: Config>(contract: &T::AccountId) -> Result<AssetIdOf<T>, &'static str> {
+    let asset = AssetIdOf::<T>::default();
+    let owner = <T as SysConfig>::Lookup::unlookup(contract.clone());
+    pallet_assets::Pallet::<T>::force_create(
+        RawOrigin::Root.into(),
+        asset,
+        owner.clone(),
+        true,
+        1u32.into(),
+    )?;
+    pallet_assets::Pallet::<T>::mint(
+        RawOrigin::Signed(contract.clone()).into(),
+        asset,
+        owner,
+        1_000u32.into(),
+    )?;
+    Ok(asset)
+}
+
+/// Creates a collection owned, and so also issued, by `contract`.
+///
+/// Holding NFTs of the collection is free, so `contract` doesn't need any funds for deposits.
+fn create_collection<T: Config>(contract: &T::AccountId) -> Result<CollectionIdOf<T>, &'static str>
+where
+    CollectionIdOf<T>: From<u32>,
+{
+    let collection = CollectionIdOf::<T>::from(0u32);
+    pallet_uniques::Pallet::<T>::force_create(
+        RawOrigin::Root.into(),
+        collection,
+        <T as SysConfig>::Lookup::unlookup(contract.clone()),
+        true,
+    )?;
+    Ok(collection)
+}
+
+/// Mints the first item of `collection` to `contract`.
+fn mint_item<T: Config>(
+    contract: &T::AccountId,
+    collection: CollectionIdOf<T>,
+) -> Result<ItemIdOf<T>, &'static str>
+where
+    ItemIdOf<T>: From<u32>,
+{
+    let item = ItemIdOf::<T>::from(0u32);
+    pallet_uniques::Pallet::<T>::mint(
+        RawOrigin::Signed(contract.clone()).into(),
+        collection,
+        item,
+        <T as SysConfig>::Lookup::unlookup(contract.clone()),
+    )?;
+    Ok(item)
+}
+
+/// Who holds `item` of `collection`, if anyone.
+fn nft_owner<T: Config>(
+    collection: &CollectionIdOf<T>,
+    item: &ItemIdOf<T>,
+) -> Option<T::AccountId> {
+    <pallet_uniques::Pallet<T> as nonfungibles::Inspect<_>>::owner(collection, item)
+}
+
+/// Creates a counter called `name` with `admin` as its admin and `members` other members.
+fn create_counter<T: Config>(
+    admin: &T::AccountId,
+    name: &[u8],
+    members: u32,
+) -> Result<(), &'static str> {
+    pallet_template::Pallet::<T>::create_counter(admin, name.to_vec())?;
+    for i in 0 .. members {
+        pallet_template::Pallet::<T>::set_counter_access(
+            admin,
+            name.to_vec(),
+            account("member", i, 0),
+            true,
+        )?;
+    }
+    Ok(())
+}
+
+/// The largest registration `pallet_identity` lets anyone have: every additional field is taken,
+/// every field is as long as it gets and every registrar has had a say.
+fn max_registration<T: Config>() -> RegistrationOf<T> {
+    let data = || {
+        Data::Raw(
+            vec![0; MAX_IDENTITY_DISPLAY_LEN as usize]
+                .try_into()
+                .expect("raw data is this long at most; qed"),
+        )
+    };
+    let additional = (0 .. <T as pallet_identity::Config>::MaxAdditionalFields::get())
+        .map(|_| (data(), data()))
+        .collect::<Vec<_>>()
+        .try_into()
+        .expect("there's one field for each one allowed; qed");
+    // Nobody calls the identity erroneous, since that would cut going through the judgements
+    // short.
+    let judgements = (0 .. <T as pallet_identity::Config>::MaxRegistrars::get())
+        .map(|registrar| {
+            (
+                registrar,
+                Judgement::FeePaid(IdentityBalanceOf::<T>::max_value()),
+            )
+        })
+        .collect::<Vec<_>>()
+        .try_into()
+        .expect("there's one judgement for each registrar allowed; qed");
+
+    RegistrationOf::<T> {
+        judgements,
+        deposit: Zero::zero(),
+        info: IdentityInfo {
+            additional,
+            display: data(),
+            legal: data(),
+            web: data(),
+            riot: data(),
+            email: data(),
+            pgp_fingerprint: Some([0; 20]),
+            image: data(),
+            twitter: data(),
+        },
+    }
+}
+
+benchmarks! {
+    where_clause {
+        where
+            <T as pallet_scheduler::Config>::Call: From<crate::Call>,
+            CollectionIdOf<T>: From<u32>,
+            ItemIdOf<T>: From<u32>,
+    }
+
+    write_to_storage {
+        let caller: T::AccountId = whitelisted_caller();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let input = 42u32.encode();
+    }: {
+        let something = u32::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::store_something_for_contract(contract, caller, something);
+    }
+    verify {
+        assert_eq!(pallet_template::Pallet::<T>::something(), Some(42));
+    }
+
+    read_from_storage {
+        pallet_template::Something::<T>::put(42);
+    }: {
+        let _ = pallet_template::Pallet::<T>::something().encode();
+    }
+
+    write_for_caller {
+        let caller: T::AccountId = whitelisted_caller();
+        let input = 42u32.encode();
+    }: {
+        let value = u32::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::store_for_caller(RawOrigin::Signed(caller.clone()).into(), value)?;
+    }
+    verify {
+        assert_eq!(pallet_template::Pallet::<T>::account_value(&caller), Some(42));
+    }
+
+    read_for {
+        let account: T::AccountId = account("account", 0, 0);
+        pallet_template::AccountValues::<T>::insert(&account, 42);
+        let input = account.encode();
+    }: {
+        let account = T::AccountId::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_template::Pallet::<T>::account_value(account).encode();
+    }
+
+    store_envelope {
+        let n in 0 .. T::MaxCiphertextLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let input = ([0u8; 32], 10u32, vec![0u8; n as usize]).encode();
+    }: {
+        let (public_key, expires_at, ciphertext): ([u8; 32], u32, Vec<u8>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::store_envelope(
+            &contract,
+            public_key,
+            expires_at.into(),
+            ciphertext,
+        )?;
+    }
+    verify {
+        assert!(pallet_template::Pallet::<T>::envelope(0).is_some());
+    }
+
+    envelope {
+        let n in 0 .. T::MaxCiphertextLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        pallet_template::Pallet::<T>::store_envelope(
+            &contract,
+            [0u8; 32],
+            10u32.into(),
+            vec![0u8; n as usize],
+        )?;
+        let input = 0u64.encode();
+    }: {
+        let id = u64::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_template::Pallet::<T>::envelope(id).encode();
+    }
+
+    quote {
+        let input = (FUNC_READ_RAW, T::MaxRawKeyLen::get()).encode();
+    }: {
+        // The benchmark shadows the function it's named after, hence the `super::`.
+        let (func_id, input_len): (u32, u32) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = super::quote::<T>(func_id, input_len).encode();
+    }
+
+    // Every call to a function we know about pays for this on top of its own weight.
+    note_call {
+        pallet_template::ExtensionCalls::<T>::insert(
+            FUNC_WRITE_TO_STORAGE,
+            CallCounts { succeeded: 1, failed: 1 },
+        );
+    }: {
+        pallet_template::Pallet::<T>::note_extension_call(FUNC_WRITE_TO_STORAGE, true);
+    }
+    verify {
+        let counts = pallet_template::Pallet::<T>::extension_calls(FUNC_WRITE_TO_STORAGE);
+        assert_eq!(counts, CallCounts { succeeded: 2, failed: 1 });
+    }
+
+    call_counts {
+        pallet_template::ExtensionCalls::<T>::insert(
+            FUNC_WRITE_TO_STORAGE,
+            CallCounts { succeeded: 1, failed: 1 },
+        );
+        let input = FUNC_WRITE_TO_STORAGE.encode();
+    }: {
+        let func_id = u32::decode(&mut &input[..]).expect("we just encoded this; qed");
+        // Finding out whether we know about `func_id` is part of the work.
+        assert!(super::quote::<T>(func_id, 0).is_some());
+        let _ = pallet_template::Pallet::<T>::extension_calls(func_id).encode();
+    }
+
+    // The code hash we check is the last one in a full list of approved ones.
+    check_code_hash {
+        let code_hashes: Vec<T::Hash> = (0 .. T::MaxApprovedCodeHashes::get())
+            .map(|i| T::Hashing::hash_of(&i))
+            .collect();
+        let code_hash = *code_hashes.last().expect("we approve at least one code hash; qed");
+        pallet_template::Pallet::<T>::set_approved_code_hashes(
+            RawOrigin::Root.into(),
+            FUNC_WRITE_TO_STORAGE,
+            Some(code_hashes),
+        )?;
+    }: {
+        assert!(pallet_template::Pallet::<T>::is_code_hash_approved(
+            FUNC_WRITE_TO_STORAGE,
+            &code_hash,
+        ));
+    }
+
+    // The worst case is a transfer which creates the recipient's account.
+    transfer_native {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let to: T::AccountId = account("to", 0, 0);
+        let amount = <T as pallet_contracts::Config>::Currency::minimum_balance();
+        let balance = amount.saturating_mul(10u32.into());
+        <T as pallet_contracts::Config>::Currency::make_free_balance_be(&contract, balance);
+
+        let input = (to.clone(), amount).encode();
+    }: {
+        // The benchmark shadows the function it's named after, hence the full path.
+        let (to, amount): (T::AccountId, BalanceOf<T>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        balances::transfer_native::<T>(&contract, &to, amount)?;
+    }
+    verify {
+        assert_eq!(<T as pallet_contracts::Config>::Currency::free_balance(&to), amount);
+    }
+
+    free_balance {
+        let who: T::AccountId = account("who", 0, 0);
+        let balance = <T as pallet_contracts::Config>::Currency::minimum_balance();
+        <T as pallet_contracts::Config>::Currency::make_free_balance_be(&who, balance);
+        let input = who.encode();
+    }: {
+        let who = T::AccountId::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = <T as pallet_contracts::Config>::Currency::free_balance(&who).encode();
+    }
+
+    account_nonce {
+        let who: T::AccountId = account("who", 0, 0);
+        frame_system::Pallet::<T>::inc_account_nonce(&who);
+        let input = who.encode();
+    }: {
+        let who = T::AccountId::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = frame_system::Pallet::<T>::account_nonce(who).encode();
+    }
+
+    block_hash {
+        let number: T::BlockNumber = 1u32.into();
+        frame_system::BlockHash::<T>::insert(number, T::Hash::default());
+        let input = number.encode();
+    }: {
+        let number = T::BlockNumber::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = frame_system::BlockHash::<T>::try_get(number).ok().encode();
+    }
+
+    // Without a consensus engine there's no digest to look through, so this measures looking for
+    // an author and not finding one. Memoizing it is the write the weight accounts for.
+    block_author {
+    }: {
+        let _ = crate::Authorship::author().encode();
+    }
+
+    session_info {
+    }: {
+        let _ = SessionInfo {
+            session_index: crate::Session::current_index(),
+            session_length: crate::SessionPeriod::get(),
+            active_era: crate::Staking::active_era().map(|era| era.index),
+            sessions_per_era: crate::SessionsPerEra::get(),
+        }
+        .encode();
+    }
+
+    // `Ext::code_hash()` isn't available outside of a contract call, so this only measures the
+    // rest. Looking up the code hash is a single read, which the weight accounts for.
+    contract_info {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let deposit = <T as pallet_contracts::Config>::Currency::minimum_balance();
+        <T as pallet_contracts::Config>::Currency::make_free_balance_be(
+            &contract,
+            deposit.saturating_mul(10u32.into()),
+        );
+        <T as pallet_contracts::Config>::Currency::reserve(&contract, deposit)?;
+        let input = contract.encode();
+    }: {
+        let contract = T::AccountId::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = Some(chain_extension_primitives::ContractInfo {
+            code_hash: T::Hash::default(),
+            storage_deposit: <T as pallet_contracts::Config>::Currency::reserved_balance(
+                &contract,
+            ),
+        })
+        .encode();
+    }
+
+    // `Ext::call()` isn't available outside of a contract call either, so this measures decoding
+    // the input and the transfer which comes along with the call. Looking up the callee is a
+    // single read, which the weight accounts for. Running the callee is paid for out of the gas
+    // it gets handed.
+    call_contract {
+        let n in 0 .. MAX_CALL_INPUT_LEN;
+        let contract: T::AccountId = account("contract", 0, 0);
+        let dest: T::AccountId = account("dest", 0, 0);
+        let value = <T as pallet_contracts::Config>::Currency::minimum_balance();
+        <T as pallet_contracts::Config>::Currency::make_free_balance_be(
+            &contract,
+            value.saturating_mul(10u32.into()),
+        );
+        let input = (dest, value, 0 as Weight, vec![0u8; n as usize]).encode();
+    }: {
+        let (dest, value, _, _): (T::AccountId, BalanceOf<T>, Weight, Vec<u8>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        <T as pallet_contracts::Config>::Currency::transfer(
+            &contract,
+            &dest,
+            value,
+            ExistenceRequirement::KeepAlive,
+        )?;
+    }
+
+    // Much like `call_contract`, this leaves out `Ext::instantiate()` itself. Bumping the nonce,
+    // storing the new contract and bumping the code's refcount are accounted for by the weight,
+    // running the constructor is paid for out of the gas it gets handed.
+    instantiate_contract {
+        let n in 0 .. MAX_CALL_INPUT_LEN;
+        let contract: T::AccountId = account("contract", 0, 0);
+        let value = <T as pallet_contracts::Config>::Currency::minimum_balance();
+        <T as pallet_contracts::Config>::Currency::make_free_balance_be(
+            &contract,
+            value.saturating_mul(10u32.into()),
+        );
+        let salt = vec![0u8; 32];
+        let input = (T::Hash::default(), value, 0 as Weight, vec![0u8; n as usize], salt).encode();
+    }: {
+        let (code_hash, value, _, _, salt): (T::Hash, BalanceOf<T>, Weight, Vec<u8>, Vec<u8>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let dest = pallet_contracts::Pallet::<T>::contract_address(&contract, &code_hash, &salt);
+        <T as pallet_contracts::Config>::Currency::transfer(
+            &contract,
+            &dest,
+            value,
+            ExistenceRequirement::KeepAlive,
+        )?;
+    }
+
+    // The call itself is charged for on top of this, so we only measure decoding it and running
+    // it past the filter. `set_blob` is the one call contracts may dispatch whose length we get to
+    // pick.
+    dispatch {
+        let n in 0 .. <T as pallet_template::Config>::MaxBlobLen::get();
+        let call = crate::Call::Template(pallet_template::Call::set_blob {
+            blob: vec![0u8; n as usize],
+        });
+        let input = call.encode().encode();
+    }: {
+        let encoded: Vec<u8> = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let call = <T as pallet_contracts::Config>::Call::decode_all_with_depth_limit(
+            sp_api::MAX_EXTRINSIC_DEPTH,
+            &mut &encoded[..],
+        )
+        .expect("we just encoded this; qed");
+        assert!(T::is_dispatchable(&call));
+    }
+
+    // Same goes for the writes, so this only covers decoding the values and putting the batch
+    // together.
+    batch_write {
+        let n in 0 .. MAX_BATCH_WRITE_LEN;
+        let input = (false, vec![0u32; n as usize]).encode();
+    }: {
+        let (_, values): (bool, Vec<u32>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let calls = values
+            .into_iter()
+            .map(|value| crate::Call::Template(pallet_template::Call::store_for_caller { value }))
+            .collect();
+        let call = T::contracts_call(crate::Call::Utility(pallet_utility::Call::batch_all {
+            calls,
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    // Again, the Multisig pallet's call is charged for on top of these. What we put together
+    // mostly depends on the length of the call, or of the other signatories.
+    as_multi {
+        let n in 0 .. <T as pallet_template::Config>::MaxBlobLen::get();
+        let other_signatories = vec![crate::AccountId::new([0; 32]); 2];
+        let timepoint = Some(pallet_multisig::Timepoint { height: 1, index: 0 });
+        let input =
+            (false, 2u16, other_signatories, timepoint, vec![0u8; n as usize], 0 as Weight)
+                .encode();
+    }: {
+        let (_, threshold, other_signatories, maybe_timepoint, call, max_weight): (
+            bool,
+            u16,
+            Vec<crate::AccountId>,
+            Option<pallet_multisig::Timepoint<crate::BlockNumber>>,
+            Vec<u8>,
+            Weight,
+        ) = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let call = T::contracts_call(crate::Call::Multisig(pallet_multisig::Call::as_multi {
+            threshold,
+            other_signatories,
+            maybe_timepoint,
+            call: frame_support::traits::WrapperKeepOpaque::from_encoded(call),
+            store_call: false,
+            max_weight,
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    approve_as_multi {
+        let s in 2 .. <T as pallet_multisig::Config>::MaxSignatories::get() as u32;
+        let other_signatories = vec![crate::AccountId::new([0; 32]); s as usize - 1];
+        let timepoint = Some(pallet_multisig::Timepoint { height: 1, index: 0 });
+        let input = (false, 2u16, other_signatories, timepoint, [0u8; 32], 0 as Weight).encode();
+    }: {
+        let (_, threshold, other_signatories, maybe_timepoint, call_hash, max_weight): (
+            bool,
+            u16,
+            Vec<crate::AccountId>,
+            Option<pallet_multisig::Timepoint<crate::BlockNumber>>,
+            [u8; 32],
+            Weight,
+        ) = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let call = T::contracts_call(crate::Call::Multisig(pallet_multisig::Call::approve_as_multi {
+            threshold,
+            other_signatories,
+            maybe_timepoint,
+            call_hash,
+            max_weight,
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    // The Proxy pallet's calls are charged for on top of these, too.
+    add_proxy {
+        let delegate = crate::AccountId::new([0; 32]);
+        let input = (false, delegate, ProxyType::Template, 0 as crate::BlockNumber).encode();
+    }: {
+        let (_, delegate, proxy_type, delay): (
+            bool,
+            crate::AccountId,
+            ProxyType,
+            crate::BlockNumber,
+        ) = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let call = T::contracts_call(crate::Call::Proxy(pallet_proxy::Call::add_proxy {
+            delegate,
+            proxy_type,
+            delay,
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    remove_proxy {
+        let delegate = crate::AccountId::new([0; 32]);
+        let input = (false, delegate, ProxyType::Template, 0 as crate::BlockNumber).encode();
+    }: {
+        let (_, delegate, proxy_type, delay): (
+            bool,
+            crate::AccountId,
+            ProxyType,
+            crate::BlockNumber,
+        ) = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let call = T::contracts_call(crate::Call::Proxy(pallet_proxy::Call::remove_proxy {
+            delegate,
+            proxy_type,
+            delay,
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    // Just like for `dispatch()`, `set_blob` is the call whose length we get to pick.
+    proxy {
+        let n in 0 .. <T as pallet_template::Config>::MaxBlobLen::get();
+        let call = crate::Call::Template(pallet_template::Call::set_blob {
+            blob: vec![0u8; n as usize],
+        });
+        let input = (false, crate::AccountId::new([0; 32]), call.encode()).encode();
+    }: {
+        let (_, real, encoded): (bool, crate::AccountId, Vec<u8>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let call = crate::Call::decode_all_with_depth_limit(
+            sp_api::MAX_EXTRINSIC_DEPTH,
+            &mut &encoded[..],
+        )
+        .expect("we just encoded this; qed");
+        assert!(<crate::ContractDispatchFilter as Contains<_>>::contains(&call));
+        let call = T::contracts_call(crate::Call::Proxy(pallet_proxy::Call::proxy {
+            real,
+            force_proxy_type: None,
+            call: crate::Box::new(call),
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    // Reading the registration is a single read, which the weight accounts for, so this measures
+    // decoding the largest one there can be and boiling it down to what the contract gets.
+    identity_of {
+        let who: T::AccountId = account("who", 0, 0);
+        let input = who.encode();
+        let registration = max_registration::<T>().encode();
+    }: {
+        let _who = T::AccountId::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let registration = RegistrationOf::<T>::decode(&mut &registration[..])
+            .expect("we just encoded this; qed");
+        let _ = Some(IdentityInfoOf {
+            display: display_name(registration.info.display),
+            judgement: judgement_of::<T>(&registration.judgements),
+        })
+        .encode();
+    }
+
+    // The Democracy pallet's call is charged for on top of this, too.
+    vote {
+        let input = (false, 0u32, true, Conviction::Locked6x, crate::Balance::MAX).encode();
+    }: {
+        let (_, ref_index, aye, conviction, balance): (
+            bool,
+            pallet_democracy::ReferendumIndex,
+            bool,
+            Conviction,
+            crate::Balance,
+        ) = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let call = T::contracts_call(crate::Call::Democracy(pallet_democracy::Call::vote {
+            ref_index,
+            vote: pallet_democracy::AccountVote::Standard {
+                vote: pallet_democracy::Vote {
+                    aye,
+                    conviction: democracy_conviction(conviction),
+                },
+                balance,
+            },
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    current_era {
+        pallet_staking::CurrentEra::<T>::put(1);
+    }: {
+        let _ = pallet_staking::Pallet::<T>::current_era().encode();
+    }
+
+    // Exposures don't have a bound, so we go with as many nominators as get paid for backing a
+    // validator. That's what `pallet_staking`'s own benchmarks assume, too.
+    validator_stake {
+        let validator: T::AccountId = account("validator", 0, 0);
+        let nominators = <T as pallet_staking::Config>::MaxNominatorRewardedPerValidator::get();
+        let stake = <T as pallet_staking::Config>::Currency::minimum_balance();
+        pallet_staking::ActiveEra::<T>::put(pallet_staking::ActiveEraInfo {
+            index: 1,
+            start: None,
+        });
+        pallet_staking::ErasStakers::<T>::insert(1, &validator, pallet_staking::Exposure {
+            total: stake.saturating_mul((nominators + 1).into()),
+            own: stake,
+            others: (0 .. nominators)
+                .map(|i| pallet_staking::IndividualExposure {
+                    who: account("nominator", i, 0),
+                    value: stake,
+                })
+                .collect(),
+        });
+        let input = validator.encode();
+    }: {
+        let validator =
+            T::AccountId::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let era = pallet_staking::Pallet::<T>::active_era().expect("we just set it; qed");
+        let _ = pallet_staking::Pallet::<T>::eras_stakers(era.index, validator).total.encode();
+    }
+
+    is_bonded {
+        let stash: T::AccountId = account("stash", 0, 0);
+        pallet_staking::Bonded::<T>::insert(&stash, &stash);
+        let input = stash.encode();
+    }: {
+        let stash = T::AccountId::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_staking::Pallet::<T>::bonded(stash).is_some().encode();
+    }
+
+    // The Treasury pallet's call is charged for on top of this, too.
+    propose_spend {
+        let beneficiary: crate::AccountId = account("beneficiary", 0, 0);
+        let input = (false, crate::Balance::MAX, beneficiary).encode();
+    }: {
+        let (_, value, beneficiary): (bool, crate::Balance, crate::AccountId) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_treasury::Pallet::<T>::proposal_count().encode();
+        let call = T::contracts_call(crate::Call::Treasury(pallet_treasury::Call::propose_spend {
+            value,
+            beneficiary: beneficiary.into(),
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    // So is the Collective pallet's. What we pay for here is putting the motion together.
+    propose_approval {
+        let input = (false, u32::MAX, ProposalIndex::MAX).encode();
+    }: {
+        let (_, threshold, proposal_id): (bool, u32, ProposalIndex) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let proposal =
+            crate::Call::Treasury(pallet_treasury::Call::approve_proposal { proposal_id });
+        let length_bound = proposal.encoded_size() as u32;
+        let _ = T::Hashing::hash_of(&proposal).encode();
+        let call = T::contracts_call(crate::Call::Council(pallet_collective::Call::propose {
+            threshold,
+            proposal: Box::new(proposal),
+            length_bound,
+        }));
+        assert!(call.get_dispatch_info().weight > 0);
+    }
+
+    // Decoding the members is what costs, so we go with as many as there may be.
+    is_member {
+        let max = <T as pallet_membership::Config<pallet_membership::Instance1>>::MaxMembers::get();
+        let members: Vec<T::AccountId> = (0 .. max).map(|i| account("member", i, 0)).collect();
+        let input = members[0].encode();
+        pallet_membership::Pallet::<T, pallet_membership::Instance1>::reset_members(
+            RawOrigin::Root.into(),
+            members,
+        )?;
+    }: {
+        let account = T::AccountId::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_membership::Pallet::<T, pallet_membership::Instance1>::members()
+            .binary_search(&account)
+            .is_ok()
+            .encode();
+    }
+
+    // Whatever our router charges for sending the message is part of this, its execution at the
+    // destination isn't.
+    xcm_transact {
+        let n in 0 .. MAX_XCM_CALL_LEN;
+        let input = (false, vec![0u8; n as usize], u64::MAX).encode();
+    }: {
+        let (_, call, require_weight_at_most): (bool, Vec<u8>, u64) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        assert!(call.len() as u32 <= MAX_XCM_CALL_LEN);
+        let message = transact_message([0; 32], call, require_weight_at_most);
+        let _ = <crate::XcmRouter as xcm::latest::SendXcm>::send_xcm(
+            crate::XcmDestination::get(),
+            message,
+        );
+    }
+
+    // Just like for `proxy()`, `set_blob` is the call whose length we get to pick.
+    estimate_fee {
+        let n in 0 .. <T as pallet_template::Config>::MaxBlobLen::get();
+        let call = crate::Call::Template(pallet_template::Call::set_blob {
+            blob: vec![0u8; n as usize],
+        });
+        let input = call.encode().encode();
+    }: {
+        let encoded: Vec<u8> = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let call = crate::Call::decode_all_with_depth_limit(
+            sp_api::MAX_EXTRINSIC_DEPTH,
+            &mut &encoded[..],
+        )
+        .expect("we just encoded this; qed");
+        let info = call.get_dispatch_info();
+        let _ = crate::TransactionPayment::compute_fee_details(encoded.len() as u32, &info, 0);
+    }
+
+    weight_to_fee {
+        let input = (u64::MAX, u64::MAX).encode();
+    }: {
+        let (ref_time, _proof_size): (u64, u64) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = <crate::Runtime as pallet_transaction_payment::Config>::WeightToFee::weight_to_fee(
+            &ref_time,
+        )
+        .encode();
+    }
+
+    asset_balance {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let asset = create_asset::<T>(&contract)?;
+        let input = (asset, contract).encode();
+    }: {
+        let (asset, who): (AssetIdOf<T>, T::AccountId) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_assets::Pallet::<T>::balance(asset, who).encode();
+    }
+
+    // The worst case is a transfer which creates the recipient's account.
+    asset_transfer {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let to: T::AccountId = account("to", 0, 0);
+        let asset = create_asset::<T>(&contract)?;
+        let amount: AssetBalanceOf<T> = 100u32.into();
+        let input = (asset, to.clone(), amount).encode();
+    }: {
+        let (asset, to, amount): (AssetIdOf<T>, T::AccountId, AssetBalanceOf<T>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_assets::Pallet::<T>::transfer(
+            RawOrigin::Signed(contract).into(),
+            asset,
+            <T as SysConfig>::Lookup::unlookup(to),
+            amount,
+        )?;
+    }
+    verify {
+        assert_eq!(pallet_assets::Pallet::<T>::balance(asset, &to), amount);
+    }
+
+    asset_total_supply {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let asset = create_asset::<T>(&contract)?;
+        let input = asset.encode();
+    }: {
+        let asset = AssetIdOf::<T>::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_assets::Pallet::<T>::total_supply(asset).encode();
+    }
+
+    // The worst case is a transfer which creates the recipient's account and uses up the whole
+    // approval, which then gets removed.
+    asset_transfer_approved {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let owner: T::AccountId = account("owner", 0, 0);
+        let to: T::AccountId = account("to", 0, 0);
+        let asset = create_asset::<T>(&contract)?;
+        let amount: AssetBalanceOf<T> = 100u32.into();
+
+        // The owner needs to be able to pay for the approval deposit.
+        type AssetsCurrency<T> = <T as pallet_assets::Config>::Currency;
+        let funds = <AssetsCurrency<T> as Currency<T::AccountId>>::Balance::max_value();
+        AssetsCurrency::<T>::make_free_balance_be(&owner, funds / 2u32.into());
+        pallet_assets::Pallet::<T>::transfer(
+            RawOrigin::Signed(contract.clone()).into(),
+            asset,
+            <T as SysConfig>::Lookup::unlookup(owner.clone()),
+            amount,
+        )?;
+        pallet_assets::Pallet::<T>::approve_transfer(
+            RawOrigin::Signed(owner.clone()).into(),
+            asset,
+            <T as SysConfig>::Lookup::unlookup(contract.clone()),
+            amount,
+        )?;
+
+        let input = (asset, owner, to.clone(), amount).encode();
+    }: {
+        let (asset, owner, to, amount): (
+            AssetIdOf<T>,
+            T::AccountId,
+            T::AccountId,
+            AssetBalanceOf<T>,
+        ) = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_assets::Pallet::<T>::transfer_approved(
+            RawOrigin::Signed(contract).into(),
+            asset,
+            <T as SysConfig>::Lookup::unlookup(owner),
+            <T as SysConfig>::Lookup::unlookup(to),
+            amount,
+        )?;
+    }
+    verify {
+        assert_eq!(pallet_assets::Pallet::<T>::balance(asset, &to), amount);
+    }
+
+    // Same as above, minting to an account which doesn't hold any of the asset yet is the most
+    // expensive.
+    asset_mint {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let to: T::AccountId = account("to", 0, 0);
+        let asset = create_asset::<T>(&contract)?;
+        let amount: AssetBalanceOf<T> = 100u32.into();
+        let input = (asset, to.clone(), amount).encode();
+    }: {
+        let (asset, to, amount): (AssetIdOf<T>, T::AccountId, AssetBalanceOf<T>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_assets::Pallet::<T>::mint(
+            RawOrigin::Signed(contract).into(),
+            asset,
+            <T as SysConfig>::Lookup::unlookup(to),
+            amount,
+        )?;
+    }
+    verify {
+        assert_eq!(pallet_assets::Pallet::<T>::balance(asset, &to), amount);
+    }
+
+    // We always benchmark minting along with setting the metadata, even if there's none.
+    nft_mint {
+        let n in 0 .. <T as pallet_uniques::Config>::StringLimit::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let to: T::AccountId = account("to", 0, 0);
+        let collection = create_collection::<T>(&contract)?;
+        let item = ItemIdOf::<T>::from(0u32);
+        let input = (collection, item, to.clone(), vec![0u8; n as usize]).encode();
+    }: {
+        let (collection, item, to, metadata): (
+            CollectionIdOf<T>,
+            ItemIdOf<T>,
+            T::AccountId,
+            Vec<u8>,
+        ) = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let metadata: NftMetadataOf<T> = metadata.try_into().map_err(|_| "metadata is too long")?;
+        pallet_uniques::Pallet::<T>::mint(
+            RawOrigin::Signed(contract.clone()).into(),
+            collection,
+            item,
+            <T as SysConfig>::Lookup::unlookup(to),
+        )?;
+        pallet_uniques::Pallet::<T>::set_metadata(
+            RawOrigin::Signed(contract).into(),
+            collection,
+            item,
+            metadata,
+            false,
+        )?;
+    }
+    verify {
+        assert_eq!(nft_owner::<T>(&collection, &item), Some(to));
+    }
+
+    nft_transfer {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let to: T::AccountId = account("to", 0, 0);
+        let collection = create_collection::<T>(&contract)?;
+        let item = mint_item::<T>(&contract, collection)?;
+        let input = (collection, item, to.clone()).encode();
+    }: {
+        let (collection, item, to): (CollectionIdOf<T>, ItemIdOf<T>, T::AccountId) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_uniques::Pallet::<T>::transfer(
+            RawOrigin::Signed(contract).into(),
+            collection,
+            item,
+            <T as SysConfig>::Lookup::unlookup(to),
+        )?;
+    }
+    verify {
+        assert_eq!(nft_owner::<T>(&collection, &item), Some(to));
+    }
+
+    nft_owner_of {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let collection = create_collection::<T>(&contract)?;
+        let item = mint_item::<T>(&contract, collection)?;
+        let input = (collection, item).encode();
+    }: {
+        let (collection, item): (CollectionIdOf<T>, ItemIdOf<T>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = nft_owner::<T>(&collection, &item).encode();
+    }
+
+    random {
+        let n in 0 .. MAX_SUBJECT_LEN;
+        let input = vec![0u8; n as usize].encode();
+    }: {
+        let subject: Vec<u8> = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let (random, _) = <T as pallet_contracts::Config>::Randomness::random(&subject);
+        let _ = random.encode();
+    }
+
+    // We benchmark valid signatures, so verification never bails out early.
+    verify_sr25519 {
+        let n in 0 .. MAX_MESSAGE_LEN;
+        let message = vec![0u8; n as usize];
+        let public = sp_io::crypto::sr25519_generate(BENCHMARK_KEY_TYPE, None);
+        let signature = sp_io::crypto::sr25519_sign(BENCHMARK_KEY_TYPE, &public, &message)
+            .ok_or("the keystore should be able to sign")?;
+        let input = (signature.0, message, public.0).encode();
+    }: {
+        let (signature, message, public): ([u8; 64], Vec<u8>, [u8; 32]) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        assert!(sp_io::crypto::sr25519_verify(
+            &sr25519::Signature::from_raw(signature),
+            &message,
+            &sr25519::Public::from_raw(public),
+        ));
+    }
+
+    verify_ecdsa {
+        let n in 0 .. MAX_MESSAGE_LEN;
+        let message = vec![0u8; n as usize];
+        let public = sp_io::crypto::ecdsa_generate(BENCHMARK_KEY_TYPE, None);
+        let signature = sp_io::crypto::ecdsa_sign(BENCHMARK_KEY_TYPE, &public, &message)
+            .ok_or("the keystore should be able to sign")?;
+        let input = (signature.0, message, public.0).encode();
+    }: {
+        let (signature, message, public): ([u8; 65], Vec<u8>, [u8; 33]) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        assert!(sp_io::crypto::ecdsa_verify(
+            &ecdsa::Signature::from_raw(signature),
+            &message,
+            &ecdsa::Public::from_raw(public),
+        ));
+    }
+
+    counter_create {
+        let n in 0 .. T::MaxCounterNameLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let input = vec![0u8; n as usize].encode();
+    }: {
+        let name: Vec<u8> = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::create_counter(&contract, name)?;
+    }
+    verify {
+        assert_eq!(pallet_template::Pallet::<T>::counter_value(vec![0u8; n as usize]), Some(0));
+    }
+
+    // The worst case is the counter's last member incrementing it, since we look through all of
+    // the members before finding it.
+    counter_increment {
+        let n in 0 .. T::MaxCounterNameLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let members = T::MaxCounterMembers::get();
+        let name = vec![0u8; n as usize];
+        create_counter::<T>(&contract, &name, members)?;
+
+        let member: T::AccountId = account("member", members - 1, 0);
+        let input = name.encode();
+    }: {
+        let name: Vec<u8> = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::increment_counter(&member, name)?;
+    }
+    verify {
+        assert_eq!(pallet_template::Pallet::<T>::counter_value(vec![0u8; n as usize]), Some(1));
+    }
+
+    counter_value {
+        let n in 0 .. T::MaxCounterNameLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let name = vec![0u8; n as usize];
+        create_counter::<T>(&contract, &name, T::MaxCounterMembers::get())?;
+        let input = name.encode();
+    }: {
+        let name: Vec<u8> = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_template::Pallet::<T>::counter_value(name).encode();
+    }
+
+    // The worst case is adding the last member the counter has room for.
+    counter_set_access {
+        let n in 0 .. T::MaxCounterNameLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let members = T::MaxCounterMembers::get();
+        let name = vec![0u8; n as usize];
+        create_counter::<T>(&contract, &name, members - 1)?;
+
+        let member: T::AccountId = account("member", members - 1, 0);
+        let input = (name, member.clone(), true).encode();
+    }: {
+        let (name, who, allowed): (Vec<u8>, T::AccountId, bool) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::set_counter_access(&contract, name, who, allowed)?;
+    }
+    verify {
+        let name = vec![0u8; n as usize];
+        assert_eq!(pallet_template::Pallet::<T>::increment_counter(&member, name), Ok(1));
+    }
+
+    // Scheduling the removal of the commitment is covered by `schedule_call`, see
+    // `commit_weight()`.
+    commit {
+        let contract: T::AccountId = account("contract", 0, 0);
+        let hash = T::Hashing::hash(b"secret");
+        let input = (hash, 5u32, 5u32).encode();
+    }: {
+        let (hash, opens_in, window): (T::Hash, u32, u32) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let opens_at = T::BlockNumber::from(opens_in);
+        pallet_template::Pallet::<T>::commit(
+            &contract,
+            hash,
+            opens_at,
+            opens_at.saturating_add(window.into()),
+        )?;
+    }
+    verify {
+        assert!(pallet_template::Pallet::<T>::commitment(0).is_some());
+    }
+
+    reveal {
+        let n in 0 .. MAX_PREIMAGE_LEN;
+        let contract: T::AccountId = account("contract", 0, 0);
+        let preimage = vec![0u8; n as usize];
+        pallet_template::Pallet::<T>::commit(
+            &contract,
+            T::Hashing::hash(&preimage),
+            0u32.into(),
+            10u32.into(),
+        )?;
+        let input = (0u64, preimage).encode();
+    }: {
+        let (id, preimage): (u64, Vec<u8>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::reveal(&contract, id, &preimage)
+            .expect("the preimage matches and the window is open; qed");
+    }
+    verify {
+        assert!(pallet_template::Pallet::<T>::commitment(0).is_none());
+    }
+
+    custom_type_with_result {
+        let n in 0 .. MAX_CUSTOM_LEN;
+        let caller: T::AccountId = whitelisted_caller();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let input = Custom { inner: vec![0u8; n as usize] }.encode();
+    }: {
+        let custom = Custom::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::store_something_for_contract(
+            contract,
+            caller,
+            custom.inner.len() as u32,
+        );
+    }
+    verify {
+        assert_eq!(pallet_template::Pallet::<T>::something(), Some(n));
+    }
+
+    schedule_call {
+        // The cost of scheduling depends on how full the agenda for the target block already is,
+        // and on how many blocks we had to look at before finding one with room.
+        let s in 0 .. T::MaxScheduledPerBlock::get() - 1;
+        let b in 1 .. T::MaxCallbackDelay::get() + 1;
+        let caller: T::AccountId = whitelisted_caller();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let requested = 10u32;
+        let when = requested + b - 1;
+
+        for full in requested .. when {
+            pallet_template::CallbacksPerBlock::<T>::insert(
+                T::BlockNumber::from(full),
+                T::MaxCallbacksPerBlock::get(),
+            );
+        }
+
+        // The selector we check is the last one in a full allowlist.
+        let selectors: Vec<[u8; 4]> =
+            (0 .. T::MaxCallbackSelectors::get()).map(|i| i.to_be_bytes()).collect();
+        let selector = *selectors.last().expect("we allow at least one selector; qed");
+        pallet_template::Pallet::<T>::set_callback_selectors(RawOrigin::Root.into(), selectors)?;
+
+        // Same goes for the contract, which is the last one in a full list of those allowed to
+        // schedule.
+        let allowed: Vec<T::AccountId> = (1 .. T::MaxAllowedSchedulers::get())
+            .map(|i| account("allowed", i, 0))
+            .chain(Some(contract.clone()))
+            .collect();
+        pallet_template::Pallet::<T>::set_scheduler_allowlist(
+            RawOrigin::Root.into(),
+            Some(allowed),
+        )?;
+
+        // The contract was sent some value along with scheduling, which we hold on to. On top of
+        // that the caller puts down a deposit.
+        let value = <T as pallet_template::Config>::Currency::minimum_balance();
+        let balance = value.saturating_mul(10u32.into());
+        let deposit = <T as pallet_template::Config>::CallbackDeposit::get();
+        <T as pallet_template::Config>::Currency::make_free_balance_be(&contract, balance);
+        <T as pallet_template::Config>::Currency::make_free_balance_be(
+            &caller,
+            balance.saturating_add(deposit),
+        );
+
+        let call = || {
+            let call: <T as pallet_scheduler::Config>::Call =
+                crate::Call::Template(pallet_template::Call::do_something { something: 0 })
+                    .into();
+            crate::Box::new(MaybeHashed::Value(call))
+        };
+
+        for _ in 0 .. s {
+            pallet_scheduler::Pallet::<T>::schedule(
+                RawOrigin::Signed(caller.clone()).into(),
+                when.into(),
+                None,
+                Default::default(),
+                call(),
+            )?;
+        }
+    }: {
+        assert!(pallet_template::Pallet::<T>::is_scheduler_allowed(&contract));
+        assert!(pallet_template::Pallet::<T>::is_callback_selector_allowed(selector));
+        let handle = pallet_template::Pallet::<T>::next_callback_handle();
+        let at = pallet_template::Pallet::<T>::reserve_callback(&contract, requested.into())
+            .expect("the last block we look at has room; qed");
+        pallet_template::Pallet::<T>::escrow_callback_value(handle, &contract, value)?;
+        pallet_template::Pallet::<T>::reserve_callback_deposit(handle, &caller, 0u32.into())?;
+        let (at, index) = <pallet_scheduler::Pallet<T> as Anon<_, _, _>>::schedule(
+            DispatchTime::At(at),
+            None,
+            Default::default(),
+            RawOrigin::Signed(contract.clone()).into(),
+            *call(),
+        )?;
+        pallet_template::Pallet::<T>::note_callback_address(handle, at, index);
+        pallet_template::Pallet::<T>::note_callback_origin(handle, &contract, &contract);
+    }
+    verify {
+        assert_eq!(pallet_scheduler::Agenda::<T>::get(T::BlockNumber::from(when)).len(), s as usize + 1);
+        assert!(pallet_template::Pallet::<T>::callback_escrow(0).is_some());
+        assert!(pallet_template::Pallet::<T>::callback_deposit(0).is_some());
+        assert!(pallet_template::Pallet::<T>::callback_address(0).is_some());
+        assert!(pallet_template::Pallet::<T>::callback_origin(0).is_some());
+    }
+
+    reschedule {
+        // Moving a callback means finding it in the agenda it's in and appending it to the one
+        // it's moving to, both of which already hold `s` other calls. On top of that we may have
+        // to look at `b` blocks before finding one with room.
+        let s in 0 .. T::MaxScheduledPerBlock::get() - 1;
+        let b in 1 .. T::MaxCallbackDelay::get() + 1;
+        let caller: T::AccountId = whitelisted_caller();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let old = 5u32;
+        let requested = 10u32;
+        let when = requested + b - 1;
+
+        for full in requested .. when {
+            pallet_template::CallbacksPerBlock::<T>::insert(
+                T::BlockNumber::from(full),
+                T::MaxCallbacksPerBlock::get(),
+            );
+        }
+
+        let call = || {
+            let call: <T as pallet_scheduler::Config>::Call =
+                crate::Call::Template(pallet_template::Call::do_something { something: 0 })
+                    .into();
+            crate::Box::new(MaybeHashed::Value(call))
+        };
+
+        for at in [old, when] {
+            for _ in 0 .. s {
+                pallet_scheduler::Pallet::<T>::schedule(
+                    RawOrigin::Signed(caller.clone()).into(),
+                    at.into(),
+                    None,
+                    Default::default(),
+                    call(),
+                )?;
+            }
+        }
+
+        // The callback we're moving comes last in its agenda.
+        let callback: <T as pallet_scheduler::Config>::Call =
+            crate::Call::Template(pallet_template::Call::dispatch_callback {
+                handle: 0,
+                call: crate::Box::new(crate::Call::Contracts(pallet_contracts::Call::call {
+                    dest: T::runtime_address(contract.clone()),
+                    value: 0,
+                    gas_limit: 0,
+                    storage_deposit_limit: None,
+                    data: Vec::new(),
+                })),
+            })
+            .into();
+        pallet_scheduler::Pallet::<T>::schedule(
+            RawOrigin::Signed(caller).into(),
+            old.into(),
+            None,
+            Default::default(),
+            crate::Box::new(MaybeHashed::Value(callback)),
+        )?;
+        pallet_template::Pallet::<T>::reserve_callback(&contract, old.into());
+        pallet_template::Pallet::<T>::note_callback_address(0, old.into(), s);
+    }: {
+        let address = pallet_template::Pallet::<T>::callback_address(0)
+            .expect("we just noted its address; qed");
+        let handle = scheduler::callback_at::<T>(address, &contract)
+            .expect("the callback is for the contract; qed");
+        let at = pallet_template::Pallet::<T>::reserve_callback(&contract, requested.into())
+            .expect("the last block we look at has room; qed");
+        pallet_template::Pallet::<T>::release_callback(&contract, address.0);
+        let (at, index) = <pallet_scheduler::Pallet<T> as Anon<_, _, _>>::reschedule(
+            address,
+            DispatchTime::At(at),
+        )?;
+        pallet_template::Pallet::<T>::note_callback_address(handle, at, index);
+    }
+    verify {
+        assert_eq!(
+            pallet_template::Pallet::<T>::callback_address(0),
+            Some((when.into(), s))
+        );
+    }
+
+    // Callbacks which are too long to go into the agenda get noted with the Preimage pallet, which
+    // the Scheduler then requests when we hand it the hash.
+    note_callback_preimage {
+        let n in 0 .. 1024;
+        let preimage = vec![0u8; n as usize];
+        let hash = T::Hashing::hash(&preimage);
+        let bounded = preimage.try_into().expect("preimages are allowed to be this long; qed");
+    }: {
+        <pallet_preimage::Pallet<T> as PreimageRecipient<_>>::note_preimage(bounded);
+        <pallet_preimage::Pallet<T> as PreimageProvider<_>>::request_preimage(&hash);
+    }
+    verify {
+        assert!(<pallet_preimage::Pallet<T> as PreimageProvider<_>>::have_preimage(&hash));
+    }
+
+    capabilities {
+    }: {
+        let _ = (capabilities::WRITE_TO_STORAGE
+            | capabilities::CUSTOM_TYPE_WITH_RESULT
+            | capabilities::SCHEDULE_CALL
+            | capabilities::CALLBACK_WEIGHTS
+            | capabilities::WRITE_RAW
+            | capabilities::CHECKED_CUSTOM_TYPE
+            | capabilities::CONTRACT_VALUES
+            | capabilities::READ_RAW
+            | capabilities::ACCOUNT_VALUES
+            | capabilities::TRANSFER_NATIVE
+            | capabilities::QUOTE
+            | capabilities::ENVELOPES
+            | capabilities::ASSETS
+            | capabilities::COMMIT_REVEAL
+            | capabilities::NFTS
+            | capabilities::RANDOMNESS
+            | capabilities::COUNTERS
+            | capabilities::SIGNATURES
+            | capabilities::CALLBACK_RESERVATION
+            | capabilities::CALLBACK_RECEIPTS
+            | capabilities::CALLBACK_DEPOSIT
+            | capabilities::BOUNDED_CUSTOM_TYPE
+            | capabilities::RICH_CUSTOM_TYPE
+            | capabilities::TRANSFER_NATIVE_COMPACT
+            | capabilities::BLOB
+            | capabilities::RESCHEDULE
+            | capabilities::SCHEDULE_IN
+            | capabilities::CALL_COUNTS
+            | capabilities::READ_FROM_STORAGE
+            | capabilities::CHAIN_CONSTANTS
+            | capabilities::FREE_BALANCE
+            | capabilities::ACCOUNT_NONCE)
+            .encode();
+    }
+
+    // Both extensions hand back a constant, so one benchmark covers the two of them.
+    version {
+    }: {
+        let _ = MAIN_EXTENSION_VERSION.encode();
+    }
+
+    chain_constants {
+    }: {
+        let _ = super::introspection::chain_constants::<T>().encode();
+    }
+
+    callback_reservation {
+        pallet_template::CallbackWeightUsed::<T>::put(1_000_000_000);
+    }: {
+        let _ = pallet_template::Pallet::<T>::reservation_usage().encode();
+    }
+
+    callback_deposit {
+    }: {
+        let _ = <T as pallet_template::Config>::CallbackDeposit::get().encode();
+    }
+
+    callback_receipt {
+        pallet_template::Receipts::<T>::insert(0, chain_extension_primitives::CallbackReceipt {
+            block: T::BlockNumber::from(1u32),
+            weight: 1_000_000_000,
+            success: true,
+            output_hash: T::Hashing::hash(b"outcome"),
+        });
+        let input = 0u64.encode();
+    }: {
+        let handle = u64::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let _ = pallet_template::Pallet::<T>::receipt(handle).encode();
+    }
+
+    callback_weights {
+        // We don't want this read to be whitelisted, so we can't use `whitelisted_caller()`.
+        let contract: T::AccountId = account("contract", 0, 0);
+        pallet_template::CallbackWeights::<T>::mutate(&contract, |histogram| {
+            for weight in 0 .. 100 {
+                histogram.note(weight << 28);
+            }
+        });
+    }: {
+        let _ = pallet_template::Pallet::<T>::callback_weights(contract).encode();
+    }
+
+    write_raw {
+        let n in 0 .. T::MaxRawValueLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        pallet_template::SystemContract::<T>::put(&contract);
+
+        let key = vec![0u8; T::MaxRawKeyLen::get() as usize];
+        let input = (key, vec![0u8; n as usize]).encode();
+    }: {
+        let (key, value): (Vec<u8>, Vec<u8>) =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::write_raw(&contract, key, value)?;
+    }
+
+    // We benchmark the worst case, where there's no registered schema and we need to compute the
+    // descriptor hash ourselves.
+    checked_custom_type {
+        let n in 0 .. MAX_CUSTOM_LEN;
+        let caller: T::AccountId = whitelisted_caller();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let schema = sp_io::hashing::blake2_256(&type_descriptor::<Custom>());
+        let input = (schema, Custom { inner: vec![0u8; n as usize] }).encode();
+    }: {
+        let mut input = &input[..];
+        let schema = SchemaHash::decode(&mut input).expect("we just encoded this; qed");
+        let expected = pallet_template::Pallet::<T>::schema(FUNC_CHECKED_CUSTOM_TYPE)
+            .map(|descriptor| descriptor.hash)
+            .unwrap_or_else(|| sp_io::hashing::blake2_256(&type_descriptor::<Custom>()));
+        assert_eq!(schema, expected);
+
+        let custom = Custom::decode(&mut input).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::store_something_for_contract(
+            contract,
+            caller,
+            custom.inner.len() as u32,
+        );
+    }
+
+    // The input is bounded, so we only need to look at the longest one.
+    bounded_custom_type {
+        let caller: T::AccountId = whitelisted_caller();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let input = Custom { inner: vec![0u8; MAX_BOUNDED_CUSTOM_LEN as usize] }.encode();
+    }: {
+        let custom: Custom = BoundedCustom::decode(&mut &input[..])
+            .expect("we just encoded this; qed")
+            .into();
+        pallet_template::Pallet::<T>::store_something_for_contract(
+            contract,
+            caller,
+            custom.inner.len() as u32,
+        );
+    }
+    verify {
+        assert_eq!(pallet_template::Pallet::<T>::something(), Some(MAX_BOUNDED_CUSTOM_LEN));
+    }
+
+    // Summing up the payload is the most expensive of the measures, since it has to look at every
+    // byte.
+    rich_custom_type {
+        let n in 0 .. MAX_CUSTOM_LEN;
+        let caller: T::AccountId = whitelisted_caller();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let input = RichCustom {
+            measure: Measure::Sum,
+            only_for: Some(caller.clone()),
+            payload: Custom { inner: vec![1u8; n as usize] },
+        }
+        .encode();
+    }: {
+        let custom = RichCustom::<T::AccountId>::decode(&mut &input[..])
+            .expect("we just encoded this; qed");
+        assert!(custom.only_for.as_ref() == Some(&caller));
+        let value = custom.measure.apply(&custom.payload.inner).expect("sums always work; qed");
+        pallet_template::Pallet::<T>::store_something_for_contract(contract, caller, value);
+    }
+    verify {
+        assert_eq!(pallet_template::Pallet::<T>::something(), Some(n));
+    }
+
+    store_batch {
+        let n in 0 .. T::MaxBatchLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        let input = (0 .. n).map(|i| (i, i)).collect::<Vec<(u32, u32)>>().encode();
+    }: {
+        let values: Vec<(u32, u32)> =
+            Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        pallet_template::Pallet::<T>::store_batch(&contract, values)?;
+    }
+    verify {
+        assert_eq!(pallet_template::ContractValues::<T>::iter_prefix(&contract).count() as u32, n);
+    }
+
+    load_value {
+        let contract: T::AccountId = account("contract", 0, 0);
+        pallet_template::ContractValues::<T>::insert(&contract, 0, 42);
+    }: {
+        let _ = pallet_template::Pallet::<T>::contract_value(&contract, 0).encode();
+    }
+
+    blob {
+        let n in 0 .. T::MaxBlobLen::get();
+        pallet_template::Pallet::<T>::set_blob(RawOrigin::Root.into(), vec![0u8; n as usize])?;
+    }: {
+        let _ = pallet_template::Pallet::<T>::blob().encode();
+    }
+
+    read_raw {
+        let k in 1 .. T::MaxRawKeyLen::get();
+        let n in 0 .. T::MaxRawValueLen::get();
+        let contract: T::AccountId = account("contract", 0, 0);
+        pallet_template::SystemContract::<T>::put(&contract);
+
+        let key = vec![0u8; k as usize];
+        pallet_template::Pallet::<T>::write_raw(&contract, key.clone(), vec![0u8; n as usize])?;
+        let input = key.encode();
+    }: {
+        let key: Vec<u8> = Decode::decode(&mut &input[..]).expect("we just encoded this; qed");
+        let value = pallet_template::Pallet::<T>::read_raw(key);
+        let _ = value.encode();
+    }
+    verify {
+        assert_eq!(
+            pallet_template::Pallet::<T>::read_raw(vec![0u8; k as usize]).map(|v| v.len() as u32),
+            Some(n)
+        );
+    }
+}
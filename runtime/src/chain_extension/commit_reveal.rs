@@ -0,0 +1,146 @@
+//! Functions for committing to a value now and only revealing it later on.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_COMMIT,
+    FUNC_REVEAL,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::{
+        schedule::MaybeHashed,
+        Get,
+    },
+    weights::Weight,
+};
+use frame_system::RawOrigin;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use pallet_template::RevealError;
+use sp_runtime::{
+    traits::{
+        Saturating,
+        UniqueSaturatedInto,
+    },
+    DispatchError,
+};
+
+pub(super) struct CommitRevealFuncs;
+
+impl HandleFunc for CommitRevealFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_COMMIT, FUNC_REVEAL];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `commit()`
+            FUNC_COMMIT => {
+                let (_, hash, opens_in, window): (bool, <T as SysConfig>::Hash, u32, u32) =
+                    env.read_as()?;
+
+                // Besides storing the commitment we schedule its removal, and just like with
+                // `schedule_call()` we don't know how long it'll take to find room for that.
+                let max_blocks = <T as pallet_template::Config>::MaxCallbackDelay::get() + 1;
+                let charged = env.charge_weight(commit_weight::<T>(max_blocks))?;
+
+                let now = frame_system::Pallet::<T>::block_number();
+                let opens_at = now.saturating_add(opens_in.into());
+                let closes_at = opens_at.saturating_add(window.into());
+                let retention = <T as pallet_template::Config>::CommitmentRetention::get();
+                let remove_at = closes_at.saturating_add(retention.into());
+
+                // We reserve the slot before storing anything, that way there's nothing to undo
+                // if there's no room.
+                let contract = env.ext().address().clone();
+                let at = match pallet_template::Pallet::<T>::reserve_callback(&contract, remove_at)
+                {
+                    Some(reserved) => reserved,
+                    None => return Ok(RetVal::Converging(status::AGENDA_FULL)),
+                };
+
+                let blocks: u32 = (at - remove_at).unique_saturated_into();
+                env.adjust_weight(charged, commit_weight::<T>(blocks + 1));
+
+                let id =
+                    pallet_template::Pallet::<T>::commit(&contract, hash, opens_at, closes_at)?;
+
+                let call = T::scheduler_call(crate::Call::Template(
+                    pallet_template::Call::remove_expired_commitment { id },
+                ));
+                pallet_scheduler::Pallet::<T>::schedule(
+                    RawOrigin::Signed(contract).into(),
+                    at,
+                    None,
+                    Default::default(),
+                    crate::Box::new(MaybeHashed::Value(call)),
+                )?;
+
+                env.write(&id.encode(), false, None)?;
+                Ok(RetVal::Converging(status::SUCCESS))
+            }
+            // Corresponds to `reveal()`
+            FUNC_REVEAL => {
+                // Hashing the preimage gets more expensive the longer it is.
+                let (_, id, preimage): (bool, u64, crate::Vec<u8>) =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::reveal)?;
+
+                // Contracts only get to reveal their own commitments.
+                let contract = env.ext().address().clone();
+                let status = match pallet_template::Pallet::<T>::reveal(&contract, id, &preimage) {
+                    Ok(()) => status::SUCCESS,
+                    Err(RevealError::Unknown) => status::UNKNOWN_COMMITMENT,
+                    Err(RevealError::NotOwner) => status::NOT_PERMITTED,
+                    Err(RevealError::TooEarly) => status::REVEAL_TOO_EARLY,
+                    Err(RevealError::TooLate) => status::REVEAL_TOO_LATE,
+                    Err(RevealError::Mismatch) => status::INVALID_REVEAL,
+                };
+                Ok(RetVal::Converging(status))
+            }
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        let weight = match func_id {
+            FUNC_COMMIT => {
+                commit_weight::<T>(<T as pallet_template::Config>::MaxCallbackDelay::get() + 1)
+            }
+            FUNC_REVEAL => ExtensionWeightInfo::<T>::reveal(input_len),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// What `commit()` costs when scheduling the removal of the commitment means looking at `blocks`
+/// blocks.
+fn commit_weight<T>(blocks: u32) -> Weight
+where
+    T: pallet_template::Config + pallet_scheduler::Config,
+{
+    ExtensionWeightInfo::<T>::commit().saturating_add(ExtensionWeightInfo::<T>::schedule_call(
+        T::MaxScheduledPerBlock::get(),
+        blocks,
+    ))
+}
@@ -0,0 +1,220 @@
+//! Functions which tell contracts about, and let them call, other contracts.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    BalanceOf,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    ContractInfo,
+    FUNC_CALL_CONTRACT,
+    FUNC_CONTRACT_INFO,
+    FUNC_INSTANTIATE_CONTRACT,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::ReservableCurrency,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct ContractFuncs;
+
+impl HandleFunc for ContractFuncs {
+    const FUNCS: &'static [u32] = &[
+        FUNC_CONTRACT_INFO,
+        FUNC_CALL_CONTRACT,
+        FUNC_INSTANTIATE_CONTRACT,
+    ];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `contract_info()`
+            FUNC_CONTRACT_INFO => {
+                let account: <T as SysConfig>::AccountId = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::contract_info())?;
+
+                // Only contracts have a code hash, for everyone else there's nothing to report.
+                //
+                // `pallet_contracts` moves the storage deposit into the contract's own account
+                // and reserves it there. Contracts can't reserve anything else themselves, so
+                // whatever's reserved is the deposit.
+                let info = env.ext().code_hash(&account).map(|code_hash| ContractInfo {
+                    code_hash,
+                    storage_deposit: <T as pallet_contracts::Config>::Currency::reserved_balance(
+                        &account,
+                    ),
+                });
+                env.write(&info.encode(), false, None)?;
+            }
+            // Corresponds to `call_contract()`
+            FUNC_CALL_CONTRACT => {
+                let (simulate, dest, value, gas_limit, input): (
+                    bool,
+                    <T as SysConfig>::AccountId,
+                    BalanceOf<T>,
+                    Weight,
+                    crate::Vec<u8>,
+                ) = charge_and_read(env, ExtensionWeightInfo::<T>::call_contract)?;
+
+                // We'd only be rolling back half of what the callee did, see
+                // `UNSIMULATABLE_FUNCS`.
+                if simulate {
+                    return Ok(RetVal::Converging(status::SIMULATE_NOT_SUPPORTED))
+                }
+
+                // Just like `blob()` we leave it to `write()` to charge for every byte of output,
+                // once we know how much of it there is.
+                let per_byte = ExtensionWeightInfo::<T>::call_contract(1)
+                    .saturating_sub(ExtensionWeightInfo::<T>::call_contract(0));
+                let gas_limit = callee_gas_limit::<T, E>(env, gas_limit)?;
+
+                // Going through `Ext` rather than `bare_call()` makes this a nested call like any
+                // other: the contract is the caller, `value` comes out of its account, and
+                // whatever the callee does is rolled back if it fails. The callee may not call
+                // back into contracts which are already on the call stack.
+                let output = match env.ext().call(gas_limit, dest, value, input, false) {
+                    Ok(output) => output,
+                    Err(e) => {
+                        return call_status::<T>(e.error)
+                            .map(RetVal::Converging)
+                            .ok_or(e.error)
+                    }
+                };
+
+                // A callee which reverted hasn't failed as far as we're concerned, its flags tell
+                // the contract about it.
+                env.write(
+                    &(output.flags.bits(), output.data.0).encode(),
+                    false,
+                    Some(per_byte),
+                )?;
+            }
+            // Corresponds to `instantiate_contract()`
+            FUNC_INSTANTIATE_CONTRACT => {
+                let (simulate, code_hash, value, gas_limit, input, salt): (
+                    bool,
+                    <T as SysConfig>::Hash,
+                    BalanceOf<T>,
+                    Weight,
+                    crate::Vec<u8>,
+                    crate::Vec<u8>,
+                ) = charge_and_read(env, ExtensionWeightInfo::<T>::instantiate_contract)?;
+
+                // Just like with `call_contract()`, there'd be more to roll back than we can.
+                if simulate {
+                    return Ok(RetVal::Converging(status::SIMULATE_NOT_SUPPORTED))
+                }
+
+                // All that's left to do afterwards is handing back the address, which the weight
+                // above already covers.
+                let gas_limit = callee_gas_limit::<T, E>(env, gas_limit)?;
+
+                // Just like with `call_contract()`, the contract is the one instantiating. So the
+                // new contract's address is derived from the calling contract's, and `value`
+                // comes out of its account.
+                let (address, output) = match env
+                    .ext()
+                    .instantiate(gas_limit, code_hash, value, input, &salt)
+                {
+                    Ok(instantiated) => instantiated,
+                    Err(e) => {
+                        return call_status::<T>(e.error)
+                            .map(RetVal::Converging)
+                            .ok_or(e.error)
+                    }
+                };
+
+                // The new contract is gone again if its constructor reverted.
+                if output.did_revert() {
+                    return Ok(RetVal::Converging(status::CONSTRUCTOR_REVERTED))
+                }
+
+                env.write(&address.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_CONTRACT_INFO => W::<T>::contract_info(),
+            FUNC_CALL_CONTRACT => W::<T>::call_contract(input_len),
+            FUNC_INSTANTIATE_CONTRACT => W::<T>::instantiate_contract(input_len),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// How much of the contract's remaining gas to hand a contract we call or instantiate on its
+/// behalf, given the `gas_limit` it asked for.
+///
+/// The callee's gas comes out of what the contract has left after paying for the extension call,
+/// and a limit of zero hands it all of that, just like `seal_call()` does. A limit above what's
+/// left is capped rather than trapping the contract.
+///
+/// Whatever we do once the callee returns, like copying out its output, is paid for from what the
+/// callee left over. A callee which used it all up leaves the contract to run out of gas there, the
+/// same as it would anywhere else.
+fn callee_gas_limit<T: Config, E: Ext<T = T>>(
+    env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    gas_limit: Weight,
+) -> Result<Weight, DispatchError>
+where
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    let available = env.ext().gas_meter().gas_left();
+    match gas_limit {
+        // `Ext` would take zero to mean all of the gas, which is none.
+        _ if available == 0 => Err(pallet_contracts::Error::<T>::OutOfGas.into()),
+        0 => Ok(available),
+        limit => Ok(limit.min(available)),
+    }
+}
+
+/// The status code for an error `Ext::call()` or `Ext::instantiate()` ran into, if it's one
+/// contracts can reasonably do something about.
+///
+/// Running out of gas, be it the contract or the callee, keeps trapping the contract. So does
+/// anything else we don't know about.
+fn call_status<T: pallet_contracts::Config>(error: DispatchError) -> Option<u32> {
+    use pallet_contracts::Error;
+
+    let status = match error {
+        e if e == Error::<T>::ContractNotFound.into() => status::NOT_A_CONTRACT,
+        e if e == Error::<T>::ContractTrapped.into() => status::CALLEE_TRAPPED,
+        e if e == Error::<T>::TransferFailed.into() => status::TRANSFER_FAILED,
+        e if e == Error::<T>::ReentranceDenied.into() => status::NOT_PERMITTED,
+        e if e == Error::<T>::CodeNotFound.into() => status::UNKNOWN_CODE_HASH,
+        e if e == Error::<T>::DuplicateContract.into() => status::CONTRACT_EXISTS,
+        _ => return None,
+    };
+
+    Some(status)
+}
@@ -0,0 +1,89 @@
+//! Functions which let contracts sitting on the Council, our instance of `pallet_collective`,
+//! propose motions to it. This is how a sub-DAO would bring matters before the wider DAO.
+
+use super::{
+    runtime_calls::dispatch_as,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    ProposalIndex,
+    FUNC_PROPOSE_APPROVAL,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::{
+    traits::Hash,
+    DispatchError,
+};
+
+pub(super) struct CouncilFuncs;
+
+impl HandleFunc for CouncilFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_PROPOSE_APPROVAL];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `propose_approval()`
+            FUNC_PROPOSE_APPROVAL => {
+                let (_, threshold, proposal_id): (bool, u32, ProposalIndex) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::propose_approval())?;
+
+                // Contracts can't encode our runtime's calls, so rather than taking any motion we
+                // put together the one they're after ourselves: approving a treasury proposal.
+                let proposal =
+                    crate::Call::Treasury(pallet_treasury::Call::approve_proposal { proposal_id });
+                let length_bound = proposal.encoded_size() as u32;
+                // The Collective pallet identifies motions by the hash of their call, which is how
+                // members get to vote on them.
+                let hash = <T as SysConfig>::Hashing::hash_of(&proposal);
+                let call = crate::Call::Council(pallet_collective::Call::propose {
+                    threshold,
+                    proposal: crate::Box::new(proposal),
+                    length_bound,
+                });
+
+                // It's the contract which sits on the Council.
+                let contract = env.ext().address().clone();
+                let result = dispatch_as(env, contract, T::contracts_call(call))?;
+                if let RetVal::Converging(status::SUCCESS) = result {
+                    env.write(&hash.encode(), false, None)?;
+                }
+                Ok(result)
+            }
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        // Just like with `dispatch()`, the Collective pallet's call is charged for on top of this.
+        let weight = match func_id {
+            FUNC_PROPOSE_APPROVAL => W::<T>::propose_approval(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
@@ -0,0 +1,111 @@
+//! Functions for counters which several contracts can share, without having to trust each other.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_COUNTER_CREATE,
+    FUNC_COUNTER_INCREMENT,
+    FUNC_COUNTER_SET_ACCESS,
+    FUNC_COUNTER_VALUE,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct CounterFuncs;
+
+impl HandleFunc for CounterFuncs {
+    const FUNCS: &'static [u32] = &[
+        FUNC_COUNTER_CREATE,
+        FUNC_COUNTER_INCREMENT,
+        FUNC_COUNTER_VALUE,
+        FUNC_COUNTER_SET_ACCESS,
+    ];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `counter_create()`
+            FUNC_COUNTER_CREATE => {
+                let (_, name): (bool, crate::Vec<u8>) =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::counter_create)?;
+
+                // The contract creating the counter becomes its admin.
+                let contract = env.ext().address().clone();
+                pallet_template::Pallet::<T>::create_counter(&contract, name)?;
+            }
+            // Corresponds to `counter_increment()`
+            FUNC_COUNTER_INCREMENT => {
+                let (_, name): (bool, crate::Vec<u8>) =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::counter_increment)?;
+
+                // The runtime is what keeps the counter's members from handing out the same
+                // value twice, none of them need to trust the others.
+                let contract = env.ext().address().clone();
+                let value = pallet_template::Pallet::<T>::increment_counter(&contract, name)?;
+                env.write(&value.encode(), false, None)?;
+            }
+            // Corresponds to `counter_value()`
+            FUNC_COUNTER_VALUE => {
+                let name: crate::Vec<u8> =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::counter_value)?;
+
+                let value = pallet_template::Pallet::<T>::counter_value(name);
+                env.write(&value.encode(), false, None)?;
+            }
+            // Corresponds to `counter_set_access()`
+            FUNC_COUNTER_SET_ACCESS => {
+                let (_, name, who, allowed): (
+                    bool,
+                    crate::Vec<u8>,
+                    <T as SysConfig>::AccountId,
+                    bool,
+                ) = charge_and_read(env, ExtensionWeightInfo::<T>::counter_set_access)?;
+
+                // Only the contract which created the counter gets to do this.
+                let contract = env.ext().address().clone();
+                pallet_template::Pallet::<T>::set_counter_access(&contract, name, who, allowed)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_COUNTER_CREATE => W::<T>::counter_create(input_len),
+            FUNC_COUNTER_INCREMENT => W::<T>::counter_increment(input_len),
+            FUNC_COUNTER_VALUE => W::<T>::counter_value(input_len),
+            FUNC_COUNTER_SET_ACCESS => W::<T>::counter_set_access(input_len),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
@@ -0,0 +1,116 @@
+//! Functions which let contracts send messages to other chains over XCM.
+//!
+//! Contracts don't get to put together messages of their own. We build them here, so every one of
+//! them acts as the contract which sent it and goes where our runtime says.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_XCM_TRANSACT,
+    MAX_XCM_CALL_LEN,
+};
+use frame_support::{
+    traits::Get,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+use xcm::latest::prelude::*;
+
+pub(super) struct CrossChainFuncs;
+
+impl HandleFunc for CrossChainFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_XCM_TRANSACT];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        let code = match func_id {
+            // Corresponds to `xcm_transact()`
+            FUNC_XCM_TRANSACT => {
+                let (_, call, require_weight_at_most): (bool, crate::Vec<u8>, u64) =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::xcm_transact)?;
+
+                // We can't decode calls of other chains, so the most we can do is keep them from
+                // getting too large.
+                if call.len() as u32 > MAX_XCM_CALL_LEN {
+                    return Ok(RetVal::Converging(status::XCM_CALL_TOO_LONG))
+                }
+
+                let contract = <[u8; 32]>::try_from(env.ext().address().as_ref())
+                    .expect("our runtime's accounts are 32 bytes long; qed");
+                let message = transact_message(contract, call, require_weight_at_most);
+                match crate::XcmRouter::send_xcm(crate::XcmDestination::get(), message) {
+                    Ok(()) => status::SUCCESS,
+                    Err(e) => send_error_status(e),
+                }
+            }
+            _ => status::UNKNOWN_FUNCTION,
+        };
+
+        Ok(RetVal::Converging(code))
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        // Whatever the message costs at its destination is paid for over there.
+        let weight = match func_id {
+            FUNC_XCM_TRANSACT => W::<T>::xcm_transact(input_len),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// The message which has `call` dispatched at its destination, as the account of `contract` on
+/// our chain.
+///
+/// We don't pay for its execution, so the destination has to be willing to run it for free.
+pub(super) fn transact_message(
+    contract: [u8; 32],
+    call: crate::Vec<u8>,
+    require_weight_at_most: u64,
+) -> Xcm<()> {
+    Xcm(sp_std::vec![
+        // Otherwise the destination would see our chain as a whole sending this.
+        DescendOrigin(X1(AccountId32 {
+            network: NetworkId::Any,
+            id: contract,
+        })),
+        Transact {
+            origin_type: OriginKind::SovereignAccount,
+            require_weight_at_most,
+            call: call.into(),
+        },
+    ])
+}
+
+/// The status code for a message which couldn't be sent.
+fn send_error_status(error: SendError) -> u32 {
+    match error {
+        SendError::CannotReachDestination(..)
+        | SendError::Unroutable
+        | SendError::DestinationUnsupported => status::XCM_UNROUTABLE,
+        SendError::Transport(_) | SendError::ExceedsMaxMessageSize => status::XCM_SEND_FAILED,
+    }
+}
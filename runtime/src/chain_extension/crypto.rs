@@ -0,0 +1,119 @@
+//! Functions for randomness and signature verification, which contracts can't do (cheaply) on
+//! their own.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_RANDOM,
+    FUNC_VERIFY_ECDSA,
+    FUNC_VERIFY_SR25519,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::Randomness,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_core::{
+    ecdsa,
+    sr25519,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct CryptoFuncs;
+
+impl HandleFunc for CryptoFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_RANDOM, FUNC_VERIFY_SR25519, FUNC_VERIFY_ECDSA];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `random()`
+            FUNC_RANDOM => {
+                // The subject can be of any length, and it gets hashed along with the random
+                // material.
+                let subject: crate::Vec<u8> =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::random)?;
+
+                // We leave out the block number the randomness was determined at, the contract
+                // can't do much with it anyways.
+                let (random, _) = <T as pallet_contracts::Config>::Randomness::random(&subject);
+                env.write(&random.encode(), false, None)?;
+                Ok(RetVal::Converging(status::SUCCESS))
+            }
+            // Corresponds to `verify_sr25519()`
+            FUNC_VERIFY_SR25519 => {
+                // Verifying involves hashing the message, so longer messages cost more.
+                //
+                // Tuples get decoded one element after the other, just like they were encoded on
+                // the ink! side.
+                let (signature, message, public): ([u8; 64], crate::Vec<u8>, [u8; 32]) =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::verify_sr25519)?;
+
+                let valid = sp_io::crypto::sr25519_verify(
+                    &sr25519::Signature::from_raw(signature),
+                    &message,
+                    &sr25519::Public::from_raw(public),
+                );
+                Ok(signature_status(valid))
+            }
+            // Corresponds to `verify_ecdsa()`
+            FUNC_VERIFY_ECDSA => {
+                let (signature, message, public): ([u8; 65], crate::Vec<u8>, [u8; 33]) =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::verify_ecdsa)?;
+
+                // This hashes the message with BLAKE2-256 before checking the signature, which is
+                // how Substrate's own ecdsa keys sign things.
+                let valid = sp_io::crypto::ecdsa_verify(
+                    &ecdsa::Signature::from_raw(signature),
+                    &message,
+                    &ecdsa::Public::from_raw(public),
+                );
+                Ok(signature_status(valid))
+            }
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_RANDOM => W::<T>::random(input_len),
+            FUNC_VERIFY_SR25519 => W::<T>::verify_sr25519(input_len),
+            FUNC_VERIFY_ECDSA => W::<T>::verify_ecdsa(input_len),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// The status code for a signature which did, or didn't, check out.
+fn signature_status(valid: bool) -> RetVal {
+    if valid {
+        RetVal::Converging(status::SUCCESS)
+    } else {
+        RetVal::Converging(status::INVALID_SIGNATURE)
+    }
+}
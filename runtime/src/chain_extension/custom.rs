@@ -0,0 +1,239 @@
+//! Functions which take our `Custom` type as their input.
+//!
+//! `custom_type_with_result()` and `checked_custom_type()` can be handed a `Custom` of any length,
+//! so they have to decode it with `read_as_unbounded()`. `bounded_custom_type()` only takes ones
+//! up to [`MAX_BOUNDED_CUSTOM_LEN`], which lets it use `read_as()` instead.
+//!
+//! `rich_custom_type()` takes a [`RichCustom`], which wraps a `Custom` along with a few more
+//! involved types to show that those make it across just as well.
+
+use super::{
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    type_descriptor,
+    Custom,
+    RichCustom,
+    SchemaHash,
+    FUNC_BOUNDED_CUSTOM_TYPE,
+    FUNC_CHECKED_CUSTOM_TYPE,
+    FUNC_CUSTOM_TYPE_WITH_RESULT,
+    FUNC_RICH_CUSTOM_TYPE,
+    MAX_BOUNDED_CUSTOM_LEN,
+};
+use frame_support::{
+    pallet_prelude::{
+        ConstU32,
+        Decode,
+        MaxEncodedLen,
+    },
+    traits::Get,
+    weights::Weight,
+    BoundedVec,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct CustomFuncs;
+
+impl HandleFunc for CustomFuncs {
+    const FUNCS: &'static [u32] = &[
+        FUNC_CUSTOM_TYPE_WITH_RESULT,
+        FUNC_CHECKED_CUSTOM_TYPE,
+        FUNC_BOUNDED_CUSTOM_TYPE,
+        FUNC_RICH_CUSTOM_TYPE,
+    ];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `custom_type_with_result()`
+            FUNC_CUSTOM_TYPE_WITH_RESULT => {
+                // Since our type interally uses a `Vec` we don't know what the size of it will be
+                // ahead of time, see `charge_and_read()` for what that means for us. We do the
+                // same thing here by hand, since we need to hang on to what we charged.
+                let len = env.in_len();
+                let weight = ExtensionWeightInfo::<T>::custom_type_with_result(len);
+                let charged = env.charge_weight(weight)?;
+                let (_, custom): (bool, Custom) = env.read_as_unbounded(len)?;
+
+                let caller = env.ext().caller().clone();
+                let contract = env.ext().address().clone();
+                if !store_custom_len::<T>(contract, caller, custom) {
+                    // We charged as if we'd be writing to storage, which we didn't end up doing.
+                    env.adjust_weight(charged, without_write::<T>(weight));
+                    return Ok(custom_len_rejected())
+                }
+                Ok(RetVal::Converging(status::SUCCESS))
+            }
+            // Corresponds to `checked_custom_type()`
+            FUNC_CHECKED_CUSTOM_TYPE => {
+                let len = env.in_len();
+                let weight = ExtensionWeightInfo::<T>::checked_custom_type(len);
+                let charged = env.charge_weight(weight)?;
+
+                // We can't decode the whole input in one go: if the schemas don't match then the
+                // `Custom` part may not decode at all, or worse, decode into garbage.
+                let input = env.read(len)?;
+                let mut input = &input[..];
+                bool::decode(&mut input).map_err(|_| pallet_contracts::Error::<T>::DecodingFailed)?;
+                let schema = SchemaHash::decode(&mut input)
+                    .map_err(|_| pallet_contracts::Error::<T>::DecodingFailed)?;
+
+                let expected = pallet_template::Pallet::<T>::schema(FUNC_CHECKED_CUSTOM_TYPE)
+                    .map(|descriptor| descriptor.hash)
+                    .unwrap_or_else(|| {
+                        sp_io::hashing::blake2_256(&type_descriptor::<Custom>())
+                    });
+                if schema != expected {
+                    env.adjust_weight(charged, without_write::<T>(weight));
+                    return Ok(RetVal::Converging(status::SCHEMA_MISMATCH))
+                }
+
+                let custom = Custom::decode(&mut input)
+                    .map_err(|_| pallet_contracts::Error::<T>::DecodingFailed)?;
+                let caller = env.ext().caller().clone();
+                let contract = env.ext().address().clone();
+                if !store_custom_len::<T>(contract, caller, custom) {
+                    env.adjust_weight(charged, without_write::<T>(weight));
+                    return Ok(custom_len_rejected())
+                }
+                Ok(RetVal::Converging(status::SUCCESS))
+            }
+            // Corresponds to `bounded_custom_type()`
+            FUNC_BOUNDED_CUSTOM_TYPE => {
+                // Since `BoundedCustom` knows how long it can get, we can charge for the longest
+                // one up front and leave it to `read_as()` to figure out how much to read. Inputs
+                // which are too long don't decode, in which case the contract gets trapped.
+                let weight = ExtensionWeightInfo::<T>::bounded_custom_type();
+                let charged = env.charge_weight(weight)?;
+                let (_, custom): (bool, BoundedCustom) = env.read_as()?;
+
+                let caller = env.ext().caller().clone();
+                let contract = env.ext().address().clone();
+                if !store_custom_len::<T>(contract, caller, custom.into()) {
+                    env.adjust_weight(charged, without_write::<T>(weight));
+                    return Ok(custom_len_rejected())
+                }
+                Ok(RetVal::Converging(status::SUCCESS))
+            }
+            // Corresponds to `rich_custom_type()`
+            FUNC_RICH_CUSTOM_TYPE => {
+                // The payload is still a `Vec` underneath, so this goes just like
+                // `custom_type_with_result()`. The enum, `Option` and nested struct around it
+                // don't need any special treatment.
+                let len = env.in_len();
+                let weight = ExtensionWeightInfo::<T>::rich_custom_type(len);
+                let charged = env.charge_weight(weight)?;
+                let (_, custom): (bool, RichCustom<T::AccountId>) = env.read_as_unbounded(len)?;
+
+                let caller = env.ext().caller().clone();
+                if custom.only_for.map_or(false, |account| account != caller) {
+                    env.adjust_weight(charged, without_write::<T>(weight));
+                    return Ok(RetVal::Converging(status::NOT_PERMITTED))
+                }
+
+                let value = match custom.measure.apply(&custom.payload.inner) {
+                    Some(value) => value,
+                    None => {
+                        env.adjust_weight(charged, without_write::<T>(weight));
+                        return Ok(RetVal::Converging(status::CUSTOM_CALL_FAILED))
+                    }
+                };
+                let contract = env.ext().address().clone();
+                pallet_template::Pallet::<T>::store_something_for_contract(contract, caller, value);
+                Ok(RetVal::Converging(status::SUCCESS))
+            }
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_CUSTOM_TYPE_WITH_RESULT => W::<T>::custom_type_with_result(input_len),
+            FUNC_CHECKED_CUSTOM_TYPE => W::<T>::checked_custom_type(input_len),
+            FUNC_BOUNDED_CUSTOM_TYPE => W::<T>::bounded_custom_type(),
+            FUNC_RICH_CUSTOM_TYPE => W::<T>::rich_custom_type(input_len),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// A `Custom` which is at most [`MAX_BOUNDED_CUSTOM_LEN`] bytes long.
+///
+/// A `BoundedVec` is encoded just like a `Vec`, so contracts send us a plain `Custom`. On our side
+/// though we get a `MaxEncodedLen` implementation out of it, which is what `read_as()` needs.
+#[derive(Decode, MaxEncodedLen)]
+pub(super) struct BoundedCustom {
+    pub inner: BoundedVec<u8, ConstU32<MAX_BOUNDED_CUSTOM_LEN>>,
+}
+
+impl From<BoundedCustom> for Custom {
+    fn from(custom: BoundedCustom) -> Self {
+        Custom {
+            inner: custom.inner.into_inner(),
+        }
+    }
+}
+
+/// Stores the length of `custom` on behalf of `caller`, who went through `contract`, as long as
+/// that length is a power of two.
+///
+/// This is shared between all of our functions. Returns whether anything got stored.
+fn store_custom_len<T: pallet_template::Config>(
+    contract: T::AccountId,
+    caller: T::AccountId,
+    custom: Custom,
+) -> bool {
+    if !custom.inner.len().is_power_of_two() {
+        return false
+    }
+
+    // Here we don't do anything useful, we just store the length of our vector in storage.
+    pallet_template::Pallet::<T>::store_something_for_contract(
+        contract,
+        caller,
+        custom.inner.len() as u32,
+    );
+
+    true
+}
+
+/// What we hand back when `store_custom_len()` turned the value down.
+fn custom_len_rejected() -> RetVal {
+    // Remember that we have a `FromStatusCode` implementation in our contract which will know to
+    // to handle this `RetVal` correctly.
+    //
+    // In our case this maps to our `ExtensionError::CustomCallFailed` error.
+    RetVal::Converging(status::CUSTOM_CALL_FAILED)
+}
+
+/// What `weight` comes down to for calls which bail out before writing to storage.
+///
+/// All of our functions charge for a single write, which is by far the most expensive part of
+/// them. Everything else, i.e. decoding the input, has already happened by the time we know.
+fn without_write<T: frame_system::Config>(weight: Weight) -> Weight {
+    weight.saturating_sub(T::DbWeight::get().writes(1))
+}
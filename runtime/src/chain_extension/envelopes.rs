@@ -0,0 +1,110 @@
+//! Functions for storing encrypted envelopes which only their recipient can open, until they
+//! expire.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_ENVELOPE,
+    FUNC_STORE_ENVELOPE,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::Get,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct EnvelopeFuncs;
+
+impl HandleFunc for EnvelopeFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_STORE_ENVELOPE, FUNC_ENVELOPE];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `store_envelope()`
+            FUNC_STORE_ENVELOPE => {
+                let (_, public_key, expires_at, ciphertext): (
+                    bool,
+                    [u8; 32],
+                    u32,
+                    crate::Vec<u8>,
+                ) = charge_and_read(env, ExtensionWeightInfo::<T>::store_envelope)?;
+                let expires_at = <T as SysConfig>::BlockNumber::from(expires_at);
+                if expires_at <= frame_system::Pallet::<T>::block_number() {
+                    return Ok(RetVal::Converging(status::ENVELOPE_EXPIRED))
+                }
+
+                let owner = env.ext().address().clone();
+                let id = pallet_template::Pallet::<T>::store_envelope(
+                    &owner,
+                    public_key,
+                    expires_at,
+                    ciphertext,
+                )?;
+
+                env.write(&id.encode(), false, None)?;
+            }
+            // Corresponds to `envelope()`
+            FUNC_ENVELOPE => {
+                // Just like with `read_raw()` we don't know how much we'll be handing back, so we
+                // charge for the worst case and refund what we didn't need.
+                let max_len = <T as pallet_template::Config>::MaxCiphertextLen::get();
+                let charged = env.charge_weight(ExtensionWeightInfo::<T>::envelope(max_len))?;
+
+                let id: u64 = env.read_as()?;
+                let envelope = pallet_template::Pallet::<T>::envelope(id);
+
+                let len = envelope.as_ref().map_or(0, |e| e.ciphertext.len() as u32);
+                env.adjust_weight(charged, ExtensionWeightInfo::<T>::envelope(len));
+
+                match envelope {
+                    Some(envelope)
+                        if envelope.expires_at <= frame_system::Pallet::<T>::block_number() =>
+                    {
+                        return Ok(RetVal::Converging(status::ENVELOPE_EXPIRED))
+                    }
+                    envelope => env.write(&envelope.encode(), false, None)?,
+                }
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_STORE_ENVELOPE => W::<T>::store_envelope(input_len),
+            FUNC_ENVELOPE => {
+                W::<T>::envelope(<T as pallet_template::Config>::MaxCiphertextLen::get())
+            }
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
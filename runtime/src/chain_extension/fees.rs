@@ -0,0 +1,119 @@
+//! Functions which tell contracts what `pallet_transaction_payment` charges for calls and weight,
+//! e.g. so that contracts which sponsor their users' transactions know what they're in for.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FeeEstimate,
+    FUNC_ESTIMATE_FEE,
+    FUNC_WEIGHT_TO_FEE,
+};
+use codec::DecodeLimit;
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::{
+        GetDispatchInfo,
+        Weight,
+        WeightToFee,
+    },
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct FeeFuncs;
+
+impl HandleFunc for FeeFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_ESTIMATE_FEE, FUNC_WEIGHT_TO_FEE];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `estimate_fee()`
+            FUNC_ESTIMATE_FEE => {
+                let encoded: crate::Vec<u8> =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::estimate_fee)?;
+
+                // Unlike with `dispatch()` we don't filter anything, knowing what a call costs
+                // doesn't let anyone make it.
+                let call = match crate::Call::decode_all_with_depth_limit(
+                    sp_api::MAX_EXTRINSIC_DEPTH,
+                    &mut &encoded[..],
+                ) {
+                    Ok(call) => call,
+                    Err(_) => return Ok(RetVal::Converging(status::SCHEMA_MISMATCH)),
+                };
+
+                // We only know how long the call is, not the extrinsic it'd end up in. The
+                // signature and signed extensions on top of it only add to the length fee.
+                let info = call.get_dispatch_info();
+                let details =
+                    crate::TransactionPayment::compute_fee_details(encoded.len() as u32, &info, 0);
+                let estimate = match details.inclusion_fee {
+                    Some(fee) => {
+                        FeeEstimate {
+                            weight: info.weight,
+                            base_fee: fee.base_fee,
+                            len_fee: fee.len_fee,
+                            adjusted_weight_fee: fee.adjusted_weight_fee,
+                        }
+                    }
+                    // Calls which don't pay fees come for free.
+                    None => {
+                        FeeEstimate {
+                            weight: info.weight,
+                            ..Default::default()
+                        }
+                    }
+                };
+                env.write(&estimate.encode(), false, None)?;
+            }
+            // Corresponds to `weight_to_fee()`
+            FUNC_WEIGHT_TO_FEE => {
+                // Our weights don't track proof size yet, so there's nothing to charge for it.
+                let (ref_time, _proof_size): (Weight, u64) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::weight_to_fee())?;
+
+                // Unlike `estimate_fee()` this leaves out the fee multiplier, so it's what the
+                // weight would cost in a block which isn't congested.
+                type RuntimeWeightToFee =
+                    <crate::Runtime as pallet_transaction_payment::Config>::WeightToFee;
+                let fee = RuntimeWeightToFee::weight_to_fee(&ref_time);
+                env.write(&fee.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_ESTIMATE_FEE => W::<T>::estimate_fee(input_len),
+            FUNC_WEIGHT_TO_FEE => W::<T>::weight_to_fee(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
@@ -0,0 +1,189 @@
+//! Compares how much gas it takes to get some representative piece of work done through our
+//! chain extension versus doing it entirely inside of a contract.
+//!
+//! Both sides are hand-written fixtures (see `fixtures/`) which do as little as possible besides
+//! the task at hand, so the difference between the two is down to the extension.
+//!
+//! To get a report of the numbers run:
+//!
+//! ```text
+//! cargo test -p contracts-node-runtime gas_golf::report -- --ignored --nocapture
+//! ```
+//!
+//! Note that hashing is only measured on the contract side. ink!'s `hash_bytes()` already calls
+//! into the runtime through a host function, so there's nothing an extension could do better.
+
+use super::{
+    tests::{
+        deploy_fixture,
+        GAS_LIMIT,
+    },
+    *,
+};
+use crate::{
+    tests::{
+        new_test_ext,
+        ALICE,
+    },
+    AccountId,
+    Contracts,
+    Template,
+};
+use chain_extension_primitives::{
+    FUNC_STORE_BATCH,
+    FUNC_WRITE_TO_STORAGE,
+};
+use frame_support::pallet_prelude::Encode;
+
+// The tasks `fixtures/pure.wat` knows how to do.
+const TASK_STORE_VALUE: u32 = 1;
+const TASK_STORE_BATCH: u32 = 2;
+const TASK_HASH: u32 = 3;
+
+/// Something we'd like to get done, along with how to get it done on either side.
+struct Task {
+    name: String,
+    /// The `func_id` and input for `fixtures/chain_extension.wat`, if the extension can do this.
+    extension: Option<(u32, Vec<u8>)>,
+    /// The task and arguments for `fixtures/pure.wat`.
+    pure: (u32, Vec<u8>),
+}
+
+fn tasks() -> Vec<Task> {
+    let mut tasks = vec![Task {
+        name: "store a value".into(),
+        extension: Some((FUNC_WRITE_TO_STORAGE, (false, 42u32).encode())),
+        pure: (TASK_STORE_VALUE, 42u32.encode()),
+    }];
+
+    for n in [1, 8, chain_extension_primitives::MAX_STORE_BATCH_LEN] {
+        let values: Vec<(u32, u32)> = (0..n).map(|i| (i, i)).collect();
+        tasks.push(Task {
+            name: format!("store {} values", n),
+            extension: Some((FUNC_STORE_BATCH, (false, &values).encode())),
+            // The fixture doesn't bother with a length prefix, it just reads pairs until it runs
+            // out of input.
+            pure: (TASK_STORE_BATCH, values.iter().flat_map(Encode::encode).collect()),
+        });
+    }
+
+    for len in [32, 1024 - 8] {
+        tasks.push(Task {
+            name: format!("hash {} bytes", len),
+            extension: None,
+            pure: (TASK_HASH, vec![0xAB; len]),
+        });
+    }
+
+    tasks
+}
+
+// Calls `contract` with the given selector (either a `func_id` or a task) and arguments, returning
+// the gas used.
+fn gas_used(contract: &AccountId, selector: u32, args: &[u8]) -> u64 {
+    let mut data = selector.encode();
+    data.extend_from_slice(args);
+
+    let result = Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false);
+    result.result.expect("contract call should succeed");
+    result.gas_consumed
+}
+
+// Runs `task` on both sides, each in a fresh externalities so neither benefits from the other
+// having already touched storage.
+fn measure(task: &Task) -> (Option<u64>, u64) {
+    let extension = task.extension.as_ref().map(|(func_id, input)| {
+        new_test_ext().execute_with(|| {
+            gas_used(&deploy_fixture("chain_extension"), *func_id, input)
+        })
+    });
+    let pure = new_test_ext().execute_with(|| {
+        let (task, args) = &task.pure;
+        gas_used(&deploy_fixture("pure"), *task, args)
+    });
+
+    (extension, pure)
+}
+
+#[test]
+fn both_sides_do_the_same_work() {
+    new_test_ext().execute_with(|| {
+        let extension = deploy_fixture("chain_extension");
+        let pure = deploy_fixture("pure");
+
+        gas_used(&extension, FUNC_WRITE_TO_STORAGE, &(false, 42u32).encode());
+        gas_used(&pure, TASK_STORE_VALUE, &42u32.encode());
+
+        let mut key = [0u8; 32];
+        assert_eq!(Template::something(), Some(42));
+        assert_eq!(
+            Contracts::get_storage(pure.clone(), key),
+            Ok(Some(42u32.encode()))
+        );
+
+        let values = vec![(1u32, 10u32), (2, 20)];
+        gas_used(&extension, FUNC_STORE_BATCH, &(false, &values).encode());
+        let pairs: Vec<u8> = values.iter().flat_map(Encode::encode).collect();
+        gas_used(&pure, TASK_STORE_BATCH, &pairs);
+
+        for (k, v) in values {
+            key[..4].copy_from_slice(&k.encode());
+            assert_eq!(Template::contract_value(&extension, k), Some(v));
+            assert_eq!(Contracts::get_storage(pure.clone(), key), Ok(Some(v.encode())));
+        }
+    });
+}
+
+#[test]
+fn every_task_can_be_measured() {
+    for task in tasks() {
+        let (extension, pure) = measure(&task);
+
+        assert!(pure > 0, "{}", task.name);
+        if task.extension.is_some() {
+            assert!(extension.unwrap_or_default() > 0, "{}", task.name);
+        }
+    }
+}
+
+#[test]
+fn batch_cost_grows_with_its_size() {
+    let cost = |n: u32| {
+        let values: Vec<(u32, u32)> = (0..n).map(|i| (i, i)).collect();
+        new_test_ext().execute_with(|| {
+            gas_used(
+                &deploy_fixture("chain_extension"),
+                FUNC_STORE_BATCH,
+                &(false, values).encode(),
+            )
+        })
+    };
+
+    // Otherwise contracts could sneak a large batch in for the price of a small one.
+    assert!(cost(1) < cost(2));
+    assert!(cost(2) < cost(chain_extension_primitives::MAX_STORE_BATCH_LEN));
+}
+
+#[test]
+#[ignore = "only prints a report, run it with --nocapture"]
+fn report() {
+    println!();
+    println!("| task | extension | pure | extension / pure |");
+    println!("|------|----------:|-----:|-----------------:|");
+
+    for task in tasks() {
+        let (extension, pure) = measure(&task);
+        match extension {
+            Some(extension) => {
+                println!(
+                    "| {} | {} | {} | {:.2} |",
+                    task.name,
+                    extension,
+                    pure,
+                    extension as f64 / pure as f64
+                )
+            }
+            None => println!("| {} | - | {} | - |", task.name, pure),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Functions which let contracts take part in governance, e.g. DAOs voting with the funds they
+//! hold.
+
+use super::{
+    runtime_calls::dispatch_as,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    Conviction,
+    FUNC_VOTE,
+};
+use frame_support::weights::Weight;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use pallet_democracy::{
+    AccountVote,
+    Vote,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct GovernanceFuncs;
+
+impl HandleFunc for GovernanceFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_VOTE];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        let call = match func_id {
+            // Corresponds to `vote()`
+            FUNC_VOTE => {
+                let (_, ref_index, aye, conviction, balance): (
+                    bool,
+                    pallet_democracy::ReferendumIndex,
+                    bool,
+                    Conviction,
+                    crate::Balance,
+                ) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::vote())?;
+
+                // Voting again on the same referendum replaces the earlier vote.
+                crate::Call::Democracy(pallet_democracy::Call::vote {
+                    ref_index,
+                    vote: AccountVote::Standard {
+                        vote: Vote {
+                            aye,
+                            conviction: democracy_conviction(conviction),
+                        },
+                        balance,
+                    },
+                })
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        };
+
+        // The contract votes with its own balance, which stays locked up for as long as the
+        // conviction says once the referendum is over.
+        let contract = env.ext().address().clone();
+        dispatch_as(env, contract, T::contracts_call(call))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        // Just like with `dispatch()`, the Democracy pallet's call is charged for on top of this.
+        let weight = match func_id {
+            FUNC_VOTE => W::<T>::vote(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// Our `Conviction` as the Democracy pallet's, which encodes the same way.
+pub(super) fn democracy_conviction(conviction: Conviction) -> pallet_democracy::Conviction {
+    match conviction {
+        Conviction::None => pallet_democracy::Conviction::None,
+        Conviction::Locked1x => pallet_democracy::Conviction::Locked1x,
+        Conviction::Locked2x => pallet_democracy::Conviction::Locked2x,
+        Conviction::Locked3x => pallet_democracy::Conviction::Locked3x,
+        Conviction::Locked4x => pallet_democracy::Conviction::Locked4x,
+        Conviction::Locked5x => pallet_democracy::Conviction::Locked5x,
+        Conviction::Locked6x => pallet_democracy::Conviction::Locked6x,
+    }
+}
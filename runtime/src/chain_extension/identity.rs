@@ -0,0 +1,131 @@
+//! Functions which tell contracts about the identities accounts have set with `pallet_identity`.
+
+use super::{
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    BoundedIdentityInfo,
+    IdentityJudgement,
+    FUNC_IDENTITY_OF,
+    MAX_IDENTITY_DISPLAY_LEN,
+};
+use frame_support::{
+    pallet_prelude::{
+        ConstU32,
+        Encode,
+    },
+    traits::Currency,
+    weights::Weight,
+    BoundedVec,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use pallet_identity::{
+    Data,
+    Judgement,
+    RegistrarIndex,
+};
+use sp_runtime::DispatchError;
+
+pub(super) type IdentityBalanceOf<T> =
+    <<T as pallet_identity::Config>::Currency as Currency<<T as SysConfig>::AccountId>>::Balance;
+
+pub(super) type RegistrationOf<T> = pallet_identity::Registration<
+    IdentityBalanceOf<T>,
+    <T as pallet_identity::Config>::MaxRegistrars,
+    <T as pallet_identity::Config>::MaxAdditionalFields,
+>;
+
+/// What we hand back for an identity. `pallet_identity` keeps raw display names in a
+/// `BoundedVec` of exactly this size, so they fit without having to be cut short.
+pub(super) type IdentityInfoOf =
+    BoundedIdentityInfo<BoundedVec<u8, ConstU32<MAX_IDENTITY_DISPLAY_LEN>>>;
+
+pub(super) struct IdentityFuncs;
+
+impl HandleFunc for IdentityFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_IDENTITY_OF];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `identity_of()`
+            FUNC_IDENTITY_OF => {
+                let account: <T as SysConfig>::AccountId = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::identity_of())?;
+
+                // Decoding the registration is what we charge for, its additional fields and
+                // judgements are bounded. What we hand back is bounded too, so unlike `blob()`
+                // there's no need to charge for the output by the byte.
+                let info = pallet_identity::Pallet::<T>::identity(account).map(|registration| {
+                    IdentityInfoOf {
+                        display: display_name(registration.info.display),
+                        judgement: judgement_of::<T>(&registration.judgements),
+                    }
+                });
+                env.write(&info.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_IDENTITY_OF => W::<T>::identity_of(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// The display name in `data`, if it holds one rather than just its hash.
+pub(super) fn display_name(data: Data) -> BoundedVec<u8, ConstU32<MAX_IDENTITY_DISPLAY_LEN>> {
+    match data {
+        Data::Raw(display) => display,
+        _ => Default::default(),
+    }
+}
+
+/// Boils the judgements of every registrar which looked at an identity down to a single one.
+///
+/// Registrars which say an identity is erroneous get the final word, since they only do so when
+/// someone tried to pass off a fake one. Otherwise we go with the most trustworthy judgement.
+pub(super) fn judgement_of<T: Config>(
+    judgements: &[(RegistrarIndex, Judgement<IdentityBalanceOf<T>>)],
+) -> IdentityJudgement {
+    let mut best = None;
+    for (_, judgement) in judgements {
+        let judgement = match judgement {
+            Judgement::Unknown => IdentityJudgement::Unknown,
+            Judgement::FeePaid(_) => IdentityJudgement::FeePaid,
+            Judgement::Reasonable => IdentityJudgement::Reasonable,
+            Judgement::KnownGood => IdentityJudgement::KnownGood,
+            Judgement::OutOfDate => IdentityJudgement::OutOfDate,
+            Judgement::LowQuality => IdentityJudgement::LowQuality,
+            Judgement::Erroneous => return IdentityJudgement::Erroneous,
+        };
+        best = best.max(Some(judgement));
+    }
+    best.unwrap_or(IdentityJudgement::Unknown)
+}
@@ -0,0 +1,199 @@
+//! Functions which tell contracts about the extension itself, rather than doing anything.
+
+use super::{
+    quote,
+    weights::WeightInfo,
+    BalanceOf,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    capabilities,
+    more_capabilities,
+    status,
+    ChainConstants,
+    FUNC_CALL_COUNTS,
+    FUNC_CAPABILITIES,
+    FUNC_CHAIN_CONSTANTS,
+    FUNC_MORE_CAPABILITIES,
+    FUNC_QUOTE,
+    FUNC_VERSION,
+    MAIN_EXTENSION_VERSION,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::{
+        Currency,
+        Get,
+    },
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct IntrospectionFuncs;
+
+impl HandleFunc for IntrospectionFuncs {
+    const FUNCS: &'static [u32] = &[
+        FUNC_VERSION,
+        FUNC_CAPABILITIES,
+        FUNC_QUOTE,
+        FUNC_CALL_COUNTS,
+        FUNC_CHAIN_CONSTANTS,
+        FUNC_MORE_CAPABILITIES,
+    ];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `version()`
+            FUNC_VERSION => {
+                // Contracts check this before anything else, if it isn't what they were built
+                // against they can't rely on any of our other functions behaving as they expect.
+                env.charge_weight(ExtensionWeightInfo::<T>::version())?;
+                env.write(&MAIN_EXTENSION_VERSION.encode(), false, None)?;
+            }
+            // Corresponds to `capabilities()`
+            FUNC_CAPABILITIES => {
+                // There's no storage access here, we're just handing back a constant.
+                env.charge_weight(ExtensionWeightInfo::<T>::capabilities())?;
+
+                let capabilities = capabilities::WRITE_TO_STORAGE
+                    | capabilities::CUSTOM_TYPE_WITH_RESULT
+                    | capabilities::SCHEDULE_CALL
+                    | capabilities::CALLBACK_WEIGHTS
+                    | capabilities::WRITE_RAW
+                    | capabilities::CHECKED_CUSTOM_TYPE
+                    | capabilities::CONTRACT_VALUES
+                    | capabilities::READ_RAW
+                    | capabilities::ACCOUNT_VALUES
+                    | capabilities::TRANSFER_NATIVE
+                    | capabilities::QUOTE
+                    | capabilities::ENVELOPES
+                    | capabilities::ASSETS
+                    | capabilities::COMMIT_REVEAL
+                    | capabilities::NFTS
+                    | capabilities::RANDOMNESS
+                    | capabilities::COUNTERS
+                    | capabilities::SIGNATURES
+                    | capabilities::CALLBACK_RESERVATION
+                    | capabilities::CALLBACK_RECEIPTS
+                    | capabilities::CALLBACK_DEPOSIT
+                    | capabilities::BOUNDED_CUSTOM_TYPE
+                    | capabilities::RICH_CUSTOM_TYPE
+                    | capabilities::TRANSFER_NATIVE_COMPACT
+                    | capabilities::BLOB
+                    | capabilities::RESCHEDULE
+                    | capabilities::SCHEDULE_IN
+                    | capabilities::CALL_COUNTS
+                    | capabilities::READ_FROM_STORAGE
+                    | capabilities::CHAIN_CONSTANTS
+                    | capabilities::FREE_BALANCE
+                    | capabilities::ACCOUNT_NONCE;
+
+                // This is how we pass data back to the contract. It'll get decoded on the ink! side
+                // as the `Ok` value of the extension's return type.
+                env.write(&capabilities.encode(), false, None)?;
+            }
+            // Corresponds to `more_capabilities()`
+            FUNC_MORE_CAPABILITIES => {
+                // Just like `capabilities()`, this is a constant.
+                env.charge_weight(ExtensionWeightInfo::<T>::capabilities())?;
+
+                let capabilities = more_capabilities::CONTRACT_INFO
+                    | more_capabilities::CALL_CONTRACT
+                    | more_capabilities::INSTANTIATE_CONTRACT
+                    | more_capabilities::DISPATCH
+                    | more_capabilities::BATCH_WRITE
+                    | more_capabilities::MULTISIG
+                    | more_capabilities::PROXY
+                    | more_capabilities::IDENTITY
+                    | more_capabilities::GOVERNANCE
+                    | more_capabilities::STAKING
+                    | more_capabilities::TREASURY
+                    | more_capabilities::COUNCIL
+                    | more_capabilities::MEMBERSHIP
+                    | more_capabilities::XCM
+                    | more_capabilities::FEES
+                    | more_capabilities::WEIGHT_TO_FEE
+                    | more_capabilities::BLOCK_HASH
+                    | more_capabilities::BLOCK_AUTHOR
+                    | more_capabilities::SESSION;
+                env.write(&capabilities.encode(), false, None)?;
+            }
+            // Corresponds to `quote()`
+            FUNC_QUOTE => {
+                let (quoted_func_id, input_len): (u32, u32) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::quote())?;
+
+                match quote::<T>(quoted_func_id, input_len) {
+                    Some(weight) => env.write(&weight.encode(), false, None)?,
+                    None => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+                }
+            }
+            // Corresponds to `call_counts()`
+            FUNC_CALL_COUNTS => {
+                let counted_func_id: u32 = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::call_counts())?;
+
+                // Only functions we know about get counted, so there's nothing to hand back for
+                // anything else.
+                if quote::<T>(counted_func_id, 0).is_none() {
+                    return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION))
+                }
+                let counts = pallet_template::Pallet::<T>::extension_calls(counted_func_id);
+                env.write(&counts.encode(), false, None)?;
+            }
+            // Corresponds to `chain_constants()`
+            FUNC_CHAIN_CONSTANTS => {
+                env.charge_weight(ExtensionWeightInfo::<T>::chain_constants())?;
+                env.write(&chain_constants::<T>().encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_VERSION => W::<T>::version(),
+            FUNC_CAPABILITIES | FUNC_MORE_CAPABILITIES => W::<T>::capabilities(),
+            FUNC_QUOTE => W::<T>::quote(),
+            FUNC_CALL_COUNTS => W::<T>::call_counts(),
+            FUNC_CHAIN_CONSTANTS => W::<T>::chain_constants(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// What `chain_constants()` hands back. It's all configuration, so this doesn't touch storage.
+pub(super) fn chain_constants<T: Config>() -> ChainConstants<BalanceOf<T>> {
+    ChainConstants {
+        existential_deposit: <T as pallet_contracts::Config>::Currency::minimum_balance(),
+        ss58_prefix: <T as SysConfig>::SS58Prefix::get(),
+        // Unlike everything else this isn't part of any pallet's configuration, so it can only
+        // come from our own runtime.
+        token_decimals: crate::TOKEN_DECIMALS,
+        maximum_scheduler_weight: <T as pallet_scheduler::Config>::MaximumWeight::get(),
+    }
+}
@@ -0,0 +1,71 @@
+//! Functions which tell contracts about membership sets kept by `pallet_membership`. Unlike a list
+//! of members in a contract's own storage, these are managed through governance.
+
+use super::{
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_IS_MEMBER,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct MembershipFuncs;
+
+impl HandleFunc for MembershipFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_IS_MEMBER];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `is_member()`
+            FUNC_IS_MEMBER => {
+                let account: <T as SysConfig>::AccountId = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::is_member())?;
+
+                // The Membership pallet keeps its members sorted, and there's a bounded number of
+                // them. We charge for decoding as many as there can be.
+                let is_member =
+                    pallet_membership::Pallet::<T, pallet_membership::Instance1>::members()
+                        .binary_search(&account)
+                        .is_ok();
+                env.write(&is_member.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_IS_MEMBER => W::<T>::is_member(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
@@ -0,0 +1,113 @@
+//! Functions which let contracts take part in `pallet_multisig` operations, as one of the
+//! signatories.
+
+use super::{
+    charge_and_read,
+    runtime_calls::dispatch_as,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_APPROVE_AS_MULTI,
+    FUNC_AS_MULTI,
+};
+use frame_support::{
+    traits::WrapperKeepOpaque,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use pallet_multisig::Timepoint;
+use sp_runtime::DispatchError;
+
+pub(super) struct MultisigFuncs;
+
+impl HandleFunc for MultisigFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_AS_MULTI, FUNC_APPROVE_AS_MULTI];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        let call = match func_id {
+            // Corresponds to `as_multi()`
+            FUNC_AS_MULTI => {
+                let (_, threshold, other_signatories, maybe_timepoint, call, max_weight): (
+                    bool,
+                    u16,
+                    crate::Vec<crate::AccountId>,
+                    Option<Timepoint<crate::BlockNumber>>,
+                    crate::Vec<u8>,
+                    Weight,
+                ) = charge_and_read(env, ExtensionWeightInfo::<T>::as_multi)?;
+
+                // The Multisig pallet only decodes the call once the operation has all the
+                // approvals it needs. We don't have it keep the call around until then, whoever
+                // gives the last approval has to hand it over again.
+                crate::Call::Multisig(pallet_multisig::Call::as_multi {
+                    threshold,
+                    other_signatories,
+                    maybe_timepoint,
+                    call: WrapperKeepOpaque::from_encoded(call),
+                    store_call: false,
+                    max_weight,
+                })
+            }
+            // Corresponds to `approve_as_multi()`
+            FUNC_APPROVE_AS_MULTI => {
+                let (_, threshold, other_signatories, maybe_timepoint, call_hash, max_weight): (
+                    bool,
+                    u16,
+                    crate::Vec<crate::AccountId>,
+                    Option<Timepoint<crate::BlockNumber>>,
+                    [u8; 32],
+                    Weight,
+                ) = charge_and_read(env, |len| {
+                    // Every signatory takes up 32 bytes, so this is an upper bound on how many
+                    // there are.
+                    ExtensionWeightInfo::<T>::approve_as_multi(len / 32)
+                })?;
+
+                crate::Call::Multisig(pallet_multisig::Call::approve_as_multi {
+                    threshold,
+                    other_signatories,
+                    maybe_timepoint,
+                    call_hash,
+                    max_weight,
+                })
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        };
+
+        // Unlike with `dispatch()`, the contract is a signatory in its own right rather than
+        // approving on behalf of whoever called it. Any deposit comes out of its own balance.
+        let contract = env.ext().address().clone();
+        dispatch_as(env, contract, T::contracts_call(call))
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        // Just like with `dispatch()`, the Multisig pallet's call is charged for on top of this.
+        let weight = match func_id {
+            FUNC_AS_MULTI => W::<T>::as_multi(input_len),
+            FUNC_APPROVE_AS_MULTI => W::<T>::approve_as_multi(input_len / 32),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
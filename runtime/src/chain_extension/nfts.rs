@@ -0,0 +1,180 @@
+//! Functions for working with the NFTs of `pallet_uniques`.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_NFT_MINT,
+    FUNC_NFT_OWNER_OF,
+    FUNC_NFT_TRANSFER,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    storage::{
+        with_transaction,
+        TransactionOutcome,
+    },
+    traits::tokens::nonfungibles,
+    weights::Weight,
+};
+use frame_system::RawOrigin;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::{
+    traits::StaticLookup,
+    DispatchError,
+    DispatchResult,
+};
+
+pub(super) type CollectionIdOf<T> = <T as pallet_uniques::Config>::CollectionId;
+pub(super) type ItemIdOf<T> = <T as pallet_uniques::Config>::ItemId;
+pub(super) type NftMetadataOf<T> =
+    frame_support::BoundedVec<u8, <T as pallet_uniques::Config>::StringLimit>;
+
+pub(super) struct NftFuncs;
+
+impl HandleFunc for NftFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_NFT_MINT, FUNC_NFT_TRANSFER, FUNC_NFT_OWNER_OF];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `nft_mint()`
+            FUNC_NFT_MINT => {
+                // The metadata can be of any length, so we charge for it before decoding anything.
+                let (_, collection, item, owner, metadata): (
+                    bool,
+                    CollectionIdOf<T>,
+                    ItemIdOf<T>,
+                    <T as SysConfig>::AccountId,
+                    crate::Vec<u8>,
+                ) = charge_and_read(env, ExtensionWeightInfo::<T>::nft_mint)?;
+
+                // We check that the metadata fits before minting anything, that way we don't end
+                // up with an NFT that's missing its metadata.
+                let metadata: NftMetadataOf<T> = match metadata.try_into() {
+                    Ok(metadata) => metadata,
+                    Err(_) => return Ok(RetVal::Converging(status::NFT_OPERATION_FAILED)),
+                };
+
+                // `pallet_uniques` only lets a collection's issuer mint and its owner set
+                // metadata, so this only works for contracts which are both.
+                let contract = env.ext().address().clone();
+                let result = with_transaction(|| {
+                    let result = pallet_uniques::Pallet::<T>::mint(
+                        RawOrigin::Signed(contract.clone()).into(),
+                        collection,
+                        item,
+                        <T as SysConfig>::Lookup::unlookup(owner),
+                    )
+                    .and_then(|()| {
+                        if metadata.is_empty() {
+                            return Ok(())
+                        }
+                        pallet_uniques::Pallet::<T>::set_metadata(
+                            RawOrigin::Signed(contract).into(),
+                            collection,
+                            item,
+                            metadata,
+                            false,
+                        )
+                    });
+
+                    // Either both of these happen or neither does.
+                    if result.is_ok() {
+                        TransactionOutcome::Commit(result)
+                    } else {
+                        TransactionOutcome::Rollback(result)
+                    }
+                });
+                Ok(nft_status::<T>(result))
+            }
+            // Corresponds to `nft_transfer()`
+            FUNC_NFT_TRANSFER => {
+                let (_, collection, item, to): (
+                    bool,
+                    CollectionIdOf<T>,
+                    ItemIdOf<T>,
+                    <T as SysConfig>::AccountId,
+                ) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::nft_transfer())?;
+
+                // The contract needs to own the NFT, be the admin of its collection or have been
+                // approved as a delegate for it.
+                let contract = env.ext().address().clone();
+                let result = pallet_uniques::Pallet::<T>::transfer(
+                    RawOrigin::Signed(contract).into(),
+                    collection,
+                    item,
+                    <T as SysConfig>::Lookup::unlookup(to),
+                );
+                Ok(nft_status::<T>(result))
+            }
+            // Corresponds to `nft_owner_of()`
+            FUNC_NFT_OWNER_OF => {
+                let (collection, item): (CollectionIdOf<T>, ItemIdOf<T>) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::nft_owner_of())?;
+
+                let owner = <pallet_uniques::Pallet<T> as nonfungibles::Inspect<_>>::owner(
+                    &collection,
+                    &item,
+                );
+                env.write(&owner.encode(), false, None)?;
+                Ok(RetVal::Converging(status::SUCCESS))
+            }
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_NFT_MINT => W::<T>::nft_mint(input_len),
+            FUNC_NFT_TRANSFER => W::<T>::nft_transfer(),
+            FUNC_NFT_OWNER_OF => W::<T>::nft_owner_of(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// Turns the outcome of a `pallet_uniques` dispatchable into a status code.
+///
+/// Just like with `asset_status()`, only the errors contracts can do something about get a status
+/// code of their own.
+fn nft_status<T: pallet_uniques::Config>(result: DispatchResult) -> RetVal {
+    use pallet_uniques::Error;
+
+    let status = match result {
+        Ok(()) => status::SUCCESS,
+        Err(e) if e == Error::<T>::UnknownCollection.into() => status::UNKNOWN_NFT,
+        Err(e) if e == Error::<T>::UnknownItem.into() => status::UNKNOWN_NFT,
+        Err(e) if e == Error::<T>::AlreadyExists.into() => status::NFT_ALREADY_EXISTS,
+        Err(e) if e == Error::<T>::Frozen.into() => status::NFT_FROZEN,
+        Err(e) if e == Error::<T>::Locked.into() => status::NFT_FROZEN,
+        Err(e) if e == Error::<T>::NoPermission.into() => status::NOT_PERMITTED,
+        Err(_) => status::NFT_OPERATION_FAILED,
+    };
+
+    RetVal::Converging(status)
+}
@@ -0,0 +1,129 @@
+//! Functions which let contracts take part in `pallet_proxy` relationships, be it by adding
+//! proxies of their own or by acting for an account which made them its proxy.
+
+use super::{
+    charge_and_read,
+    runtime_calls::dispatch_as,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    ProxyType,
+    FUNC_ADD_PROXY,
+    FUNC_PROXY,
+    FUNC_REMOVE_PROXY,
+};
+use codec::DecodeLimit;
+use frame_support::{
+    traits::Contains,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct ProxyFuncs;
+
+impl HandleFunc for ProxyFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_ADD_PROXY, FUNC_REMOVE_PROXY, FUNC_PROXY];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        let call = match func_id {
+            // Corresponds to `add_proxy()`
+            FUNC_ADD_PROXY => {
+                let (_, delegate, proxy_type, delay): (
+                    bool,
+                    crate::AccountId,
+                    ProxyType,
+                    crate::BlockNumber,
+                ) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::add_proxy())?;
+
+                // The deposit for the proxy comes out of the contract's balance.
+                crate::Call::Proxy(pallet_proxy::Call::add_proxy {
+                    delegate,
+                    proxy_type,
+                    delay,
+                })
+            }
+            // Corresponds to `remove_proxy()`
+            FUNC_REMOVE_PROXY => {
+                let (_, delegate, proxy_type, delay): (
+                    bool,
+                    crate::AccountId,
+                    ProxyType,
+                    crate::BlockNumber,
+                ) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::remove_proxy())?;
+
+                crate::Call::Proxy(pallet_proxy::Call::remove_proxy {
+                    delegate,
+                    proxy_type,
+                    delay,
+                })
+            }
+            // Corresponds to `proxy()`
+            FUNC_PROXY => {
+                let (_, real, encoded): (bool, crate::AccountId, crate::Vec<u8>) =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::proxy)?;
+
+                // Whatever `real` lets its proxies do, contracts only get to make the calls they
+                // could also `dispatch()`.
+                let call = match crate::Call::decode_all_with_depth_limit(
+                    sp_api::MAX_EXTRINSIC_DEPTH,
+                    &mut &encoded[..],
+                ) {
+                    Ok(call) => call,
+                    Err(_) => return Ok(RetVal::Converging(status::SCHEMA_MISMATCH)),
+                };
+                if !crate::ContractDispatchFilter::contains(&call) {
+                    return Ok(RetVal::Converging(status::NOT_PERMITTED))
+                }
+
+                // The Proxy pallet only records how the call went in its `ProxyExecuted` event, so
+                // as far as the contract is concerned this succeeds as long as it may act for
+                // `real`.
+                crate::Call::Proxy(pallet_proxy::Call::proxy {
+                    real,
+                    force_proxy_type: None,
+                    call: crate::Box::new(call),
+                })
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        };
+
+        // It's the contract which adds proxies, or acts as one.
+        let contract = env.ext().address().clone();
+        dispatch_as(env, contract, T::contracts_call(call))
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        // Just like with `dispatch()`, the Proxy pallet's call is charged for on top of this.
+        let weight = match func_id {
+            FUNC_ADD_PROXY => W::<T>::add_proxy(),
+            FUNC_REMOVE_PROXY => W::<T>::remove_proxy(),
+            FUNC_PROXY => W::<T>::proxy(input_len),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
@@ -0,0 +1,160 @@
+//! Functions which let contracts dispatch calls of our runtime on behalf of whoever called them,
+//! rather than going through a `func_id` of their own for each of them.
+
+use super::{
+    charge_and_read,
+    dispatch_error_status,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_BATCH_WRITE,
+    FUNC_DISPATCH,
+    MAX_BATCH_WRITE_LEN,
+};
+use codec::DecodeLimit;
+use frame_support::{
+    storage::{
+        with_transaction,
+        TransactionOutcome,
+    },
+    weights::{
+        extract_actual_weight,
+        GetDispatchInfo,
+        Weight,
+    },
+};
+use frame_system::RawOrigin;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::{
+    traits::Dispatchable,
+    DispatchError,
+};
+
+pub(super) struct RuntimeCallFuncs;
+
+impl HandleFunc for RuntimeCallFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_DISPATCH, FUNC_BATCH_WRITE];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `dispatch()`
+            FUNC_DISPATCH => {
+                let (_, encoded): (bool, crate::Vec<u8>) =
+                    charge_and_read(env, ExtensionWeightInfo::<T>::dispatch)?;
+
+                // Calls can be nested inside of each other, so just like with extrinsics we limit
+                // how deep that may go. A call which doesn't decode was most likely encoded
+                // against a different version of our runtime.
+                let call = match <T as pallet_contracts::Config>::Call::decode_all_with_depth_limit(
+                    sp_api::MAX_EXTRINSIC_DEPTH,
+                    &mut &encoded[..],
+                ) {
+                    Ok(call) => call,
+                    Err(_) => return Ok(RetVal::Converging(status::UNDECODABLE_CALL)),
+                };
+                if !T::is_dispatchable(&call) {
+                    return Ok(RetVal::Converging(status::NOT_PERMITTED))
+                }
+
+                // Just like `write_for_caller()`, this acts on behalf of the account calling into
+                // the contract.
+                let caller = env.ext().caller().clone();
+                dispatch_as(env, caller, call)
+            }
+            // Corresponds to `batch_write()`
+            FUNC_BATCH_WRITE => {
+                // Every value takes up four bytes, so this is an upper bound on the number of
+                // calls we're about to put together.
+                let (_, values): (bool, crate::Vec<u32>) =
+                    charge_and_read(env, |len| ExtensionWeightInfo::<T>::batch_write(len / 4))?;
+                if values.len() as u32 > MAX_BATCH_WRITE_LEN {
+                    return Ok(RetVal::Converging(status::BATCH_TOO_LARGE))
+                }
+
+                // `batch_all()` either goes through with every write or none of them. Its weight
+                // is that of all the writes put together, plus a little for going through them.
+                let calls = values
+                    .into_iter()
+                    .map(|value| {
+                        crate::Call::Template(pallet_template::Call::store_for_caller { value })
+                    })
+                    .collect();
+                let call =
+                    T::contracts_call(crate::Call::Utility(pallet_utility::Call::batch_all {
+                        calls,
+                    }));
+
+                let caller = env.ext().caller().clone();
+                dispatch_as(env, caller, call)
+            }
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            // The call itself is charged for on top of this, we can't know how much that is
+            // without decoding it.
+            FUNC_DISPATCH => W::<T>::dispatch(input_len),
+            // Just like with `dispatch()`, the writes are charged for on top of this.
+            FUNC_BATCH_WRITE => W::<T>::batch_write(input_len / 4),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// Dispatches `call` on behalf of `who`, handing back how that went as a status code.
+pub(super) fn dispatch_as<T: Config, E>(
+    env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    who: <T as SysConfig>::AccountId,
+    call: <T as pallet_contracts::Config>::Call,
+) -> Result<RetVal, DispatchError>
+where
+    E: Ext<T = T>,
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    // We charge for as much as the call could use up front, and refund whatever it didn't end up
+    // using afterwards.
+    let info = call.get_dispatch_info();
+    let charged = env.charge_weight(info.weight)?;
+
+    // A call which fails gets rolled back on its own, that way the contract can carry on with a
+    // status code rather than a half-done call.
+    let result = with_transaction(|| {
+        let result = call.dispatch(RawOrigin::Signed(who).into());
+        if result.is_ok() {
+            TransactionOutcome::Commit(result)
+        } else {
+            TransactionOutcome::Rollback(result)
+        }
+    });
+    env.adjust_weight(charged, extract_actual_weight(&result, &info));
+
+    let code = match result {
+        Ok(_) => status::SUCCESS,
+        Err(e) => dispatch_error_status::<T>(e.error).unwrap_or(status::DISPATCH_FAILED),
+    };
+    Ok(RetVal::Converging(code))
+}
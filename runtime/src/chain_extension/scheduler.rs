@@ -0,0 +1,488 @@
+//! Functions for having the runtime call back into contracts later on, along with the ones for
+//! finding out how those callbacks went.
+
+use super::{
+    weights::WeightInfo,
+    BalanceOf,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    ScheduledCallback,
+    FUNC_CALLBACK_DEPOSIT,
+    FUNC_CALLBACK_RECEIPT,
+    FUNC_CALLBACK_RESERVATION,
+    FUNC_CALLBACK_WEIGHTS,
+    FUNC_RESCHEDULE,
+    FUNC_RESCHEDULE_CALLBACK,
+    FUNC_SCHEDULER_VERSION,
+    FUNC_SCHEDULE_CALL,
+    FUNC_SCHEDULE_IN,
+    SCHEDULER_EXTENSION_VERSION,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::{
+        schedule::{
+            v2::Anon,
+            DispatchTime,
+            MaybeHashed,
+        },
+        Get,
+        PreimageRecipient,
+    },
+    weights::Weight,
+};
+use frame_system::RawOrigin;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    ChargedAmount,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::{
+    traits::{
+        CheckedAdd,
+        Hash,
+        Saturating,
+        UniqueSaturatedInto,
+        Zero,
+    },
+    DispatchError,
+};
+
+/// The longest any of the calls we schedule gets once encoded.
+///
+/// They're all a `dispatch_callback` wrapping a `pallet_contracts::Call::call` with a selector, a
+/// `u32` and the callback's handle for data, which doesn't come close to this. We use it to
+/// quote the cost of noting a callback's preimage.
+const MAX_CALLBACK_LEN: u32 = 128;
+
+pub(super) struct SchedulerFuncs;
+
+impl HandleFunc for SchedulerFuncs {
+    const FUNCS: &'static [u32] = &[
+        FUNC_SCHEDULER_VERSION,
+        FUNC_SCHEDULE_CALL,
+        FUNC_CALLBACK_WEIGHTS,
+        FUNC_CALLBACK_RECEIPT,
+        FUNC_CALLBACK_RESERVATION,
+        FUNC_CALLBACK_DEPOSIT,
+        FUNC_RESCHEDULE,
+        FUNC_RESCHEDULE_CALLBACK,
+        FUNC_SCHEDULE_IN,
+    ];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `version()`
+            FUNC_SCHEDULER_VERSION => {
+                env.charge_weight(ExtensionWeightInfo::<T>::version())?;
+                env.write(&SCHEDULER_EXTENSION_VERSION.encode(), false, None)?;
+            }
+            // Corresponds to `schedule_call()`
+            FUNC_SCHEDULE_CALL => {
+                let (_, at, selector, gas_limit, storage_deposit_limit): (
+                    bool,
+                    u32,
+                    [u8; 4],
+                    Weight,
+                    Option<crate::Balance>,
+                ) = env.read_as()?;
+
+                return schedule_callback::<T, E>(
+                    env,
+                    at.into(),
+                    selector,
+                    gas_limit,
+                    storage_deposit_limit,
+                )
+            }
+            // Corresponds to `schedule_in()`
+            FUNC_SCHEDULE_IN => {
+                let (_, blocks, selector, gas_limit, storage_deposit_limit): (
+                    bool,
+                    u32,
+                    [u8; 4],
+                    Weight,
+                    Option<crate::Balance>,
+                ) = env.read_as()?;
+
+                // The Scheduler won't take calls for the current block, so we turn away a delay
+                // of zero here, along with any which would take us past the last block there is.
+                let now = frame_system::Pallet::<T>::block_number();
+                let at = match now.checked_add(&blocks.into()) {
+                    Some(at) if at > now => at,
+                    _ => return Ok(RetVal::Converging(status::INVALID_SCHEDULE_DELAY)),
+                };
+
+                return schedule_callback::<T, E>(
+                    env,
+                    at,
+                    selector,
+                    gas_limit,
+                    storage_deposit_limit,
+                )
+            }
+            // Corresponds to `callback_weights()`
+            FUNC_CALLBACK_WEIGHTS => {
+                env.charge_weight(ExtensionWeightInfo::<T>::callback_weights())?;
+
+                // Contracts only get to look at their own history.
+                let contract = env.ext().address().clone();
+                let histogram = pallet_template::Pallet::<T>::callback_weights(contract);
+
+                env.write(&histogram.encode(), false, None)?;
+            }
+            // Corresponds to `callback_receipt()`
+            FUNC_CALLBACK_RECEIPT => {
+                env.charge_weight(ExtensionWeightInfo::<T>::callback_receipt())?;
+
+                // Receipts aren't private, anyone holding a handle may check what came of it.
+                let handle: u64 = env.read_as()?;
+                let receipt = pallet_template::Pallet::<T>::receipt(handle);
+                env.write(&receipt.encode(), false, None)?;
+            }
+            // Corresponds to `callback_reservation()`
+            FUNC_CALLBACK_RESERVATION => {
+                env.charge_weight(ExtensionWeightInfo::<T>::callback_reservation())?;
+
+                let usage = pallet_template::Pallet::<T>::reservation_usage();
+                env.write(&usage.encode(), false, None)?;
+            }
+            // Corresponds to `callback_deposit()`
+            FUNC_CALLBACK_DEPOSIT => {
+                env.charge_weight(ExtensionWeightInfo::<T>::callback_deposit())?;
+
+                let deposit = <T as pallet_template::Config>::CallbackDeposit::get();
+                env.write(&deposit.encode(), false, None)?;
+            }
+            // Corresponds to `reschedule()`
+            FUNC_RESCHEDULE => {
+                let (_, when, index, new_when): (bool, u32, u32, u32) = env.read_as()?;
+                let charged = env.charge_weight(max_reschedule_weight::<T>())?;
+
+                // Contracts only get to move their own callbacks around, and nothing else the
+                // Scheduler might have in its agenda.
+                let contract = env.ext().address().clone();
+                let address = (when.into(), index);
+                let handle = match callback_at::<T>(address, &contract) {
+                    Some(handle) => handle,
+                    None => return Ok(RetVal::Converging(status::UNKNOWN_CALLBACK)),
+                };
+
+                return move_callback::<T, E>(env, charged, handle, address, new_when.into())
+            }
+            // Corresponds to `reschedule_callback()`
+            FUNC_RESCHEDULE_CALLBACK => {
+                let (_, handle, new_when): (bool, u64, u32) = env.read_as()?;
+                let charged = env.charge_weight(max_reschedule_weight::<T>())?;
+
+                // This is the counterpart of the Scheduler's `reschedule_named()`, with our
+                // handles taking the place of names. We can't have the Scheduler look them up for
+                // us, since anyone may schedule a call under whichever name they like.
+                let contract = env.ext().address().clone();
+                let address = match pallet_template::Pallet::<T>::callback_address(handle) {
+                    Some(address) if callback_at::<T>(address, &contract) == Some(handle) => {
+                        address
+                    }
+                    _ => return Ok(RetVal::Converging(status::UNKNOWN_CALLBACK)),
+                };
+
+                return move_callback::<T, E>(env, charged, handle, address, new_when.into())
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_SCHEDULER_VERSION => W::<T>::version(),
+            FUNC_SCHEDULE_CALL | FUNC_SCHEDULE_IN => {
+                W::<T>::schedule_call(
+                    T::MaxScheduledPerBlock::get(),
+                    <T as pallet_template::Config>::MaxCallbackDelay::get() + 1,
+                )
+                .saturating_add(W::<T>::note_callback_preimage(MAX_CALLBACK_LEN))
+            }
+            FUNC_CALLBACK_WEIGHTS => W::<T>::callback_weights(),
+            FUNC_CALLBACK_RECEIPT => W::<T>::callback_receipt(),
+            FUNC_CALLBACK_RESERVATION => W::<T>::callback_reservation(),
+            FUNC_CALLBACK_DEPOSIT => W::<T>::callback_deposit(),
+            FUNC_RESCHEDULE | FUNC_RESCHEDULE_CALLBACK => max_reschedule_weight::<T>(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
+
+/// Schedules a callback into the calling contract's `selector` for block `at`, or as close after
+/// it as there's room, and writes the `ScheduledCallback` to the output.
+fn schedule_callback<T: Config, E>(
+    env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    at: T::BlockNumber,
+    selector: [u8; 4],
+    gas_limit: Weight,
+    storage_deposit_limit: Option<crate::Balance>,
+) -> Result<RetVal, DispatchError>
+where
+    E: Ext<T = T>,
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    // We don't know how full the agenda for `at` is, nor how many blocks we'll need to
+    // look at before finding one with room, so we charge for the worst case.
+    let max_scheduled_per_block = T::MaxScheduledPerBlock::get();
+    let max_blocks = <T as pallet_template::Config>::MaxCallbackDelay::get() + 1;
+    let charged = env.charge_weight(ExtensionWeightInfo::<T>::schedule_call(
+        max_scheduled_per_block,
+        max_blocks,
+    ))?;
+
+    // Governance may want to keep arbitrary contracts from filling up the agenda, in which
+    // case only the ones it trusts get to schedule anything at all.
+    let contract = env.ext().address().clone();
+    if !pallet_template::Pallet::<T>::is_scheduler_allowed(&contract) {
+        return Ok(RetVal::Converging(status::NOT_ALLOWED_TO_SCHEDULE))
+    }
+
+    // Contracts only get to have the runtime call back into messages which governance
+    // has signed off on.
+    if !pallet_template::Pallet::<T>::is_callback_selector_allowed(selector) {
+        return Ok(RetVal::Converging(status::INVALID_CALLBACK_SELECTOR))
+    }
+
+    // The gas for the callback itself is only paid for once it runs, so we need to
+    // keep contracts from handing the Scheduler arbitrarily heavy calls.
+    if gas_limit > <T as pallet_template::Config>::MaxCallbackGasLimit::get() {
+        return Ok(RetVal::Converging(status::CALLBACK_GAS_LIMIT_TOO_HIGH))
+    }
+
+    // Whichever blocks its callbacks end up in, a contract only gets to schedule so
+    // many of them in the first place.
+    if pallet_template::Pallet::<T>::schedule_quota_left(&contract) == 0 {
+        return Ok(RetVal::Converging(status::SCHEDULING_QUOTA_EXCEEDED))
+    }
+
+    // `pallet_template` decides which block the callback actually ends up in. If
+    // `at` is full we get pushed back a little, but never arbitrarily far.
+    let requested = at;
+    let at = match pallet_template::Pallet::<T>::reserve_callback(&contract, requested) {
+        Some(reserved) => reserved,
+        None => return Ok(RetVal::Converging(status::AGENDA_FULL)),
+    };
+    pallet_template::Pallet::<T>::use_schedule_quota(&contract);
+
+    let blocks: u32 = (at - requested).unique_saturated_into();
+    env.adjust_weight(
+        charged,
+        ExtensionWeightInfo::<T>::schedule_call(max_scheduled_per_block, blocks + 1),
+    );
+
+    let caller = env.ext().caller().clone();
+    let value = env.ext().value_transferred();
+
+    // Rather than scheduling the contract call directly we go through
+    // `pallet_template`, which keeps track of how much weight the callback ends up
+    // using and leaves a receipt under the handle we give back to the contract.
+    let handle = pallet_template::Pallet::<T>::next_callback_handle();
+
+    // The message gets the handle too, so the contract knows which of its callbacks is
+    // running. Messages which only take the `u32` simply never look at it.
+    let mut data = selector.to_vec();
+    let magic_number = 15663040;
+    data.append(&mut magic_number.encode());
+    data.append(&mut handle.encode());
+
+    let call = crate::Call::Contracts(pallet_contracts::Call::call {
+        dest: T::runtime_address(contract.clone()),
+        value: T::runtime_balance(value),
+        gas_limit,
+        // Whatever deposit the callback needs is charged to the contract itself, so
+        // it's up to the contract how much of it it's willing to put down.
+        storage_deposit_limit: storage_deposit_limit.map(Into::into),
+        data,
+    });
+
+    // Whatever value the contract was sent along with this message is held on to until
+    // the callback runs, which then passes it on to the contract again.
+    pallet_template::Pallet::<T>::escrow_callback_value(handle, &contract, value)?;
+
+    let call = T::scheduler_call(crate::Call::Template(
+        pallet_template::Call::dispatch_callback {
+            handle,
+            call: crate::Box::new(call),
+        },
+    ));
+
+    // Callbacks which would take up too much room in the agenda are noted with the
+    // Preimage pallet instead, and the agenda only holds on to their hash.
+    let encoded = call.encode();
+    let len = encoded.len() as u32;
+    let max_inline_len = <T as pallet_template::Config>::MaxInlineCallbackLen::get();
+    let preimage = if len > max_inline_len {
+        env.charge_weight(ExtensionWeightInfo::<T>::note_callback_preimage(len))?;
+        Some(T::Hashing::hash(&encoded))
+    } else {
+        None
+    };
+
+    // The caller also pays for the room the callback takes up in the agenda, and for
+    // its preimage if it has one, until the callback runs. We can't leave the latter
+    // to the Preimage pallet, which gives its deposit back as soon as the Scheduler
+    // requests the preimage.
+    let preimage_deposit = match preimage {
+        Some(_) => preimage_deposit::<T>(len),
+        None => Zero::zero(),
+    };
+    pallet_template::Pallet::<T>::reserve_callback_deposit(handle, &caller, preimage_deposit)?;
+
+    let call = match preimage {
+        Some(hash) => {
+            let encoded = encoded.try_into().map_err(|_| {
+                DispatchError::Other("callback is too long for the Preimage pallet")
+            })?;
+            <pallet_preimage::Pallet<T> as PreimageRecipient<_>>::note_preimage(encoded);
+            MaybeHashed::Hash(hash)
+        }
+        None => MaybeHashed::Value(call),
+    };
+
+    // Unlike the `schedule()` dispatchable this tells us where in the agenda the
+    // callback went, which contracts need in order to move it around later on.
+    //
+    // The callback is dispatched on behalf of the contract itself. Nobody can sign for
+    // a contract's account, so that's how the contract tells its callbacks apart from
+    // anybody calling the same message, including whoever scheduled the callback.
+    let (at, index) = <pallet_scheduler::Pallet<T> as Anon<_, _, _>>::schedule(
+        DispatchTime::At(at),
+        None,
+        Default::default(),
+        RawOrigin::Signed(contract.clone()).into(),
+        call,
+    )?;
+    pallet_template::Pallet::<T>::note_callback_address(handle, at, index);
+    pallet_template::Pallet::<T>::note_callback_origin(handle, &contract, &contract);
+    pallet_template::Pallet::<T>::note_contract_scheduled(contract, caller, at);
+
+    let address = (at.unique_saturated_into(), index);
+    env.write(
+        &ScheduledCallback::<u32, _> {
+            handle,
+            address,
+            preimage,
+        }
+        .encode(),
+        false,
+        None,
+    )?;
+
+    Ok(RetVal::Converging(status::SUCCESS))
+}
+
+/// What the caller puts down for a callback's preimage, which is what the Preimage pallet would
+/// have charged them for it.
+fn preimage_deposit<T: Config>(len: u32) -> BalanceOf<T> {
+    let per_byte = <T as pallet_preimage::Config>::ByteDeposit::get();
+    <T as pallet_preimage::Config>::BaseDeposit::get()
+        .saturating_add(per_byte.saturating_mul(len.into()))
+}
+
+/// What we charge up front for moving a callback, before we know how full the agendas involved
+/// are or how far it gets pushed back.
+fn max_reschedule_weight<T: Config>() -> Weight {
+    ExtensionWeightInfo::<T>::reschedule(
+        T::MaxScheduledPerBlock::get(),
+        <T as pallet_template::Config>::MaxCallbackDelay::get() + 1,
+    )
+}
+
+/// The handle of the callback at `address` in the Scheduler's agenda, if there is one and it's
+/// going to call back into `contract`.
+pub(super) fn callback_at<T: Config>(
+    address: (T::BlockNumber, u32),
+    contract: &<T as SysConfig>::AccountId,
+) -> Option<u64> {
+    let (when, index) = address;
+    let scheduled = pallet_scheduler::Agenda::<T>::get(when)
+        .into_iter()
+        .nth(index as usize)??;
+
+    // Callbacks which went by hash need their preimage to tell who they're for.
+    let (call, _) = scheduled
+        .call
+        .resolved::<<T as pallet_scheduler::Config>::PreimageProvider>();
+    let call = T::runtime_call(call.as_value()?.clone());
+
+    match call {
+        crate::Call::Template(pallet_template::Call::dispatch_callback { handle, call }) => {
+            match *call {
+                crate::Call::Contracts(pallet_contracts::Call::call { dest, .. })
+                    if dest == T::runtime_address(contract.clone()) =>
+                {
+                    Some(handle)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Moves the callback with the given `handle` from `address` in the Scheduler's agenda to
+/// `new_when`, or as close after it as there's room, and writes its new address to the output.
+///
+/// The callback keeps its handle, along with whatever value and deposit it was scheduled with.
+fn move_callback<T: Config, E>(
+    env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    charged: ChargedAmount,
+    handle: u64,
+    address: (T::BlockNumber, u32),
+    new_when: T::BlockNumber,
+) -> Result<RetVal, DispatchError>
+where
+    E: Ext<T = T>,
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    // Moving a callback doesn't count against the contract's quota, but it does need a slot in
+    // whichever block it ends up in. We only give up the old one once we have the new one, so a
+    // callback we can't move stays right where it is.
+    let contract = env.ext().address().clone();
+    let at = match pallet_template::Pallet::<T>::reserve_callback(&contract, new_when) {
+        Some(reserved) => reserved,
+        None => return Ok(RetVal::Converging(status::AGENDA_FULL)),
+    };
+    pallet_template::Pallet::<T>::release_callback(&contract, address.0);
+
+    let blocks: u32 = (at - new_when).unique_saturated_into();
+    env.adjust_weight(
+        charged,
+        ExtensionWeightInfo::<T>::reschedule(T::MaxScheduledPerBlock::get(), blocks + 1),
+    );
+
+    let (at, index) =
+        <pallet_scheduler::Pallet<T> as Anon<_, _, _>>::reschedule(address, DispatchTime::At(at))?;
+    pallet_template::Pallet::<T>::note_callback_address(handle, at, index);
+
+    let at: u32 = at.unique_saturated_into();
+    env.write(&(at, index).encode(), false, None)?;
+
+    Ok(RetVal::Converging(status::SUCCESS))
+}
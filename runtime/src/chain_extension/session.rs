@@ -0,0 +1,76 @@
+//! Functions which tell contracts about the chain's sessions, as `pallet_session` keeps track of
+//! them, and how they add up to staking eras.
+//!
+//! Contracts which do something every so often can line up with these instead of counting blocks.
+
+use super::{
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    SessionInfo,
+    FUNC_SESSION_INFO,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::Get,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct SessionFuncs;
+
+impl HandleFunc for SessionFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_SESSION_INFO];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `session_info()`
+            FUNC_SESSION_INFO => {
+                env.charge_weight(ExtensionWeightInfo::<T>::session_info())?;
+
+                // Eras only start once staking has held an election for them, so there's no
+                // active one for the first few sessions.
+                let info = SessionInfo {
+                    session_index: crate::Session::current_index(),
+                    session_length: crate::SessionPeriod::get(),
+                    active_era: crate::Staking::active_era().map(|era| era.index),
+                    sessions_per_era: crate::SessionsPerEra::get(),
+                };
+                env.write(&info.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_SESSION_INFO => W::<T>::session_info(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
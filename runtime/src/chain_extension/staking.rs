@@ -0,0 +1,99 @@
+//! Functions which tell contracts about staking, as `pallet_staking` keeps track of it.
+//!
+//! None of these change anything. They're what contracts building on top of staking, e.g. liquid
+//! staking, need to know about it.
+
+use super::{
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_CURRENT_ERA,
+    FUNC_IS_BONDED,
+    FUNC_VALIDATOR_STAKE,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::{
+    traits::Zero,
+    DispatchError,
+};
+
+pub(super) struct StakingFuncs;
+
+impl HandleFunc for StakingFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_CURRENT_ERA, FUNC_VALIDATOR_STAKE, FUNC_IS_BONDED];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `current_era()`
+            FUNC_CURRENT_ERA => {
+                env.charge_weight(ExtensionWeightInfo::<T>::current_era())?;
+
+                // This is the era which has been planned most recently, which can be ahead of the
+                // one validators are currently being paid for. There's none before the first
+                // election.
+                let era = pallet_staking::Pallet::<T>::current_era();
+                env.write(&era.encode(), false, None)?;
+            }
+            // Corresponds to `validator_stake()`
+            FUNC_VALIDATOR_STAKE => {
+                let validator: <T as SysConfig>::AccountId = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::validator_stake())?;
+
+                // What's backing `validator` in the active era, both its own stake and that of
+                // its nominators. Accounts which aren't validating have nothing backing them.
+                let stake = pallet_staking::Pallet::<T>::active_era()
+                    .map_or_else(Zero::zero, |era| {
+                        pallet_staking::Pallet::<T>::eras_stakers(era.index, validator).total
+                    });
+                env.write(&stake.encode(), false, None)?;
+            }
+            // Corresponds to `is_bonded()`
+            FUNC_IS_BONDED => {
+                let stash: <T as SysConfig>::AccountId = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::is_bonded())?;
+
+                // Only stash accounts count as bonded, not their controllers.
+                let bonded = pallet_staking::Pallet::<T>::bonded(stash).is_some();
+                env.write(&bonded.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_CURRENT_ERA => W::<T>::current_era(),
+            FUNC_VALIDATOR_STAKE => W::<T>::validator_stake(),
+            FUNC_IS_BONDED => W::<T>::is_bonded(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
@@ -0,0 +1,228 @@
+//! Functions which store values in, and load them back out of, `pallet_template`.
+
+use super::{
+    charge_and_read,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_BLOB,
+    FUNC_LOAD_VALUE,
+    FUNC_READ_FOR,
+    FUNC_READ_FROM_STORAGE,
+    FUNC_READ_RAW,
+    FUNC_STORE_BATCH,
+    FUNC_WRITE_FOR_CALLER,
+    FUNC_WRITE_RAW,
+    FUNC_WRITE_TO_STORAGE,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::Get,
+    weights::Weight,
+};
+use frame_system::RawOrigin;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct StorageFuncs;
+
+impl HandleFunc for StorageFuncs {
+    const FUNCS: &'static [u32] = &[
+        FUNC_WRITE_TO_STORAGE,
+        FUNC_READ_FROM_STORAGE,
+        FUNC_WRITE_FOR_CALLER,
+        FUNC_READ_FOR,
+        FUNC_WRITE_RAW,
+        FUNC_READ_RAW,
+        FUNC_STORE_BATCH,
+        FUNC_LOAD_VALUE,
+        FUNC_BLOB,
+    ];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `write_to_storage()`
+            FUNC_WRITE_TO_STORAGE => {
+                // This will read some bytes from the memory buffer mentioned in `call()` and try
+                // to decode them into the specified type. This method should only be used if the
+                // size of the type is known ahead of time.
+                //
+                // Like every function which changes state this one takes a leading `simulate`
+                // flag, which `call()` has already taken care of.
+                let (_, something): (bool, u32) = env.read_as()?;
+
+                // We need to ensure that we're charging weight to account for the amount of compute
+                // used by the call to our pallet. This is something we typically don't have to
+                // worry about in the context of smart contracts since they're gas metered.
+                //
+                // These weights live in `chain_extension/weights.rs`, see there for where they
+                // come from.
+                env.charge_weight(ExtensionWeightInfo::<T>::write_to_storage())?;
+
+                // Using `env.ext()` we can access all sorts of info about the execution
+                // environment. You can this of this as equivalent to `self.env()` in an ink!
+                // contract.
+                //
+                // We store the value on behalf of whoever called the contract, but make sure the
+                // event also names the contract so it can't be mistaken for a direct extrinsic.
+                let caller = env.ext().caller().clone();
+                let contract = env.ext().address().clone();
+                pallet_template::Pallet::<T>::store_something_for_contract(
+                    contract, caller, something,
+                );
+            }
+            // Corresponds to `read_from_storage()`
+            FUNC_READ_FROM_STORAGE => {
+                env.charge_weight(ExtensionWeightInfo::<T>::read_from_storage())?;
+
+                // This is the value `write_to_storage()` wrote, whoever did the writing.
+                let value = pallet_template::Pallet::<T>::something();
+                env.write(&value.encode(), false, None)?;
+            }
+            // Corresponds to `write_for_caller()`
+            FUNC_WRITE_FOR_CALLER => {
+                let (_, value): (bool, u32) = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::write_for_caller())?;
+
+                // This is the account calling into the contract, not the contract itself. That
+                // way every user of the contract gets a slot of their own.
+                let caller = env.ext().caller().clone();
+                pallet_template::Pallet::<T>::store_for_caller(
+                    RawOrigin::Signed(caller).into(),
+                    value,
+                )?;
+            }
+            // Corresponds to `read_for()`
+            FUNC_READ_FOR => {
+                // Unlike a `Vec`, an `AccountId` has a known maximum size so we can use
+                // `read_as()` here.
+                let account: <T as SysConfig>::AccountId = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::read_for())?;
+
+                let value = pallet_template::Pallet::<T>::account_value(account);
+                env.write(&value.encode(), false, None)?;
+            }
+            // Corresponds to `write_raw()`
+            FUNC_WRITE_RAW => {
+                let len = env.in_len();
+                env.charge_weight(ExtensionWeightInfo::<T>::write_raw(len))?;
+
+                // Ordinary contracts stay sandboxed. We tell them so with a status code rather
+                // than trapping, that way they can handle it gracefully.
+                let contract = env.ext().address().clone();
+                if pallet_template::Pallet::<T>::system_contract().as_ref() != Some(&contract) {
+                    return Ok(RetVal::Converging(status::NOT_PERMITTED))
+                }
+
+                let (_, key, value): (bool, crate::Vec<u8>, crate::Vec<u8>) =
+                    env.read_as_unbounded(len)?;
+                pallet_template::Pallet::<T>::write_raw(&contract, key, value)?;
+            }
+            // Corresponds to `read_raw()`
+            FUNC_READ_RAW => {
+                // We don't know how large the value is until we've read it, so we charge as if it
+                // were as large as it could possibly be and refund the difference afterwards.
+                let len = env.in_len();
+                let max_value_len = <T as pallet_template::Config>::MaxRawValueLen::get();
+                let charged = env.charge_weight(ExtensionWeightInfo::<T>::read_raw(
+                    len,
+                    max_value_len,
+                ))?;
+
+                let key: crate::Vec<u8> = env.read_as_unbounded(len)?;
+                let value = pallet_template::Pallet::<T>::read_raw(key);
+
+                let value_len = value.as_ref().map_or(0, |value| value.len() as u32);
+                env.adjust_weight(
+                    charged,
+                    ExtensionWeightInfo::<T>::read_raw(len, value_len),
+                );
+
+                // Unlike the functions above the size of what we hand back isn't known ahead of
+                // time, but as far as `write()` is concerned that doesn't make a difference. The
+                // contract needs to give us a large enough output buffer though, otherwise this
+                // fails with `OutputBufferTooSmall`.
+                env.write(&value.encode(), false, None)?;
+            }
+            // Corresponds to `blob()`
+            FUNC_BLOB => {
+                // Rather than charging for the largest blob there could be and refunding the
+                // difference like `read_raw()` does, we charge for the read now and leave it to
+                // `write()` to charge for every byte we end up handing back.
+                let read = ExtensionWeightInfo::<T>::blob(0);
+                let per_byte = ExtensionWeightInfo::<T>::blob(1).saturating_sub(read);
+                env.charge_weight(read)?;
+
+                let blob = pallet_template::Pallet::<T>::blob();
+                env.write(&blob.encode(), false, Some(per_byte))?;
+            }
+            // Corresponds to `store_batch()`
+            FUNC_STORE_BATCH => {
+                // Every `(u32, u32)` pair takes up eight bytes, so this is an upper bound on the
+                // number of values we're about to store.
+                let (_, values): (bool, crate::Vec<(u32, u32)>) =
+                    charge_and_read(env, |len| ExtensionWeightInfo::<T>::store_batch(len / 8))?;
+
+                let contract = env.ext().address().clone();
+                pallet_template::Pallet::<T>::store_batch(&contract, values)?;
+            }
+            // Corresponds to `load_value()`
+            FUNC_LOAD_VALUE => {
+                let key: u32 = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::load_value())?;
+
+                // Contracts only get to see the values they stored themselves.
+                let contract = env.ext().address().clone();
+                let value = pallet_template::Pallet::<T>::contract_value(contract, key);
+
+                env.write(&value.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_WRITE_TO_STORAGE => W::<T>::write_to_storage(),
+            FUNC_READ_FROM_STORAGE => W::<T>::read_from_storage(),
+            FUNC_WRITE_FOR_CALLER => W::<T>::write_for_caller(),
+            FUNC_READ_FOR => W::<T>::read_for(),
+            FUNC_WRITE_RAW => W::<T>::write_raw(input_len),
+            FUNC_READ_RAW => {
+                W::<T>::read_raw(
+                    input_len,
+                    <T as pallet_template::Config>::MaxRawValueLen::get(),
+                )
+            }
+            FUNC_STORE_BATCH => W::<T>::store_batch(input_len / 8),
+            FUNC_LOAD_VALUE => W::<T>::load_value(),
+            FUNC_BLOB => W::<T>::blob(<T as pallet_template::Config>::MaxBlobLen::get()),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
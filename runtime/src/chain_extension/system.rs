@@ -0,0 +1,81 @@
+//! Functions which tell contracts about accounts and blocks, as `frame_system` keeps track of them.
+
+use super::{
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_ACCOUNT_NONCE,
+    FUNC_BLOCK_HASH,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct SystemFuncs;
+
+impl HandleFunc for SystemFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_ACCOUNT_NONCE, FUNC_BLOCK_HASH];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId:
+            UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `account_nonce()`
+            FUNC_ACCOUNT_NONCE => {
+                let account: <T as SysConfig>::AccountId = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::account_nonce())?;
+
+                // This only goes up when `account` signs a transaction, calls which come in
+                // through a contract don't count.
+                let nonce = frame_system::Pallet::<T>::account_nonce(account);
+                env.write(&nonce.encode(), false, None)?;
+            }
+            // Corresponds to `block_hash()`
+            FUNC_BLOCK_HASH => {
+                let number: <T as SysConfig>::BlockNumber = env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::block_hash())?;
+
+                // The System pallet only keeps the last `BlockHashCount` hashes around, and the
+                // current block doesn't have one yet. It hands out a zero hash for anything it
+                // doesn't have, which we'd rather not pass off as a real one.
+                let hash = frame_system::BlockHash::<T>::try_get(number).ok();
+                env.write(&hash.encode(), false, None)?;
+            }
+            _ => return Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+
+        Ok(RetVal::Converging(status::SUCCESS))
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        let weight = match func_id {
+            FUNC_ACCOUNT_NONCE => W::<T>::account_nonce(),
+            FUNC_BLOCK_HASH => W::<T>::block_hash(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
@@ -0,0 +1,3306 @@
+use super::{
+    weights::WeightInfo,
+    *,
+};
+use crate::{
+    tests::{
+        new_test_ext,
+        run_to_block,
+        ALICE,
+    },
+    AccountId,
+    Assets,
+    Balance,
+    Balances,
+    BlockNumber,
+    CallbackDeposit,
+    Contracts,
+    Council,
+    CouncilMembership,
+    Democracy,
+    Executive,
+    ExistentialDeposit,
+    Identity,
+    MaxInlineCallbackLen,
+    MaximumSchedulerWeight,
+    Multisig,
+    MultisigDepositBase,
+    MultisigDepositFactor,
+    Origin,
+    PreimageBaseDeposit,
+    PreimageByteDeposit,
+    ProposalBondMinimum,
+    Proxy,
+    ProxyDepositBase,
+    ProxyDepositFactor,
+    ProxyType,
+    Runtime,
+    Session,
+    SessionPeriod,
+    SessionsPerEra,
+    Staking,
+    System,
+    Template,
+    TransactionPayment,
+    Treasury,
+    Uniques,
+};
+use chain_extension_primitives::{
+    capabilities,
+    more_capabilities,
+    pack_func_id,
+    type_descriptor,
+    BoundedIdentityInfo,
+    CallCounts,
+    ChainConstants,
+    ContractInfo,
+    Conviction,
+    Custom,
+    Envelope,
+    FeeEstimate,
+    IdentityJudgement,
+    Measure,
+    ReservationUsage,
+    RichCustom,
+    ScheduledCallback,
+    SchemaDescriptor,
+    SchemaHash,
+    SessionInfo,
+    WeightHistogram,
+    DEFAULT_CALLBACK_SELECTOR,
+    FUNC_ACCOUNT_NONCE,
+    FUNC_ADD_PROXY,
+    FUNC_APPROVE_AS_MULTI,
+    FUNC_ASSET_BALANCE,
+    FUNC_ASSET_MINT,
+    FUNC_ASSET_TOTAL_SUPPLY,
+    FUNC_ASSET_TRANSFER,
+    FUNC_ASSET_TRANSFER_APPROVED,
+    FUNC_AS_MULTI,
+    FUNC_BATCH_WRITE,
+    FUNC_BLOB,
+    FUNC_BLOCK_AUTHOR,
+    FUNC_BLOCK_HASH,
+    FUNC_BOUNDED_CUSTOM_TYPE,
+    FUNC_CALLBACK_DEPOSIT,
+    FUNC_CALLBACK_RECEIPT,
+    FUNC_CALLBACK_RESERVATION,
+    FUNC_CALLBACK_WEIGHTS,
+    FUNC_CALL_CONTRACT,
+    FUNC_CALL_COUNTS,
+    FUNC_CAPABILITIES,
+    FUNC_CHAIN_CONSTANTS,
+    FUNC_CHECKED_CUSTOM_TYPE,
+    FUNC_COMMIT,
+    FUNC_CONTRACT_INFO,
+    FUNC_COUNTER_CREATE,
+    FUNC_COUNTER_INCREMENT,
+    FUNC_COUNTER_SET_ACCESS,
+    FUNC_COUNTER_VALUE,
+    FUNC_CURRENT_ERA,
+    FUNC_CUSTOM_TYPE_WITH_RESULT,
+    FUNC_DISPATCH,
+    FUNC_ENVELOPE,
+    FUNC_ESTIMATE_FEE,
+    FUNC_FREE_BALANCE,
+    FUNC_IDENTITY_OF,
+    FUNC_INSTANTIATE_CONTRACT,
+    FUNC_IS_BONDED,
+    FUNC_IS_MEMBER,
+    FUNC_LOAD_VALUE,
+    FUNC_MORE_CAPABILITIES,
+    FUNC_NFT_MINT,
+    FUNC_NFT_OWNER_OF,
+    FUNC_NFT_TRANSFER,
+    FUNC_PROPOSE_APPROVAL,
+    FUNC_PROPOSE_SPEND,
+    FUNC_PROXY,
+    FUNC_QUOTE,
+    FUNC_RANDOM,
+    FUNC_READ_FOR,
+    FUNC_READ_FROM_STORAGE,
+    FUNC_READ_RAW,
+    FUNC_REMOVE_PROXY,
+    FUNC_RESCHEDULE,
+    FUNC_RESCHEDULE_CALLBACK,
+    FUNC_REVEAL,
+    FUNC_RICH_CUSTOM_TYPE,
+    FUNC_SCHEDULER_VERSION,
+    FUNC_SCHEDULE_CALL,
+    FUNC_SCHEDULE_IN,
+    FUNC_SESSION_INFO,
+    FUNC_STORE_BATCH,
+    FUNC_STORE_ENVELOPE,
+    FUNC_TRANSFER_NATIVE,
+    FUNC_TRANSFER_NATIVE_COMPACT,
+    FUNC_VALIDATOR_STAKE,
+    FUNC_VERIFY_ECDSA,
+    FUNC_VERIFY_SR25519,
+    FUNC_VERSION,
+    FUNC_VOTE,
+    FUNC_WEIGHT_TO_FEE,
+    FUNC_WRITE_FOR_CALLER,
+    FUNC_WRITE_RAW,
+    FUNC_WRITE_TO_STORAGE,
+    FUNC_XCM_TRANSACT,
+    MAIN_EXTENSION_VERSION,
+    MAX_BATCH_WRITE_LEN,
+    MAX_BOUNDED_CUSTOM_LEN,
+    MAX_XCM_CALL_LEN,
+    SCHEDULER_EXTENSION_VERSION,
+};
+use frame_support::{
+    assert_ok,
+    pallet_prelude::{
+        Decode,
+        Encode,
+    },
+    traits::{
+        schedule::MaybeHashed,
+        Get,
+        ReservableCurrency,
+    },
+    weights::GetDispatchInfo,
+};
+use pallet_democracy::VoteThreshold;
+use pallet_identity::{
+    Data,
+    IdentityInfo,
+    Judgement,
+};
+use sp_core::{
+    ecdsa,
+    sr25519,
+    Pair,
+};
+use sp_runtime::traits::{
+    BlakeTwo256,
+    Hash,
+};
+
+pub(super) const GAS_LIMIT: u64 = 100_000_000_000;
+
+// What our scheduled callbacks get to use, which is well below `MaxCallbackGasLimit`.
+const CALLBACK_GAS_LIMIT: u64 = GAS_LIMIT / 10;
+
+// Rather than the demo contract (see `tests::deploy_demo_contract()`) most tests use a tiny
+// hand-written contract, which lets us call any of our extension functions with whatever input we
+// like.
+fn deploy() -> AccountId {
+    deploy_fixture("chain_extension")
+}
+
+// Deploys one of the contracts in `fixtures/`.
+pub(super) fn deploy_fixture(name: &str) -> AccountId {
+    deploy_fixture_with_salt(name, Vec::new())
+}
+
+// The same contract can be deployed more than once, as long as it's with a different salt each
+// time.
+fn deploy_fixture_with_salt(name: &str, salt: Vec<u8>) -> AccountId {
+    Contracts::bare_instantiate(
+        ALICE,
+        0,
+        GAS_LIMIT,
+        None,
+        pallet_contracts_primitives::Code::Upload(fixture_wasm(name).into()),
+        Vec::new(),
+        salt,
+        false,
+    )
+    .result
+    .expect("instantiation should succeed")
+    .account_id
+}
+
+// The code of one of the contracts in `fixtures/`.
+fn fixture_wasm(name: &str) -> Vec<u8> {
+    wat::parse_file(format!(
+        "{}/fixtures/{}.wat",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    ))
+    .expect("fixture is valid wat; qed")
+}
+
+// The code hash contracts deployed from one of the fixtures end up with.
+fn fixture_code_hash(name: &str) -> crate::Hash {
+    BlakeTwo256::hash(&fixture_wasm(name))
+}
+
+// Calls `func_id` with the given input, returning the status code and output of the extension.
+fn call(contract: &AccountId, func_id: u32, input: impl Encode) -> (u32, Vec<u8>) {
+    call_with_value(contract, 0, func_id, input)
+}
+
+// Like `call()`, except that the contract is sent `value` along with the call.
+fn call_with_value(
+    contract: &AccountId,
+    value: Balance,
+    func_id: u32,
+    input: impl Encode,
+) -> (u32, Vec<u8>) {
+    let mut data = func_id.encode();
+    input.encode_to(&mut data);
+
+    let output = Contracts::bare_call(ALICE, contract.clone(), value, GAS_LIMIT, None, data, false)
+        .result
+        .expect("contract call should succeed")
+        .data
+        .0;
+
+    let status = u32::decode(&mut &output[..4]).expect("the fixture returns a status code");
+    (status, output[4..].to_vec())
+}
+
+// Schedules a callback to the default selector, returning the status code.
+fn schedule(contract: &AccountId, at: u32) -> u32 {
+    let input = (false, at, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+    call(contract, FUNC_SCHEDULE_CALL, input).0
+}
+
+// The gas limit of the contract call a scheduled callback wraps, if it's stored inline.
+fn callback_gas_limit(call: &mut MaybeHashed<crate::Call, crate::Hash>) -> Option<&mut u64> {
+    match call {
+        MaybeHashed::Value(crate::Call::Template(pallet_template::Call::dispatch_callback {
+            call,
+            ..
+        })) => match &mut **call {
+            crate::Call::Contracts(pallet_contracts::Call::call { gas_limit, .. }) => {
+                Some(gas_limit)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// What the migrations of a runtime upgrade which lowers `MaxCallbackGasLimit` would have to do:
+// halve the gas limit of every callback which is still waiting in the agenda.
+fn halve_callback_gas_limits() {
+    let agendas: Vec<_> = pallet_scheduler::Agenda::<Runtime>::iter().collect();
+    for (at, mut agenda) in agendas {
+        for scheduled in agenda.iter_mut().flatten() {
+            if let Some(gas_limit) = callback_gas_limit(&mut scheduled.call) {
+                *gas_limit /= 2;
+            }
+        }
+        pallet_scheduler::Agenda::<Runtime>::insert(at, agenda);
+    }
+}
+
+#[test]
+fn write_to_storage_works() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, FUNC_WRITE_TO_STORAGE, (false, 42u32));
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::something(), Some(42));
+    });
+}
+
+#[test]
+fn written_values_can_be_read_back() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(&contract, FUNC_READ_FROM_STORAGE, ());
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Option::<u32>::decode(&mut &output[..]).unwrap(), None);
+
+        call(&contract, FUNC_WRITE_TO_STORAGE, (false, 42u32));
+        let (status, output) = call(&contract, FUNC_READ_FROM_STORAGE, ());
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Option::<u32>::decode(&mut &output[..]).unwrap(), Some(42));
+    });
+}
+
+#[test]
+fn writes_name_the_contract_they_came_through() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, FUNC_WRITE_TO_STORAGE, (false, 42u32));
+        assert_eq!(status, status::SUCCESS);
+
+        let stored = crate::Event::Template(pallet_template::Event::SomethingStoredByContract(
+            42, ALICE, contract,
+        ));
+        assert!(System::events().iter().any(|record| record.event == stored));
+    });
+}
+
+#[test]
+fn simulated_calls_leave_storage_untouched() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, FUNC_WRITE_TO_STORAGE, (true, 42u32));
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::something(), None);
+    });
+}
+
+#[test]
+fn simulated_calls_report_what_would_have_happened() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        create_asset(&contract);
+
+        let (status, _) = call(&contract, FUNC_ASSET_TRANSFER, (true, ASSET, bob, 30u128));
+        assert_eq!(status, status::ASSET_BALANCE_LOW);
+
+        let (status, _) = call(
+            &contract,
+            FUNC_ASSET_MINT,
+            (true, ASSET, contract.clone(), 100u128),
+        );
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Assets::balance(ASSET, &contract), 0);
+
+        // The output is what we'd have gotten too, here the id the envelope would have had.
+        let store = |simulate| {
+            call(&contract, FUNC_STORE_ENVELOPE, (simulate, [7u8; 32], 10u32, vec![1u8])).1
+        };
+        let simulated = store(true);
+        let stored = store(false);
+        assert_eq!(simulated, stored);
+    });
+}
+
+#[test]
+fn every_caller_gets_their_own_value() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+
+        let (status, _) = call(&contract, FUNC_WRITE_FOR_CALLER, (false, 42u32));
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::account_value(&ALICE), Some(42));
+        // The value belongs to whoever called the contract, not to the contract itself.
+        assert_eq!(Template::account_value(&contract), None);
+
+        let (status, output) = call(&contract, FUNC_READ_FOR, ALICE);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Option::<u32>::decode(&mut &output[..]).unwrap(), Some(42));
+
+        let (_, output) = call(&contract, FUNC_READ_FOR, bob);
+        assert_eq!(Option::<u32>::decode(&mut &output[..]).unwrap(), None);
+    });
+}
+
+fn fund(contract: &AccountId, amount: Balance) {
+    assert_ok!(Balances::transfer(
+        Origin::signed(ALICE),
+        contract.clone().into(),
+        amount
+    ));
+}
+
+#[test]
+fn contracts_can_transfer_native_tokens() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        fund(&contract, 100 * ExistentialDeposit::get());
+
+        let (status, _) = call(
+            &contract,
+            FUNC_TRANSFER_NATIVE,
+            (false, bob.clone(), 10 * ExistentialDeposit::get()),
+        );
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Balances::free_balance(&bob), 10 * ExistentialDeposit::get());
+    });
+}
+
+#[test]
+fn contracts_can_look_up_free_balances() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+
+        let (status, output) = call(&contract, FUNC_FREE_BALANCE, ALICE);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Balance::decode(&mut &output[..]).unwrap(), Balances::free_balance(&ALICE));
+
+        // Accounts which don't exist simply don't have anything to spend.
+        let (status, output) = call(&contract, FUNC_FREE_BALANCE, bob);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Balance::decode(&mut &output[..]).unwrap(), 0);
+    });
+}
+
+#[test]
+fn contracts_can_look_up_block_hashes() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let block_hash = |number: BlockNumber| {
+            let (status, output) = call(&contract, FUNC_BLOCK_HASH, number);
+            assert_eq!(status, status::SUCCESS);
+            Option::<crate::Hash>::decode(&mut &output[..]).unwrap()
+        };
+
+        let hash = crate::Hash::repeat_byte(7);
+        frame_system::BlockHash::<Runtime>::insert(2, hash);
+        assert_eq!(block_hash(2), Some(hash));
+        // Rather than the zero hash the System pallet would make up.
+        assert_eq!(block_hash(1_000), None);
+    });
+}
+
+#[test]
+fn blocks_sealed_without_a_consensus_engine_have_no_author() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(&contract, FUNC_BLOCK_AUTHOR, ());
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Option::<AccountId>::decode(&mut &output[..]).unwrap(), None);
+    });
+}
+
+#[test]
+fn contracts_can_look_up_account_nonces() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        System::inc_account_nonce(&ALICE);
+
+        let (status, output) = call(&contract, FUNC_ACCOUNT_NONCE, ALICE);
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(u32::decode(&mut &output[..]).unwrap(), System::account_nonce(&ALICE));
+    });
+}
+
+#[test]
+fn compact_amounts_can_be_transferred() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        fund(&contract, 100 * ExistentialDeposit::get());
+
+        let amount = codec::Compact(10 * ExistentialDeposit::get());
+        let (status, _) = call(
+            &contract,
+            FUNC_TRANSFER_NATIVE_COMPACT,
+            (false, bob.clone(), amount),
+        );
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Balances::free_balance(&bob), 10 * ExistentialDeposit::get());
+    });
+}
+
+#[test]
+fn compact_transfers_respect_the_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        fund(&contract, 10 * ExistentialDeposit::get());
+
+        let amount = codec::Compact(ExistentialDeposit::get() - 1);
+        let (status, _) = call(
+            &contract,
+            FUNC_TRANSFER_NATIVE_COMPACT,
+            (false, bob.clone(), amount),
+        );
+
+        assert_eq!(status, status::BELOW_EXISTENTIAL_DEPOSIT);
+        assert_eq!(Balances::free_balance(&bob), 0);
+    });
+}
+
+#[test]
+fn transfers_respect_the_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        fund(&contract, 10 * ExistentialDeposit::get());
+
+        // Bob doesn't exist yet, so he'd need at least the existential deposit.
+        let (status, _) = call(
+            &contract,
+            FUNC_TRANSFER_NATIVE,
+            (false, bob.clone(), ExistentialDeposit::get() - 1),
+        );
+        assert_eq!(status, status::BELOW_EXISTENTIAL_DEPOSIT);
+
+        // The contract needs to hang on to its own existential deposit.
+        let everything = Balances::free_balance(&contract);
+        let (status, _) = call(&contract, FUNC_TRANSFER_NATIVE, (false, bob.clone(), everything));
+        assert_eq!(status, status::WOULD_REAP_SENDER);
+
+        assert_eq!(Balances::free_balance(&bob), 0);
+        assert_eq!(Balances::free_balance(&contract), everything);
+    });
+}
+
+const ASSET: u32 = 1;
+
+// Creates `ASSET` with `contract` as its owner, which also makes it the asset's issuer.
+fn create_asset(contract: &AccountId) {
+    assert_ok!(Assets::force_create(
+        Origin::root(),
+        ASSET,
+        contract.clone().into(),
+        true,
+        1
+    ));
+}
+
+#[test]
+fn issuing_contracts_can_mint_and_transfer_assets() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        create_asset(&contract);
+
+        let (status, _) = call(
+            &contract,
+            FUNC_ASSET_MINT,
+            (false, ASSET, contract.clone(), 100u128),
+        );
+        assert_eq!(status, status::SUCCESS);
+
+        let (status, _) = call(&contract, FUNC_ASSET_TRANSFER, (false, ASSET, bob.clone(), 30u128));
+        assert_eq!(status, status::SUCCESS);
+
+        let (status, output) = call(&contract, FUNC_ASSET_BALANCE, (ASSET, bob));
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Balance::decode(&mut &output[..]).unwrap(), 30);
+        assert_eq!(Assets::balance(ASSET, &contract), 70);
+    });
+}
+
+#[test]
+fn asset_errors_get_their_own_status_codes() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+
+        let (status, _) = call(&contract, FUNC_ASSET_MINT, (false, ASSET, bob.clone(), 100u128));
+        assert_eq!(status, status::UNKNOWN_ASSET);
+
+        // Only the issuer gets to mint.
+        create_asset(&bob);
+        let (status, _) = call(&contract, FUNC_ASSET_MINT, (false, ASSET, bob.clone(), 100u128));
+        assert_eq!(status, status::NOT_PERMITTED);
+
+        let (status, _) = call(&contract, FUNC_ASSET_TRANSFER, (false, ASSET, bob, 100u128));
+        assert_eq!(status, status::ASSET_BALANCE_LOW);
+    });
+}
+
+#[test]
+fn approved_contracts_can_move_assets_on_behalf_of_their_owner() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        create_asset(&contract);
+        assert_ok!(Assets::mint(Origin::signed(contract.clone()), ASSET, ALICE.into(), 100));
+
+        let (status, _) = call(
+            &contract,
+            FUNC_ASSET_TRANSFER_APPROVED,
+            (false, ASSET, ALICE, bob.clone(), 30u128),
+        );
+        assert_eq!(status, status::ASSET_UNAPPROVED);
+
+        assert_ok!(Assets::approve_transfer(
+            Origin::signed(ALICE),
+            ASSET,
+            contract.clone().into(),
+            30
+        ));
+        let (status, _) = call(
+            &contract,
+            FUNC_ASSET_TRANSFER_APPROVED,
+            (false, ASSET, ALICE, bob.clone(), 30u128),
+        );
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Assets::balance(ASSET, &bob), 30);
+
+        // Transferring doesn't change how much of the asset there is overall.
+        let (status, output) = call(&contract, FUNC_ASSET_TOTAL_SUPPLY, ASSET);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Balance::decode(&mut &output[..]).unwrap(), 100);
+    });
+}
+
+const COLLECTION: u32 = 1;
+
+// Creates `COLLECTION` with `contract` as its owner, which also makes it the collection's issuer
+// and admin. Holding its NFTs is free, so nobody needs funds for deposits.
+fn create_collection(contract: &AccountId) {
+    assert_ok!(Uniques::force_create(
+        Origin::root(),
+        COLLECTION,
+        contract.clone().into(),
+        true
+    ));
+}
+
+fn nft_owner(contract: &AccountId, item: u32) -> Option<AccountId> {
+    let (status, output) = call(contract, FUNC_NFT_OWNER_OF, (COLLECTION, item));
+    assert_eq!(status, status::SUCCESS);
+    Option::<AccountId>::decode(&mut &output[..]).unwrap()
+}
+
+#[test]
+fn issuing_contracts_can_mint_and_transfer_nfts() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        create_collection(&contract);
+
+        // The metadata is the dynamically sized part of the input, and comes last.
+        let metadata = b"ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+        let (status, _) = call(
+            &contract,
+            FUNC_NFT_MINT,
+            (false, COLLECTION, 7u32, contract.clone(), metadata.to_vec()),
+        );
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(nft_owner(&contract, 7), Some(contract.clone()));
+
+        let (status, _) = call(
+            &contract,
+            FUNC_NFT_TRANSFER,
+            (false, COLLECTION, 7u32, bob.clone()),
+        );
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(nft_owner(&contract, 7), Some(bob));
+        assert_eq!(nft_owner(&contract, 8), None);
+    });
+}
+
+#[test]
+fn nft_errors_get_their_own_status_codes() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+
+        let (status, _) = call(
+            &contract,
+            FUNC_NFT_MINT,
+            (false, COLLECTION, 7u32, bob.clone(), Vec::<u8>::new()),
+        );
+        assert_eq!(status, status::UNKNOWN_NFT);
+
+        create_collection(&contract);
+        let (status, _) = call(
+            &contract,
+            FUNC_NFT_TRANSFER,
+            (false, COLLECTION, 7u32, bob.clone()),
+        );
+        assert_eq!(status, status::UNKNOWN_NFT);
+
+        let mint = |metadata: Vec<u8>| {
+            call(&contract, FUNC_NFT_MINT, (false, COLLECTION, 7u32, bob.clone(), metadata)).0
+        };
+
+        // Metadata which doesn't fit means nothing gets minted at all.
+        let limit = <Runtime as pallet_uniques::Config>::StringLimit::get() as usize;
+        assert_eq!(mint(vec![1u8; limit + 1]), status::NFT_OPERATION_FAILED);
+        assert_eq!(nft_owner(&contract, 7), None);
+
+        assert_eq!(mint(vec![1u8; limit]), status::SUCCESS);
+        assert_eq!(mint(Vec::new()), status::NFT_ALREADY_EXISTS);
+    });
+}
+
+#[test]
+fn quotes_match_what_gets_charged() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(&contract, FUNC_QUOTE, (FUNC_CUSTOM_TYPE_WITH_RESULT, 64u32));
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+        // Every call also pays for being counted and having its code checked.
+        let expected = ExtensionWeightInfo::<Runtime>::custom_type_with_result(64)
+            + ExtensionWeightInfo::<Runtime>::note_call()
+            + ExtensionWeightInfo::<Runtime>::check_code_hash();
+        assert_eq!(Weight::decode(&mut &output[..]).unwrap(), expected);
+        // The runtime API hands out the very same numbers.
+        assert_eq!(quote::<Runtime>(FUNC_CUSTOM_TYPE_WITH_RESULT, 64), Some(expected));
+    });
+}
+
+#[test]
+fn unknown_functions_cannot_be_quoted() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, FUNC_QUOTE, (0xDEADu32, 0u32));
+
+        assert_eq!(status, status::UNKNOWN_FUNCTION);
+        assert_eq!(quote::<Runtime>(0xDEAD, 0), None);
+    });
+}
+
+#[test]
+fn envelopes_can_be_stored_and_retrieved() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(
+            &contract,
+            FUNC_STORE_ENVELOPE,
+            (false, [7u8; 32], 10u32, vec![1u8; 100]),
+        );
+        assert_eq!(status, status::SUCCESS);
+        let id = u64::decode(&mut &output[..]).unwrap();
+
+        let (status, output) = call(&contract, FUNC_ENVELOPE, id);
+        assert_eq!(status, status::SUCCESS);
+        let envelope = Option::<Envelope<AccountId, BlockNumber>>::decode(&mut &output[..])
+            .unwrap()
+            .unwrap();
+        assert_eq!(envelope.owner, contract);
+        assert_eq!(envelope.public_key, [7u8; 32]);
+        assert_eq!(envelope.expires_at, 10);
+        assert_eq!(envelope.ciphertext, vec![1u8; 100]);
+
+        let (status, output) = call(&contract, FUNC_ENVELOPE, id + 1);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            Option::<Envelope<AccountId, BlockNumber>>::decode(&mut &output[..]).unwrap(),
+            None
+        );
+    });
+}
+
+#[test]
+fn expired_envelopes_cannot_be_read() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // We start out at block 1.
+        let (status, _) = call(&contract, FUNC_STORE_ENVELOPE, (false, [7u8; 32], 1u32, vec![1u8]));
+        assert_eq!(status, status::ENVELOPE_EXPIRED);
+
+        let (_, output) = call(&contract, FUNC_STORE_ENVELOPE, (false, [7u8; 32], 3u32, vec![1u8]));
+        let id = u64::decode(&mut &output[..]).unwrap();
+
+        run_to_block(3);
+        let (status, _) = call(&contract, FUNC_ENVELOPE, id);
+        assert_eq!(status, status::ENVELOPE_EXPIRED);
+    });
+}
+
+#[test]
+fn custom_type_with_result_works() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(
+            &contract,
+            FUNC_CUSTOM_TYPE_WITH_RESULT,
+            (
+                false,
+                Custom {
+                    inner: vec![1, 2, 3, 4],
+                },
+            ),
+        );
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::something(), Some(4));
+    });
+}
+
+#[test]
+fn custom_type_with_result_rejects_bad_lengths() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(
+            &contract,
+            FUNC_CUSTOM_TYPE_WITH_RESULT,
+            (
+                false,
+                Custom {
+                    inner: vec![1, 2, 3],
+                },
+            ),
+        );
+
+        assert_eq!(status, status::CUSTOM_CALL_FAILED);
+        assert_eq!(Template::something(), None);
+    });
+}
+
+#[test]
+fn rejected_custom_values_get_a_refund() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // We charge for a write up front, but hand it back if the length turns out to be bad.
+        let gas_used = |len: usize| {
+            let mut data = FUNC_CUSTOM_TYPE_WITH_RESULT.encode();
+            let custom = Custom {
+                inner: vec![0; len],
+            };
+            (false, custom).encode_to(&mut data);
+            Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false)
+                .gas_consumed
+        };
+
+        // Even though its input is longer, the rejected call comes out cheaper.
+        assert!(gas_used(5) < gas_used(4));
+        assert_eq!(Template::something(), Some(4));
+    });
+}
+
+#[test]
+fn custom_values_pay_for_their_length() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // Both of these get stored, the only thing setting them apart is how much we had to decode.
+        let gas_used = |len: usize| {
+            let mut data = FUNC_CUSTOM_TYPE_WITH_RESULT.encode();
+            let custom = Custom {
+                inner: vec![0; len],
+            };
+            (false, custom).encode_to(&mut data);
+            Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false)
+                .gas_consumed
+        };
+
+        let charged = ExtensionWeightInfo::<Runtime>::custom_type_with_result;
+        assert!(gas_used(4096) - gas_used(4) >= charged(4096) - charged(4));
+    });
+}
+
+#[test]
+fn bounded_custom_type_works() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let custom = Custom {
+            inner: vec![0; MAX_BOUNDED_CUSTOM_LEN as usize],
+        };
+
+        let (status, _) = call(&contract, FUNC_BOUNDED_CUSTOM_TYPE, (false, custom));
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::something(), Some(MAX_BOUNDED_CUSTOM_LEN));
+    });
+}
+
+#[test]
+fn bounded_custom_type_rejects_bad_lengths() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let custom = Custom {
+            inner: vec![1, 2, 3],
+        };
+
+        let (status, _) = call(&contract, FUNC_BOUNDED_CUSTOM_TYPE, (false, custom));
+
+        assert_eq!(status, status::CUSTOM_CALL_FAILED);
+        assert_eq!(Template::something(), None);
+    });
+}
+
+#[test]
+fn overlong_bounded_custom_values_trap() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // Unlike with `custom_type_with_result()` there's no status code for this, the value
+        // simply doesn't decode.
+        let mut data = FUNC_BOUNDED_CUSTOM_TYPE.encode();
+        let custom = Custom {
+            inner: vec![0; MAX_BOUNDED_CUSTOM_LEN as usize * 2],
+        };
+        (false, custom).encode_to(&mut data);
+        let result =
+            Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false).result;
+
+        assert!(result.is_err());
+        assert_eq!(Template::something(), None);
+    });
+}
+
+// Builds the input of `rich_custom_type()`, with `only_for` as the contract would encode it.
+fn rich_custom(measure: Measure, only_for: Option<[u8; 32]>, payload: Vec<u8>) -> impl Encode {
+    let custom = RichCustom {
+        measure,
+        only_for,
+        payload: Custom {
+            inner: payload,
+        },
+    };
+    (false, custom)
+}
+
+#[test]
+fn rich_custom_type_works() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let input = rich_custom(Measure::Sum, None, vec![1, 2, 3]);
+        let (status, _) = call(&contract, FUNC_RICH_CUSTOM_TYPE, input);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::something(), Some(6));
+
+        let input = rich_custom(Measure::Nth(1), None, vec![1, 2, 3]);
+        let (status, _) = call(&contract, FUNC_RICH_CUSTOM_TYPE, input);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::something(), Some(2));
+    });
+}
+
+#[test]
+fn rich_custom_type_decodes_accounts_like_the_contract_encodes_them() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let input = rich_custom(Measure::Length, Some([2u8; 32]), vec![1, 2, 3]);
+        let (status, _) = call(&contract, FUNC_RICH_CUSTOM_TYPE, input);
+        assert_eq!(status, status::NOT_PERMITTED);
+        assert_eq!(Template::something(), None);
+
+        let input = rich_custom(Measure::Length, Some(ALICE.into()), vec![1, 2, 3]);
+        let (status, _) = call(&contract, FUNC_RICH_CUSTOM_TYPE, input);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::something(), Some(3));
+    });
+}
+
+#[test]
+fn rich_custom_type_rejects_measures_which_dont_fit() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let input = rich_custom(Measure::Nth(3), None, vec![1, 2, 3]);
+        let (status, _) = call(&contract, FUNC_RICH_CUSTOM_TYPE, input);
+
+        assert_eq!(status, status::CUSTOM_CALL_FAILED);
+        assert_eq!(Template::something(), None);
+    });
+}
+
+#[test]
+fn schedule_call_adds_to_agenda_and_fires() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let status = schedule(&contract, 5);
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(5).len(), 1);
+
+        run_to_block(5);
+
+        // The callback went through `pallet_template`, which recorded its weight.
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(5).is_empty());
+        assert_eq!(Template::callback_weights(&contract).count, 1);
+    });
+}
+
+#[test]
+fn schedule_call_hands_back_where_the_callback_went() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+
+        for index in 0..2 {
+            let (_, output) = call(&contract, FUNC_SCHEDULE_CALL, input);
+            let scheduled =
+                ScheduledCallback::<BlockNumber, crate::Hash>::decode(&mut &output[..]).unwrap();
+            assert_eq!(scheduled.address, (5, index));
+        }
+
+        // Which is what it takes to move the callback.
+        let (status, _) = call(&contract, FUNC_RESCHEDULE, (false, 5u32, 1u32, 8u32));
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(8).len(), 1);
+    });
+}
+
+#[test]
+fn callbacks_can_be_scheduled_relative_to_the_current_block() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        run_to_block(3);
+
+        let input = (
+            false,
+            4u32,
+            DEFAULT_CALLBACK_SELECTOR,
+            CALLBACK_GAS_LIMIT,
+            None::<Balance>,
+        );
+        let (status, output) = call(&contract, FUNC_SCHEDULE_IN, input);
+        assert_eq!(status, status::SUCCESS);
+        let scheduled =
+            ScheduledCallback::<BlockNumber, crate::Hash>::decode(&mut &output[..]).unwrap();
+        assert_eq!(scheduled.address, (7, 0));
+        assert_eq!(Template::contract_callbacks_in(7, &contract), 1);
+    });
+}
+
+#[test]
+fn schedule_in_needs_a_future_block() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        for blocks in [0, u32::MAX] {
+            let input = (
+                false,
+                blocks,
+                DEFAULT_CALLBACK_SELECTOR,
+                CALLBACK_GAS_LIMIT,
+                None::<Balance>,
+            );
+            let (status, _) = call(&contract, FUNC_SCHEDULE_IN, input);
+            assert_eq!(status, status::INVALID_SCHEDULE_DELAY);
+        }
+
+        // Neither attempt took a slot or counted towards the quota.
+        assert_eq!(
+            Template::schedule_quota_left(&contract),
+            MaxSchedulesPerPeriod::get()
+        );
+    });
+}
+
+#[test]
+fn callbacks_only_invoke_allowed_selectors() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let selector = [0xDE, 0xAD, 0xBE, 0xEF];
+
+        let input = (false, 5u32, selector, CALLBACK_GAS_LIMIT, None::<Balance>);
+        let (status, _) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::INVALID_CALLBACK_SELECTOR);
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(5).is_empty());
+        assert_eq!(Template::contract_callbacks_in(5, &contract), 0);
+
+        assert_ok!(Template::set_callback_selectors(Origin::root(), vec![selector]));
+
+        let (status, _) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(5).len(), 1);
+
+        run_to_block(5);
+        assert_eq!(Template::callback_weights(&contract).count, 1);
+    });
+}
+
+#[test]
+fn only_allowed_contracts_schedule_once_governance_says_so() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let other = deploy_fixture_with_salt("chain_extension", vec![1]);
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+
+        assert_ok!(Template::set_scheduler_allowlist(Origin::root(), Some(vec![other.clone()])));
+
+        for func_id in [FUNC_SCHEDULE_CALL, FUNC_SCHEDULE_IN] {
+            let (status, _) = call(&contract, func_id, input);
+            assert_eq!(status, status::NOT_ALLOWED_TO_SCHEDULE);
+        }
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(5).is_empty());
+        assert_eq!(
+            Template::schedule_quota_left(&contract),
+            MaxSchedulesPerPeriod::get()
+        );
+
+        let (status, _) = call(&other, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+
+        // Lifting the restriction lets everyone back in.
+        assert_ok!(Template::set_scheduler_allowlist(Origin::root(), None));
+        let (status, _) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(5).len(), 2);
+    });
+}
+
+#[test]
+fn scheduling_is_announced_with_a_runtime_event() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+
+        let (status, _) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+
+        let scheduled = crate::Event::Template(pallet_template::Event::ContractScheduled(
+            contract, ALICE, 5,
+        ));
+        assert!(System::events().iter().any(|record| record.event == scheduled));
+    });
+}
+
+#[test]
+fn callbacks_leave_receipts_behind() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+        let (_, output) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        let handle = ScheduledCallback::<BlockNumber, crate::Hash>::decode(&mut &output[..])
+            .unwrap()
+            .handle;
+
+        // Nothing to see until the callback has run.
+        let (_, output) = call(&contract, FUNC_CALLBACK_RECEIPT, handle);
+        assert_eq!(Option::<ReceiptOf>::decode(&mut &output[..]).unwrap(), None);
+
+        run_to_block(5);
+
+        let (status, output) = call(&contract, FUNC_CALLBACK_RECEIPT, handle);
+        assert_eq!(status, status::SUCCESS);
+        let receipt = Option::<ReceiptOf>::decode(&mut &output[..]).unwrap().unwrap();
+        assert_eq!(receipt.block, 5);
+        assert!(receipt.success);
+        assert_eq!(receipt.weight, Template::callback_weights(&contract).max);
+
+        // Receipts are only kept around for so long.
+        run_to_block(5 + <Runtime as pallet_template::Config>::ReceiptRetention::get());
+        let (_, output) = call(&contract, FUNC_CALLBACK_RECEIPT, handle);
+        assert_eq!(Option::<ReceiptOf>::decode(&mut &output[..]).unwrap(), None);
+    });
+}
+
+#[test]
+fn callbacks_keep_their_storage_deposit_limit() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let limit: Option<Balance> = Some(42);
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, limit);
+
+        let (status, _) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+
+        let scheduled = pallet_scheduler::Agenda::<Runtime>::get(5)[0].clone().unwrap();
+        match scheduled.call.as_value().unwrap() {
+            crate::Call::Template(pallet_template::Call::dispatch_callback { call, .. }) => {
+                match &**call {
+                    crate::Call::Contracts(pallet_contracts::Call::call {
+                        storage_deposit_limit,
+                        ..
+                    }) => assert_eq!(storage_deposit_limit.map(|limit| limit.0), limit),
+                    _ => panic!("expected a contract call"),
+                }
+            }
+            _ => panic!("expected a callback"),
+        }
+    });
+}
+
+#[test]
+fn callbacks_tell_the_contract_their_handle() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+        call(&contract, FUNC_SCHEDULE_CALL, input);
+        let (_, output) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        let handle = ScheduledCallback::<BlockNumber, crate::Hash>::decode(&mut &output[..])
+            .unwrap()
+            .handle;
+        assert_eq!(handle, 1);
+
+        let scheduled = pallet_scheduler::Agenda::<Runtime>::get(5)[1].clone().unwrap();
+        match scheduled.call.as_value().unwrap() {
+            crate::Call::Template(pallet_template::Call::dispatch_callback { call, .. }) => {
+                match &**call {
+                    crate::Call::Contracts(pallet_contracts::Call::call { data, .. }) => {
+                        let mut expected = DEFAULT_CALLBACK_SELECTOR.to_vec();
+                        (15663040u32, handle).encode_to(&mut expected);
+                        assert_eq!(*data, expected);
+                    }
+                    _ => panic!("expected a contract call"),
+                }
+            }
+            _ => panic!("expected a callback"),
+        }
+    });
+}
+
+#[test]
+fn scheduled_callbacks_survive_a_runtime_upgrade() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+        let (status, output) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+        let handle = ScheduledCallback::<BlockNumber, crate::Hash>::decode(&mut &output[..])
+            .unwrap()
+            .handle;
+
+        run_to_block(3);
+
+        // Runs our `Migrations` as well as the `on_runtime_upgrade` hooks of every pallet, which is
+        // exactly what happens when a new runtime gets enacted. This one also changes the
+        // callback we wrapped up for the contract.
+        Executive::execute_on_runtime_upgrade();
+        halve_callback_gas_limits();
+
+        let mut scheduled = pallet_scheduler::Agenda::<Runtime>::get(5)[0].clone().unwrap();
+        assert_eq!(
+            callback_gas_limit(&mut scheduled.call).copied(),
+            Some(CALLBACK_GAS_LIMIT / 2)
+        );
+        assert_eq!(Template::callback_origin(handle), Some((contract.clone(), contract.clone())));
+
+        run_to_block(5);
+
+        // The contract still got called, with whatever gas the upgrade left it.
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(5).is_empty());
+        assert_eq!(Template::callback_weights(&contract).count, 1);
+        let (_, output) = call(&contract, FUNC_CALLBACK_RECEIPT, handle);
+        let receipt = Option::<ReceiptOf>::decode(&mut &output[..]).unwrap().unwrap();
+        assert_eq!(receipt.block, 5);
+        assert!(receipt.success);
+        assert!(receipt.weight <= CALLBACK_GAS_LIMIT / 2);
+    });
+}
+
+#[test]
+fn callbacks_are_dispatched_on_behalf_of_the_contract() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+        let (status, _) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+
+        // Rather than on behalf of whoever scheduled them, who could otherwise call the contract
+        // the same way before the callback is due.
+        let scheduled = pallet_scheduler::Agenda::<Runtime>::get(5)[0].clone().unwrap();
+        assert_eq!(
+            scheduled.origin,
+            crate::OriginCaller::system(frame_system::RawOrigin::Signed(contract.clone()))
+        );
+        assert_eq!(
+            Template::callback_origin(0),
+            Some((contract.clone(), contract))
+        );
+    });
+}
+
+#[test]
+fn callbacks_receive_the_value_they_were_scheduled_with() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let value = 10 * ExistentialDeposit::get();
+        let balance = Balances::free_balance(&contract);
+        let held = Balances::reserved_balance(&contract);
+        let reserved = Balances::reserved_balance(&ALICE);
+
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+        let (status, _) = call_with_value(&contract, value, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+
+        // Until the callback runs the value is held in reserve by the contract, while whoever
+        // sent it puts down the deposit for the callback...
+        let deposit = CallbackDeposit::get();
+        assert_eq!(Balances::free_balance(&contract), balance);
+        assert_eq!(Balances::reserved_balance(&contract), held + value);
+        assert_eq!(Balances::reserved_balance(&ALICE), reserved + deposit);
+        assert_eq!(Template::callback_escrow(0), Some((contract.clone(), value)));
+
+        run_to_block(5);
+
+        // ...and then it's sent along with the callback, which the contract makes to itself.
+        assert_eq!(Template::callback_escrow(0), None);
+        assert_eq!(Balances::reserved_balance(&contract), held);
+        assert_eq!(Balances::reserved_balance(&ALICE), reserved);
+        assert_eq!(Balances::free_balance(&contract), balance + value);
+    });
+}
+
+#[test]
+fn callback_deposits_are_held_until_the_callback_runs() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let reserved = Balances::reserved_balance(&ALICE);
+
+        assert_eq!(schedule(&contract, 5), status::SUCCESS);
+        assert_eq!(
+            Balances::reserved_balance(&ALICE),
+            reserved + CallbackDeposit::get()
+        );
+        assert_eq!(
+            Template::callback_deposit(0),
+            Some((ALICE, CallbackDeposit::get()))
+        );
+
+        run_to_block(5);
+
+        assert_eq!(Balances::reserved_balance(&ALICE), reserved);
+        assert_eq!(Template::callback_deposit(0), None);
+    });
+}
+
+#[test]
+fn long_callbacks_only_leave_their_hash_in_the_agenda() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+
+        // Our callbacks are short enough to go into the agenda as they are...
+        let (_, output) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        let scheduled =
+            ScheduledCallback::<BlockNumber, crate::Hash>::decode(&mut &output[..]).unwrap();
+        assert_eq!(scheduled.preimage, None);
+
+        // ...unless governance decides that they're not.
+        MaxInlineCallbackLen::set(&0);
+        let (status, output) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::SUCCESS);
+        let scheduled =
+            ScheduledCallback::<BlockNumber, crate::Hash>::decode(&mut &output[..]).unwrap();
+        let hash = scheduled
+            .preimage
+            .expect("the callback is longer than we allow");
+
+        let agenda = pallet_scheduler::Agenda::<Runtime>::get(5);
+        assert_eq!(agenda[1].clone().unwrap().call, MaybeHashed::Hash(hash));
+        assert!(pallet_preimage::PreimageFor::<Runtime>::contains_key(hash));
+
+        run_to_block(5);
+
+        // Both callbacks ran, and the preimage was cleared once it was no longer needed.
+        assert_eq!(Template::callback_weights(&contract).count, 2);
+        assert!(!pallet_preimage::PreimageFor::<Runtime>::contains_key(hash));
+    });
+}
+
+#[test]
+fn callback_preimages_are_paid_for_until_the_callback_runs() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let reserved = Balances::reserved_balance(&ALICE);
+
+        MaxInlineCallbackLen::set(&0);
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, CALLBACK_GAS_LIMIT, None::<Balance>);
+        let (_, output) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        let scheduled =
+            ScheduledCallback::<BlockNumber, crate::Hash>::decode(&mut &output[..]).unwrap();
+        let hash = scheduled.preimage.unwrap();
+
+        // The Preimage pallet doesn't hold on to a deposit of its own for the callback, we do.
+        let len = pallet_preimage::PreimageFor::<Runtime>::get(hash)
+            .unwrap()
+            .len() as Balance;
+        let deposit =
+            CallbackDeposit::get() + PreimageBaseDeposit::get() + len * PreimageByteDeposit::get();
+        assert_eq!(Balances::reserved_balance(&ALICE), reserved + deposit);
+        assert_eq!(Template::callback_deposit(0), Some((ALICE, deposit)));
+
+        run_to_block(5);
+
+        assert_eq!(Balances::reserved_balance(&ALICE), reserved);
+    });
+}
+
+#[test]
+fn callbacks_need_a_deposit() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        Balances::make_free_balance_be(&ALICE, CallbackDeposit::get() / 2);
+        let reserved = Balances::reserved_balance(&ALICE);
+
+        assert_eq!(schedule(&contract, 5), status::INSUFFICIENT_DEPOSIT);
+
+        // Nothing is left behind from the attempt.
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(5).is_empty());
+        assert_eq!(Template::callbacks_in(5), 0);
+        assert_eq!(Balances::reserved_balance(&ALICE), reserved);
+    });
+}
+
+#[test]
+fn callbacks_can_be_moved_to_a_later_block() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        assert_eq!(schedule(&contract, 5), status::SUCCESS);
+        assert_eq!(Template::callback_address(0), Some((5, 0)));
+
+        let (status, output) = call(&contract, FUNC_RESCHEDULE, (false, 5u32, 0u32, 8u32));
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(<(u32, u32)>::decode(&mut &output[..]).unwrap(), (8, 0));
+
+        // The callback took its slot along with it.
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(5)[0].is_none());
+        assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(8).len(), 1);
+        assert_eq!(Template::contract_callbacks_in(5, &contract), 0);
+        assert_eq!(Template::contract_callbacks_in(8, &contract), 1);
+        assert_eq!(Template::callback_address(0), Some((8, 0)));
+
+        run_to_block(5);
+        assert_eq!(Template::callback_weights(&contract).count, 0);
+
+        run_to_block(8);
+        assert_eq!(Template::callback_weights(&contract).count, 1);
+        assert_eq!(Template::callback_address(0), None);
+    });
+}
+
+#[test]
+fn callbacks_can_be_moved_by_handle() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // Callbacks which went by hash can be moved just the same.
+        MaxInlineCallbackLen::set(&0);
+        assert_eq!(schedule(&contract, 5), status::SUCCESS);
+        assert_eq!(schedule(&contract, 5), status::SUCCESS);
+
+        let (status, output) = call(&contract, FUNC_RESCHEDULE_CALLBACK, (false, 1u64, 7u32));
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(<(u32, u32)>::decode(&mut &output[..]).unwrap(), (7, 0));
+
+        // We keep track of where the callback went, so it can be moved again.
+        let (status, output) = call(&contract, FUNC_RESCHEDULE_CALLBACK, (false, 1u64, 9u32));
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(<(u32, u32)>::decode(&mut &output[..]).unwrap(), (9, 0));
+
+        run_to_block(9);
+        assert_eq!(Template::receipt(0).unwrap().block, 5);
+        assert_eq!(Template::receipt(1).unwrap().block, 9);
+    });
+}
+
+#[test]
+fn contracts_can_only_move_their_own_callbacks() {
+    new_test_ext().execute_with(|| {
+        let contracts = deploy_many(2);
+        assert_eq!(schedule(&contracts[0], 5), status::SUCCESS);
+
+        let input = (false, 5u32, 0u32, 8u32);
+        let (status, _) = call(&contracts[1], FUNC_RESCHEDULE, input);
+        assert_eq!(status, status::UNKNOWN_CALLBACK);
+        let (status, _) = call(&contracts[1], FUNC_RESCHEDULE_CALLBACK, (false, 0u64, 8u32));
+        assert_eq!(status, status::UNKNOWN_CALLBACK);
+
+        // Neither can callbacks which don't exist be moved.
+        let (status, _) = call(&contracts[0], FUNC_RESCHEDULE, (false, 5u32, 1u32, 8u32));
+        assert_eq!(status, status::UNKNOWN_CALLBACK);
+        let (status, _) = call(&contracts[0], FUNC_RESCHEDULE_CALLBACK, (false, 1u64, 8u32));
+        assert_eq!(status, status::UNKNOWN_CALLBACK);
+
+        assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(5).len(), 1);
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(8).is_empty());
+        assert_eq!(Template::callback_address(0), Some((5, 0)));
+    });
+}
+
+#[test]
+fn callbacks_cannot_be_moved_into_the_past() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        assert_eq!(schedule(&contract, 5), status::SUCCESS);
+        run_to_block(3);
+
+        let (status, _) = call(&contract, FUNC_RESCHEDULE, (false, 5u32, 0u32, 2u32));
+        assert_eq!(status, status::SCHEDULED_IN_PAST);
+
+        // Moving a callback to where it already is doesn't do anything either.
+        let (status, _) = call(&contract, FUNC_RESCHEDULE, (false, 5u32, 0u32, 5u32));
+        assert_eq!(status, status::SCHEDULING_FAILED);
+
+        // Whatever was done before the Scheduler turned us down got rolled back.
+        assert_eq!(Template::callbacks_in(2), 0);
+        assert_eq!(Template::contract_callbacks_in(5, &contract), 1);
+        assert_eq!(Template::callback_address(0), Some((5, 0)));
+    });
+}
+
+#[test]
+fn chain_constants_come_from_the_runtime() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(&contract, FUNC_CHAIN_CONSTANTS, ());
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            ChainConstants::<Balance>::decode(&mut &output[..]).unwrap(),
+            ChainConstants {
+                existential_deposit: ExistentialDeposit::get(),
+                ss58_prefix: 42,
+                token_decimals: 12,
+                maximum_scheduler_weight: MaximumSchedulerWeight::get(),
+            }
+        );
+    });
+}
+
+#[test]
+fn callback_deposit_comes_from_the_runtime() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(&contract, FUNC_CALLBACK_DEPOSIT, ());
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            Balance::decode(&mut &output[..]).unwrap(),
+            CallbackDeposit::get()
+        );
+    });
+}
+
+#[test]
+fn callbacks_cannot_be_scheduled_in_the_past() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // The Scheduler turns this down, which gets us a status code rather than a trap.
+        assert_eq!(schedule(&contract, 1), status::SCHEDULED_IN_PAST);
+
+        // Whatever was done before the Scheduler turned us down got rolled back.
+        assert_eq!(Template::callbacks_in(1), 0);
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(1).is_empty());
+    });
+}
+
+#[test]
+fn callback_gas_limits_are_capped() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let max = <Runtime as pallet_template::Config>::MaxCallbackGasLimit::get();
+
+        let input = (false, 5u32, DEFAULT_CALLBACK_SELECTOR, max + 1, None::<Balance>);
+        let (status, _) = call(&contract, FUNC_SCHEDULE_CALL, input);
+        assert_eq!(status, status::CALLBACK_GAS_LIMIT_TOO_HIGH);
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(5).is_empty());
+
+        assert_eq!(schedule(&contract, 5), status::SUCCESS);
+
+        // The callback's weight is down to the limit we picked, not to how much gas we had left
+        // while scheduling it.
+        let scheduled = pallet_scheduler::Agenda::<Runtime>::get(5)[0].clone().unwrap();
+        let weight = scheduled.call.as_value().unwrap().get_dispatch_info().weight;
+        assert!(weight >= CALLBACK_GAS_LIMIT);
+        assert!(weight < 2 * CALLBACK_GAS_LIMIT);
+    });
+}
+
+#[test]
+fn randomness_changes_across_blocks_and_subjects() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let random = |subject: &[u8]| {
+            let (status, output) = call(&contract, FUNC_RANDOM, subject.to_vec());
+            assert_eq!(status, status::SUCCESS);
+            crate::Hash::decode(&mut &output[..]).unwrap()
+        };
+
+        let first = random(b"lottery");
+        assert_eq!(random(b"lottery"), first);
+        assert_ne!(random(b"raffle"), first);
+
+        run_to_block(2);
+        assert_ne!(random(b"lottery"), first);
+    });
+}
+
+#[test]
+fn sr25519_signatures_are_verified() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let pair = sr25519::Pair::from_seed(&[1; 32]);
+        let message = b"pay bob 10 units".to_vec();
+        let signature = pair.sign(&message);
+
+        let (status, _) =
+            call(&contract, FUNC_VERIFY_SR25519, (&signature, &message, pair.public()));
+        assert_eq!(status, status::SUCCESS);
+
+        let forged = b"pay bob 99 units".to_vec();
+        let (status, _) =
+            call(&contract, FUNC_VERIFY_SR25519, (&signature, &forged, pair.public()));
+        assert_eq!(status, status::INVALID_SIGNATURE);
+    });
+}
+
+#[test]
+fn ecdsa_signatures_are_verified() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let pair = ecdsa::Pair::from_seed(&[1; 32]);
+        let message = b"pay bob 10 units".to_vec();
+        let signature = pair.sign(&message);
+
+        let (status, _) = call(&contract, FUNC_VERIFY_ECDSA, (&signature, &message, pair.public()));
+        assert_eq!(status, status::SUCCESS);
+
+        let other = ecdsa::Pair::from_seed(&[2; 32]);
+        let (status, _) =
+            call(&contract, FUNC_VERIFY_ECDSA, (&signature, &message, other.public()));
+        assert_eq!(status, status::INVALID_SIGNATURE);
+    });
+}
+
+#[test]
+fn commitments_can_be_revealed_during_their_window() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let hash = BlakeTwo256::hash(b"secret");
+
+        // We're at block 1, so the window is blocks 2 and 3.
+        let (status, output) = call(&contract, FUNC_COMMIT, (false, hash, 1u32, 2u32));
+        assert_eq!(status, status::SUCCESS);
+        let id = u64::decode(&mut &output[..]).unwrap();
+
+        let (status, _) = call(&contract, FUNC_REVEAL, (false, id, b"secret".to_vec()));
+        assert_eq!(status, status::REVEAL_TOO_EARLY);
+
+        run_to_block(2);
+        let (status, _) = call(&contract, FUNC_REVEAL, (false, id, b"guess".to_vec()));
+        assert_eq!(status, status::INVALID_REVEAL);
+        let (status, _) = call(&contract, FUNC_REVEAL, (true, id, b"secret".to_vec()));
+        assert_eq!(status, status::SUCCESS);
+        assert!(Template::commitment(id).is_some());
+        let (status, _) = call(&contract, FUNC_REVEAL, (false, id, b"secret".to_vec()));
+        assert_eq!(status, status::SUCCESS);
+
+        let (status, _) = call(&contract, FUNC_REVEAL, (false, id, b"secret".to_vec()));
+        assert_eq!(status, status::UNKNOWN_COMMITMENT);
+    });
+}
+
+#[test]
+fn unrevealed_commitments_get_cleaned_up() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let hash = BlakeTwo256::hash(b"secret");
+
+        let (_, output) = call(&contract, FUNC_COMMIT, (false, hash, 1u32, 2u32));
+        let id = u64::decode(&mut &output[..]).unwrap();
+
+        run_to_block(4);
+        let (status, _) = call(&contract, FUNC_REVEAL, (false, id, b"secret".to_vec()));
+        assert_eq!(status, status::REVEAL_TOO_LATE);
+
+        // The Scheduler removes the commitment once the retention period is over.
+        let retention = <Runtime as pallet_template::Config>::CommitmentRetention::get();
+        run_to_block(4 + retention);
+        assert_eq!(Template::commitment(id), None);
+    });
+}
+
+// Deploys `n` copies of our fixture.
+fn deploy_many(n: u8) -> Vec<AccountId> {
+    (0..n)
+        .map(|i| deploy_fixture_with_salt("chain_extension", vec![i]))
+        .collect()
+}
+
+type MaxCallbacksPerContract = <Runtime as pallet_template::Config>::MaxCallbacksPerContract;
+type MaxCallbackDelay = <Runtime as pallet_template::Config>::MaxCallbackDelay;
+type MaxSchedulesPerPeriod = <Runtime as pallet_template::Config>::MaxSchedulesPerPeriod;
+type ReceiptOf = pallet_template::ReceiptOf<Runtime>;
+
+#[test]
+fn saturated_blocks_overflow_into_the_next_one() {
+    new_test_ext().execute_with(|| {
+        let max_per_block = crate::MaxScheduledPerBlock::get();
+        let max_per_contract = MaxCallbacksPerContract::get();
+
+        // Just enough contracts to fill up a block on their own, plus one.
+        let contracts = deploy_many((max_per_block / max_per_contract) as u8 + 1);
+        for contract in &contracts {
+            for _ in 0..max_per_contract {
+                let status = schedule(contract, 10);
+                assert_eq!(status, status::SUCCESS);
+            }
+        }
+
+        // The agenda never goes past what the Scheduler is configured for.
+        assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(10).len() as u32, max_per_block);
+        assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(11).len() as u32, max_per_contract);
+        let last = contracts.last().unwrap();
+        assert_eq!(Template::contract_callbacks_in(10, last), 0);
+        assert_eq!(Template::contract_callbacks_in(11, last), max_per_contract);
+
+        // Every single callback still gets to run. Since each one is allowed to use a lot of gas
+        // the Scheduler may push some of them back further on its own, so we give it some time.
+        run_to_block(30);
+        for contract in &contracts {
+            assert_eq!(Template::callback_weights(contract).count, max_per_contract);
+        }
+    });
+}
+
+#[test]
+fn agenda_full_once_no_block_has_room() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let slots = MaxCallbacksPerContract::get() * (MaxCallbackDelay::get() + 1);
+
+        for _ in 0..slots {
+            let status = schedule(&contract, 10);
+            assert_eq!(status, status::SUCCESS);
+        }
+
+        let status = schedule(&contract, 10);
+        assert_eq!(status, status::AGENDA_FULL);
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(10 + MaxCallbackDelay::get() + 1)
+            .is_empty());
+    });
+}
+
+#[test]
+fn contracts_only_get_to_schedule_so_much_per_period() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // Spreading the calls out over many blocks keeps them clear of `AGENDA_FULL`.
+        let per_block = MaxCallbacksPerContract::get();
+        for i in 0..MaxSchedulesPerPeriod::get() {
+            assert_eq!(schedule(&contract, 20 + i / per_block), status::SUCCESS);
+        }
+        assert_eq!(schedule(&contract, 100), status::SCHEDULING_QUOTA_EXCEEDED);
+        assert!(pallet_scheduler::Agenda::<Runtime>::get(100).is_empty());
+
+        // The quota is back once the next period starts.
+        run_to_block(<Runtime as pallet_template::Config>::SchedulingPeriod::get());
+        assert_eq!(schedule(&contract, 100), status::SUCCESS);
+    });
+}
+
+#[test]
+fn no_contract_can_starve_the_others() {
+    new_test_ext().execute_with(|| {
+        let contracts = deploy_many(2);
+        let (greedy, other) = (&contracts[0], &contracts[1]);
+
+        // The greedy contract takes every slot it can get its hands on...
+        while schedule(greedy, 10) == status::SUCCESS {}
+
+        // ...but that still leaves room for everyone else, right at the block they asked for.
+        let status = schedule(other, 10);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::contract_callbacks_in(10, other), 1);
+    });
+}
+
+#[test]
+fn cooperating_contracts_can_share_a_counter() {
+    new_test_ext().execute_with(|| {
+        let contracts = deploy_many(3);
+        let (admin, member, outsider) = (&contracts[0], &contracts[1], &contracts[2]);
+        let name = b"orders".to_vec();
+        let increment = |contract| call(contract, FUNC_COUNTER_INCREMENT, (false, &name));
+
+        let (status, _) = call(admin, FUNC_COUNTER_CREATE, (false, &name));
+        assert_eq!(status, status::SUCCESS);
+        let (status, _) = call(outsider, FUNC_COUNTER_CREATE, (false, &name));
+        assert_eq!(status, status::COUNTER_EXISTS);
+
+        let (status, _) =
+            call(admin, FUNC_COUNTER_SET_ACCESS, (false, &name, member.clone(), true));
+        assert_eq!(status, status::SUCCESS);
+        let (status, _) =
+            call(member, FUNC_COUNTER_SET_ACCESS, (false, &name, outsider.clone(), true));
+        assert_eq!(status, status::NOT_PERMITTED);
+
+        // Whoever increments the counter, every value gets handed out exactly once.
+        assert_eq!(increment(admin), (status::SUCCESS, 1u64.encode()));
+        assert_eq!(increment(member), (status::SUCCESS, 2u64.encode()));
+        assert_eq!(increment(outsider).0, status::NOT_PERMITTED);
+
+        // Anyone can look, though.
+        let (status, output) = call(outsider, FUNC_COUNTER_VALUE, &name);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Option::<u64>::decode(&mut &output[..]).unwrap(), Some(2));
+
+        let (status, _) = call(admin, FUNC_COUNTER_INCREMENT, (false, b"invoices".to_vec()));
+        assert_eq!(status, status::UNKNOWN_COUNTER);
+    });
+}
+
+#[test]
+fn counter_names_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let max_len = <Runtime as pallet_template::Config>::MaxCounterNameLen::get();
+
+        let (status, _) =
+            call(&contract, FUNC_COUNTER_CREATE, (false, vec![0u8; max_len as usize + 1]));
+        assert_eq!(status, status::COUNTER_NAME_TOO_LONG);
+    });
+}
+
+#[test]
+fn capabilities_are_reported() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(&contract, FUNC_CAPABILITIES, ());
+
+        assert_eq!(status, status::SUCCESS);
+        let reported = u32::decode(&mut &output[..]).unwrap();
+        assert_ne!(reported & capabilities::SCHEDULE_CALL, 0);
+        assert_ne!(reported & capabilities::CALLBACK_WEIGHTS, 0);
+
+        let (status, output) = call(&contract, FUNC_MORE_CAPABILITIES, ());
+
+        assert_eq!(status, status::SUCCESS);
+        let reported = u32::decode(&mut &output[..]).unwrap();
+        assert_ne!(reported & more_capabilities::CONTRACT_INFO, 0);
+        assert_ne!(reported & more_capabilities::CALL_CONTRACT, 0);
+        assert_ne!(reported & more_capabilities::INSTANTIATE_CONTRACT, 0);
+        assert_ne!(reported & more_capabilities::DISPATCH, 0);
+        assert_ne!(reported & more_capabilities::BATCH_WRITE, 0);
+        assert_ne!(reported & more_capabilities::MULTISIG, 0);
+        assert_ne!(reported & more_capabilities::PROXY, 0);
+        assert_ne!(reported & more_capabilities::IDENTITY, 0);
+        assert_ne!(reported & more_capabilities::GOVERNANCE, 0);
+        assert_ne!(reported & more_capabilities::STAKING, 0);
+        assert_ne!(reported & more_capabilities::TREASURY, 0);
+        assert_ne!(reported & more_capabilities::COUNCIL, 0);
+        assert_ne!(reported & more_capabilities::MEMBERSHIP, 0);
+        assert_ne!(reported & more_capabilities::XCM, 0);
+        assert_ne!(reported & more_capabilities::FEES, 0);
+        assert_ne!(reported & more_capabilities::WEIGHT_TO_FEE, 0);
+        assert_ne!(reported & more_capabilities::BLOCK_HASH, 0);
+        assert_ne!(reported & more_capabilities::BLOCK_AUTHOR, 0);
+        assert_ne!(reported & more_capabilities::SESSION, 0);
+    });
+}
+
+#[test]
+fn contracts_can_look_up_other_contracts() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let other = deploy_fixture("pure");
+
+        let (status, output) = call(&contract, FUNC_CONTRACT_INFO, other.clone());
+        assert_eq!(status, status::SUCCESS);
+        let info = Option::<ContractInfo<crate::Hash, Balance>>::decode(&mut &output[..]).unwrap();
+        assert_eq!(
+            info,
+            Some(ContractInfo {
+                code_hash: fixture_code_hash("pure"),
+                storage_deposit: Balances::reserved_balance(&other),
+            })
+        );
+
+        // Plain accounts aren't contracts.
+        let (status, output) = call(&contract, FUNC_CONTRACT_INFO, ALICE);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            Option::<ContractInfo<crate::Hash, Balance>>::decode(&mut &output[..]).unwrap(),
+            None
+        );
+    });
+}
+
+#[test]
+fn contracts_can_call_other_contracts() {
+    new_test_ext().execute_with(|| {
+        let contracts = deploy_many(2);
+        let (caller, callee) = (&contracts[0], &contracts[1]);
+
+        // The callee forwards its input to the extension too, so we have it ask for the version.
+        let (status, output) = call(
+            caller,
+            FUNC_CALL_CONTRACT,
+            (false, callee.clone(), 0 as Balance, 0 as Weight, FUNC_VERSION.encode()),
+        );
+        assert_eq!(status, status::SUCCESS);
+        let (flags, data) = <(u32, Vec<u8>)>::decode(&mut &output[..]).unwrap();
+        assert_eq!(flags, 0);
+        assert_eq!(data, (status::SUCCESS, MAIN_EXTENSION_VERSION).encode());
+
+        // Plain accounts can't be called.
+        let (status, _) = call(
+            caller,
+            FUNC_CALL_CONTRACT,
+            (false, ALICE, 0 as Balance, 0 as Weight, Vec::<u8>::new()),
+        );
+        assert_eq!(status, status::NOT_A_CONTRACT);
+    });
+}
+
+#[test]
+fn callees_with_a_modest_gas_limit_get_called() {
+    new_test_ext().execute_with(|| {
+        let contracts = deploy_many(2);
+        let (caller, callee) = (&contracts[0], &contracts[1]);
+
+        // Plenty for the callee, but less than copying out a whole callee's memory worth of output
+        // would take. That's no reason to turn the call down, the output is only a few bytes.
+        let gas_limit: Weight = 1_500_000_000;
+        let mut data = FUNC_CALL_CONTRACT.encode();
+        let callee_gas_limit: Weight = 500_000_000;
+        (false, callee.clone(), 0 as Balance, callee_gas_limit, FUNC_VERSION.encode())
+            .encode_to(&mut data);
+        let output = Contracts::bare_call(ALICE, caller.clone(), 0, gas_limit, None, data, false)
+            .result
+            .expect("the caller has enough gas")
+            .data
+            .0;
+
+        assert_eq!(u32::decode(&mut &output[..4]).unwrap(), status::SUCCESS);
+        let (flags, data) = <(u32, Vec<u8>)>::decode(&mut &output[4..]).unwrap();
+        assert_eq!(flags, 0);
+        assert_eq!(data, (status::SUCCESS, MAIN_EXTENSION_VERSION).encode());
+    });
+}
+
+#[test]
+fn calls_to_other_contracts_cannot_be_simulated() {
+    new_test_ext().execute_with(|| {
+        let contracts = deploy_many(2);
+        let (caller, callee) = (&contracts[0], &contracts[1]);
+        fund(caller, 100 * ExistentialDeposit::get());
+        let balances = |contract: &AccountId| {
+            (Balances::free_balance(contract), Balances::reserved_balance(contract))
+        };
+        let before = (balances(caller), balances(callee));
+
+        // The callee would store a value, which takes a storage deposit, and get some tokens.
+        let value = 10 * ExistentialDeposit::get();
+        let input = (FUNC_WRITE_TO_STORAGE, false, 42u32).encode();
+        let (status, _) = call(
+            caller,
+            FUNC_CALL_CONTRACT,
+            (true, callee.clone(), value, 0 as Weight, input),
+        );
+        assert_eq!(status, status::SIMULATE_NOT_SUPPORTED);
+
+        assert_eq!((balances(caller), balances(callee)), before);
+        assert_eq!(Template::something(), None);
+    });
+}
+
+#[test]
+fn contracts_can_instantiate_other_contracts() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        // This uploads the code we're about to instantiate.
+        deploy_fixture("pure");
+        let code_hash = fixture_code_hash("pure");
+        let salt = vec![1u8];
+        let instantiate = |code_hash: crate::Hash| {
+            call(
+                &contract,
+                FUNC_INSTANTIATE_CONTRACT,
+                (false, code_hash, 0 as Balance, 0 as Weight, Vec::<u8>::new(), salt.clone()),
+            )
+        };
+
+        let (status, output) = instantiate(code_hash);
+        assert_eq!(status, status::SUCCESS);
+        let address = AccountId::decode(&mut &output[..]).unwrap();
+        // The calling contract is the one doing the instantiating.
+        assert_eq!(address, Contracts::contract_address(&contract, &code_hash, &salt));
+        let (_, output) = call(&contract, FUNC_CONTRACT_INFO, address);
+        let info = Option::<ContractInfo<crate::Hash, Balance>>::decode(&mut &output[..]).unwrap();
+        assert_eq!(info.map(|info| info.code_hash), Some(code_hash));
+
+        // Same deployer, code and salt make for the same address.
+        let (status, _) = instantiate(code_hash);
+        assert_eq!(status, status::CONTRACT_EXISTS);
+
+        let (status, _) = instantiate(crate::Hash::default());
+        assert_eq!(status, status::UNKNOWN_CODE_HASH);
+    });
+}
+
+#[test]
+fn instantiating_other_contracts_cannot_be_simulated() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        fund(&contract, 100 * ExistentialDeposit::get());
+        // Uploading the code on its own leaves nothing referring to it yet.
+        let code_hash = Contracts::bare_upload_code(ALICE, fixture_wasm("pure"), None)
+            .expect("the fixture can be uploaded")
+            .code_hash;
+        let salt = vec![1u8];
+        let address = Contracts::contract_address(&contract, &code_hash, &salt);
+        let balances = || {
+            (Balances::free_balance(&contract), Balances::reserved_balance(&contract))
+        };
+        let before = balances();
+
+        let value = 10 * ExistentialDeposit::get();
+        let (status, _) = call(
+            &contract,
+            FUNC_INSTANTIATE_CONTRACT,
+            (true, code_hash, value, 0 as Weight, Vec::<u8>::new(), salt),
+        );
+        assert_eq!(status, status::SIMULATE_NOT_SUPPORTED);
+
+        // Nothing was reserved for the new contract, which isn't there.
+        assert_eq!(balances(), before);
+        assert_eq!(Balances::total_balance(&address), 0);
+        let (_, output) = call(&contract, FUNC_CONTRACT_INFO, address);
+        assert_eq!(
+            Option::<ContractInfo<crate::Hash, Balance>>::decode(&mut &output[..]).unwrap(),
+            None
+        );
+
+        // Code can only be removed while no contract refers to it.
+        assert_ok!(Contracts::remove_code(Origin::signed(ALICE), code_hash));
+    });
+}
+
+#[test]
+fn contracts_can_dispatch_the_calls_the_runtime_lets_them() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let dispatch = |runtime_call: crate::Call| {
+            call(&contract, FUNC_DISPATCH, (false, runtime_call.encode()))
+        };
+
+        // This acts on behalf of whoever called the contract.
+        let (status, _) = dispatch(crate::Call::Template(
+            pallet_template::Call::store_for_caller { value: 42 },
+        ));
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::account_value(&ALICE), Some(42));
+
+        // Only governance gets to replace the blob.
+        let (status, _) = dispatch(crate::Call::Template(pallet_template::Call::set_blob {
+            blob: vec![1],
+        }));
+        assert_eq!(status, status::BAD_ORIGIN);
+
+        // Calls outside of `pallet_template` don't make it past the filter, not even when
+        // they're wrapped in one of the Scheduler's.
+        let remark = crate::Call::System(frame_system::Call::remark { remark: Vec::new() });
+        let (status, _) = dispatch(remark.clone());
+        assert_eq!(status, status::NOT_PERMITTED);
+        let (status, _) = dispatch(crate::Call::Scheduler(pallet_scheduler::Call::schedule_after {
+            after: 1,
+            maybe_periodic: None,
+            priority: 0,
+            call: Box::new(MaybeHashed::Value(remark)),
+        }));
+        assert_eq!(status, status::NOT_PERMITTED);
+
+        let (status, _) = call(&contract, FUNC_DISPATCH, (false, vec![0xFFu8; 4]));
+        assert_eq!(status, status::UNDECODABLE_CALL);
+    });
+}
+
+#[test]
+fn calls_nested_too_deeply_are_not_dispatched() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // Just like extrinsics, calls can only be nested so deeply before they no longer decode.
+        // Every batch takes us a level deeper, and this is one more than we may go.
+        let mut runtime_call =
+            crate::Call::Template(pallet_template::Call::store_for_caller { value: 42 });
+        for _ in 0..=sp_api::MAX_EXTRINSIC_DEPTH {
+            runtime_call = crate::Call::Utility(pallet_utility::Call::batch {
+                calls: vec![runtime_call],
+            });
+        }
+
+        let (status, _) = call(&contract, FUNC_DISPATCH, (false, runtime_call.encode()));
+        assert_eq!(status, status::UNDECODABLE_CALL);
+        assert_eq!(Template::account_value(&ALICE), None);
+    });
+}
+
+#[test]
+fn contracts_can_batch_writes_for_their_caller() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, FUNC_BATCH_WRITE, (false, vec![1u32, 2, 3]));
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::account_value(&ALICE), Some(3));
+
+        // The batch is rejected as a whole, rather than going through with the first few writes.
+        let values = vec![4u32; MAX_BATCH_WRITE_LEN as usize + 1];
+        let (status, _) = call(&contract, FUNC_BATCH_WRITE, (false, values));
+        assert_eq!(status, status::BATCH_TOO_LARGE);
+        assert_eq!(Template::account_value(&ALICE), Some(3));
+    });
+}
+
+// A 2-of-3 multisig between Alice, Bob and `contract`, along with the call it's about. The call
+// stores a value in the multisig account's own slot.
+fn multisig_of(contract: &AccountId) -> (Vec<AccountId>, AccountId, Vec<u8>) {
+    let mut signatories = vec![ALICE, AccountId::new([2u8; 32]), contract.clone()];
+    signatories.sort();
+    let account = Multisig::multi_account_id(&signatories, 2);
+    let call = crate::Call::Template(pallet_template::Call::store_for_caller { value: 7 });
+
+    (signatories, account, call.encode())
+}
+
+// Everyone who signs off on a multisig operation names the other signatories, in order.
+fn other_signatories(signatories: &[AccountId], who: &AccountId) -> Vec<AccountId> {
+    signatories.iter().filter(|s| *s != who).cloned().collect()
+}
+
+#[test]
+fn contracts_can_give_the_last_approval_of_a_multisig() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let (signatories, multisig, multisig_call) = multisig_of(&contract);
+        let max_weight: Weight = 1_000_000_000;
+
+        assert_ok!(Multisig::approve_as_multi(
+            Origin::signed(ALICE),
+            2,
+            other_signatories(&signatories, &ALICE),
+            None,
+            sp_io::hashing::blake2_256(&multisig_call),
+            0,
+        ));
+        let timepoint = Multisig::timepoint();
+
+        let others = other_signatories(&signatories, &contract);
+        let (status, _) = call(
+            &contract,
+            FUNC_AS_MULTI,
+            (false, 2u16, others.clone(), Some(timepoint), multisig_call.clone(), 0 as Weight),
+        );
+        assert_eq!(status, status::MAX_WEIGHT_TOO_LOW);
+
+        let (status, _) = call(
+            &contract,
+            FUNC_AS_MULTI,
+            (false, 2u16, others, Some(timepoint), multisig_call, max_weight),
+        );
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::account_value(&multisig), Some(7));
+    });
+}
+
+#[test]
+fn contracts_can_approve_multisig_operations() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let (signatories, multisig, multisig_call) = multisig_of(&contract);
+        let call_hash = sp_io::hashing::blake2_256(&multisig_call);
+        let max_weight: Weight = 1_000_000_000;
+        let approve = |others: Vec<AccountId>, timepoint: Option<pallet_multisig::Timepoint<_>>| {
+            let input = (false, 2u16, others, timepoint, call_hash, max_weight);
+            call(&contract, FUNC_APPROVE_AS_MULTI, input).0
+        };
+
+        // The first approval reserves a deposit, which comes out of the contract's balance.
+        let deposit = MultisigDepositBase::get() + 2 * MultisigDepositFactor::get();
+        fund(&contract, deposit + ExistentialDeposit::get());
+
+        let mut others = other_signatories(&signatories, &contract);
+        others.reverse();
+        assert_eq!(approve(others.clone(), None), status::INVALID_SIGNATORIES);
+        others.reverse();
+
+        assert_eq!(approve(others.clone(), None), status::SUCCESS);
+        let timepoint: pallet_multisig::Timepoint<BlockNumber> = Multisig::timepoint();
+        assert_eq!(approve(others.clone(), None), status::WRONG_TIMEPOINT);
+        assert_eq!(approve(others, Some(timepoint)), status::ALREADY_APPROVED);
+
+        let bob = AccountId::new([2u8; 32]);
+        assert_ok!(Multisig::as_multi(
+            Origin::signed(bob.clone()),
+            2,
+            other_signatories(&signatories, &bob),
+            Some(timepoint),
+            frame_support::traits::WrapperKeepOpaque::from_encoded(multisig_call),
+            false,
+            max_weight,
+        ));
+        assert_eq!(Template::account_value(&multisig), Some(7));
+    });
+}
+
+#[test]
+fn contracts_can_act_for_accounts_which_made_them_their_proxy() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let proxy = |runtime_call: crate::Call| {
+            call(&contract, FUNC_PROXY, (false, ALICE, runtime_call.encode())).0
+        };
+        let write = crate::Call::Template(pallet_template::Call::store_for_caller { value: 42 });
+
+        assert_eq!(proxy(write.clone()), status::NOT_A_PROXY);
+
+        assert_ok!(Proxy::add_proxy(
+            Origin::signed(ALICE),
+            contract.clone(),
+            ProxyType::Template,
+            0
+        ));
+        assert_eq!(proxy(write), status::SUCCESS);
+        assert_eq!(Template::account_value(&ALICE), Some(42));
+
+        // Alice may let her proxies do anything she likes, contracts still only get to make the
+        // calls they could also dispatch.
+        let remark = crate::Call::System(frame_system::Call::remark { remark: Vec::new() });
+        assert_eq!(proxy(remark), status::NOT_PERMITTED);
+    });
+}
+
+#[test]
+fn contracts_can_manage_proxies_of_their_own() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        let input = (false, bob.clone(), ProxyType::Template, 0 as BlockNumber);
+
+        // The deposit for the proxy comes out of the contract's balance.
+        let deposit = ProxyDepositBase::get() + ProxyDepositFactor::get();
+        fund(&contract, deposit + ExistentialDeposit::get());
+        let reserved = Balances::reserved_balance(&contract);
+
+        assert_eq!(call(&contract, FUNC_ADD_PROXY, input.clone()).0, status::SUCCESS);
+        assert_eq!(call(&contract, FUNC_ADD_PROXY, input.clone()).0, status::DUPLICATE_PROXY);
+        assert_eq!(Balances::reserved_balance(&contract), reserved + deposit);
+
+        let write = crate::Call::Template(pallet_template::Call::store_for_caller { value: 7 });
+        assert_ok!(Proxy::proxy(
+            Origin::signed(bob.clone()),
+            contract.clone(),
+            None,
+            Box::new(write)
+        ));
+        assert_eq!(Template::account_value(&contract), Some(7));
+
+        assert_eq!(call(&contract, FUNC_REMOVE_PROXY, input.clone()).0, status::SUCCESS);
+        assert_eq!(call(&contract, FUNC_REMOVE_PROXY, input).0, status::UNKNOWN_PROXY);
+        assert_eq!(Balances::reserved_balance(&contract), reserved);
+    });
+}
+
+#[test]
+fn contracts_can_look_up_identities() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        let identity_of = |account: &AccountId| {
+            let (status, output) = call(&contract, FUNC_IDENTITY_OF, account);
+            assert_eq!(status, status::SUCCESS);
+            Option::<BoundedIdentityInfo>::decode(&mut &output[..]).unwrap()
+        };
+
+        assert_eq!(identity_of(&ALICE), None);
+
+        let display = b"Alice".to_vec();
+        assert_ok!(Identity::set_identity(
+            Origin::signed(ALICE),
+            Box::new(IdentityInfo {
+                display: Data::Raw(display.clone().try_into().unwrap()),
+                ..Default::default()
+            })
+        ));
+        assert_eq!(
+            identity_of(&ALICE),
+            Some(BoundedIdentityInfo {
+                display: display.clone(),
+                judgement: IdentityJudgement::Unknown,
+            })
+        );
+
+        // Once a registrar vouches for the identity, contracts can tell it's been verified.
+        assert_ok!(Identity::add_registrar(Origin::root(), bob.clone()));
+        assert_ok!(Identity::request_judgement(Origin::signed(ALICE), 0, 0));
+        assert_ok!(Identity::provide_judgement(
+            Origin::signed(bob),
+            0,
+            ALICE.into(),
+            Judgement::Reasonable
+        ));
+        let info = identity_of(&ALICE).unwrap();
+        assert_eq!(info.display, display);
+        assert!(info.judgement.is_verified());
+    });
+}
+
+#[test]
+fn hashed_display_names_are_left_out() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        assert_ok!(Identity::set_identity(
+            Origin::signed(ALICE),
+            Box::new(IdentityInfo {
+                display: Data::BlakeTwo256(BlakeTwo256::hash(b"Alice").into()),
+                ..Default::default()
+            })
+        ));
+
+        let (_, output) = call(&contract, FUNC_IDENTITY_OF, ALICE);
+        let info = Option::<BoundedIdentityInfo>::decode(&mut &output[..]).unwrap().unwrap();
+        assert!(info.display.is_empty());
+    });
+}
+
+#[test]
+fn erroneous_judgements_win_over_any_others() {
+    use super::identity::judgement_of;
+
+    assert_eq!(judgement_of::<Runtime>(&[]), IdentityJudgement::Unknown);
+    assert_eq!(
+        judgement_of::<Runtime>(&[(0, Judgement::LowQuality)]),
+        IdentityJudgement::LowQuality
+    );
+    assert_eq!(
+        judgement_of::<Runtime>(&[(0, Judgement::FeePaid(1)), (1, Judgement::KnownGood)]),
+        IdentityJudgement::KnownGood
+    );
+    assert_eq!(
+        judgement_of::<Runtime>(&[(0, Judgement::KnownGood), (1, Judgement::Erroneous)]),
+        IdentityJudgement::Erroneous
+    );
+}
+
+#[test]
+fn contracts_can_vote_on_referenda() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let balance = 10 * ExistentialDeposit::get();
+        fund(&contract, 2 * balance);
+        let ref_index = Democracy::internal_start_referendum(
+            BlakeTwo256::hash(b"proposal"),
+            VoteThreshold::SuperMajorityApprove,
+            0,
+        );
+
+        let input = (false, ref_index, true, Conviction::Locked6x, balance);
+        assert_eq!(call(&contract, FUNC_VOTE, input).0, status::SUCCESS);
+
+        // The contract's balance counts six times over, for as long as it stays locked up.
+        let tally = Democracy::referendum_status(ref_index).unwrap().tally;
+        assert_eq!(tally.ayes, 6 * balance);
+        assert_eq!(tally.turnout, balance);
+    });
+}
+
+#[test]
+fn voting_errors_get_their_own_status_codes() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let balance = 10 * ExistentialDeposit::get();
+        fund(&contract, balance);
+
+        let input = (false, 0u32, true, Conviction::None, balance);
+        assert_eq!(call(&contract, FUNC_VOTE, input).0, status::REFERENDUM_NOT_ONGOING);
+
+        let ref_index = Democracy::internal_start_referendum(
+            BlakeTwo256::hash(b"proposal"),
+            VoteThreshold::SuperMajorityApprove,
+            0,
+        );
+        let input = (false, ref_index, false, Conviction::None, 2 * balance);
+        assert_eq!(call(&contract, FUNC_VOTE, input).0, status::INSUFFICIENT_VOTING_BALANCE);
+    });
+}
+
+#[test]
+fn contracts_can_look_up_the_current_era() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let current_era = || {
+            let (status, output) = call(&contract, FUNC_CURRENT_ERA, ());
+            assert_eq!(status, status::SUCCESS);
+            Option::<u32>::decode(&mut &output[..]).unwrap()
+        };
+
+        // There's no era before the first election.
+        assert_eq!(current_era(), None);
+
+        pallet_staking::CurrentEra::<Runtime>::put(3);
+        assert_eq!(current_era(), Some(3));
+    });
+}
+
+#[test]
+fn contracts_can_look_up_what_backs_a_validator() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        let validator_stake = |validator: &AccountId| {
+            let (status, output) = call(&contract, FUNC_VALIDATOR_STAKE, validator);
+            assert_eq!(status, status::SUCCESS);
+            Balance::decode(&mut &output[..]).unwrap()
+        };
+
+        pallet_staking::ActiveEra::<Runtime>::put(pallet_staking::ActiveEraInfo {
+            index: 1,
+            start: None,
+        });
+        pallet_staking::ErasStakers::<Runtime>::insert(1, &bob, pallet_staking::Exposure {
+            total: 300,
+            own: 100,
+            others: vec![pallet_staking::IndividualExposure { who: ALICE, value: 200 }],
+        });
+
+        assert_eq!(validator_stake(&bob), 300);
+        assert_eq!(validator_stake(&ALICE), 0);
+    });
+}
+
+#[test]
+fn contracts_can_tell_whether_accounts_are_bonded() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        let is_bonded = |stash: &AccountId| {
+            let (status, output) = call(&contract, FUNC_IS_BONDED, stash);
+            assert_eq!(status, status::SUCCESS);
+            bool::decode(&mut &output[..]).unwrap()
+        };
+
+        assert!(!is_bonded(&ALICE));
+
+        assert_ok!(Staking::bond(
+            Origin::signed(ALICE),
+            bob.clone().into(),
+            10 * ExistentialDeposit::get(),
+            pallet_staking::RewardDestination::Staked
+        ));
+        assert!(is_bonded(&ALICE));
+        // Controllers don't count, only the stash they control.
+        assert!(!is_bonded(&bob));
+    });
+}
+
+#[test]
+fn contracts_can_propose_treasury_spends() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        let bond = ProposalBondMinimum::get();
+        fund(&contract, 3 * bond);
+        let propose = || {
+            let (status, output) = call(&contract, FUNC_PROPOSE_SPEND, (false, 10 * bond, &bob));
+            assert_eq!(status, status::SUCCESS);
+            u32::decode(&mut &output[..]).unwrap()
+        };
+
+        assert_eq!(propose(), 0);
+        assert_eq!(propose(), 1);
+
+        // The contract put up the bond for both of its proposals.
+        assert!(pallet_treasury::Proposals::<Runtime>::contains_key(1));
+        assert_eq!(Treasury::proposal_count(), 2);
+        assert_eq!(Balances::reserved_balance(&contract), 2 * bond);
+    });
+}
+
+#[test]
+fn proposals_the_contract_cant_afford_the_bond_for_are_reported() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+
+        let input = (false, 10 * ProposalBondMinimum::get(), bob);
+        assert_eq!(
+            call(&contract, FUNC_PROPOSE_SPEND, input).0,
+            status::INSUFFICIENT_PROPOSAL_BOND
+        );
+        assert_eq!(Treasury::proposal_count(), 0);
+    });
+}
+
+// Puts `contract` on the Council, along with one other member.
+fn join_council(contract: &AccountId) {
+    let bob = AccountId::new([2u8; 32]);
+    assert_ok!(Council::set_members(
+        Origin::root(),
+        vec![contract.clone(), bob],
+        None,
+        0
+    ));
+}
+
+#[test]
+fn contracts_can_propose_council_motions() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        join_council(&contract);
+
+        let (status, output) = call(&contract, FUNC_PROPOSE_APPROVAL, (false, 2u32, 0u32));
+        assert_eq!(status, status::SUCCESS);
+        let hash = crate::Hash::decode(&mut &output[..]).unwrap();
+
+        // The other member gets to vote on the motion under the hash we got back.
+        assert_eq!(Council::proposals().into_inner(), vec![hash]);
+        assert_eq!(
+            Council::proposal_of(hash),
+            Some(crate::Call::Treasury(pallet_treasury::Call::approve_proposal {
+                proposal_id: 0
+            }))
+        );
+    });
+}
+
+#[test]
+fn motions_which_need_no_other_votes_approve_spends_right_away() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        fund(&contract, 3 * ProposalBondMinimum::get());
+        join_council(&contract);
+        let input = (false, 10 * ProposalBondMinimum::get(), bob);
+        assert_eq!(call(&contract, FUNC_PROPOSE_SPEND, input).0, status::SUCCESS);
+
+        // Half of the Council is enough to approve spends.
+        let (status, _) = call(&contract, FUNC_PROPOSE_APPROVAL, (false, 1u32, 0u32));
+        assert_eq!(status, status::SUCCESS);
+        assert!(Council::proposals().is_empty());
+        assert_eq!(pallet_treasury::Approvals::<Runtime>::get().into_inner(), vec![0]);
+    });
+}
+
+#[test]
+fn council_errors_get_their_own_status_codes() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let input = (false, 2u32, 0u32);
+        assert_eq!(call(&contract, FUNC_PROPOSE_APPROVAL, input).0, status::NOT_A_COUNCIL_MEMBER);
+
+        join_council(&contract);
+        assert_eq!(call(&contract, FUNC_PROPOSE_APPROVAL, input).0, status::SUCCESS);
+        assert_eq!(call(&contract, FUNC_PROPOSE_APPROVAL, input).0, status::DUPLICATE_MOTION);
+    });
+}
+
+#[test]
+fn contracts_can_tell_whether_accounts_are_members() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let bob = AccountId::new([2u8; 32]);
+        let is_member = |account: &AccountId| {
+            let (status, output) = call(&contract, FUNC_IS_MEMBER, account);
+            assert_eq!(status, status::SUCCESS);
+            bool::decode(&mut &output[..]).unwrap()
+        };
+
+        assert!(!is_member(&bob));
+
+        assert_ok!(CouncilMembership::add_member(Origin::root(), bob.clone()));
+        assert!(is_member(&bob));
+        assert!(!is_member(&ALICE));
+        // Whoever is a member also sits on the Council.
+        assert_eq!(Council::members(), vec![bob.clone()]);
+
+        assert_ok!(CouncilMembership::remove_member(Origin::root(), bob.clone()));
+        assert!(!is_member(&bob));
+    });
+}
+
+#[test]
+fn xcms_have_nowhere_to_go_on_a_standalone_chain() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let input = (false, vec![0u8; 8], 1_000_000u64);
+        assert_eq!(call(&contract, FUNC_XCM_TRANSACT, input).0, status::XCM_UNROUTABLE);
+    });
+}
+
+#[test]
+fn calls_too_long_to_send_over_xcm_are_rejected() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let input = (false, vec![0u8; MAX_XCM_CALL_LEN as usize + 1], 1_000_000u64);
+        assert_eq!(call(&contract, FUNC_XCM_TRANSACT, input).0, status::XCM_CALL_TOO_LONG);
+    });
+}
+
+#[test]
+fn xcms_act_as_the_contract_which_sent_them() {
+    use xcm::latest::prelude::*;
+
+    let message = cross_chain::transact_message([7; 32], vec![1, 2, 3], 1_000_000);
+    assert_eq!(
+        message.0,
+        vec![
+            DescendOrigin(X1(AccountId32 {
+                network: NetworkId::Any,
+                id: [7; 32],
+            })),
+            Transact {
+                origin_type: OriginKind::SovereignAccount,
+                require_weight_at_most: 1_000_000,
+                call: vec![1, 2, 3].into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn contracts_get_the_fees_transaction_payment_would_charge() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let runtime_call =
+            crate::Call::Template(pallet_template::Call::store_for_caller { value: 7 });
+        let encoded = runtime_call.encode();
+
+        let (status, output) = call(&contract, FUNC_ESTIMATE_FEE, &encoded);
+        assert_eq!(status, status::SUCCESS);
+        let estimate = FeeEstimate::<Balance>::decode(&mut &output[..]).unwrap();
+
+        let info = runtime_call.get_dispatch_info();
+        let fee = TransactionPayment::compute_fee_details(encoded.len() as u32, &info, 0)
+            .inclusion_fee
+            .unwrap();
+        assert_eq!(estimate.weight, info.weight);
+        assert_eq!(estimate.base_fee, fee.base_fee);
+        assert_eq!(estimate.len_fee, fee.len_fee);
+        assert_eq!(estimate.adjusted_weight_fee, fee.adjusted_weight_fee);
+        assert_eq!(
+            estimate.partial_fee(),
+            TransactionPayment::compute_fee(encoded.len() as u32, &info, 0)
+        );
+    });
+}
+
+#[test]
+fn fees_cant_be_estimated_for_calls_which_dont_decode() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, FUNC_ESTIMATE_FEE, vec![0xffu8; 4]);
+        assert_eq!(status, status::SCHEMA_MISMATCH);
+    });
+}
+
+#[test]
+fn contracts_can_convert_weight_into_fees() {
+    use frame_support::weights::WeightToFee;
+
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let fee_for = |ref_time: u64, proof_size: u64| {
+            let (status, output) = call(&contract, FUNC_WEIGHT_TO_FEE, (ref_time, proof_size));
+            assert_eq!(status, status::SUCCESS);
+            Balance::decode(&mut &output[..]).unwrap()
+        };
+
+        let ref_time = 1_000_000_000;
+        assert_eq!(
+            fee_for(ref_time, 0),
+            <Runtime as pallet_transaction_payment::Config>::WeightToFee::weight_to_fee(&ref_time)
+        );
+        // Our weights don't have a proof size, so it doesn't cost anything.
+        assert_eq!(fee_for(ref_time, u64::MAX), fee_for(ref_time, 0));
+        assert!(fee_for(2 * ref_time, 0) > fee_for(ref_time, 0));
+    });
+}
+
+#[test]
+fn contracts_can_follow_sessions_and_eras() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let session_info = || {
+            let (status, output) = call(&contract, FUNC_SESSION_INFO, ());
+            assert_eq!(status, status::SUCCESS);
+            SessionInfo::<BlockNumber>::decode(&mut &output[..]).unwrap()
+        };
+
+        assert_eq!(
+            session_info(),
+            SessionInfo {
+                session_index: 0,
+                session_length: SessionPeriod::get(),
+                active_era: None,
+                sessions_per_era: SessionsPerEra::get(),
+            }
+        );
+
+        // Staking plans the first era when the first session ends, and it starts a session later.
+        run_to_block(2 * SessionPeriod::get());
+        let info = session_info();
+        assert_eq!(info.session_index, Session::current_index());
+        assert_eq!(info.session_index, 2);
+        assert_eq!(info.active_era, Some(0));
+    });
+}
+
+#[test]
+fn callbacks_use_up_their_reservation() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        assert_eq!(schedule(&contract, 5), status::SUCCESS);
+
+        run_to_block(5);
+
+        let (status, output) = call(&contract, FUNC_CALLBACK_RESERVATION, ());
+        assert_eq!(status, status::SUCCESS);
+        let usage = ReservationUsage::decode(&mut &output[..]).unwrap();
+        assert_eq!(usage.reserved, Template::reserved_callback_weight());
+        assert!(usage.used > 0);
+        assert!(usage.used <= usage.reserved);
+
+        // Every block gets a fresh reservation.
+        run_to_block(6);
+        let (_, output) = call(&contract, FUNC_CALLBACK_RESERVATION, ());
+        assert_eq!(ReservationUsage::decode(&mut &output[..]).unwrap().used, 0);
+    });
+}
+
+#[test]
+fn callback_weights_start_out_empty() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(&contract, FUNC_CALLBACK_WEIGHTS, ());
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            WeightHistogram::decode(&mut &output[..]).unwrap(),
+            WeightHistogram::default()
+        );
+    });
+}
+
+#[test]
+fn unknown_func_id_is_reported() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, 0xDEAD, ());
+
+        assert_eq!(status, status::UNKNOWN_FUNCTION);
+    });
+}
+
+#[test]
+fn failed_calls_are_explained_in_the_debug_buffer() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        // Our fixture takes the `func_id` followed by its input.
+        let debug_message = |data: Vec<u8>| {
+            let result =
+                Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, true);
+            String::from_utf8(result.debug_message).unwrap()
+        };
+
+        assert_eq!(
+            debug_message(0xDEADu32.encode()),
+            "chain-extension: func_id 0xdead returned status 2: \
+             the runtime doesn't know this function\n"
+        );
+
+        // Calls which go through don't add anything.
+        assert_eq!(
+            debug_message((FUNC_WRITE_TO_STORAGE, false, 42u32).encode()),
+            ""
+        );
+    });
+}
+
+#[test]
+fn calls_are_counted_per_func_id() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let counts = |func_id: u32| {
+            let (status, output) = call(&contract, FUNC_CALL_COUNTS, func_id);
+            assert_eq!(status, status::SUCCESS);
+            CallCounts::decode(&mut &output[..]).unwrap()
+        };
+
+        assert_eq!(call(&contract, FUNC_WRITE_TO_STORAGE, (false, 42u32)).0, status::SUCCESS);
+        // Simulated calls count just the same.
+        assert_eq!(call(&contract, FUNC_WRITE_TO_STORAGE, (true, 42u32)).0, status::SUCCESS);
+        let (status, _) = call(
+            &contract,
+            FUNC_WRITE_RAW,
+            (false, b"key".to_vec(), b"value".to_vec()),
+        );
+        assert_eq!(status, status::NOT_PERMITTED);
+
+        assert_eq!(counts(FUNC_WRITE_TO_STORAGE), CallCounts { succeeded: 2, failed: 0 });
+        assert_eq!(counts(FUNC_WRITE_RAW), CallCounts { succeeded: 0, failed: 1 });
+        // Reading the counters is a call like any other, which gets counted once it returns.
+        assert_eq!(counts(FUNC_CALL_COUNTS), CallCounts { succeeded: 2, failed: 0 });
+        assert_eq!(Template::extension_calls(FUNC_CALL_COUNTS).succeeded, 3);
+    });
+}
+
+#[test]
+fn only_approved_code_calls_restricted_functions() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let code_hash = fixture_code_hash("chain_extension");
+        let audited = crate::Hash::repeat_byte(1);
+
+        assert_ok!(Template::set_approved_code_hashes(
+            Origin::root(),
+            FUNC_WRITE_TO_STORAGE,
+            Some(vec![audited]),
+        ));
+        let (status, _) = call(&contract, FUNC_WRITE_TO_STORAGE, (false, 42u32));
+        assert_eq!(status, status::CODE_NOT_APPROVED);
+        assert_eq!(Template::something(), None);
+        assert_eq!(Template::extension_calls(FUNC_WRITE_TO_STORAGE).failed, 1);
+
+        // Functions governance hasn't restricted work as before.
+        let (status, _) = call(&contract, FUNC_WRITE_FOR_CALLER, (false, 42u32));
+        assert_eq!(status, status::SUCCESS);
+
+        // Every contract instantiated from approved code gets in.
+        assert_ok!(Template::set_approved_code_hashes(
+            Origin::root(),
+            FUNC_WRITE_TO_STORAGE,
+            Some(vec![audited, code_hash]),
+        ));
+        let other = deploy_fixture_with_salt("chain_extension", vec![1]);
+        for contract in [&contract, &other] {
+            let (status, _) = call(contract, FUNC_WRITE_TO_STORAGE, (false, 42u32));
+            assert_eq!(status, status::SUCCESS);
+        }
+        assert_eq!(Template::something(), Some(42));
+    });
+}
+
+#[test]
+fn unknown_functions_are_not_counted() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, 0xDEADu32, ());
+        assert_eq!(status, status::UNKNOWN_FUNCTION);
+        assert_eq!(pallet_template::ExtensionCalls::<Runtime>::iter().count(), 0);
+
+        let (status, _) = call(&contract, FUNC_CALL_COUNTS, 0xDEADu32);
+        assert_eq!(status, status::UNKNOWN_FUNCTION);
+    });
+}
+
+#[test]
+fn every_extension_reports_its_version() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, output) = call(&contract, FUNC_VERSION, ());
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            u32::decode(&mut &output[..]).unwrap(),
+            MAIN_EXTENSION_VERSION
+        );
+
+        let (status, output) = call(&contract, FUNC_SCHEDULER_VERSION, ());
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            u32::decode(&mut &output[..]).unwrap(),
+            SCHEDULER_EXTENSION_VERSION
+        );
+    });
+}
+
+#[test]
+fn unknown_extension_is_reported() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, pack_func_id(0xFF, 1), ());
+
+        assert_eq!(status, status::UNKNOWN_FUNCTION);
+    });
+}
+
+#[test]
+fn scheduler_functions_are_only_reachable_through_their_extension() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // `callback_weights()` used to be func_id 5, before the scheduler got an extension of
+        // its own.
+        let (status, _) = call(&contract, 5, ());
+        assert_eq!(status, status::UNKNOWN_FUNCTION);
+
+        let (status, _) = call(&contract, FUNC_CALLBACK_WEIGHTS, ());
+        assert_eq!(status, status::SUCCESS);
+    });
+}
+
+#[test]
+fn func_ids_carry_the_id_of_their_extension() {
+    let scheduler_id = <SchedulerExtension as RegisteredChainExtension<Runtime>>::ID;
+    for func_id in SchedulerFuncs::FUNCS {
+        assert_eq!(
+            unpack_func_id(*func_id).0,
+            scheduler_id,
+            "func_id {:#x}",
+            func_id
+        );
+    }
+
+    let main_id = <MyExtension as RegisteredChainExtension<Runtime>>::ID;
+    let main = [
+        StorageFuncs::FUNCS,
+        CustomFuncs::FUNCS,
+        IntrospectionFuncs::FUNCS,
+        EnvelopeFuncs::FUNCS,
+        BalanceFuncs::FUNCS,
+        AssetFuncs::FUNCS,
+        NftFuncs::FUNCS,
+        CryptoFuncs::FUNCS,
+        CounterFuncs::FUNCS,
+        CommitRevealFuncs::FUNCS,
+        SystemFuncs::FUNCS,
+        ContractFuncs::FUNCS,
+        RuntimeCallFuncs::FUNCS,
+        MultisigFuncs::FUNCS,
+        ProxyFuncs::FUNCS,
+        IdentityFuncs::FUNCS,
+        GovernanceFuncs::FUNCS,
+        StakingFuncs::FUNCS,
+        TreasuryFuncs::FUNCS,
+        CouncilFuncs::FUNCS,
+        MembershipFuncs::FUNCS,
+        CrossChainFuncs::FUNCS,
+        FeeFuncs::FUNCS,
+        AuthorshipFuncs::FUNCS,
+        SessionFuncs::FUNCS,
+    ]
+    .concat();
+    for func_id in main {
+        assert_eq!(unpack_func_id(func_id).0, main_id, "func_id {:#x}", func_id);
+    }
+}
+
+#[test]
+fn no_func_id_has_more_than_one_handler() {
+    // Calls go to the first handler claiming them, so any others would silently never be used.
+    let handled = [
+        StorageFuncs::FUNCS,
+        CustomFuncs::FUNCS,
+        SchedulerFuncs::FUNCS,
+        IntrospectionFuncs::FUNCS,
+        EnvelopeFuncs::FUNCS,
+        BalanceFuncs::FUNCS,
+        AssetFuncs::FUNCS,
+        NftFuncs::FUNCS,
+        CryptoFuncs::FUNCS,
+        CounterFuncs::FUNCS,
+        CommitRevealFuncs::FUNCS,
+        SystemFuncs::FUNCS,
+        ContractFuncs::FUNCS,
+        RuntimeCallFuncs::FUNCS,
+        MultisigFuncs::FUNCS,
+        ProxyFuncs::FUNCS,
+        IdentityFuncs::FUNCS,
+        GovernanceFuncs::FUNCS,
+        StakingFuncs::FUNCS,
+        TreasuryFuncs::FUNCS,
+        CouncilFuncs::FUNCS,
+        MembershipFuncs::FUNCS,
+        CrossChainFuncs::FUNCS,
+        FeeFuncs::FUNCS,
+        AuthorshipFuncs::FUNCS,
+        SessionFuncs::FUNCS,
+    ]
+    .concat();
+
+    let mut seen = std::collections::BTreeSet::new();
+    for func_id in handled {
+        assert!(seen.insert(func_id), "func_id {} has more than one handler", func_id);
+    }
+}
+
+#[test]
+fn manifest_describes_every_handled_func_id() {
+    use std::collections::BTreeSet;
+
+    let handled: BTreeSet<_> = [
+        StorageFuncs::FUNCS,
+        CustomFuncs::FUNCS,
+        SchedulerFuncs::FUNCS,
+        IntrospectionFuncs::FUNCS,
+        EnvelopeFuncs::FUNCS,
+        BalanceFuncs::FUNCS,
+        AssetFuncs::FUNCS,
+        NftFuncs::FUNCS,
+        CryptoFuncs::FUNCS,
+        CounterFuncs::FUNCS,
+        CommitRevealFuncs::FUNCS,
+        SystemFuncs::FUNCS,
+        ContractFuncs::FUNCS,
+        RuntimeCallFuncs::FUNCS,
+        MultisigFuncs::FUNCS,
+        ProxyFuncs::FUNCS,
+        IdentityFuncs::FUNCS,
+        GovernanceFuncs::FUNCS,
+        StakingFuncs::FUNCS,
+        TreasuryFuncs::FUNCS,
+        CouncilFuncs::FUNCS,
+        MembershipFuncs::FUNCS,
+        CrossChainFuncs::FUNCS,
+        FeeFuncs::FUNCS,
+        AuthorshipFuncs::FUNCS,
+        SessionFuncs::FUNCS,
+    ]
+    .concat()
+    .into_iter()
+    .collect();
+    let described: Vec<_> = chain_extension_primitives::manifest::FUNCS
+        .iter()
+        .map(|func| func.func_id)
+        .collect();
+
+    assert!(
+        described.windows(2).all(|pair| pair[0] < pair[1]),
+        "not in func_id order"
+    );
+    assert_eq!(described.into_iter().collect::<BTreeSet<_>>(), handled);
+}
+
+#[test]
+fn system_contract_can_write_raw() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        assert_ok!(Template::set_system_contract(
+            Origin::root(),
+            Some(contract.clone())
+        ));
+
+        let (status, _) = call(
+            &contract,
+            FUNC_WRITE_RAW,
+            (false, b"key".to_vec(), b"value".to_vec()),
+        );
+
+        assert_eq!(status, status::SUCCESS);
+        let key: frame_support::BoundedVec<
+            u8,
+            <Runtime as pallet_template::Config>::MaxRawKeyLen,
+        > = b"key".to_vec().try_into().unwrap();
+        assert_eq!(
+            Template::raw_storage(&key).map(|v| v.into_inner()),
+            Some(b"value".to_vec())
+        );
+    });
+}
+
+#[test]
+fn ordinary_contracts_cannot_write_raw() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let (status, _) = call(
+            &contract,
+            FUNC_WRITE_RAW,
+            (false, b"key".to_vec(), b"value".to_vec()),
+        );
+        assert_eq!(status, status::NOT_PERMITTED);
+
+        // Designating some other contract doesn't help either.
+        assert_ok!(Template::set_system_contract(Origin::root(), Some(ALICE)));
+        let (status, _) = call(
+            &contract,
+            FUNC_WRITE_RAW,
+            (false, b"key".to_vec(), b"value".to_vec()),
+        );
+        assert_eq!(status, status::NOT_PERMITTED);
+
+        assert_eq!(pallet_template::RawStorage::<Runtime>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn any_contract_can_read_raw() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        assert_ok!(Template::set_system_contract(Origin::root(), Some(ALICE)));
+        assert_ok!(Template::write_raw(&ALICE, b"key".to_vec(), vec![7u8; 512]));
+
+        let (status, output) = call(&contract, FUNC_READ_RAW, b"key".to_vec());
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            Option::<Vec<u8>>::decode(&mut &output[..]).unwrap(),
+            Some(vec![7u8; 512])
+        );
+
+        let (status, output) = call(&contract, FUNC_READ_RAW, b"missing".to_vec());
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Option::<Vec<u8>>::decode(&mut &output[..]).unwrap(), None);
+    });
+}
+
+#[test]
+fn read_raw_refunds_unused_weight() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        assert_ok!(Template::set_system_contract(Origin::root(), Some(ALICE)));
+        assert_ok!(Template::write_raw(&ALICE, b"small".to_vec(), vec![0u8; 1]));
+        assert_ok!(Template::write_raw(&ALICE, b"large".to_vec(), vec![0u8; 1000]));
+
+        // We always charge for the largest possible value up front, but only end up paying for
+        // what we actually read.
+        let gas_used = |key: &[u8]| {
+            let mut data = FUNC_READ_RAW.encode();
+            key.to_vec().encode_to(&mut data);
+            Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false)
+                .gas_consumed
+        };
+        assert!(gas_used(b"small") < gas_used(b"large"));
+    });
+}
+
+#[test]
+fn blobs_can_be_read() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        assert_ok!(Template::set_blob(Origin::root(), vec![7u8; 4096]));
+
+        let (status, output) = call(&contract, FUNC_BLOB, ());
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(
+            Vec::<u8>::decode(&mut &output[..]).unwrap(),
+            vec![7u8; 4096]
+        );
+    });
+}
+
+#[test]
+fn blobs_pay_for_their_length() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let gas_used = |len: usize| {
+            assert_ok!(Template::set_blob(Origin::root(), vec![0u8; len]));
+            Contracts::bare_call(
+                ALICE,
+                contract.clone(),
+                0,
+                GAS_LIMIT,
+                None,
+                FUNC_BLOB.encode(),
+                false,
+            )
+            .gas_consumed
+        };
+
+        let charged = ExtensionWeightInfo::<Runtime>::blob;
+        assert!(gas_used(4096) - gas_used(0) >= charged(4096) - charged(0));
+    });
+}
+
+fn custom_schema() -> SchemaHash {
+    sp_io::hashing::blake2_256(&type_descriptor::<Custom>())
+}
+
+#[test]
+fn checked_custom_type_accepts_matching_schema() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(
+            &contract,
+            FUNC_CHECKED_CUSTOM_TYPE,
+            (
+                false,
+                custom_schema(),
+                Custom {
+                    inner: vec![1, 2],
+                },
+            ),
+        );
+
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Template::something(), Some(2));
+    });
+}
+
+#[test]
+fn checked_custom_type_rejects_diverging_schema() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        // The payload doesn't even need to be a valid `Custom`, we never get around to decoding it.
+        let (status, _) = call(&contract, FUNC_CHECKED_CUSTOM_TYPE, (false, [0u8; 32], 42u64));
+
+        assert_eq!(status, status::SCHEMA_MISMATCH);
+        assert_eq!(Template::something(), None);
+    });
+}
+
+#[test]
+fn checked_custom_type_uses_registered_schema() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let custom = Custom {
+            inner: vec![1, 2],
+        };
+
+        // Once governance registers a new schema, contracts built against the old one get turned
+        // away.
+        assert_ok!(Template::register_schema(
+            Origin::root(),
+            FUNC_CHECKED_CUSTOM_TYPE,
+            Some(SchemaDescriptor {
+                version: 2,
+                hash: [2; 32],
+            }),
+        ));
+
+        let (status, _) = call(
+            &contract,
+            FUNC_CHECKED_CUSTOM_TYPE,
+            (false, custom_schema(), custom.clone()),
+        );
+        assert_eq!(status, status::SCHEMA_MISMATCH);
+
+        let (status, _) = call(&contract, FUNC_CHECKED_CUSTOM_TYPE, (false, [2u8; 32], custom));
+        assert_eq!(status, status::SUCCESS);
+    });
+}
+
+#[test]
+fn diverging_schemas_get_a_refund() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let custom = Custom {
+            inner: vec![1, 2],
+        };
+
+        let gas_used = |schema: SchemaHash| {
+            let mut data = FUNC_CHECKED_CUSTOM_TYPE.encode();
+            (false, schema, custom.clone()).encode_to(&mut data);
+            Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, None, data, false)
+                .gas_consumed
+        };
+
+        assert!(gas_used([0; 32]) < gas_used(custom_schema()));
+    });
+}
+
+#[test]
+fn stored_values_can_be_loaded() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+
+        let (status, _) = call(&contract, FUNC_STORE_BATCH, (false, vec![(0u32, 10u32), (1, 11)]));
+        assert_eq!(status, status::SUCCESS);
+
+        let (status, output) = call(&contract, FUNC_LOAD_VALUE, 1u32);
+        assert_eq!(status, status::SUCCESS);
+        assert_eq!(Option::<u32>::decode(&mut &output[..]).unwrap(), Some(11));
+
+        let (_, output) = call(&contract, FUNC_LOAD_VALUE, 2u32);
+        assert_eq!(Option::<u32>::decode(&mut &output[..]).unwrap(), None);
+    });
+}
+
+#[test]
+fn oversized_batches_get_a_status_code() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy();
+        let max_len = <Runtime as pallet_template::Config>::MaxBatchLen::get();
+        let values: Vec<(u32, u32)> = (0..=max_len).map(|i| (i, i)).collect();
+
+        let (status, _) = call(&contract, FUNC_STORE_BATCH, (false, values));
+        assert_eq!(status, status::BATCH_TOO_LARGE);
+        assert_eq!(Template::contract_value(&contract, 0), None);
+    });
+}
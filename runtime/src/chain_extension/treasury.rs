@@ -0,0 +1,80 @@
+//! Functions which let contracts file spending proposals with `pallet_treasury`, e.g. a grants
+//! contract asking for the funds it hands out.
+
+use super::{
+    runtime_calls::dispatch_as,
+    weights::WeightInfo,
+    Config,
+    ExtensionWeightInfo,
+    HandleFunc,
+};
+use chain_extension_primitives::{
+    status,
+    FUNC_PROPOSE_SPEND,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    RetVal,
+    SysConfig,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+pub(super) struct TreasuryFuncs;
+
+impl HandleFunc for TreasuryFuncs {
+    const FUNCS: &'static [u32] = &[FUNC_PROPOSE_SPEND];
+
+    fn handle<T: Config, E>(
+        func_id: u32,
+        env: &mut Environment<'_, '_, E, BufInBufOutState>,
+    ) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        match func_id {
+            // Corresponds to `propose_spend()`
+            FUNC_PROPOSE_SPEND => {
+                let (_, value, beneficiary): (bool, crate::Balance, crate::AccountId) =
+                    env.read_as()?;
+                env.charge_weight(ExtensionWeightInfo::<T>::propose_spend())?;
+
+                // The Treasury pallet only tells the proposer which index its proposal got in an
+                // event, but it's always the number of proposals made before it.
+                let index = pallet_treasury::Pallet::<T>::proposal_count();
+                let call = crate::Call::Treasury(pallet_treasury::Call::propose_spend {
+                    value,
+                    beneficiary: beneficiary.into(),
+                });
+
+                // The contract is the proposer, so the bond comes out of its own balance.
+                let contract = env.ext().address().clone();
+                let result = dispatch_as(env, contract, T::contracts_call(call))?;
+                if let RetVal::Converging(status::SUCCESS) = result {
+                    env.write(&index.encode(), false, None)?;
+                }
+                Ok(result)
+            }
+            _ => Ok(RetVal::Converging(status::UNKNOWN_FUNCTION)),
+        }
+    }
+
+    fn weight<T: Config>(func_id: u32, _input_len: u32) -> Option<Weight> {
+        type W<T> = ExtensionWeightInfo<T>;
+
+        // Just like with `dispatch()`, the Treasury pallet's call is charged for on top of this.
+        let weight = match func_id {
+            FUNC_PROPOSE_SPEND => W::<T>::propose_spend(),
+            _ => return None,
+        };
+
+        Some(weight)
+    }
+}
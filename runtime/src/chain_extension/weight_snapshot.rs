@@ -0,0 +1,288 @@
+//! Keeps the weights of our extension functions from changing by accident.
+//!
+//! For every function we record what it charges up front for a fixed input length in
+//! `weight_snapshot.txt`, which is checked in. If a change to a handler, its benchmark or the
+//! runtime's configuration moves any of these by more than the tolerance the test below fails, so
+//! contract calls can't get drastically more expensive without anyone noticing.
+//!
+//! If the change is intended, regenerate the snapshot and commit it along with the change:
+//!
+//! ```text
+//! UPDATE_WEIGHT_SNAPSHOT=1 cargo test -p contracts-node-runtime weight_snapshot
+//! ```
+//!
+//! The tolerance is given in percent by `WEIGHT_SNAPSHOT_TOLERANCE` and defaults to 10.
+
+use super::*;
+use crate::Runtime;
+use chain_extension_primitives::{
+    pack_func_id,
+    FUNC_ACCOUNT_NONCE,
+    FUNC_ADD_PROXY,
+    FUNC_APPROVE_AS_MULTI,
+    FUNC_ASSET_BALANCE,
+    FUNC_ASSET_MINT,
+    FUNC_ASSET_TOTAL_SUPPLY,
+    FUNC_ASSET_TRANSFER,
+    FUNC_ASSET_TRANSFER_APPROVED,
+    FUNC_AS_MULTI,
+    FUNC_BATCH_WRITE,
+    FUNC_BLOB,
+    FUNC_BLOCK_AUTHOR,
+    FUNC_BLOCK_HASH,
+    FUNC_BOUNDED_CUSTOM_TYPE,
+    FUNC_CALLBACK_DEPOSIT,
+    FUNC_CALLBACK_RECEIPT,
+    FUNC_CALLBACK_RESERVATION,
+    FUNC_CALLBACK_WEIGHTS,
+    FUNC_CALL_CONTRACT,
+    FUNC_CALL_COUNTS,
+    FUNC_CAPABILITIES,
+    FUNC_CHAIN_CONSTANTS,
+    FUNC_CHECKED_CUSTOM_TYPE,
+    FUNC_COMMIT,
+    FUNC_CONTRACT_INFO,
+    FUNC_COUNTER_CREATE,
+    FUNC_COUNTER_INCREMENT,
+    FUNC_COUNTER_SET_ACCESS,
+    FUNC_COUNTER_VALUE,
+    FUNC_CURRENT_ERA,
+    FUNC_CUSTOM_TYPE_WITH_RESULT,
+    FUNC_DISPATCH,
+    FUNC_ENVELOPE,
+    FUNC_ESTIMATE_FEE,
+    FUNC_FREE_BALANCE,
+    FUNC_IDENTITY_OF,
+    FUNC_INSTANTIATE_CONTRACT,
+    FUNC_IS_BONDED,
+    FUNC_IS_MEMBER,
+    FUNC_LOAD_VALUE,
+    FUNC_MORE_CAPABILITIES,
+    FUNC_NFT_MINT,
+    FUNC_NFT_OWNER_OF,
+    FUNC_NFT_TRANSFER,
+    FUNC_PROPOSE_APPROVAL,
+    FUNC_PROPOSE_SPEND,
+    FUNC_PROXY,
+    FUNC_QUOTE,
+    FUNC_RANDOM,
+    FUNC_READ_FOR,
+    FUNC_READ_FROM_STORAGE,
+    FUNC_READ_RAW,
+    FUNC_REMOVE_PROXY,
+    FUNC_RESCHEDULE,
+    FUNC_RESCHEDULE_CALLBACK,
+    FUNC_REVEAL,
+    FUNC_RICH_CUSTOM_TYPE,
+    FUNC_SCHEDULER_VERSION,
+    FUNC_SCHEDULE_CALL,
+    FUNC_SCHEDULE_IN,
+    FUNC_SESSION_INFO,
+    FUNC_STORE_BATCH,
+    FUNC_STORE_ENVELOPE,
+    FUNC_TRANSFER_NATIVE,
+    FUNC_TRANSFER_NATIVE_COMPACT,
+    FUNC_VALIDATOR_STAKE,
+    FUNC_VERIFY_ECDSA,
+    FUNC_VERIFY_SR25519,
+    FUNC_VERSION,
+    FUNC_VOTE,
+    FUNC_WEIGHT_TO_FEE,
+    FUNC_WRITE_FOR_CALLER,
+    FUNC_WRITE_RAW,
+    FUNC_WRITE_TO_STORAGE,
+    FUNC_XCM_TRANSACT,
+};
+use std::collections::BTreeMap;
+
+const SNAPSHOT_PATH: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/src/chain_extension/weight_snapshot.txt");
+
+const SNAPSHOT_HEADER: &str = "\
+# What each of our extension functions charges up front for the input lengths in
+# `weight_snapshot.rs`. Regenerate with:
+#
+#     UPDATE_WEIGHT_SNAPSHOT=1 cargo test -p contracts-node-runtime weight_snapshot
+";
+
+const DEFAULT_TOLERANCE_PERCENT: u64 = 10;
+
+/// Every function along with the input length we record its weight for.
+///
+/// The lengths are roughly what our ink! contract sends for typical arguments.
+const CASES: &[(&str, u32, u32)] = &[
+    ("write_to_storage", FUNC_WRITE_TO_STORAGE, 5),
+    ("custom_type_with_result", FUNC_CUSTOM_TYPE_WITH_RESULT, 64),
+    ("schedule_call", FUNC_SCHEDULE_CALL, 34),
+    ("capabilities", FUNC_CAPABILITIES, 0),
+    ("callback_weights", FUNC_CALLBACK_WEIGHTS, 0),
+    ("write_raw", FUNC_WRITE_RAW, 64),
+    ("checked_custom_type", FUNC_CHECKED_CUSTOM_TYPE, 96),
+    ("store_batch", FUNC_STORE_BATCH, 64),
+    ("load_value", FUNC_LOAD_VALUE, 4),
+    ("read_raw", FUNC_READ_RAW, 32),
+    ("write_for_caller", FUNC_WRITE_FOR_CALLER, 5),
+    ("read_for", FUNC_READ_FOR, 32),
+    ("transfer_native", FUNC_TRANSFER_NATIVE, 49),
+    ("quote", FUNC_QUOTE, 8),
+    ("store_envelope", FUNC_STORE_ENVELOPE, 256),
+    ("envelope", FUNC_ENVELOPE, 8),
+    ("asset_balance", FUNC_ASSET_BALANCE, 36),
+    ("asset_transfer", FUNC_ASSET_TRANSFER, 53),
+    ("asset_mint", FUNC_ASSET_MINT, 53),
+    ("commit", FUNC_COMMIT, 41),
+    ("reveal", FUNC_REVEAL, 64),
+    ("asset_total_supply", FUNC_ASSET_TOTAL_SUPPLY, 4),
+    ("asset_transfer_approved", FUNC_ASSET_TRANSFER_APPROVED, 85),
+    ("nft_mint", FUNC_NFT_MINT, 64),
+    ("nft_transfer", FUNC_NFT_TRANSFER, 41),
+    ("nft_owner_of", FUNC_NFT_OWNER_OF, 8),
+    ("random", FUNC_RANDOM, 32),
+    ("counter_create", FUNC_COUNTER_CREATE, 16),
+    ("counter_increment", FUNC_COUNTER_INCREMENT, 16),
+    ("counter_value", FUNC_COUNTER_VALUE, 16),
+    ("counter_set_access", FUNC_COUNTER_SET_ACCESS, 48),
+    ("verify_sr25519", FUNC_VERIFY_SR25519, 128),
+    ("verify_ecdsa", FUNC_VERIFY_ECDSA, 128),
+    ("callback_reservation", FUNC_CALLBACK_RESERVATION, 0),
+    ("callback_receipt", FUNC_CALLBACK_RECEIPT, 8),
+    ("version", FUNC_VERSION, 0),
+    ("scheduler_version", FUNC_SCHEDULER_VERSION, 0),
+    ("callback_deposit", FUNC_CALLBACK_DEPOSIT, 0),
+    ("bounded_custom_type", FUNC_BOUNDED_CUSTOM_TYPE, 64),
+    ("rich_custom_type", FUNC_RICH_CUSTOM_TYPE, 104),
+    ("transfer_native_compact", FUNC_TRANSFER_NATIVE_COMPACT, 38),
+    ("blob", FUNC_BLOB, 0),
+    ("reschedule", FUNC_RESCHEDULE, 13),
+    ("reschedule_callback", FUNC_RESCHEDULE_CALLBACK, 13),
+    ("schedule_in", FUNC_SCHEDULE_IN, 34),
+    ("call_counts", FUNC_CALL_COUNTS, 4),
+    ("read_from_storage", FUNC_READ_FROM_STORAGE, 0),
+    ("chain_constants", FUNC_CHAIN_CONSTANTS, 0),
+    ("free_balance", FUNC_FREE_BALANCE, 32),
+    ("account_nonce", FUNC_ACCOUNT_NONCE, 32),
+    ("more_capabilities", FUNC_MORE_CAPABILITIES, 0),
+    ("contract_info", FUNC_CONTRACT_INFO, 32),
+    ("call_contract", FUNC_CALL_CONTRACT, 62),
+    ("instantiate_contract", FUNC_INSTANTIATE_CONTRACT, 63),
+    ("dispatch", FUNC_DISPATCH, 8),
+    ("batch_write", FUNC_BATCH_WRITE, 14),
+    ("as_multi", FUNC_AS_MULTI, 92),
+    ("approve_as_multi", FUNC_APPROVE_AS_MULTI, 117),
+    ("add_proxy", FUNC_ADD_PROXY, 38),
+    ("remove_proxy", FUNC_REMOVE_PROXY, 38),
+    ("proxy", FUNC_PROXY, 41),
+    ("identity_of", FUNC_IDENTITY_OF, 32),
+    ("vote", FUNC_VOTE, 23),
+    ("current_era", FUNC_CURRENT_ERA, 0),
+    ("validator_stake", FUNC_VALIDATOR_STAKE, 32),
+    ("is_bonded", FUNC_IS_BONDED, 32),
+    ("propose_spend", FUNC_PROPOSE_SPEND, 49),
+    ("propose_approval", FUNC_PROPOSE_APPROVAL, 9),
+    ("is_member", FUNC_IS_MEMBER, 32),
+    ("xcm_transact", FUNC_XCM_TRANSACT, 45),
+    ("estimate_fee", FUNC_ESTIMATE_FEE, 8),
+    ("weight_to_fee", FUNC_WEIGHT_TO_FEE, 16),
+    ("block_hash", FUNC_BLOCK_HASH, 4),
+    ("block_author", FUNC_BLOCK_AUTHOR, 0),
+    ("session_info", FUNC_SESSION_INFO, 0),
+];
+
+fn current_weights() -> BTreeMap<String, Weight> {
+    CASES
+        .iter()
+        .map(|(name, func_id, input_len)| {
+            let weight = quote::<Runtime>(*func_id, *input_len)
+                .unwrap_or_else(|| panic!("{} can't be quoted", name));
+            (name.to_string(), weight)
+        })
+        .collect()
+}
+
+fn read_snapshot() -> BTreeMap<String, Weight> {
+    let snapshot = std::fs::read_to_string(SNAPSHOT_PATH).expect("the snapshot is checked in");
+    snapshot
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .map(|line| {
+            let (name, weight) = line
+                .split_once(' ')
+                .unwrap_or_else(|| panic!("malformed snapshot line: {}", line));
+            let weight = weight
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("malformed snapshot line: {}", line));
+            (name.to_string(), weight)
+        })
+        .collect()
+}
+
+fn write_snapshot(weights: &BTreeMap<String, Weight>) {
+    // We keep the order of `CASES` rather than sorting by name, that way related functions stay
+    // next to each other.
+    let mut snapshot = SNAPSHOT_HEADER.to_string();
+    for (name, _, _) in CASES {
+        snapshot.push_str(&format!("{} {}\n", name, weights[*name]));
+    }
+    std::fs::write(SNAPSHOT_PATH, snapshot).expect("the snapshot should be writable");
+}
+
+fn tolerance_percent() -> u64 {
+    std::env::var("WEIGHT_SNAPSHOT_TOLERANCE")
+        .map(|tolerance| tolerance.parse().expect("the tolerance is a whole number of percent"))
+        .unwrap_or(DEFAULT_TOLERANCE_PERCENT)
+}
+
+#[test]
+fn weights_match_the_snapshot() {
+    let current = current_weights();
+    if std::env::var_os("UPDATE_WEIGHT_SNAPSHOT").is_some() {
+        write_snapshot(&current);
+        return
+    }
+
+    let recorded = read_snapshot();
+    let tolerance = tolerance_percent();
+
+    let mut changes = Vec::new();
+    for (name, weight) in &current {
+        match recorded.get(name) {
+            None => changes.push(format!("{}: not in the snapshot", name)),
+            Some(before) if before.abs_diff(*weight) * 100 > before * tolerance => {
+                changes.push(format!("{}: {} -> {}", name, before, weight))
+            }
+            Some(_) => {}
+        }
+    }
+    for name in recorded.keys().filter(|name| !current.contains_key(*name)) {
+        changes.push(format!("{}: no longer exists", name));
+    }
+
+    assert!(
+        changes.is_empty(),
+        "weights changed by more than {}%, rerun with UPDATE_WEIGHT_SNAPSHOT=1 if that's \
+         intended:\n{}",
+        tolerance,
+        changes.join("\n")
+    );
+}
+
+#[test]
+fn every_function_is_in_the_snapshot() {
+    // Anything we can quote is something contracts can call, so it should be covered. Each of our
+    // extensions gets its own range of `func_id`s.
+    let func_ids = [MAIN_EXTENSION_ID, SCHEDULER_EXTENSION_ID]
+        .into_iter()
+        .flat_map(|extension_id| {
+            (0..=u8::MAX as u16).map(move |function| pack_func_id(extension_id, function))
+        });
+    for func_id in func_ids {
+        let covered = CASES.iter().any(|(_, id, _)| *id == func_id);
+        assert_eq!(
+            quote::<Runtime>(func_id, 0).is_some(),
+            covered,
+            "func_id {:#x} needs to be added to, or removed from, `CASES`",
+            func_id
+        );
+    }
+}
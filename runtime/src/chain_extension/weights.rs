@@ -0,0 +1,959 @@
+//! Weights for the functions exposed by our chain extension.
+//!
+//! These are hand-estimated placeholders, not benchmark output. They're laid out the way
+//! `benchmark pallet` would write them, but the numbers were picked by hand and adjusted whenever a
+//! handler changed, so they can't be checked against `chain_extension/benchmarking.rs`. Until they
+//! are replaced, `weight_snapshot.txt` at least keeps them from drifting by accident.
+//!
+//! To replace them with measured ones, build the node with `--features runtime-benchmarks` and
+//! commit the output of:
+//!
+//! ```text
+//! ./target/release/substrate-contracts-node benchmark pallet \
+//!     --chain=dev --steps=50 --repeat=20 --pallet=chain_extension --extrinsic='*' \
+//!     --execution=wasm --wasm-execution=compiled \
+//!     --output=./runtime/src/chain_extension/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for our chain extension.
+pub trait WeightInfo {
+	fn write_to_storage() -> Weight;
+	fn custom_type_with_result(n: u32, ) -> Weight;
+	fn schedule_call(s: u32, b: u32, ) -> Weight;
+	fn capabilities() -> Weight;
+	fn callback_weights() -> Weight;
+	fn write_raw(n: u32, ) -> Weight;
+	fn checked_custom_type(n: u32, ) -> Weight;
+	fn store_batch(n: u32, ) -> Weight;
+	fn load_value() -> Weight;
+	fn read_raw(k: u32, n: u32, ) -> Weight;
+	fn write_for_caller() -> Weight;
+	fn read_for() -> Weight;
+	fn transfer_native() -> Weight;
+	fn quote() -> Weight;
+	fn store_envelope(n: u32, ) -> Weight;
+	fn envelope(n: u32, ) -> Weight;
+	fn asset_balance() -> Weight;
+	fn asset_transfer() -> Weight;
+	fn asset_mint() -> Weight;
+	fn commit() -> Weight;
+	fn reveal(n: u32, ) -> Weight;
+	fn asset_total_supply() -> Weight;
+	fn asset_transfer_approved() -> Weight;
+	fn nft_mint(n: u32, ) -> Weight;
+	fn nft_transfer() -> Weight;
+	fn nft_owner_of() -> Weight;
+	fn random(n: u32, ) -> Weight;
+	fn counter_create(n: u32, ) -> Weight;
+	fn counter_increment(n: u32, ) -> Weight;
+	fn counter_value(n: u32, ) -> Weight;
+	fn counter_set_access(n: u32, ) -> Weight;
+	fn verify_sr25519(n: u32, ) -> Weight;
+	fn verify_ecdsa(n: u32, ) -> Weight;
+	fn callback_reservation() -> Weight;
+	fn callback_receipt() -> Weight;
+	fn version() -> Weight;
+	fn callback_deposit() -> Weight;
+	fn bounded_custom_type() -> Weight;
+	fn rich_custom_type(n: u32, ) -> Weight;
+	fn blob(n: u32, ) -> Weight;
+	fn note_callback_preimage(n: u32, ) -> Weight;
+	fn reschedule(s: u32, b: u32, ) -> Weight;
+	fn note_call() -> Weight;
+	fn call_counts() -> Weight;
+	fn read_from_storage() -> Weight;
+	fn chain_constants() -> Weight;
+	fn free_balance() -> Weight;
+	fn account_nonce() -> Weight;
+	fn contract_info() -> Weight;
+	fn call_contract(n: u32, ) -> Weight;
+	fn instantiate_contract(n: u32, ) -> Weight;
+	fn dispatch(n: u32, ) -> Weight;
+	fn batch_write(n: u32, ) -> Weight;
+	fn as_multi(n: u32, ) -> Weight;
+	fn approve_as_multi(s: u32, ) -> Weight;
+	fn add_proxy() -> Weight;
+	fn remove_proxy() -> Weight;
+	fn proxy(n: u32, ) -> Weight;
+	fn identity_of() -> Weight;
+	fn vote() -> Weight;
+	fn current_era() -> Weight;
+	fn validator_stake() -> Weight;
+	fn is_bonded() -> Weight;
+	fn propose_spend() -> Weight;
+	fn propose_approval() -> Weight;
+	fn is_member() -> Weight;
+	fn xcm_transact(n: u32, ) -> Weight;
+	fn estimate_fee(n: u32, ) -> Weight;
+	fn weight_to_fee() -> Weight;
+	fn block_hash() -> Weight;
+	fn block_author() -> Weight;
+	fn session_info() -> Weight;
+	fn check_code_hash() -> Weight;
+}
+
+/// Weights for our chain extension using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: Template Something (r:0 w:1)
+	fn write_to_storage() -> Weight {
+		(16_512_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Something (r:0 w:1)
+	fn custom_type_with_result(n: u32, ) -> Weight {
+		(16_980_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template CallbacksPerBlock (r:6 w:1)
+	// Storage: Template ScheduleCounts (r:1 w:1)
+	// Storage: Template CallbackSlots (r:1 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	// Storage: Template SchedulerAllowlist (r:1 w:0)
+	// Storage: Template CallbackSelectors (r:1 w:0)
+	// Storage: Template NextCallbackHandle (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Template CallbackEscrow (r:0 w:1)
+	// Storage: Template CallbackDeposits (r:0 w:1)
+	// Storage: Template CallbackAddresses (r:0 w:1)
+	// Storage: Template CallbackOrigins (r:0 w:1)
+	fn schedule_call(s: u32, b: u32, ) -> Weight {
+		(67_380_000 as Weight)
+			.saturating_add((77_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((1_090_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(T::DbWeight::get().writes(11 as Weight))
+	}
+	fn capabilities() -> Weight {
+		(1_240_000 as Weight)
+	}
+	// Storage: Template CallbackWeights (r:1 w:0)
+	fn callback_weights() -> Weight {
+		(4_310_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template SystemContract (r:1 w:0)
+	// Storage: Template RawStorage (r:0 w:1)
+	fn write_raw(n: u32, ) -> Weight {
+		(18_460_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Schemas (r:1 w:0)
+	// Storage: Template Something (r:0 w:1)
+	fn checked_custom_type(n: u32, ) -> Weight {
+		(103_820_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template ContractValues (r:0 w:1)
+	fn store_batch(n: u32, ) -> Weight {
+		(9_870_000 as Weight)
+			.saturating_add((3_450_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: Template ContractValues (r:1 w:0)
+	fn load_value() -> Weight {
+		(5_120_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template RawStorage (r:1 w:0)
+	fn read_raw(k: u32, n: u32, ) -> Weight {
+		(7_310_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template AccountValues (r:0 w:1)
+	fn write_for_caller() -> Weight {
+		(14_920_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template AccountValues (r:1 w:0)
+	fn read_for() -> Weight {
+		(5_870_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: System Account (r:2 w:2)
+	fn transfer_native() -> Weight {
+		(42_610_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn quote() -> Weight {
+		(1_870_000 as Weight)
+	}
+	// Storage: Template NextEnvelopeId (r:1 w:1)
+	// Storage: Template Envelopes (r:0 w:1)
+	fn store_envelope(n: u32, ) -> Weight {
+		(21_540_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Template Envelopes (r:1 w:0)
+	fn envelope(n: u32, ) -> Weight {
+		(6_920_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Assets Account (r:1 w:0)
+	fn asset_balance() -> Weight {
+		(6_410_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn asset_transfer() -> Weight {
+		(47_830_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn asset_mint() -> Weight {
+		(31_260_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Template NextCommitmentId (r:1 w:1)
+	// Storage: Template Commitments (r:0 w:1)
+	fn commit() -> Weight {
+		(18_760_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Template Commitments (r:1 w:1)
+	fn reveal(n: u32, ) -> Weight {
+		(17_390_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	fn asset_total_supply() -> Weight {
+		(6_180_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	fn asset_transfer_approved() -> Weight {
+		(66_540_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Uniques Class (r:1 w:1)
+	// Storage: Uniques Asset (r:1 w:1)
+	// Storage: Uniques InstanceMetadataOf (r:1 w:1)
+	// Storage: Uniques Account (r:0 w:1)
+	fn nft_mint(n: u32, ) -> Weight {
+		(41_270_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Uniques Class (r:1 w:0)
+	// Storage: Uniques Asset (r:1 w:1)
+	// Storage: Uniques Account (r:0 w:2)
+	fn nft_transfer() -> Weight {
+		(29_840_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Uniques Asset (r:1 w:0)
+	fn nft_owner_of() -> Weight {
+		(6_930_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	fn random(n: u32, ) -> Weight {
+		(9_470_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template Counters (r:1 w:1)
+	fn counter_create(n: u32, ) -> Weight {
+		(15_820_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Counters (r:1 w:1)
+	fn counter_increment(n: u32, ) -> Weight {
+		(18_240_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Counters (r:1 w:0)
+	fn counter_value(n: u32, ) -> Weight {
+		(7_910_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template Counters (r:1 w:1)
+	fn counter_set_access(n: u32, ) -> Weight {
+		(19_660_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn verify_sr25519(n: u32, ) -> Weight {
+		(47_620_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+	}
+	fn verify_ecdsa(n: u32, ) -> Weight {
+		(55_140_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+	}
+	// Storage: Template CallbackWeightUsed (r:1 w:0)
+	fn callback_reservation() -> Weight {
+		(3_870_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template Receipts (r:1 w:0)
+	fn callback_receipt() -> Weight {
+		(4_120_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn version() -> Weight {
+		(1_180_000 as Weight)
+	}
+	fn callback_deposit() -> Weight {
+		(1_210_000 as Weight)
+	}
+	// Storage: Template Something (r:0 w:1)
+	fn bounded_custom_type() -> Weight {
+		(17_046_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Something (r:0 w:1)
+	fn rich_custom_type(n: u32, ) -> Weight {
+		(17_392_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Blob (r:1 w:0)
+	fn blob(n: u32, ) -> Weight {
+		(3_870_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Preimage StatusFor (r:1 w:1)
+	// Storage: Preimage PreimageFor (r:0 w:1)
+	fn note_callback_preimage(n: u32, ) -> Weight {
+		(15_730_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Template CallbackAddresses (r:1 w:1)
+	// Storage: Scheduler Agenda (r:2 w:2)
+	// Storage: Preimage PreimageFor (r:1 w:0)
+	// Storage: Template CallbacksPerBlock (r:6 w:2)
+	// Storage: Template CallbackSlots (r:1 w:2)
+	fn reschedule(s: u32, b: u32, ) -> Weight {
+		(48_930_000 as Weight)
+			.saturating_add((154_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((1_090_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: Template ExtensionCalls (r:1 w:1)
+	fn note_call() -> Weight {
+		(3_510_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template ExtensionCalls (r:1 w:0)
+	fn call_counts() -> Weight {
+		(4_120_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template Something (r:1 w:0)
+	fn read_from_storage() -> Weight {
+		(3_950_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn chain_constants() -> Weight {
+		(1_310_000 as Weight)
+	}
+	// Storage: System Account (r:1 w:0)
+	fn free_balance() -> Weight {
+		(5_640_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: System Account (r:1 w:0)
+	fn account_nonce() -> Weight {
+		(5_310_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Contracts ContractInfoOf (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	fn contract_info() -> Weight {
+		(9_870_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Contracts ContractInfoOf (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn call_contract(n: u32, ) -> Weight {
+		(44_920_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Contracts Nonce (r:1 w:1)
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: Contracts OwnerInfoOf (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn instantiate_contract(n: u32, ) -> Weight {
+		(98_450_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn dispatch(n: u32, ) -> Weight {
+		(5_120_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+	}
+	fn batch_write(n: u32, ) -> Weight {
+		(3_920_000 as Weight)
+			.saturating_add((120_000 as Weight).saturating_mul(n as Weight))
+	}
+	fn as_multi(n: u32, ) -> Weight {
+		(6_210_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+	}
+	fn approve_as_multi(s: u32, ) -> Weight {
+		(5_840_000 as Weight)
+			.saturating_add((32_000 as Weight).saturating_mul(s as Weight))
+	}
+	fn add_proxy() -> Weight {
+		(4_730_000 as Weight)
+	}
+	fn remove_proxy() -> Weight {
+		(4_650_000 as Weight)
+	}
+	fn proxy(n: u32, ) -> Weight {
+		(5_310_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+	}
+	// Storage: Identity IdentityOf (r:1 w:0)
+	fn identity_of() -> Weight {
+		(21_470_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn vote() -> Weight {
+		(4_880_000 as Weight)
+	}
+	// Storage: Staking CurrentEra (r:1 w:0)
+	fn current_era() -> Weight {
+		(2_210_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Staking ActiveEra (r:1 w:0)
+	// Storage: Staking ErasStakers (r:1 w:0)
+	fn validator_stake() -> Weight {
+		(9_140_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Staking Bonded (r:1 w:0)
+	fn is_bonded() -> Weight {
+		(4_560_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Treasury ProposalCount (r:1 w:0)
+	fn propose_spend() -> Weight {
+		(5_170_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn propose_approval() -> Weight {
+		(6_390_000 as Weight)
+	}
+	// Storage: CouncilMembership Members (r:1 w:0)
+	fn is_member() -> Weight {
+		(8_420_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn xcm_transact(n: u32, ) -> Weight {
+		(6_870_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+	}
+	// Storage: TransactionPayment NextFeeMultiplier (r:1 w:0)
+	fn estimate_fee(n: u32, ) -> Weight {
+		(5_480_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn weight_to_fee() -> Weight {
+		(1_920_000 as Weight)
+	}
+	// Storage: System BlockHash (r:1 w:0)
+	fn block_hash() -> Weight {
+		(4_960_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Authorship Author (r:1 w:1)
+	// Storage: System Digest (r:1 w:0)
+	fn block_author() -> Weight {
+		(5_720_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Session CurrentIndex (r:1 w:0)
+	// Storage: Staking ActiveEra (r:1 w:0)
+	fn session_info() -> Weight {
+		(6_150_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Template ApprovedCodeHashes (r:1 w:0)
+	fn check_code_hash() -> Weight {
+		(3_840_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: Template Something (r:0 w:1)
+	fn write_to_storage() -> Weight {
+		(16_512_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Something (r:0 w:1)
+	fn custom_type_with_result(n: u32, ) -> Weight {
+		(16_980_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template CallbacksPerBlock (r:6 w:1)
+	// Storage: Template ScheduleCounts (r:1 w:1)
+	// Storage: Template CallbackSlots (r:1 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	// Storage: Template SchedulerAllowlist (r:1 w:0)
+	// Storage: Template CallbackSelectors (r:1 w:0)
+	// Storage: Template NextCallbackHandle (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Template CallbackEscrow (r:0 w:1)
+	// Storage: Template CallbackDeposits (r:0 w:1)
+	// Storage: Template CallbackAddresses (r:0 w:1)
+	// Storage: Template CallbackOrigins (r:0 w:1)
+	fn schedule_call(s: u32, b: u32, ) -> Weight {
+		(67_380_000 as Weight)
+			.saturating_add((77_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((1_090_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(11 as Weight))
+	}
+	fn capabilities() -> Weight {
+		(1_240_000 as Weight)
+	}
+	// Storage: Template CallbackWeights (r:1 w:0)
+	fn callback_weights() -> Weight {
+		(4_310_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template SystemContract (r:1 w:0)
+	// Storage: Template RawStorage (r:0 w:1)
+	fn write_raw(n: u32, ) -> Weight {
+		(18_460_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Schemas (r:1 w:0)
+	// Storage: Template Something (r:0 w:1)
+	fn checked_custom_type(n: u32, ) -> Weight {
+		(103_820_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template ContractValues (r:0 w:1)
+	fn store_batch(n: u32, ) -> Weight {
+		(9_870_000 as Weight)
+			.saturating_add((3_450_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: Template ContractValues (r:1 w:0)
+	fn load_value() -> Weight {
+		(5_120_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template RawStorage (r:1 w:0)
+	fn read_raw(k: u32, n: u32, ) -> Weight {
+		(7_310_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template AccountValues (r:0 w:1)
+	fn write_for_caller() -> Weight {
+		(14_920_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template AccountValues (r:1 w:0)
+	fn read_for() -> Weight {
+		(5_870_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: System Account (r:2 w:2)
+	fn transfer_native() -> Weight {
+		(42_610_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn quote() -> Weight {
+		(1_870_000 as Weight)
+	}
+	// Storage: Template NextEnvelopeId (r:1 w:1)
+	// Storage: Template Envelopes (r:0 w:1)
+	fn store_envelope(n: u32, ) -> Weight {
+		(21_540_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Template Envelopes (r:1 w:0)
+	fn envelope(n: u32, ) -> Weight {
+		(6_920_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Assets Account (r:1 w:0)
+	fn asset_balance() -> Weight {
+		(6_410_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn asset_transfer() -> Weight {
+		(47_830_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn asset_mint() -> Weight {
+		(31_260_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Template NextCommitmentId (r:1 w:1)
+	// Storage: Template Commitments (r:0 w:1)
+	fn commit() -> Weight {
+		(18_760_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Template Commitments (r:1 w:1)
+	fn reveal(n: u32, ) -> Weight {
+		(17_390_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Assets Asset (r:1 w:0)
+	fn asset_total_supply() -> Weight {
+		(6_180_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Assets Approvals (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	fn asset_transfer_approved() -> Weight {
+		(66_540_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	// Storage: Uniques Class (r:1 w:1)
+	// Storage: Uniques Asset (r:1 w:1)
+	// Storage: Uniques InstanceMetadataOf (r:1 w:1)
+	// Storage: Uniques Account (r:0 w:1)
+	fn nft_mint(n: u32, ) -> Weight {
+		(41_270_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Uniques Class (r:1 w:0)
+	// Storage: Uniques Asset (r:1 w:1)
+	// Storage: Uniques Account (r:0 w:2)
+	fn nft_transfer() -> Weight {
+		(29_840_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Uniques Asset (r:1 w:0)
+	fn nft_owner_of() -> Weight {
+		(6_930_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	fn random(n: u32, ) -> Weight {
+		(9_470_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template Counters (r:1 w:1)
+	fn counter_create(n: u32, ) -> Weight {
+		(15_820_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Counters (r:1 w:1)
+	fn counter_increment(n: u32, ) -> Weight {
+		(18_240_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Counters (r:1 w:0)
+	fn counter_value(n: u32, ) -> Weight {
+		(7_910_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template Counters (r:1 w:1)
+	fn counter_set_access(n: u32, ) -> Weight {
+		(19_660_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn verify_sr25519(n: u32, ) -> Weight {
+		(47_620_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+	}
+	fn verify_ecdsa(n: u32, ) -> Weight {
+		(55_140_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+	}
+	// Storage: Template CallbackWeightUsed (r:1 w:0)
+	fn callback_reservation() -> Weight {
+		(3_870_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template Receipts (r:1 w:0)
+	fn callback_receipt() -> Weight {
+		(4_120_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn version() -> Weight {
+		(1_180_000 as Weight)
+	}
+	fn callback_deposit() -> Weight {
+		(1_210_000 as Weight)
+	}
+	// Storage: Template Something (r:0 w:1)
+	fn bounded_custom_type() -> Weight {
+		(17_046_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Something (r:0 w:1)
+	fn rich_custom_type(n: u32, ) -> Weight {
+		(17_392_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template Blob (r:1 w:0)
+	fn blob(n: u32, ) -> Weight {
+		(3_870_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Preimage StatusFor (r:1 w:1)
+	// Storage: Preimage PreimageFor (r:0 w:1)
+	fn note_callback_preimage(n: u32, ) -> Weight {
+		(15_730_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Template CallbackAddresses (r:1 w:1)
+	// Storage: Scheduler Agenda (r:2 w:2)
+	// Storage: Preimage PreimageFor (r:1 w:0)
+	// Storage: Template CallbacksPerBlock (r:6 w:2)
+	// Storage: Template CallbackSlots (r:1 w:2)
+	fn reschedule(s: u32, b: u32, ) -> Weight {
+		(48_930_000 as Weight)
+			.saturating_add((154_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((1_090_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: Template ExtensionCalls (r:1 w:1)
+	fn note_call() -> Weight {
+		(3_510_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Template ExtensionCalls (r:1 w:0)
+	fn call_counts() -> Weight {
+		(4_120_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Template Something (r:1 w:0)
+	fn read_from_storage() -> Weight {
+		(3_950_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn chain_constants() -> Weight {
+		(1_310_000 as Weight)
+	}
+	// Storage: System Account (r:1 w:0)
+	fn free_balance() -> Weight {
+		(5_640_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: System Account (r:1 w:0)
+	fn account_nonce() -> Weight {
+		(5_310_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Contracts ContractInfoOf (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
+	fn contract_info() -> Weight {
+		(9_870_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Contracts ContractInfoOf (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn call_contract(n: u32, ) -> Weight {
+		(44_920_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Contracts Nonce (r:1 w:1)
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: Contracts OwnerInfoOf (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn instantiate_contract(n: u32, ) -> Weight {
+		(98_450_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn dispatch(n: u32, ) -> Weight {
+		(5_120_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+	}
+	fn batch_write(n: u32, ) -> Weight {
+		(3_920_000 as Weight)
+			.saturating_add((120_000 as Weight).saturating_mul(n as Weight))
+	}
+	fn as_multi(n: u32, ) -> Weight {
+		(6_210_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+	}
+	fn approve_as_multi(s: u32, ) -> Weight {
+		(5_840_000 as Weight)
+			.saturating_add((32_000 as Weight).saturating_mul(s as Weight))
+	}
+	fn add_proxy() -> Weight {
+		(4_730_000 as Weight)
+	}
+	fn remove_proxy() -> Weight {
+		(4_650_000 as Weight)
+	}
+	fn proxy(n: u32, ) -> Weight {
+		(5_310_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+	}
+	// Storage: Identity IdentityOf (r:1 w:0)
+	fn identity_of() -> Weight {
+		(21_470_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn vote() -> Weight {
+		(4_880_000 as Weight)
+	}
+	// Storage: Staking CurrentEra (r:1 w:0)
+	fn current_era() -> Weight {
+		(2_210_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Staking ActiveEra (r:1 w:0)
+	// Storage: Staking ErasStakers (r:1 w:0)
+	fn validator_stake() -> Weight {
+		(9_140_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Staking Bonded (r:1 w:0)
+	fn is_bonded() -> Weight {
+		(4_560_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Treasury ProposalCount (r:1 w:0)
+	fn propose_spend() -> Weight {
+		(5_170_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn propose_approval() -> Weight {
+		(6_390_000 as Weight)
+	}
+	// Storage: CouncilMembership Members (r:1 w:0)
+	fn is_member() -> Weight {
+		(8_420_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn xcm_transact(n: u32, ) -> Weight {
+		(6_870_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+	}
+	// Storage: TransactionPayment NextFeeMultiplier (r:1 w:0)
+	fn estimate_fee(n: u32, ) -> Weight {
+		(5_480_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn weight_to_fee() -> Weight {
+		(1_920_000 as Weight)
+	}
+	// Storage: System BlockHash (r:1 w:0)
+	fn block_hash() -> Weight {
+		(4_960_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: Authorship Author (r:1 w:1)
+	// Storage: System Digest (r:1 w:0)
+	fn block_author() -> Weight {
+		(5_720_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Session CurrentIndex (r:1 w:0)
+	// Storage: Staking ActiveEra (r:1 w:0)
+	fn session_info() -> Weight {
+		(6_150_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Template ApprovedCodeHashes (r:1 w:0)
+	fn check_code_hash() -> Weight {
+		(3_840_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+}
@@ -2,20 +2,29 @@
 // `construct_runtime!` does a lot of recursion and requires us to increase the limit to 256.
 #![recursion_limit = "256"]
 
+extern crate alloc;
+
 // Make the WASM binary available.
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 mod chain_extension;
+pub use chain_extension::ChainExtensionApi;
+
+#[cfg(test)]
+mod tests;
 
-use frame_support::{traits::OnRuntimeUpgrade, weights::DispatchClass};
+use frame_support::{
+    traits::{AsEnsureOriginWithArg, OnRuntimeUpgrade},
+    weights::DispatchClass,
+};
 use frame_system::limits::{BlockLength, BlockWeights};
 use pallet_contracts::{migration, weights::WeightInfo, DefaultContractAccessWeight};
 use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
-    traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, Verify},
+    traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, OpaqueKeys, Verify},
     transaction_validity::{TransactionSource, TransactionValidity},
     ApplyExtrinsicResult, MultiSignature,
 };
@@ -36,6 +45,7 @@ pub use frame_support::{
     },
     StorageValue,
 };
+pub use chain_extension_primitives::ProxyType;
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
@@ -93,12 +103,14 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     // The version of the runtime specification. A full node will not attempt to use its native
     //   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
-    // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
-    //   the compatible custom types.
-    spec_version: 100,
+    // This value started out at 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to
+    //   use the compatible custom types.
+    spec_version: 102,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    // Bumped whenever the indices of pallets or calls change, since that's what signed
+    //   transactions encode.
+    transaction_version: 3,
     state_version: 1,
 };
 
@@ -126,6 +138,8 @@ const CONTRACTS_DEBUG_OUTPUT: bool = true;
 
 // Unit = the base number of indivisible units for balances
 const UNIT: Balance = 1_000_000_000_000;
+// The number of decimal places in a `UNIT`, which is what wallets should display balances in.
+const TOKEN_DECIMALS: u8 = 12;
 const MILLIUNIT: Balance = 1_000_000_000;
 const EXISTENTIAL_DEPOSIT: Balance = MILLIUNIT;
 
@@ -263,6 +277,58 @@ impl pallet_balances::Config for Runtime {
     type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+    pub const AssetDeposit: Balance = 100 * UNIT;
+    pub const AssetAccountDeposit: Balance = deposit(1, 16);
+    pub const ApprovalDeposit: Balance = EXISTENTIAL_DEPOSIT;
+    pub const MetadataDepositBase: Balance = deposit(1, 68);
+    pub const MetadataDepositPerByte: Balance = deposit(0, 1);
+}
+
+impl pallet_assets::Config for Runtime {
+    type Event = Event;
+    type Balance = Balance;
+    type AssetId = chain_extension_primitives::AssetId;
+    type Currency = Balances;
+    type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+    type AssetDeposit = AssetDeposit;
+    type AssetAccountDeposit = AssetAccountDeposit;
+    type MetadataDepositBase = MetadataDepositBase;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type ApprovalDeposit = ApprovalDeposit;
+    type StringLimit = ConstU32<50>;
+    type Freezer = ();
+    type Extra = ();
+    type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const CollectionDeposit: Balance = 100 * UNIT;
+    pub const ItemDeposit: Balance = UNIT;
+    pub const UniquesMetadataDepositBase: Balance = deposit(1, 129);
+    pub const AttributeDepositBase: Balance = deposit(1, 0);
+    pub const UniquesDepositPerByte: Balance = deposit(0, 1);
+}
+
+impl pallet_uniques::Config for Runtime {
+    type Event = Event;
+    type CollectionId = chain_extension_primitives::CollectionId;
+    type ItemId = chain_extension_primitives::ItemId;
+    type Currency = Balances;
+    type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+    type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+    type Locker = ();
+    type CollectionDeposit = CollectionDeposit;
+    type ItemDeposit = ItemDeposit;
+    type MetadataDepositBase = UniquesMetadataDepositBase;
+    type AttributeDepositBase = AttributeDepositBase;
+    type DepositPerByte = UniquesDepositPerByte;
+    type StringLimit = ConstU32<128>;
+    type KeyLimit = ConstU32<32>;
+    type ValueLimit = ConstU32<64>;
+    type WeightInfo = pallet_uniques::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
     pub OperationalFeeMultiplier: u8 = 5;
 }
@@ -322,7 +388,13 @@ impl pallet_contracts::Config for Runtime {
     ///
     /// If we don't have this and try to deploy a contract which uses a chain extension we will end
     /// up getting `CodeRejected` errors.
-    type ChainExtension = chain_extension::MyExtension;
+    ///
+    /// We have more than one, so we hand over all of them and let the `func_id` of each call
+    /// decide which one it goes to.
+    type ChainExtension = chain_extension::ChainExtensions<(
+        chain_extension::MyExtension,
+        chain_extension::SchedulerExtension,
+    )>;
 
     type DeletionQueueDepth = DeletionQueueDepth;
     type DeletionWeightLimit = DeletionWeightLimit;
@@ -341,9 +413,166 @@ impl pallet_contracts::Config for Runtime {
     type RelaxedMaxCodeLen = ConstU32<{ 512 * 1024 }>;
 }
 
+/// Our chain extension schedules `pallet_contracts::Call::call` as callbacks, so that's what we
+/// look for when `pallet_template` asks us which contract a callback belongs to.
+pub struct ContractCallbackTarget;
+
+impl pallet_template::CallbackTarget<AccountId, Call> for ContractCallbackTarget {
+    fn target(call: &Call) -> Option<AccountId> {
+        match call {
+            Call::Contracts(pallet_contracts::Call::call {
+                dest: sp_runtime::MultiAddress::Id(contract),
+                ..
+            }) => Some(contract.clone()),
+            _ => None,
+        }
+    }
+
+    fn is_contract(account: &AccountId) -> bool {
+        // Reading any key of an account which isn't a contract fails with `DoesntExist`, whether
+        // the contract stored anything under that key or not.
+        !matches!(
+            Contracts::get_storage(account.clone(), [0; 32]),
+            Err(pallet_contracts_primitives::ContractAccessError::DoesntExist)
+        )
+    }
+}
+
+/// The calls contracts may `dispatch()` through our chain extension, on behalf of whoever called
+/// them, or `proxy()` on behalf of an account which made them its proxy.
+///
+/// Anything `pallet_template` offers goes, except for dispatching callbacks, since that wraps a
+/// call of its own. The Scheduler's calls only go if the call they schedule does, otherwise
+/// contracts could use them to get around this filter. That rules out calls which are only
+/// scheduled by their hash, and cancelling, which would let contracts mess with anything their
+/// caller has scheduled.
+pub struct ContractDispatchFilter;
+
+impl frame_support::traits::Contains<Call> for ContractDispatchFilter {
+    fn contains(call: &Call) -> bool {
+        use frame_support::traits::schedule::MaybeHashed;
+        use pallet_scheduler::Call as SchedulerCall;
+
+        match call {
+            Call::Template(pallet_template::Call::dispatch_callback { .. }) => false,
+            Call::Template(_) => true,
+            Call::Scheduler(
+                SchedulerCall::schedule { call, .. }
+                | SchedulerCall::schedule_named { call, .. }
+                | SchedulerCall::schedule_after { call, .. }
+                | SchedulerCall::schedule_named_after { call, .. },
+            ) => match call.as_ref() {
+                MaybeHashed::Value(call) => Self::contains(call),
+                MaybeHashed::Hash(_) => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+parameter_types! {
+    /// Where the messages contracts send with `xcm_transact()` go, our relay chain were we a
+    /// parachain.
+    pub XcmDestination: xcm::latest::MultiLocation = xcm::latest::MultiLocation::parent();
+}
+
+/// How the messages contracts send make it off our chain.
+///
+/// We're a standalone chain, so there's no transport to hand them to and none of them do. A
+/// parachain would plug its UMP and XCMP routers in here.
+pub type XcmRouter = ();
+
+/// Uploads and instantiates the contracts from `pallet_template`'s genesis config, just like
+/// `instantiate_with_code` would for their deployer.
+pub struct GenesisContractDeployer;
+
+impl pallet_template::ContractDeployer<AccountId> for GenesisContractDeployer {
+    fn deploy(
+        origin: AccountId,
+        code: Vec<u8>,
+        data: Vec<u8>,
+    ) -> Result<AccountId, sp_runtime::DispatchError> {
+        Contracts::bare_instantiate(
+            origin,
+            0,
+            RuntimeBlockWeights::get().max_block,
+            None,
+            pallet_contracts_primitives::Code::Upload(code.into()),
+            data,
+            Vec::new(),
+            false,
+        )
+        .result
+        .map(|instantiated| instantiated.account_id)
+    }
+}
+
+/// Picks the events contracts emitted through `pallet_contracts` out of all of our events.
+pub struct ContractEmittedEvents;
+
+impl pallet_template::ContractEvents<AccountId, Event> for ContractEmittedEvents {
+    fn contract_event(event: &Event) -> Option<(AccountId, Vec<u8>)> {
+        match event {
+            Event::Contracts(pallet_contracts::Event::ContractEmitted { contract, data }) => {
+                Some((contract.clone(), data.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Lets `pallet_template`'s off-chain worker submit unsigned transactions.
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = UncheckedExtrinsic;
+}
+
+parameter_types! {
+    /// Scheduled contract callbacks always get at least this much of every block.
+    pub const CallbackReservation: Perbill = Perbill::from_percent(10);
+    /// About 5% of a block is plenty for any one callback.
+    pub const MaxCallbackGasLimit: Weight = MAXIMUM_BLOCK_WEIGHT / 20;
+    /// What it takes to hold on to a spot in the agenda until the callback runs.
+    pub const CallbackDeposit: Balance = 10 * MILLIUNIT;
+    /// Our callbacks are usually well below this, but the ones which aren't only leave their hash
+    /// in the agenda. This is kept in storage so that governance can change it without an
+    /// upgrade.
+    pub storage MaxInlineCallbackLen: u32 = 128;
+}
+
 impl pallet_template::Config for Runtime {
     /// The ubiquitous event type.
     type Event = Event;
+    type Call = Call;
+    type CallbackTarget = ContractCallbackTarget;
+    type ContractDeployer = GenesisContractDeployer;
+    type ContractEvents = ContractEmittedEvents;
+    type Currency = Balances;
+    type CallbackDeposit = CallbackDeposit;
+    type MaxRawKeyLen = ConstU32<128>;
+    type MaxRawValueLen = ConstU32<{ 16 * 1024 }>;
+    // Leaves plenty of room in the 16 KiB ink! contracts read extension output into.
+    type MaxBlobLen = ConstU32<{ 8 * 1024 }>;
+    type MaxBatchLen = ConstU32<{ chain_extension_primitives::MAX_STORE_BATCH_LEN }>;
+    type MaxCallbacksPerBlock = MaxScheduledPerBlock;
+    type MaxCallbacksPerContract = ConstU32<10>;
+    type MaxSchedulesPerPeriod = ConstU32<100>;
+    type SchedulingPeriod = ConstU32<10>;
+    type MaxCallbackDelay = ConstU32<5>;
+    type MaxCallbackSelectors = ConstU32<16>;
+    type MaxAllowedSchedulers = ConstU32<16>;
+    type MaxApprovedCodeHashes = ConstU32<16>;
+    type CallbackReservation = CallbackReservation;
+    type MaxCallbackGasLimit = MaxCallbackGasLimit;
+    type MaxInlineCallbackLen = MaxInlineCallbackLen;
+    type MaxCiphertextLen = ConstU32<{ 4 * 1024 }>;
+    type CommitmentRetention = ConstU32<10>;
+    type MaxCounterNameLen = ConstU32<64>;
+    type MaxCounterMembers = ConstU32<16>;
+    type ReceiptRetention = ConstU32<100>;
 }
 
 parameter_types! {
@@ -351,6 +580,13 @@ parameter_types! {
         RuntimeBlockWeights::get().max_block;
 }
 
+parameter_types! {
+    // Shared with `pallet_template`, which makes sure our callbacks never overrun an agenda.
+    pub const MaxScheduledPerBlock: u32 = 50;
+    // Should a preimage go missing, the Scheduler tries again this many blocks later.
+    pub const NoPreimagePostponement: Option<u32> = Some(10);
+}
+
 impl pallet_scheduler::Config for Runtime {
     type Event = Event;
     type Origin = Origin;
@@ -359,10 +595,293 @@ impl pallet_scheduler::Config for Runtime {
     type MaximumWeight = MaximumSchedulerWeight;
     type ScheduleOrigin = frame_system::EnsureSigned<AccountId>;
     type OriginPrivilegeCmp = frame_support::traits::EqualPrivilegeOnly;
-    type MaxScheduledPerBlock = ConstU32<50>;
+    type MaxScheduledPerBlock = MaxScheduledPerBlock;
     type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
-    type PreimageProvider = ();
-    type NoPreimagePostponement = ();
+    type PreimageProvider = Preimage;
+    type NoPreimagePostponement = NoPreimagePostponement;
+}
+
+parameter_types! {
+    pub const PreimageMaxSize: u32 = 4096 * 1024;
+    pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+    pub const PreimageByteDeposit: Balance = deposit(0, 1);
+}
+
+impl pallet_preimage::Config for Runtime {
+    type Event = Event;
+    type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+    type Currency = Balances;
+    type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxSize = PreimageMaxSize;
+    type BaseDeposit = PreimageBaseDeposit;
+    type ByteDeposit = PreimageByteDeposit;
+}
+
+impl pallet_utility::Config for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type PalletsOrigin = OriginCaller;
+    type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    // One storage item, with a 32 byte key and a value of up to 88 bytes.
+    pub const MultisigDepositBase: Balance = deposit(1, 88);
+    // Every signatory adds another 32 bytes to the value.
+    pub const MultisigDepositFactor: Balance = deposit(0, 32);
+    pub const MaxSignatories: u16 = 100;
+}
+
+impl pallet_multisig::Config for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type DepositBase = MultisigDepositBase;
+    type DepositFactor = MultisigDepositFactor;
+    type MaxSignatories = MaxSignatories;
+    type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    // One storage item, with a 32 byte key and a value of 8 bytes.
+    pub const ProxyDepositBase: Balance = deposit(1, 8);
+    // Every proxy adds another 33 bytes to the value.
+    pub const ProxyDepositFactor: Balance = deposit(0, 33);
+    pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+    // Every announcement adds another 66 bytes to the value.
+    pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+}
+
+impl frame_support::traits::InstanceFilter<Call> for ProxyType {
+    fn filter(&self, call: &Call) -> bool {
+        match self {
+            ProxyType::Any => true,
+            ProxyType::Template => matches!(call, Call::Template(..)),
+        }
+    }
+
+    fn is_superset(&self, other: &Self) -> bool {
+        self == other || *self == ProxyType::Any
+    }
+}
+
+impl pallet_proxy::Config for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type ProxyType = ProxyType;
+    type ProxyDepositBase = ProxyDepositBase;
+    type ProxyDepositFactor = ProxyDepositFactor;
+    type MaxProxies = ConstU32<32>;
+    type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+    type MaxPending = ConstU32<32>;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = AnnouncementDepositBase;
+    type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+parameter_types! {
+    // One storage item, with a 32 byte key and a value of 258 bytes.
+    pub const BasicDeposit: Balance = deposit(1, 258);
+    // Every additional field adds another 66 bytes to the value.
+    pub const FieldDeposit: Balance = deposit(0, 66);
+    // One storage item, with a 32 byte key and a value of 53 bytes.
+    pub const SubAccountDeposit: Balance = deposit(1, 53);
+}
+
+impl pallet_identity::Config for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type BasicDeposit = BasicDeposit;
+    type FieldDeposit = FieldDeposit;
+    type SubAccountDeposit = SubAccountDeposit;
+    type MaxSubAccounts = ConstU32<100>;
+    type MaxAdditionalFields = ConstU32<100>;
+    type MaxRegistrars = ConstU32<20>;
+    // Slashed deposits are burned.
+    type Slashed = ();
+    type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+    type RegistrarOrigin = frame_system::EnsureRoot<AccountId>;
+    type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    // With six second blocks these are a few minutes each, which keeps referenda quick to try out
+    // on a development chain.
+    pub const LaunchPeriod: BlockNumber = 50;
+    pub const VotingPeriod: BlockNumber = 50;
+    pub const FastTrackVotingPeriod: BlockNumber = 10;
+    pub const EnactmentPeriod: BlockNumber = 10;
+    pub const CooloffPeriod: BlockNumber = 50;
+    pub const MinimumDeposit: Balance = UNIT;
+}
+
+impl pallet_democracy::Config for Runtime {
+    type Proposal = Call;
+    type Event = Event;
+    type Currency = Balances;
+    type EnactmentPeriod = EnactmentPeriod;
+    type LaunchPeriod = LaunchPeriod;
+    type VotingPeriod = VotingPeriod;
+    type VoteLockingPeriod = EnactmentPeriod;
+    type MinimumDeposit = MinimumDeposit;
+    // The Council doesn't take part in referenda, so root stands in for every collective origin.
+    type ExternalOrigin = frame_system::EnsureRoot<AccountId>;
+    type ExternalMajorityOrigin = frame_system::EnsureRoot<AccountId>;
+    type ExternalDefaultOrigin = frame_system::EnsureRoot<AccountId>;
+    type FastTrackOrigin = frame_system::EnsureRoot<AccountId>;
+    type InstantOrigin = frame_system::EnsureRoot<AccountId>;
+    type InstantAllowed = frame_support::traits::ConstBool<true>;
+    type FastTrackVotingPeriod = FastTrackVotingPeriod;
+    type CancellationOrigin = frame_system::EnsureRoot<AccountId>;
+    type BlacklistOrigin = frame_system::EnsureRoot<AccountId>;
+    type CancelProposalOrigin = frame_system::EnsureRoot<AccountId>;
+    type VetoOrigin = frame_system::EnsureSigned<AccountId>;
+    type CooloffPeriod = CooloffPeriod;
+    type PreimageByteDeposit = PreimageByteDeposit;
+    type OperationalPreimageOrigin = frame_system::EnsureSigned<AccountId>;
+    // Slashed deposits are burned.
+    type Slash = ();
+    type Scheduler = Scheduler;
+    type PalletsOrigin = OriginCaller;
+    type MaxVotes = ConstU32<100>;
+    type WeightInfo = pallet_democracy::weights::SubstrateWeight<Runtime>;
+    type MaxProposals = ConstU32<100>;
+}
+
+parameter_types! {
+    // Ten minutes with six second blocks, which makes for an hour long era.
+    pub const SessionPeriod: BlockNumber = 100;
+    pub const SessionOffset: BlockNumber = 0;
+}
+
+impl pallet_session::Config for Runtime {
+    type Event = Event;
+    type ValidatorId = AccountId;
+    type ValidatorIdOf = pallet_staking::StashOf<Runtime>;
+    type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    // Staking plans a new era every `SessionsPerEra` sessions.
+    type SessionManager = Staking;
+    type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+    type Keys = opaque::SessionKeys;
+    type WeightInfo = pallet_session::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const SessionsPerEra: u32 = 6;
+    pub const BondingDuration: u32 = 24;
+    // Slashes can be cancelled for a little while before they're applied.
+    pub const SlashDeferDuration: u32 = 6;
+    pub const OffendingValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+/// Elects validators right when the next era starts, there's no need for anything more involved
+/// on a development chain.
+pub struct OnChainSeqPhragmen;
+impl frame_election_provider_support::onchain::Config for OnChainSeqPhragmen {
+    type System = Runtime;
+    type Solver = frame_election_provider_support::SequentialPhragmen<AccountId, Perbill>;
+    type DataProvider = Staking;
+    type WeightInfo = frame_election_provider_support::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_staking::Config for Runtime {
+    type MaxNominations = ConstU32<16>;
+    type Currency = Balances;
+    type CurrencyBalance = Balance;
+    type UnixTime = Timestamp;
+    type CurrencyToVote = frame_support::traits::U128CurrencyToVote;
+    // Nothing is minted for stakers, and slashed or left over funds are burned.
+    type RewardRemainder = ();
+    type Event = Event;
+    type Slash = ();
+    type Reward = ();
+    type SessionsPerEra = SessionsPerEra;
+    type BondingDuration = BondingDuration;
+    type SlashDeferDuration = SlashDeferDuration;
+    type SlashCancelOrigin = frame_system::EnsureRoot<AccountId>;
+    // Our blocks are authored without a consensus engine, so the validators staking elects don't
+    // get to do any validating. The staking data contracts read is no less real for it.
+    type SessionInterface = ();
+    type EraPayout = ();
+    type NextNewSession = Session;
+    type MaxNominatorRewardedPerValidator = ConstU32<64>;
+    type OffendingValidatorsThreshold = OffendingValidatorsThreshold;
+    type ElectionProvider = frame_election_provider_support::onchain::UnboundedExecution<
+        OnChainSeqPhragmen,
+    >;
+    type GenesisElectionProvider = Self::ElectionProvider;
+    type VoterList = pallet_staking::UseNominatorsAndValidatorsMap<Self>;
+    type MaxUnlockingChunks = ConstU32<32>;
+    type OnStakerSlash = ();
+    type BenchmarkingConfig = pallet_staking::TestBenchmarkingConfig;
+    type WeightInfo = pallet_staking::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const ProposalBond: Permill = Permill::from_percent(5);
+    pub const ProposalBondMinimum: Balance = UNIT;
+    // Roughly every ten minutes with six second blocks, so approved proposals get paid out quickly
+    // on a development chain.
+    pub const SpendPeriod: BlockNumber = 100;
+    pub const TreasuryPalletId: frame_support::PalletId = frame_support::PalletId(*b"py/trsry");
+}
+
+impl pallet_treasury::Config for Runtime {
+    type PalletId = TreasuryPalletId;
+    type Currency = Balances;
+    // Half of the Council can approve proposals, but only root gets to reject them.
+    type ApproveOrigin = frame_support::traits::EitherOfDiverse<
+        frame_system::EnsureRoot<AccountId>,
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 1, 2>,
+    >;
+    type RejectOrigin = frame_system::EnsureRoot<AccountId>;
+    type Event = Event;
+    // The bonds of rejected proposals go to the treasury itself.
+    type OnSlash = Treasury;
+    type ProposalBond = ProposalBond;
+    type ProposalBondMinimum = ProposalBondMinimum;
+    type ProposalBondMaximum = ();
+    type SpendPeriod = SpendPeriod;
+    // Nothing gets burned, whatever isn't spent stays around for later.
+    type Burn = ();
+    type BurnDestination = ();
+    type SpendFunds = ();
+    type MaxApprovals = ConstU32<100>;
+    type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const CouncilMotionDuration: BlockNumber = 50;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+    type Origin = Origin;
+    type Proposal = Call;
+    type Event = Event;
+    type MotionDuration = CouncilMotionDuration;
+    type MaxProposals = ConstU32<100>;
+    type MaxMembers = ConstU32<100>;
+    // Members who don't vote go along with the prime member, if there is one.
+    type DefaultVote = pallet_collective::PrimeDefaultVote;
+    type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+// Root decides who sits on the Council, and so who gets to vote on its motions.
+impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+    type Event = Event;
+    type AddOrigin = frame_system::EnsureRoot<AccountId>;
+    type RemoveOrigin = frame_system::EnsureRoot<AccountId>;
+    type SwapOrigin = frame_system::EnsureRoot<AccountId>;
+    type ResetOrigin = frame_system::EnsureRoot<AccountId>;
+    type PrimeOrigin = frame_system::EnsureRoot<AccountId>;
+    type MembershipInitialized = Council;
+    type MembershipChanged = Council;
+    type MaxMembers = ConstU32<100>;
+    type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
 }
 
 pub struct Migrations;
@@ -389,6 +908,23 @@ construct_runtime!(
         Scheduler: pallet_scheduler,
         Contracts: pallet_contracts,
         Template: pallet_template,
+        Preimage: pallet_preimage,
+        Utility: pallet_utility,
+        Multisig: pallet_multisig,
+        Proxy: pallet_proxy,
+        Identity: pallet_identity,
+        Democracy: pallet_democracy,
+        // Nobody validates at genesis, so there's nobody to give session keys to, which the
+        // pallet's genesis config insists on.
+        Session: pallet_session::{Pallet, Call, Storage, Event},
+        Staking: pallet_staking,
+        Treasury: pallet_treasury,
+        Council: pallet_collective::<Instance1>,
+        CouncilMembership: pallet_membership::<Instance1>,
+        // Pallets only ever get added down here, anywhere else they'd shift the indices of the
+        // ones after them, which already encoded calls and scheduled agendas rely on.
+        Assets: pallet_assets,
+        Uniques: pallet_uniques,
     }
 );
 
@@ -424,6 +960,19 @@ pub type Executive = frame_executive::Executive<
     Migrations,
 >;
 
+sp_api::decl_runtime_apis! {
+    /// Lets clients check what was written to `pallet_template`, by the chain extension or
+    /// otherwise, without having to work out its storage keys.
+    pub trait TemplateApi<AccountId> where AccountId: codec::Codec {
+        /// The value last stored with `do_something`, e.g. by `write_to_storage()`.
+        fn stored_value() -> Option<u32>;
+
+        /// The value `account` stored in a slot of its own with `store_for_caller`, e.g. by
+        /// `write_for_caller()`.
+        fn stored_value_for(account: AccountId) -> Option<u32>;
+    }
+}
+
 impl_runtime_apis! {
     impl sp_api::Core<Block> for Runtime {
         fn version() -> RuntimeVersion {
@@ -525,13 +1074,27 @@ impl_runtime_apis! {
             use frame_support::traits::StorageInfoTrait;
             use frame_system_benchmarking::Pallet as SystemBench;
             use baseline::Pallet as BaselineBench;
+            use chain_extension::benchmarking::Pallet as ChainExtensionBench;
 
             let mut list = Vec::<BenchmarkList>::new();
 
             list_benchmark!(list, extra, frame_benchmarking, BaselineBench::<Runtime>);
             list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
+            list_benchmark!(list, extra, pallet_assets, Assets);
+            list_benchmark!(list, extra, pallet_uniques, Uniques);
+            list_benchmark!(list, extra, pallet_preimage, Preimage);
+            list_benchmark!(list, extra, pallet_utility, Utility);
+            list_benchmark!(list, extra, pallet_multisig, Multisig);
+            list_benchmark!(list, extra, pallet_proxy, Proxy);
+            list_benchmark!(list, extra, pallet_identity, Identity);
+            list_benchmark!(list, extra, pallet_democracy, Democracy);
+            list_benchmark!(list, extra, pallet_staking, Staking);
+            list_benchmark!(list, extra, pallet_treasury, Treasury);
+            list_benchmark!(list, extra, pallet_collective, Council);
+            list_benchmark!(list, extra, pallet_membership, CouncilMembership);
             list_benchmark!(list, extra, pallet_balances, Balances);
             list_benchmark!(list, extra, pallet_timestamp, Timestamp);
+            list_benchmark!(list, extra, chain_extension, ChainExtensionBench::<Runtime>);
 
             let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -545,9 +1108,11 @@ impl_runtime_apis! {
 
             use frame_system_benchmarking::Pallet as SystemBench;
             use baseline::Pallet as BaselineBench;
+            use chain_extension::benchmarking::Pallet as ChainExtensionBench;
 
             impl frame_system_benchmarking::Config for Runtime {}
             impl baseline::Config for Runtime {}
+            impl chain_extension::benchmarking::Config for Runtime {}
 
             let whitelist: Vec<TrackedStorageKey> = vec![
                 // Block Number
@@ -567,13 +1132,46 @@ impl_runtime_apis! {
 
             add_benchmark!(params, batches, frame_benchmarking, BaselineBench::<Runtime>);
             add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
+            add_benchmark!(params, batches, pallet_assets, Assets);
+            add_benchmark!(params, batches, pallet_uniques, Uniques);
+            add_benchmark!(params, batches, pallet_preimage, Preimage);
+            add_benchmark!(params, batches, pallet_utility, Utility);
+            add_benchmark!(params, batches, pallet_multisig, Multisig);
+            add_benchmark!(params, batches, pallet_proxy, Proxy);
+            add_benchmark!(params, batches, pallet_identity, Identity);
+            add_benchmark!(params, batches, pallet_democracy, Democracy);
+            add_benchmark!(params, batches, pallet_staking, Staking);
+            add_benchmark!(params, batches, pallet_treasury, Treasury);
+            add_benchmark!(params, batches, pallet_collective, Council);
+            add_benchmark!(params, batches, pallet_membership, CouncilMembership);
             add_benchmark!(params, batches, pallet_balances, Balances);
             add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+            add_benchmark!(params, batches, chain_extension, ChainExtensionBench::<Runtime>);
 
             Ok(batches)
         }
     }
 
+    impl chain_extension::ChainExtensionApi<Block> for Runtime {
+        fn quote(func_id: u32, input_len: u32) -> Option<Weight> {
+            chain_extension::quote::<Runtime>(func_id, input_len)
+        }
+
+        fn call_counts(func_id: u32) -> chain_extension_primitives::CallCounts {
+            Template::extension_calls(func_id)
+        }
+    }
+
+    impl self::TemplateApi<Block, AccountId> for Runtime {
+        fn stored_value() -> Option<u32> {
+            Template::something()
+        }
+
+        fn stored_value_for(account: AccountId) -> Option<u32> {
+            Template::account_value(account)
+        }
+    }
+
     impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash>
         for Runtime
     {
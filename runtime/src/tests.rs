@@ -0,0 +1,243 @@
+use crate::*;
+use chain_extension_primitives::{
+    status,
+    ErrorCode,
+};
+use frame_support::{
+    pallet_prelude::Encode,
+    traits::{
+        OnFinalize,
+        OnInitialize,
+    },
+};
+use pallet_contracts_primitives::{
+    Code,
+    ExecReturnValue,
+};
+use pallet_template::ContractEvents;
+use sp_core::hexdisplay::HexDisplay;
+use sp_runtime::AccountId32;
+
+pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+
+// The demo contract in `contracts/chain_extension`, as `cargo contract build --release` builds it.
+//
+// We can't build ink! contracts from in here, so it's checked in instead. Whenever its sources
+// change it needs rebuilding too, which `demo_contract_is_up_to_date` makes sure of.
+pub const DEMO_CONTRACT: &[u8] = include_bytes!("../fixtures/demo_contract.wasm");
+
+// What the demo contract gets built from, which is what `fixtures/demo_contract.hash` is the hash
+// of.
+const DEMO_CONTRACT_SOURCES: [&[u8]; 4] = [
+    include_bytes!("../../contracts/chain_extension/lib.rs"),
+    include_bytes!("../../contracts/chain_extension/Cargo.toml"),
+    include_bytes!("../../primitives/src/lib.rs"),
+    include_bytes!("../../primitives/Cargo.toml"),
+];
+
+// The index of some of the demo contract's events, which is the first byte of their data.
+const VALUE_WRITTEN: u8 = 0;
+const EXTENSION_FAILED: u8 = 1;
+
+// Build genesis storage with a single funded account, mirroring what the dev chain spec gives us.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Runtime>()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<Runtime> {
+        balances: vec![(ALICE, 1 << 60)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+// The Scheduler does all of its work in `on_initialize`, so that's the main hook we need to drive
+// in order to get our scheduled calls to run. `pallet_template` uses it to clean up after them,
+// and `on_finalize` to reset how much of the block's reservation they used.
+pub fn run_to_block(n: BlockNumber) {
+    while System::block_number() < n {
+        Template::on_finalize(System::block_number());
+        let next = System::block_number() + 1;
+        System::set_block_number(next);
+        // `RandomnessCollectiveFlip` mixes the parent hash into its random material, so every
+        // block needs a different one.
+        System::set_parent_hash(Hash::from_low_u64_be(next.into()));
+        RandomnessCollectiveFlip::on_initialize(next);
+        Scheduler::on_initialize(next);
+        Session::on_initialize(next);
+        Template::on_initialize(next);
+    }
+}
+
+#[test]
+fn genesis_contracts_are_deployed() {
+    let wasm = wat::parse_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/chain_extension.wat"
+    ))
+    .expect("fixture is valid wat; qed");
+
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Runtime>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Runtime> {
+        balances: vec![(ALICE, 1 << 60)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    pallet_template::GenesisConfig::<Runtime> {
+        contracts: vec![(ALICE, wasm, Vec::new())],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        let contracts = Template::genesis_contracts();
+        assert_eq!(contracts.len(), 1);
+
+        // Calls only go through to contracts which are actually there.
+        let data = chain_extension_primitives::FUNC_VERSION
+            .to_le_bytes()
+            .to_vec();
+        let gas_limit = RuntimeBlockWeights::get().max_block;
+        let result =
+            Contracts::bare_call(ALICE, contracts[0].clone(), 0, gas_limit, None, data, false);
+        assert!(result.result.is_ok());
+    });
+}
+
+#[test]
+fn demo_contract_is_up_to_date() {
+    let hash = sp_core::hashing::blake2_256(&DEMO_CONTRACT_SOURCES.concat());
+    let hash = format!("{}", HexDisplay::from(&hash));
+    let built_from = include_str!("../fixtures/demo_contract.hash").trim();
+
+    assert!(
+        hash == built_from,
+        "The demo contract changed since `fixtures/demo_contract.wasm` was built. Rebuild it with \
+         `cargo contract build --release` in `contracts/chain_extension`, copy \
+         `target/ink/chain_extension.wasm` over it and put {} into `fixtures/demo_contract.hash`.",
+        hash
+    );
+}
+
+// The selector ink! gives the constructor or message called `name`.
+fn selector(name: &str) -> [u8; 4] {
+    let hash = sp_core::hashing::blake2_256(name.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// Deploys the demo contract, which ALICE ends up owning.
+pub fn deploy_demo_contract() -> AccountId {
+    Contracts::bare_instantiate(
+        ALICE,
+        0,
+        RuntimeBlockWeights::get().max_block,
+        None,
+        Code::Upload(DEMO_CONTRACT.to_vec().into()),
+        selector("new").to_vec(),
+        Vec::new(),
+        false,
+    )
+    .result
+    .expect("the demo contract can be instantiated")
+    .account_id
+}
+
+// Calls the demo contract's `message` with the given arguments on behalf of ALICE.
+pub fn call_demo_contract(
+    contract: &AccountId,
+    message: &str,
+    args: impl Encode,
+) -> ExecReturnValue {
+    let mut data = selector(message).to_vec();
+    args.encode_to(&mut data);
+
+    let gas_limit = RuntimeBlockWeights::get().max_block;
+    Contracts::bare_call(ALICE, contract.clone(), 0, gas_limit, None, data, false)
+        .result
+        .expect("the demo contract doesn't trap")
+}
+
+// The data of all the events `contract` emitted so far, oldest first.
+pub fn contract_events(contract: &AccountId) -> Vec<Vec<u8>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| ContractEmittedEvents::contract_event(&record.event))
+        .filter(|(emitter, _)| emitter == contract)
+        .map(|(_, data)| data)
+        .collect()
+}
+
+#[test]
+fn demo_contract_writes_through_the_extension() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy_demo_contract();
+
+        let result = call_demo_contract(&contract, "write_to_storage", 42u32);
+        assert!(!result.did_revert());
+
+        assert_eq!(Template::something(), Some(42));
+        assert_eq!(
+            contract_events(&contract),
+            vec![(VALUE_WRITTEN, ALICE, 42u32).encode()]
+        );
+    });
+}
+
+#[test]
+fn demo_contract_gets_its_callback() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy_demo_contract();
+
+        // `schedule_call(at, gas_limit, storage_deposit_limit)`, which gives back where in the
+        // agenda the callback ended up.
+        let args = (5u32, 10_000_000_000u64, None::<Balance>);
+        let result = call_demo_contract(&contract, "schedule_call", args);
+        assert!(!result.did_revert());
+        assert_eq!(result.data.0, Ok::<_, ()>((5u32, 0u32)).encode());
+
+        run_to_block(5);
+
+        let trigger = pallet_template::Trigger {
+            at: 5,
+            caller: contract.clone(),
+            arg: 15663040,
+        };
+        let triggers: Vec<_> = contract_events(&contract)
+            .iter()
+            .filter_map(|data| Template::decode_trigger(data))
+            .collect();
+        assert_eq!(triggers, vec![trigger]);
+
+        // It's done with the callback once it ran.
+        let result = call_demo_contract(&contract, "pending_schedules", ());
+        assert_eq!(result.data.0, Vec::<u64>::new().encode());
+    });
+}
+
+#[test]
+fn demo_contract_failures_are_reported_through_events() {
+    new_test_ext().execute_with(|| {
+        let contract = deploy_demo_contract();
+
+        // That's the current block, which is already too late.
+        let args = (1u32, 10_000_000_000u64, None::<Balance>);
+        let result = call_demo_contract(&contract, "schedule_call", args);
+
+        // The message failed, but it can't revert or the event would be reverted with it.
+        assert!(!result.did_revert());
+        assert_eq!(result.data.0.first(), Some(&1), "the result is an `Err`");
+
+        let error = ErrorCode::from_status(status::SCHEDULED_IN_PAST).unwrap();
+        assert_eq!(
+            contract_events(&contract),
+            vec![(EXTENSION_FAILED, error.id()).encode()]
+        );
+    });
+}